@@ -0,0 +1,230 @@
+//! # DevRS Log Collector Process Control
+//!
+//! File: cli/src/commands/collector/control.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Implements `devrs collector start`, `stop` and `status`, which manage the
+//! background collector process through the PID file in the log store root.
+//!
+//! ## Architecture
+//!
+//! - `start` re-executes the current binary as `devrs collector run` in its own
+//!   process group with stdin/stdout detached and stderr appended to
+//!   `<store>/collector.err`, then records its PID.
+//! - `stop` and liveness checks use the system `kill` command (`kill -0` probes
+//!   whether the process exists), so no signal-handling crate is needed.
+//! - A stale PID file (process gone) is treated as "not running".
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs collector start
+//! devrs collector status
+//! devrs collector stop
+//! ```
+//!
+use crate::{
+    common::{
+        logstore::{self, reader, writer}, // Store paths and stored containers.
+        ui::{format, tables},             // Sizes and the status table.
+    },
+    core::{
+        config::{self, Config},      // Configuration loading.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+    },
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use std::{
+    fs::{self, OpenOptions},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+}; // PID file and background process.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// File (in the store root) receiving the background collector's stderr.
+const ERROR_LOG_FILE: &str = "collector.err";
+
+/// Path of the PID file.
+pub(super) fn pid_path(root: &Path) -> PathBuf {
+    root.join(logstore::PID_FILE)
+}
+
+/// Returns the PID of the running collector, if any.
+///
+/// A PID file pointing at a process that no longer exists is ignored.
+fn running_pid(root: &Path) -> Option<u32> {
+    let pid: u32 = fs::read_to_string(pid_path(root))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    process_alive(pid).then_some(pid)
+}
+
+/// Probes whether a process exists with `kill -0`.
+fn process_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Starts the background collector unless it is already running.
+///
+/// Used by `devrs collector start` and, with `logs.collect = true`, by `devrs env shell`.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration (for the store location).
+///
+/// # Returns
+///
+/// * `Result<(u32, bool)>` - The collector's PID, and whether it was started by this call.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ExternalCommand` if the process cannot be spawned, or
+/// `DevrsError::FileSystem` if the store directory or PID file cannot be written.
+pub fn ensure_running(cfg: &Config) -> Result<(u32, bool)> {
+    let root = logstore::root_dir(cfg);
+    if let Some(pid) = running_pid(&root) {
+        debug!("Log collector already running (PID {})", pid);
+        return Ok((pid, false));
+    }
+    fs::create_dir_all(&root).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to create log store '{}': {}",
+            root.display(),
+            e
+        )))
+    })?;
+    let error_log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(root.join(ERROR_LOG_FILE))
+        .with_context(|| format!("Failed to open '{}'", ERROR_LOG_FILE))?;
+    let exe = std::env::current_exe().context("Failed to locate the devrs executable")?;
+
+    let mut command = Command::new(exe);
+    command
+        .args(["collector", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(error_log);
+    // Own process group: the collector survives the terminal (and Ctrl+C) of its parent.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let child = command.spawn().map_err(|e| {
+        anyhow!(DevrsError::ExternalCommand {
+            cmd: "devrs collector run".to_string(),
+            status: "not started".to_string(),
+            output: e.to_string(),
+        })
+    })?;
+    let pid = child.id();
+    // Written here as well as by the collector, so an immediate `status` sees it.
+    fs::write(pid_path(&root), pid.to_string()).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to write collector PID file: {}",
+            e
+        )))
+    })?;
+    info!("Started log collector (PID {})", pid);
+    Ok((pid, true))
+}
+
+/// Handles `devrs collector start`.
+pub(super) fn handle_start() -> Result<()> {
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let (pid, started) = ensure_running(&cfg)?;
+    if started {
+        println!(
+            "Log collector started (PID {}), writing to '{}'.",
+            pid,
+            logstore::root_dir(&cfg).display()
+        );
+    } else {
+        println!("Log collector is already running (PID {}).", pid);
+    }
+    Ok(())
+}
+
+/// Handles `devrs collector stop`.
+pub(super) fn handle_stop() -> Result<()> {
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let root = logstore::root_dir(&cfg);
+    let Some(pid) = running_pid(&root) else {
+        let _ = fs::remove_file(pid_path(&root)); // Clean up a stale PID file.
+        println!("Log collector is not running.");
+        return Ok(());
+    };
+    let cmd = format!("kill {}", pid);
+    let status = Command::new("kill")
+        .arg(pid.to_string())
+        .status()
+        .map_err(|e| {
+            anyhow!(DevrsError::ExternalCommand {
+                cmd: cmd.clone(),
+                status: "not started".to_string(),
+                output: e.to_string(),
+            })
+        })?;
+    if !status.success() {
+        return Err(anyhow!(DevrsError::ExternalCommand {
+            cmd,
+            status: status.to_string(),
+            output: "Failed to stop the log collector".to_string(),
+        }));
+    }
+    println!("Log collector (PID {}) stopped.", pid);
+    Ok(())
+}
+
+/// Handles `devrs collector status`.
+pub(super) fn handle_status() -> Result<()> {
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let root = logstore::root_dir(&cfg);
+    match running_pid(&root) {
+        Some(pid) => println!("Log collector: running (PID {})", pid),
+        None => println!("Log collector: not running"),
+    }
+    println!("Log store: {}", root.display());
+
+    let stored = reader::stored_containers(&root)?;
+    if stored.is_empty() {
+        println!("\nNo archived logs yet.");
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = stored
+        .iter()
+        .map(|meta| {
+            let dir = logstore::container_dir(&root, &meta.name);
+            let segments = writer::segment_files(&dir).unwrap_or_default();
+            let size: u64 = fs::read_dir(&dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok()?.metadata().ok())
+                .map(|m| m.len())
+                .sum();
+            vec![
+                meta.name.clone(),
+                segments.len().to_string(),
+                format::format_bytes(size),
+            ]
+        })
+        .collect();
+    println!();
+    tables::print_table(&["CONTAINER", "SEGMENTS", "SIZE"], &rows);
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! # DevRS Log Collector Command Group
+//!
+//! File: cli/src/commands/collector/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Docker discards a container's logs together with the container, so history is
+//! lost on every `devrs env rebuild`. The log collector is an optional background
+//! process that follows the logs of the core environment and DevRS application
+//! containers and persists them to rotating, gzip-compressed files on the host
+//! (see `common::logstore`). The retained history is searched with
+//! `devrs env logs --archived` and `devrs logs --archived`.
+//!
+//! ## Architecture
+//!
+//! - **`start`**: Spawns `devrs collector run` as a detached background process.
+//! - **`stop`**: Sends SIGTERM to the recorded process; it flushes and exits.
+//! - **`status`**: Shows whether the collector runs and what it has stored.
+//! - **`run`**: The collector itself, in the foreground (see `run.rs`).
+//!
+//! The collector's PID is kept in `<store>/collector.pid`; its own diagnostics go to
+//! `<store>/collector.err`. With `logs.collect = true` in the configuration,
+//! `devrs env shell` starts the collector automatically.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs collector start
+//! devrs collector status
+//! devrs collector stop
+//!
+//! # Run in the foreground with debug output
+//! devrs -vv collector run
+//! ```
+//!
+use crate::core::error::Result; // Use the standard Result type for error handling.
+use clap::{Parser, Subcommand}; // Import necessary components from the Clap crate for argument parsing.
+
+// --- Subcommand Module Declarations ---
+
+/// Background process control: `start`, `stop`, `status` and the PID file.
+mod control;
+/// Implements `devrs collector run` (the collection loop).
+mod run;
+
+pub use control::ensure_running;
+
+/// # Collector Command Group Arguments (`CollectorArgs`)
+///
+/// This struct represents the top-level command group `devrs collector`.
+#[derive(Parser, Debug)]
+#[command(about = "Persist container logs to rotating files on the host")]
+pub struct CollectorArgs {
+    /// The specific collector subcommand to execute.
+    #[command(subcommand)]
+    command: CollectorCommand,
+}
+
+/// # Collector Subcommands (`CollectorCommand`)
+#[derive(Subcommand, Debug)]
+enum CollectorCommand {
+    /// Corresponds to `devrs collector start`. Starts the collector in the background.
+    Start,
+    /// Corresponds to `devrs collector stop`. Stops the background collector.
+    Stop,
+    /// Corresponds to `devrs collector status`. Shows whether the collector runs and what is stored.
+    Status,
+    /// Corresponds to `devrs collector run`. Runs the collector in the foreground until interrupted.
+    Run,
+}
+
+/// # Handle Collector Command (`handle_collector`)
+///
+/// Dispatches to the subcommand handlers.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `CollectorArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Propagates the `Result` from the called subcommand handler.
+pub async fn handle_collector(args: CollectorArgs) -> Result<()> {
+    match args.command {
+        CollectorCommand::Start => control::handle_start()?,
+        CollectorCommand::Stop => control::handle_stop()?,
+        CollectorCommand::Status => control::handle_status()?,
+        CollectorCommand::Run => run::handle_run().await?,
+    }
+    Ok(())
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every subcommand parses.
+    #[test]
+    fn test_parses_collector_subcommands() {
+        for (name, expected) in [
+            ("start", "Start"),
+            ("stop", "Stop"),
+            ("status", "Status"),
+            ("run", "Run"),
+        ] {
+            let args = CollectorArgs::try_parse_from(["collector", name]).unwrap();
+            assert_eq!(format!("{:?}", args.command), expected);
+        }
+        assert!(CollectorArgs::try_parse_from(["collector"]).is_err());
+    }
+}
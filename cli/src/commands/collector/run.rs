@@ -0,0 +1,319 @@
+//! # DevRS Log Collector Loop
+//!
+//! File: cli/src/commands/collector/run.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Implements `devrs collector run`: follow the logs of every DevRS container
+//! (core environment and applications, see `roles::container_role`) and append
+//! them to the log store until interrupted. `devrs collector start` runs this in
+//! the background.
+//!
+//! ## Architecture
+//!
+//! 1. Subscribe to container start events, then attach to every existing DevRS
+//!    container (stopped ones too, so their output is archived before removal).
+//! 2. Attaching writes the container's metadata, opens its `RotatingLog` and spawns
+//!    a task forwarding `log_lines` (following, starting at the stored cursor) over
+//!    a channel. Attachments are keyed by container ID, so when a container is
+//!    recreated under the same name, the old stream ending does not detach the new one.
+//!    Logs are stored per name and stay open while any container of that name is attached.
+//! 3. The main loop appends lines, attaches to newly started DevRS containers,
+//!    flushes every log on a short interval, and flushes once more on Ctrl+C or
+//!    SIGTERM before removing the PID file.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs -v collector run
+//! ```
+//!
+use crate::{
+    common::{
+        docker::{
+            self,
+            logs::{LogLine, LogQuery},
+            roles,
+        }, // Log/event streams and role classification.
+        logstore::{
+            self, reader,
+            writer::{RotatingLog, RotationPolicy},
+        }, // Persistent storage.
+    },
+    core::{
+        config::{self, Config}, // Configuration loading.
+        error::Result,          // Standard Result type.
+    },
+};
+use anyhow::Context; // For adding context to errors.
+use futures_util::StreamExt; // For consuming log and event streams.
+use std::{collections::HashMap, fs, path::Path, time::Duration}; // Open logs, PID file, flush interval.
+use tokio::sync::mpsc; // Channel from per-container tasks to the writer loop.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+use super::control::pid_path;
+
+/// How often buffered lines and cursors are flushed to disk.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Messages from the per-container tasks to the writer loop.
+enum CollectorEvent {
+    /// A line of output.
+    Line(LogLine),
+    /// The stream of the container with this ID ended (it stopped) or failed.
+    Ended(String),
+}
+
+/// # Handle Collector Run (`handle_run`)
+///
+/// ## Workflow:
+/// 1.  Loads configuration, resolves the store root and rotation policy, writes the PID file.
+/// 2.  Attaches to all existing DevRS containers and watches for new ones.
+/// 3.  Stores lines until Ctrl+C or SIGTERM, flushing periodically and on exit.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` after a clean shutdown, or an `Err` if Docker or the store fail at startup.
+pub(super) async fn handle_run() -> Result<()> {
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let root = logstore::root_dir(&cfg);
+    let policy = RotationPolicy::from_config(&cfg.logs);
+    fs::create_dir_all(&root)
+        .with_context(|| format!("Failed to create log store '{}'", root.display()))?;
+    fs::write(pid_path(&root), std::process::id().to_string())
+        .context("Failed to write collector PID file")?;
+    info!(
+        "Log collector writing to '{}' ({:?})",
+        root.display(),
+        policy
+    );
+
+    let result = collect(&cfg, &root, &policy).await;
+    let _ = fs::remove_file(pid_path(&root));
+    result
+}
+
+/// The collection loop.
+async fn collect(cfg: &Config, root: &Path, policy: &RotationPolicy) -> Result<()> {
+    // Subscribe before listing, so no container slips through in between.
+    let mut starts = Box::pin(docker::logs::container_starts().await?);
+    let (tx, mut rx) = mpsc::channel::<CollectorEvent>(1024);
+    let mut logs: HashMap<String, RotatingLog> = HashMap::new(); // By container name.
+    let mut attached: HashMap<String, String> = HashMap::new(); // Container ID to name.
+
+    for container in docker::state::list_containers(true, None)
+        .await
+        .context("Failed to list containers")?
+    {
+        let (Some(id), Some(name)) = (
+            container.id,
+            container
+                .names
+                .as_ref()
+                .and_then(|n| n.first())
+                .map(|n| n.trim_start_matches('/').to_string()),
+        ) else {
+            continue;
+        };
+        let meta = logstore::ContainerMeta {
+            name,
+            image: container.image.unwrap_or_default(),
+            labels: container.labels.unwrap_or_default(),
+        };
+        attach(id, meta, cfg, root, policy, &mut logs, &mut attached, &tx);
+    }
+
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            Some(event) = rx.recv() => match event {
+                CollectorEvent::Line(line) => {
+                    if let Some(log) = logs.get_mut(&line.container) {
+                        if let Err(e) = log.append(&line) {
+                            warn!("Failed to store log line of '{}': {:#}", line.container, e);
+                        }
+                    }
+                }
+                CollectorEvent::Ended(id) => detach(&id, &mut logs, &mut attached),
+            },
+            start = starts.next() => match start {
+                Some(Ok(start)) => {
+                    let meta = logstore::ContainerMeta {
+                        name: start.name,
+                        image: start.image,
+                        labels: start.labels,
+                    };
+                    attach(start.id, meta, cfg, root, policy, &mut logs, &mut attached, &tx);
+                }
+                Some(Err(e)) => {
+                    warn!("Docker event stream failed: {:#}", e);
+                    break;
+                }
+                None => break,
+            },
+            _ = ticker.tick() => flush_all(&mut logs),
+            _ = &mut shutdown => break,
+        }
+    }
+    flush_all(&mut logs);
+    info!("Log collector stopped.");
+    Ok(())
+}
+
+/// Starts storing a container's logs if it is a DevRS container and not yet attached.
+///
+/// `id` keys the attachment; the log itself is shared by all containers of the same name.
+#[allow(clippy::too_many_arguments)] // The collector state is passed piecewise.
+fn attach(
+    id: String,
+    meta: logstore::ContainerMeta,
+    cfg: &Config,
+    root: &Path,
+    policy: &RotationPolicy,
+    logs: &mut HashMap<String, RotatingLog>,
+    attached: &mut HashMap<String, String>,
+    tx: &mpsc::Sender<CollectorEvent>,
+) {
+    if attached.contains_key(&id)
+        || !roles::container_role(&meta.name, &meta.image, &meta.labels, cfg).is_devrs()
+    {
+        return;
+    }
+    if let Err(e) = reader::write_meta(root, &meta) {
+        warn!("{:#}", e);
+    }
+    if !logs.contains_key(&meta.name) {
+        match RotatingLog::open(&logstore::container_dir(root, &meta.name), policy.clone()) {
+            Ok(log) => {
+                logs.insert(meta.name.clone(), log);
+            }
+            Err(e) => {
+                warn!("Cannot store logs of '{}': {:#}", meta.name, e);
+                return;
+            }
+        }
+    }
+    // Resume at the cursor; lines within its second are de-duplicated by `append`.
+    let query = LogQuery {
+        follow: true,
+        since: logs[&meta.name].cursor().map_or(0, |c| c.timestamp()),
+        ..Default::default()
+    };
+    info!(
+        "Collecting logs of '{}' ({}, since {})",
+        meta.name, id, query.since
+    );
+    attached.insert(id.clone(), meta.name.clone());
+
+    let (name, tx) = (meta.name, tx.clone());
+    tokio::spawn(async move {
+        match docker::logs::log_lines(&name, &query).await {
+            Ok(stream) => {
+                let mut stream = Box::pin(stream);
+                while let Some(item) = stream.next().await {
+                    match item {
+                        Ok(line) => {
+                            if tx.send(CollectorEvent::Line(line)).await.is_err() {
+                                return; // Writer loop has exited.
+                            }
+                        }
+                        Err(e) => {
+                            warn!("{:#}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!("{:#}", e),
+        }
+        let _ = tx.send(CollectorEvent::Ended(id)).await;
+    });
+}
+
+/// Forgets an ended attachment, closing its log unless another container of the same
+/// name (a recreated one) is still attached.
+fn detach(
+    id: &str,
+    logs: &mut HashMap<String, RotatingLog>,
+    attached: &mut HashMap<String, String>,
+) {
+    let Some(name) = attached.remove(id) else {
+        return;
+    };
+    debug!("Log stream of '{}' ({}) ended.", name, id);
+    if attached.values().any(|n| *n == name) {
+        return;
+    }
+    if let Some(mut log) = logs.remove(&name) {
+        let _ = log.flush();
+    }
+}
+
+/// Flushes every open log, logging failures.
+fn flush_all(logs: &mut HashMap<String, RotatingLog>) {
+    for (name, log) in logs.iter_mut() {
+        if let Err(e) = log.flush() {
+            warn!("Failed to flush logs of '{}': {:#}", name, e);
+        }
+    }
+}
+
+/// Completes on Ctrl+C or (on Unix) SIGTERM, which `devrs collector stop` sends.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut term) => {
+                term.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate => {},
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that the late end of a removed container's stream does not detach the
+    /// recreated container of the same name.
+    #[test]
+    fn test_detach_keeps_recreated_container() {
+        let dir = tempdir().unwrap();
+        let log = RotatingLog::open(dir.path(), RotationPolicy::default()).unwrap();
+        let mut logs = HashMap::from([("web".to_string(), log)]);
+        let mut attached = HashMap::from([
+            ("old-id".to_string(), "web".to_string()),
+            ("new-id".to_string(), "web".to_string()),
+        ]);
+
+        detach("old-id", &mut logs, &mut attached);
+        assert!(logs.contains_key("web"));
+        assert_eq!(attached.keys().collect::<Vec<_>>(), ["new-id"]);
+
+        detach("old-id", &mut logs, &mut attached);
+        assert!(logs.contains_key("web"));
+
+        detach("new-id", &mut logs, &mut attached);
+        assert!(logs.is_empty() && attached.is_empty());
+    }
+}
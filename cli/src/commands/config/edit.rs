@@ -0,0 +1,209 @@
+//! # DevRS Config Edit Handler
+//!
+//! File: cli/src/commands/config/edit.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config edit` subcommand, which opens the user
+//! config file (default) or the project's `.devrs.toml` (`--project`) in the user's
+//! editor and validates it before saving.
+//!
+//! ## Architecture
+//!
+//! - The editor is `$VISUAL`, then `$EDITOR`, then `vi`. The variable may include
+//!   arguments (e.g. `code --wait`).
+//! - A copy of the file is edited in a private temp file (`tempfile`, created with an
+//!   unpredictable name and mode 0600, removed afterwards). When the editor exits, the
+//!   copy is validated; only a valid result replaces the real file, so a mistake never
+//!   leaves a configuration that breaks every command.
+//! - On a validation error the user can reopen the editor (keeping their changes) or
+//!   discard them.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config edit
+//! EDITOR="code --wait" devrs config edit --project
+//! ```
+//!
+use super::file::{self, FileArgs}; // File selection and validated writes.
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use clap::Parser; // For parsing command-line arguments.
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path,
+    process::Command,
+}; // Editor process, temp copy and prompt.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Edit Arguments (`EditArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config edit` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Open a config file in $EDITOR and validate it on save")]
+pub struct EditArgs {
+    /// The file to edit (default: the user config file).
+    #[command(flatten)]
+    file: FileArgs,
+}
+
+/// # Handle Config Edit Command (`handle_edit`)
+///
+/// ## Workflow:
+/// 1.  Copies the selected file (empty if it does not exist) to a temp file.
+/// 2.  Opens the copy in the editor and waits for it to exit.
+/// 3.  If nothing changed, stops. Otherwise validates the copy:
+///     * Valid: writes it to the real file.
+///     * Invalid: shows the error and asks whether to edit again or discard the changes.
+/// 4.  Removes the temp file (when it is dropped).
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `EditArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once saved, unchanged, or discarded by the user.
+/// * `Err`: If the editor cannot be run or exits with an error, or a file cannot be
+///   read or written.
+pub async fn handle_edit(args: EditArgs) -> Result<()> {
+    info!("Handling config edit command...");
+    debug!("Edit args: {:?}", args);
+    let path = args.file.path()?;
+    let original = file::read_or_empty(&path)?;
+
+    // The `.toml` suffix lets editors pick the syntax; the file is removed on drop.
+    let scratch = tempfile::Builder::new()
+        .prefix("devrs-config-")
+        .suffix(".toml")
+        .tempfile()
+        .map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to create a temp file: {}",
+                e
+            )))
+        })?;
+    write_scratch(scratch.path(), &original)?;
+    edit_loop(&path, scratch.path(), &original)
+}
+
+/// Runs the editor until the copy is valid, unchanged, or discarded.
+fn edit_loop(path: &Path, scratch: &Path, original: &str) -> Result<()> {
+    loop {
+        run_editor(scratch)?;
+        let edited = file::read_or_empty(scratch)?;
+        if edited == original {
+            println!("No changes to {}.", path.display());
+            return Ok(());
+        }
+        match file::write_checked(path, &edited) {
+            Ok(()) => {
+                println!("✅ Saved {}", path.display());
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("❌ {:#}", e);
+                if !confirm("Edit again? Answering no discards your changes. [Y/n] ")? {
+                    println!("Discarded changes; {} is unchanged.", path.display());
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Writes the temp copy that is handed to the editor.
+fn write_scratch(scratch: &Path, content: &str) -> Result<()> {
+    fs::write(scratch, content).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to write '{}': {}",
+            scratch.display(),
+            e
+        )))
+    })
+}
+
+/// Opens a file in the user's editor and waits for it to exit.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ExternalCommand` if the editor cannot be started or fails.
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = editor_command(env::var("VISUAL").ok(), env::var("EDITOR").ok());
+    debug!("Running editor: {:?} {}", editor, path.display());
+    let (program, editor_args) = editor.split_first().expect("editor command is not empty");
+    let status = Command::new(program)
+        .args(editor_args)
+        .arg(path)
+        .status()
+        .map_err(|e| {
+            anyhow!(DevrsError::ExternalCommand {
+                cmd: program.clone(),
+                status: "failed to start".to_string(),
+                output: e.to_string(),
+            })
+        })?;
+    if !status.success() {
+        return Err(anyhow!(DevrsError::ExternalCommand {
+            cmd: program.clone(),
+            status: status.to_string(),
+            output: String::new(),
+        }));
+    }
+    Ok(())
+}
+
+/// The editor command: `$VISUAL`, then `$EDITOR` (both may include arguments), then `vi`.
+fn editor_command(visual: Option<String>, editor: Option<String>) -> Vec<String> {
+    [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(|cmd| {
+            cmd.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .find(|parts| !parts.is_empty())
+        .unwrap_or_else(|| vec!["vi".to_string()])
+}
+
+/// Asks a yes/no question on the terminal; an empty answer means yes, end of input no.
+fn confirm(question: &str) -> Result<bool> {
+    print!("{}", question);
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    let read = io::stdin().read_line(&mut answer).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to read the answer: {}",
+            e
+        )))
+    })?;
+    Ok(read > 0 && !matches!(answer.trim().to_lowercase().as_str(), "n" | "no"))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the editor fallback order and argument splitting.
+    #[test]
+    fn test_editor_command() {
+        assert_eq!(
+            editor_command(Some("code --wait".into()), Some("nano".into())),
+            vec!["code", "--wait"]
+        );
+        assert_eq!(
+            editor_command(Some(" ".into()), Some("nano".into())),
+            vec!["nano"]
+        );
+        assert_eq!(editor_command(None, None), vec!["vi"]);
+    }
+}
@@ -0,0 +1,135 @@
+//! # DevRS Config Export Handler
+//!
+//! File: cli/src/commands/config/export.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config export` subcommand, which renders the
+//! effective configuration (user config, devcontainer.json and `.devrs.toml`
+//! merged) in a format other tools understand, so colleagues without DevRS can run
+//! the same environment.
+//!
+//! ## Architecture
+//!
+//! - `--devcontainer`: `core::devcontainer::export` produces a devcontainer.json
+//!   document for the core environment.
+//! - `--compose`: `core::compose::export` produces a Compose v3 file with the core
+//!   environment and the project's `[apps.<name>]` containers on a shared network.
+//! - Settings without an equivalent in the chosen format are listed on stderr.
+//! - The document goes to stdout, or to `--output` (parent directories are created).
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config export --devcontainer
+//! devrs config export --devcontainer --output .devcontainer/devcontainer.json
+//! devrs config export --compose -o docker-compose.yml
+//! ```
+//!
+use crate::core::{
+    compose,                     // docker-compose translation.
+    config,                      // Effective configuration.
+    devcontainer,                // devcontainer.json translation.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::{ArgGroup, Parser}; // For parsing command-line arguments.
+use std::{fs, path::PathBuf}; // Writing the output file.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Export Arguments (`ExportArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config export` subcommand.
+/// Exactly one format flag must be given.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Export the effective configuration in another format",
+    group(ArgGroup::new("format").required(true).args(["devcontainer", "compose"]))
+)]
+pub struct ExportArgs {
+    /// Render the core environment as `devcontainer.json`.
+    #[arg(long)]
+    devcontainer: bool,
+
+    /// Render the core environment and project apps as a Compose v3 file.
+    #[arg(long)]
+    compose: bool,
+
+    /// Write to this file instead of stdout.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+/// # Handle Config Export Command (`handle_export`)
+///
+/// ## Workflow:
+/// 1.  Loads the effective configuration.
+/// 2.  Renders it in the requested format.
+/// 3.  Prints notes about settings that could not be exported to stderr.
+/// 4.  Writes the document to `--output` or stdout.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ExportArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once the document is written.
+/// * `Err`: If the configuration cannot be loaded or the output cannot be written.
+pub async fn handle_export(args: ExportArgs) -> Result<()> {
+    info!("Handling config export command...");
+    debug!("Export args: {:?}", args);
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+
+    let (format, rendered, unsupported) = if args.compose {
+        // Paths in the project are written relative to the file's directory.
+        let dir = match args.output.as_ref().and_then(|p| p.parent()) {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let dir = std::path::absolute(&dir).context("Failed to resolve the output directory")?;
+        let (document, unsupported) = compose::export(&cfg, &dir);
+        let rendered =
+            serde_yaml::to_string(&document).context("Failed to render the compose file")?;
+        ("Compose", rendered, unsupported)
+    } else {
+        let (document, unsupported) = devcontainer::export(&cfg);
+        let mut rendered = serde_json::to_string_pretty(&document)
+            .context("Failed to render devcontainer.json")?;
+        rendered.push('\n');
+        ("devcontainer.json", rendered, unsupported)
+    };
+
+    for note in &unsupported {
+        eprintln!("⚠️  Not exported (no {} equivalent): {}", format, note);
+    }
+
+    match &args.output {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).map_err(|e| {
+                    anyhow!(DevrsError::FileSystem(format!(
+                        "Failed to create '{}': {}",
+                        parent.display(),
+                        e
+                    )))
+                })?;
+            }
+            fs::write(path, rendered).map_err(|e| {
+                anyhow!(DevrsError::FileSystem(format!(
+                    "Failed to write '{}': {}",
+                    path.display(),
+                    e
+                )))
+            })?;
+            eprintln!("✅ Wrote {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
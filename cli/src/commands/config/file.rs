@@ -0,0 +1,275 @@
+//! # DevRS Config File Helpers
+//!
+//! File: cli/src/commands/config/file.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module holds what `devrs config get`, `set`, `edit` and `migrate` share:
+//! choosing the config file to work on (`--user` or `--project`) and reading or
+//! writing a dotted key (`core_env.image_tag`) in it.
+//!
+//! ## Architecture
+//!
+//! - `FileArgs`: The `--user` / `--project` flags, flattened into each subcommand.
+//! - `parse_key`: Splits a dotted key using TOML key syntax, so keys containing dots
+//!   can be quoted (`merge."core_env.mounts"`).
+//! - `get_key`: Looks a key up in a parsed table.
+//! - `set_key`: Writes a value into a `toml_edit` document, which keeps the file's
+//!   comments, ordering and formatting intact. Missing tables are created.
+//! - `write_checked`: Validates new file contents before writing them, so a typo
+//!   never leaves a config file that breaks every command.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::commands::config::file;
+//! # fn example(doc: &mut toml_edit::DocumentMut) -> anyhow::Result<()> {
+//! let keys = file::parse_key("core_env.image_tag")?;
+//! file::set_key(doc, &keys, "\"v3\"".parse()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::{
+    config,                      // Locating the user and project files.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Args; // For the shared file selection flags.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+}; // Reading and writing config files.
+use toml::{Table, Value}; // Reading parsed files.
+use toml_edit::{DocumentMut, Item, Key, TableLike}; // Format-preserving editing.
+
+/// # Config File Selection (`FileArgs`)
+///
+/// Selects the config file a subcommand reads or writes.
+#[derive(Args, Debug)]
+pub struct FileArgs {
+    /// Use the user config file (`~/.config/devrs/config.toml`).
+    #[arg(long, conflicts_with = "project")]
+    pub user: bool,
+
+    /// Use the project's `.devrs.toml` (created in the current directory if there is none).
+    #[arg(long)]
+    pub project: bool,
+}
+
+impl FileArgs {
+    /// Whether a file was selected explicitly.
+    pub fn any(&self) -> bool {
+        self.user || self.project
+    }
+
+    /// The selected file, defaulting to the user config file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::Config` if the user config directory cannot be determined,
+    /// or an error if the current directory cannot be read.
+    pub fn path(&self) -> Result<PathBuf> {
+        if self.project {
+            return match config::find_project_config_path()? {
+                Some(path) => Ok(path),
+                None => Ok(std::env::current_dir()
+                    .context("Failed to get current directory")?
+                    .join(config::PROJECT_CONFIG_FILENAME)),
+            };
+        }
+        config::user_config_path().ok_or_else(|| {
+            anyhow!(DevrsError::Config(
+                "Could not determine the user config directory.".to_string()
+            ))
+        })
+    }
+}
+
+/// Splits a dotted key into its parts.
+///
+/// # Arguments
+///
+/// * `key` - A TOML key, e.g. `core_env.image_tag` or `merge."core_env.mounts"`.
+///
+/// # Returns
+///
+/// * `Result<Vec<Key>>` - The key parts.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ArgumentParsing` if `key` is not a valid TOML key.
+pub fn parse_key(key: &str) -> Result<Vec<Key>> {
+    Key::parse(key).map_err(|e| {
+        anyhow!(DevrsError::ArgumentParsing(format!(
+            "Invalid key '{}': {}",
+            key,
+            e.to_string().trim()
+        )))
+    })
+}
+
+/// Looks up a key in a table.
+///
+/// # Returns
+///
+/// * `Option<&Value>` - The value, or `None` if the key (or a table on its path) is not set.
+pub fn get_key<'a>(table: &'a Table, keys: &[Key]) -> Option<&'a Value> {
+    let (last, parents) = keys.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get(key.get())?.as_table()?;
+    }
+    current.get(last.get())
+}
+
+/// Sets a key in a document, keeping the formatting of everything else. An existing
+/// value keeps its surrounding whitespace and trailing comment.
+///
+/// # Arguments
+///
+/// * `doc` - The document to modify.
+/// * `keys` - The key parts (from `parse_key`).
+/// * `value` - The new value.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if a parent of the key is set to something other than a table.
+pub fn set_key(doc: &mut DocumentMut, keys: &[Key], value: toml_edit::Value) -> Result<()> {
+    let Some((last, parents)) = keys.split_last() else {
+        return Ok(());
+    };
+    let mut current: &mut dyn TableLike = doc.as_table_mut();
+    for (depth, key) in parents.iter().enumerate() {
+        let item = current.entry_format(key).or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true); // No empty `[core_env]` header before `[core_env.build]`.
+            Item::Table(table)
+        });
+        current = item.as_table_like_mut().ok_or_else(|| {
+            let path: Vec<&str> = keys[..=depth].iter().map(Key::get).collect();
+            anyhow!(DevrsError::Config(format!(
+                "Cannot set '{}': '{}' is not a table.",
+                keys.iter().map(Key::get).collect::<Vec<_>>().join("."),
+                path.join(".")
+            )))
+        })?;
+    }
+    match current.get_mut(last.get()) {
+        Some(Item::Value(existing)) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            current.insert(last.get(), Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+/// Reads a config file, treating a missing file as empty.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the file exists but cannot be read.
+pub fn read_or_empty(path: &Path) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(anyhow!(DevrsError::FileSystem(format!(
+            "Failed to read '{}': {}",
+            path.display(),
+            e
+        )))),
+    }
+}
+
+/// Validates new contents for a config file and writes them, creating parent
+/// directories. Invalid contents are not written.
+///
+/// # Errors
+///
+/// Returns the validation error (see `config::check_config_str`), or
+/// `DevrsError::FileSystem` if the file cannot be written.
+pub fn write_checked(path: &Path, content: &str) -> Result<()> {
+    config::check_config_str(content, path)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to create '{}': {}",
+                parent.display(),
+                e
+            )))
+        })?;
+    }
+    fs::write(path, content).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to write '{}': {}",
+            path.display(),
+            e
+        )))
+    })
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that setting keys keeps comments and creates missing tables.
+    #[test]
+    fn test_set_key_preserves_formatting() {
+        let text =
+            "# My settings\n[core_env]\nimage_tag = \"v2\"  # pinned\n\n[logs]\ncollect = true\n";
+        let mut doc: DocumentMut = text.parse().unwrap();
+        set_key(
+            &mut doc,
+            &parse_key("core_env.image_tag").unwrap(),
+            "\"v3\"".parse().unwrap(),
+        )
+        .unwrap();
+        set_key(
+            &mut doc,
+            &parse_key("core_env.build.args.RUST").unwrap(),
+            "\"1.80\"".parse().unwrap(),
+        )
+        .unwrap();
+        let rendered = doc.to_string();
+        assert!(rendered.starts_with("# My settings\n[core_env]\nimage_tag = \"v3\"  # pinned\n"));
+        assert!(rendered.contains("[logs]\ncollect = true\n"));
+        assert!(rendered.contains("[core_env.build.args]\nRUST = \"1.80\"\n"));
+        assert!(!rendered.contains("[core_env.build]\n"));
+
+        // A scalar cannot be descended into.
+        assert!(set_key(
+            &mut doc,
+            &parse_key("logs.collect.x").unwrap(),
+            "1".parse().unwrap()
+        )
+        .is_err());
+    }
+
+    /// Test reading dotted and quoted keys.
+    #[test]
+    fn test_get_key() {
+        let table: Table = toml::from_str(
+            "[core_env]\nimage_tag = \"v2\"\n[merge]\n\"core_env.mounts\" = \"replace\"\n",
+        )
+        .unwrap();
+        let get = |key: &str| get_key(&table, &parse_key(key).unwrap()).cloned();
+        assert_eq!(get("core_env.image_tag"), Some(Value::from("v2")));
+        assert_eq!(
+            get("merge.\"core_env.mounts\""),
+            Some(Value::from("replace"))
+        );
+        assert_eq!(get("core_env.image_tag.x"), None);
+        assert_eq!(get("logs.collect"), None);
+        assert!(parse_key("core_env.").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! # DevRS Config Get Handler
+//!
+//! File: cli/src/commands/config/get.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config get` subcommand, which prints a single
+//! setting by its dotted key: the effective value by default, or the value in the
+//! user or project config file with `--user` / `--project`.
+//!
+//! ## Architecture
+//!
+//! - Without a file flag, the key is looked up in the effective configuration (all
+//!   layers merged, defaults filled in), so every setting has a value.
+//! - With `--user` / `--project`, only that file is read; a key it does not set is an error.
+//! - Strings are printed as-is (convenient in scripts); other values as TOML.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config get core_env.image_tag
+//! devrs config get core_env.mounts --project
+//! ```
+//!
+use super::file::{self, FileArgs}; // File selection and key lookup.
+use crate::core::{
+    config,                      // Effective configuration.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use toml::{Table, Value}; // Looking up and printing values.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Get Arguments (`GetArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config get` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Print one setting by its dotted key")]
+pub struct GetArgs {
+    /// The dotted key, e.g. `core_env.image_tag`.
+    key: String,
+
+    /// Read from one config file instead of the effective configuration.
+    #[command(flatten)]
+    file: FileArgs,
+}
+
+/// # Handle Config Get Command (`handle_get`)
+///
+/// ## Workflow:
+/// 1.  Parses the dotted key.
+/// 2.  Loads the effective configuration, or reads the selected file.
+/// 3.  Prints the value.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `GetArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once printed.
+/// * `Err`: If the key is malformed or not set, or the configuration cannot be read.
+pub async fn handle_get(args: GetArgs) -> Result<()> {
+    info!("Handling config get command...");
+    debug!("Get args: {:?}", args);
+    let keys = file::parse_key(&args.key)?;
+
+    let (table, origin) = if args.file.any() {
+        let path = args.file.path()?;
+        let content = file::read_or_empty(&path)?;
+        let table: Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse TOML from file: {}", path.display()))?;
+        (table, path.display().to_string())
+    } else {
+        let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+        let table = Table::try_from(&cfg).context("Failed to convert the configuration")?;
+        (table, "the configuration".to_string())
+    };
+
+    let value = file::get_key(&table, &keys).ok_or_else(|| {
+        anyhow!(DevrsError::Config(format!(
+            "'{}' is not set in {}.",
+            args.key, origin
+        )))
+    })?;
+    print!("{}", render(value)?);
+    Ok(())
+}
+
+/// Formats a value for printing: strings verbatim, tables as TOML documents, anything
+/// else as a TOML value.
+fn render(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::String(s) => format!("{}\n", s),
+        Value::Table(table) => toml::to_string(table).context("Failed to render the value")?,
+        other => format!("{}\n", other),
+    })
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test printing strings, lists and tables.
+    #[test]
+    fn test_render() {
+        assert_eq!(render(&Value::from("v3")).unwrap(), "v3\n");
+        assert_eq!(
+            render(&Value::Array(vec!["1:1".into()])).unwrap(),
+            "[\"1:1\"]\n"
+        );
+        let table: Table = toml::from_str("A = \"1\"").unwrap();
+        assert_eq!(render(&Value::Table(table)).unwrap(), "A = \"1\"\n");
+    }
+}
@@ -0,0 +1,166 @@
+//! # DevRS Config Import Handler
+//!
+//! File: cli/src/commands/config/import.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config import` subcommand, which seeds a
+//! project's `.devrs.toml` from a configuration written for another tool, so an
+//! existing setup can be adopted without retyping it.
+//!
+//! ## Architecture
+//!
+//! - `--compose`: `core::compose::load` translates a Compose file. The `core` service
+//!   (or `--core-service`) becomes `[core_env]`; every other service becomes
+//!   `[apps.<name>]`. Relative paths are made absolute against the Compose file.
+//! - Features DevRS cannot represent are listed on stderr.
+//! - The result is checked to parse as a DevRS configuration, then written to
+//!   `--output` (default `.devrs.toml`, or `-` for stdout). An existing file is only
+//!   replaced with `--force`.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Import ./compose.yaml or ./docker-compose.yml into ./.devrs.toml
+//! devrs config import --compose
+//!
+//! # Pick the file and the service used as the core environment, print the result
+//! devrs config import --compose deploy/compose.dev.yml --core-service workspace -o -
+//! ```
+//!
+use crate::core::{
+    compose,                     // docker-compose translation.
+    config::Config,              // Checks that the result parses.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::{ArgGroup, Parser}; // For parsing command-line arguments.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+}; // Reading and writing files.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Import Arguments (`ImportArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config import` subcommand.
+/// Exactly one format flag must be given.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Seed .devrs.toml from another tool's configuration",
+    group(ArgGroup::new("format").required(true).args(["compose"]))
+)]
+pub struct ImportArgs {
+    /// Import a Compose file.
+    #[arg(long)]
+    compose: bool,
+
+    /// The file to import (default: compose.yaml, compose.yml, docker-compose.yml or
+    /// docker-compose.yaml in the current directory).
+    file: Option<PathBuf>,
+
+    /// The Compose service to import as the core environment.
+    #[arg(long, default_value = compose::CORE_SERVICE)]
+    core_service: String,
+
+    /// Where to write the configuration (`-` for stdout).
+    #[arg(long, short, default_value = ".devrs.toml")]
+    output: PathBuf,
+
+    /// Replace the output file if it exists.
+    #[arg(long, short)]
+    force: bool,
+}
+
+/// # Handle Config Import Command (`handle_import`)
+///
+/// ## Workflow:
+/// 1.  Locates the file to import (argument or default names).
+/// 2.  Translates it into `.devrs.toml` content.
+/// 3.  Checks that the content parses as a DevRS configuration.
+/// 4.  Prints notes about features that could not be imported to stderr.
+/// 5.  Writes the content to `--output` (refusing to overwrite without `--force`) or stdout.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ImportArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once the configuration is written.
+/// * `Err`: If the input cannot be found or translated, or the output cannot be written.
+pub async fn handle_import(args: ImportArgs) -> Result<()> {
+    info!("Handling config import command...");
+    debug!("Import args: {:?}", args);
+    let source = match &args.file {
+        Some(file) => file.clone(),
+        None => find_compose_file(Path::new("."))?,
+    };
+
+    let import = compose::load(&source, &args.core_service)?;
+    let mut rendered = format!(
+        "# Imported from {} by `devrs config import --compose`.\n\n",
+        source.display()
+    );
+    rendered.push_str(
+        &toml::to_string_pretty(&import.config).context("Failed to render the configuration")?,
+    );
+    toml::from_str::<Config>(&rendered).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "The imported configuration is not valid: {}",
+            e
+        )))
+    })?;
+
+    for note in &import.unsupported {
+        eprintln!("⚠️  Not imported (DevRS cannot represent it): {}", note);
+    }
+
+    if args.output == Path::new("-") {
+        print!("{}", rendered);
+        return Ok(());
+    }
+    if args.output.exists() && !args.force {
+        return Err(anyhow!(DevrsError::FileSystem(format!(
+            "'{}' already exists. Use --force to replace it.",
+            args.output.display()
+        ))));
+    }
+    fs::write(&args.output, rendered).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to write '{}': {}",
+            args.output.display(),
+            e
+        )))
+    })?;
+    eprintln!(
+        "✅ Wrote {} from {}",
+        args.output.display(),
+        source.display()
+    );
+    Ok(())
+}
+
+/// Finds the Compose file in `dir` under one of its default names.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if none exists.
+fn find_compose_file(dir: &Path) -> Result<PathBuf> {
+    compose::DEFAULT_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            anyhow!(DevrsError::Config(format!(
+                "No compose file found in '{}' (looked for {}). Pass the file to import.",
+                dir.display(),
+                compose::DEFAULT_FILES.join(", ")
+            )))
+        })
+}
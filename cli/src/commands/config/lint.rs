@@ -0,0 +1,104 @@
+//! # DevRS Config Lint Handler
+//!
+//! File: cli/src/commands/config/lint.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config lint` subcommand, which checks the
+//! effective configuration for risky settings: the Docker socket or credentials mounted
+//! into a container, the root or home directory mounted as a whole, ports published on
+//! all interfaces, and literal secrets in config files.
+//!
+//! ## Architecture
+//!
+//! - The rules live in `core::lint`; `--rules` lists them with their ids and severities.
+//! - Findings suppressed by `[lint] allow` are not shown.
+//! - The command fails if an error-severity finding remains, or with `--strict` if any
+//!   finding remains, so it can gate CI. The same checks run before the core environment
+//!   container is created.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config lint
+//! devrs config lint --strict
+//! devrs config lint --rules
+//! ```
+//!
+use crate::core::{
+    config,                      // The configuration to check.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+    lint::{self, Severity},      // The rules.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Lint Arguments (`LintArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config lint` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Check the configuration for risky mounts, ports and secrets")]
+pub struct LintArgs {
+    /// Fail on warnings too, not only on errors.
+    #[arg(long)]
+    strict: bool,
+
+    /// List the rules instead of checking the configuration.
+    #[arg(long, conflicts_with = "strict")]
+    rules: bool,
+}
+
+/// # Handle Config Lint Command (`handle_lint`)
+///
+/// ## Workflow:
+/// 1.  With `--rules`: prints the rules and stops.
+/// 2.  Loads the effective configuration and runs the rules on it and its files.
+/// 3.  Prints each finding and a summary.
+/// 4.  Fails if errors (or, with `--strict`, any findings) remain.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `LintArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` if the configuration passes.
+/// * `Err`: If it does not, or cannot be loaded.
+pub async fn handle_lint(args: LintArgs) -> Result<()> {
+    info!("Handling config lint command...");
+    debug!("Lint args: {:?}", args);
+    if args.rules {
+        for rule in lint::RULES {
+            println!("{:<18} {:<8} {}", rule.id, rule.severity, rule.summary);
+        }
+        return Ok(());
+    }
+
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let findings = lint::lint_current(&cfg)?;
+    for finding in &findings {
+        println!("{}\n", finding);
+    }
+    let errors = findings
+        .iter()
+        .filter(|f| f.rule.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    if findings.is_empty() {
+        println!("✅ No problems found.");
+        return Ok(());
+    }
+    println!("{} error(s), {} warning(s).", errors, warnings);
+    if errors > 0 || args.strict {
+        return Err(anyhow!(DevrsError::Config(
+            "The configuration did not pass the lint checks.".to_string()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! # DevRS Config Migrate Handler
+//!
+//! File: cli/src/commands/config/migrate.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config migrate` subcommand, which rewrites a
+//! config file written for an older layout to the current one and sets its `version`.
+//! DevRS already migrates such files in memory on every load, warning at each outdated
+//! key; migrating the file on disk silences the warnings.
+//!
+//! ## Architecture
+//!
+//! - The migrations live in `core::migrate`. They edit the file with `toml_edit`, so
+//!   comments and formatting are kept, and moved keys take their comments along.
+//! - The file is the user config by default, the project's `.devrs.toml` with
+//!   `--project`, or any config file given as an argument (e.g. an included file).
+//! - The result is validated before it is written (`file::write_checked`).
+//! - `--dry-run` lists the changes without writing.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config migrate
+//! devrs config migrate --project --dry-run
+//! devrs config migrate ~/tools/devrs/presets/config.toml
+//! ```
+//!
+use super::file::{self, FileArgs}; // File selection and validated writes.
+use crate::core::{
+    diagnostics,                 // Rendering a bad `version`.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+    migrate,                     // The migrations.
+};
+use anyhow::anyhow; // For creating errors.
+use clap::Parser; // For parsing command-line arguments.
+use std::path::PathBuf; // The file to migrate.
+use toml::Table; // Reading the version.
+use toml_edit::DocumentMut; // Format-preserving rewrite.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Migrate Arguments (`MigrateArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config migrate` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Update a config file written for an older DevRS to the current layout")]
+pub struct MigrateArgs {
+    /// The config file to migrate (default: the file selected by --user/--project).
+    #[arg(conflicts_with_all = ["user", "project"])]
+    path: Option<PathBuf>,
+
+    /// The file to migrate (default: the user config file).
+    #[command(flatten)]
+    file: FileArgs,
+
+    /// List the changes without writing the file.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// # Handle Config Migrate Command (`handle_migrate`)
+///
+/// ## Workflow:
+/// 1.  Reads the selected file and its `version` (1 if unset).
+/// 2.  Applies the migrations from that version on and sets `version` to the current one.
+/// 3.  Lists the moved and removed keys.
+/// 4.  Unless `--dry-run`, validates and writes the result.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `MigrateArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once migrated, or if the file is already current.
+/// * `Err`: If the file does not exist or cannot be parsed, is for a newer DevRS, or
+///   the result is invalid or cannot be written.
+pub async fn handle_migrate(args: MigrateArgs) -> Result<()> {
+    info!("Handling config migrate command...");
+    debug!("Migrate args: {:?}", args);
+    let path = match &args.path {
+        Some(path) => path.clone(),
+        None => args.file.path()?,
+    };
+    if !path.is_file() {
+        return Err(anyhow!(DevrsError::FileSystem(format!(
+            "'{}' is not a file.",
+            path.display()
+        ))));
+    }
+    let content = file::read_or_empty(&path)?;
+    let invalid = |problem| {
+        anyhow!(DevrsError::Config(format!(
+            "Invalid configuration file:\n{}",
+            diagnostics::render(&path, &content, &[problem])
+        )))
+    };
+    let table: Table =
+        toml::from_str(&content).map_err(|e| invalid(diagnostics::from_toml_error(&e)))?;
+    let from = migrate::file_version(&table, &content).map_err(invalid)?;
+    let mut doc: DocumentMut = content.parse().map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Failed to parse '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+
+    if from == migrate::CURRENT_VERSION {
+        println!(
+            "✅ {} is already at config version {}.",
+            path.display(),
+            migrate::CURRENT_VERSION
+        );
+        return Ok(());
+    }
+    let changes = migrate::migrate_document(&mut doc, from);
+    migrate::stamp_version(&mut doc);
+    println!(
+        "Migrating {} from config version {} to {}:",
+        path.display(),
+        from,
+        migrate::CURRENT_VERSION
+    );
+    for change in &changes {
+        println!("  - {}", change);
+    }
+    println!(
+        "  - set `{} = {}`",
+        migrate::VERSION_KEY,
+        migrate::CURRENT_VERSION
+    );
+
+    if args.dry_run {
+        println!("Dry run; {} is unchanged.", path.display());
+        return Ok(());
+    }
+    file::write_checked(&path, &doc.to_string())?;
+    println!("✅ Migrated {}", path.display());
+    Ok(())
+}
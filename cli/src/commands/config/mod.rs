@@ -0,0 +1,263 @@
+//! # DevRS Config Command Group
+//!
+//! File: cli/src/commands/config/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module serves as the entry point and router for the `devrs config`
+//! command group, which works with the DevRS configuration itself rather than
+//! with containers: inspecting what was resolved and where it came from, editing
+//! the config files, and translating them to and from the formats other tools use.
+//!
+//! ## Architecture
+//!
+//! The module uses Clap's derive macros to define the command structure:
+//! - `ConfigArgs`: Top-level arguments struct for the `devrs config` group.
+//! - `ConfigCommand`: Enum defining the available subcommands (`show`, `get`, `set`,
+//!   `edit`, `validate`, `migrate`, `lint`, `schema`, `team`, `export`, `import`).
+//! - `file`: Helpers shared by `get`, `set`, `edit` and `migrate` (file selection,
+//!   dotted keys, validated writes).
+//! - `handle_config`: The main handler function that routes to the subcommand handlers.
+//!
+//! ## Examples
+//!
+//! ```bash
+//! # Show the effective configuration and where each value comes from
+//! devrs config show
+//!
+//! # Read and write single settings (user config by default)
+//! devrs config get core_env.image_tag
+//! devrs config set core_env.image_tag v3 --project
+//!
+//! # Edit the user config in $EDITOR, validated on save
+//! devrs config edit
+//!
+//! # Check a file before committing it
+//! devrs config validate .devrs.toml
+//!
+//! # Update a config file written for an older DevRS
+//! devrs config migrate --dry-run
+//!
+//! # Check for risky mounts, ports and secrets
+//! devrs config lint
+//!
+//! # Write the JSON Schema for editor completion (taplo, Even Better TOML)
+//! devrs config schema -o devrs.schema.json
+//!
+//! # Load the team's shared base config beneath the user config, and update it
+//! devrs config team add git@github.com:acme/devrs-team.git
+//! devrs config team sync
+//!
+//! # Print the effective core environment as devcontainer.json
+//! devrs config export --devcontainer
+//!
+//! # Write it into the project
+//! devrs config export --devcontainer -o .devcontainer/devcontainer.json
+//!
+//! # Share the core environment and project apps as a Compose file
+//! devrs config export --compose -o docker-compose.yml
+//!
+//! # Seed .devrs.toml from an existing Compose file
+//! devrs config import --compose
+//! ```
+//!
+use crate::core::error::Result; // Use the standard Result type for error handling.
+use clap::{Parser, Subcommand}; // Import necessary components from the Clap crate for argument parsing.
+
+// --- Subcommand Module Declarations ---
+
+/// Implements the `devrs config edit` command (`$EDITOR` with validation).
+mod edit;
+/// Implements the `devrs config export` command (devcontainer.json and Compose output).
+mod export;
+/// Shared `--user`/`--project` selection and dotted-key access for config files.
+mod file;
+/// Implements the `devrs config get` command (one setting by dotted key).
+mod get;
+/// Implements the `devrs config import` command (Compose input).
+mod import;
+/// Implements the `devrs config lint` command (security checks).
+mod lint;
+/// Implements the `devrs config migrate` command (updating older config files).
+mod migrate;
+/// Implements the `devrs config schema` command (JSON Schema for editors).
+mod schema;
+/// Implements the `devrs config set` command (format-preserving writes).
+mod set;
+/// Implements the `devrs config show` command (effective configuration with sources).
+mod show;
+/// Implements the `devrs config team` commands (shared team configs).
+mod team;
+/// Implements the `devrs config validate` command (checking files and layers).
+mod validate;
+
+/// # Config Command Group Arguments (`ConfigArgs`)
+///
+/// This struct represents the top-level command group `devrs config`.
+/// Its purpose is to capture which specific subcommand the user intends to execute.
+#[derive(Parser, Debug)]
+#[command(about = "Inspect, edit and convert the DevRS configuration")]
+pub struct ConfigArgs {
+    /// The specific config subcommand to execute.
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+/// # Config Subcommands (`ConfigCommand`)
+///
+/// This enum enumerates all valid subcommands available under `devrs config`.
+/// Each variant holds the arguments struct defined within that subcommand's module.
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Corresponds to `devrs config show`. Prints the effective configuration with sources.
+    /// Holds `show::ShowArgs` for `--json`.
+    Show(show::ShowArgs),
+    /// Corresponds to `devrs config get`. Prints one setting.
+    /// Holds `get::GetArgs` for the key and file selection.
+    Get(get::GetArgs),
+    /// Corresponds to `devrs config set`. Writes one setting into a config file.
+    /// Holds `set::SetArgs` for the key, value and file selection.
+    Set(set::SetArgs),
+    /// Corresponds to `devrs config edit`. Opens a config file in the editor.
+    /// Holds `edit::EditArgs` for file selection.
+    Edit(edit::EditArgs),
+    /// Corresponds to `devrs config validate`. Checks a file or the loaded layers.
+    /// Holds `validate::ValidateArgs` for the optional file.
+    Validate(validate::ValidateArgs),
+    /// Corresponds to `devrs config migrate`. Updates a config file to the current layout.
+    /// Holds `migrate::MigrateArgs` for the file and `--dry-run`.
+    Migrate(migrate::MigrateArgs),
+    /// Corresponds to `devrs config lint`. Checks the configuration for risky settings.
+    /// Holds `lint::LintArgs` for `--strict` and `--rules`.
+    Lint(lint::LintArgs),
+    /// Corresponds to `devrs config schema`. Prints the JSON Schema of the config files.
+    /// Holds `schema::SchemaArgs` for `--srv` and `--output`.
+    Schema(schema::SchemaArgs),
+    /// Corresponds to `devrs config team`. Manages shared team configs.
+    /// Holds `team::TeamArgs` for the `add`, `sync`, `ls` and `rm` subcommands.
+    Team(team::TeamArgs),
+    /// Corresponds to `devrs config export`. Renders the effective configuration in another format.
+    /// Holds `export::ExportArgs` for the format flag and `--output`.
+    Export(export::ExportArgs),
+    /// Corresponds to `devrs config import`. Seeds `.devrs.toml` from another format.
+    /// Holds `import::ImportArgs` for the format flag, input file and `--output`.
+    Import(import::ImportArgs),
+}
+
+/// # Handle Config Command (`handle_config`)
+///
+/// The main entry point function for the `devrs config` command group.
+/// It acts as a **dispatcher**, matching on the subcommand and calling the
+/// corresponding handler.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ConfigArgs` struct containing the specific `ConfigCommand` variant.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Propagates the `Result` from the called subcommand handler.
+pub async fn handle_config(args: ConfigArgs) -> Result<()> {
+    match args.command {
+        ConfigCommand::Show(args) => show::handle_show(args).await?, // Call show handler.
+        ConfigCommand::Get(args) => get::handle_get(args).await?,    // Call get handler.
+        ConfigCommand::Set(args) => set::handle_set(args).await?,    // Call set handler.
+        ConfigCommand::Edit(args) => edit::handle_edit(args).await?, // Call edit handler.
+        ConfigCommand::Validate(args) => validate::handle_validate(args).await?, // Call validate handler.
+        ConfigCommand::Migrate(args) => migrate::handle_migrate(args).await?, // Call migrate handler.
+        ConfigCommand::Lint(args) => lint::handle_lint(args).await?,          // Call lint handler.
+        ConfigCommand::Schema(args) => schema::handle_schema(args).await?, // Call schema handler.
+        ConfigCommand::Team(args) => team::handle_team(args).await?,       // Call team handler.
+        ConfigCommand::Export(args) => export::handle_export(args).await?, // Call export handler.
+        ConfigCommand::Import(args) => import::handle_import(args).await?, // Call import handler.
+    }
+    Ok(())
+}
+
+// --- Unit Tests ---
+// Verify that clap routes the subcommands correctly.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing of `export`, which requires a format flag.
+    #[test]
+    fn test_parses_config_export() {
+        let result = ConfigArgs::try_parse_from(["config", "export", "--devcontainer"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Export(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "export"]).is_err());
+        assert!(
+            ConfigArgs::try_parse_from(["config", "export", "--devcontainer", "--compose"])
+                .is_err()
+        );
+    }
+
+    /// Test parsing of the inspection and editing subcommands.
+    #[test]
+    fn test_parses_config_show_get_set() {
+        let result = ConfigArgs::try_parse_from(["config", "show", "--json"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Show(_)));
+        let result = ConfigArgs::try_parse_from(["config", "get", "core_env.image_tag"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Get(_)));
+        let result =
+            ConfigArgs::try_parse_from(["config", "set", "logs.collect", "true", "--project"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Set(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "set", "logs.collect"]).is_err());
+        assert!(ConfigArgs::try_parse_from(["config", "edit", "--user", "--project"]).is_err());
+        let result = ConfigArgs::try_parse_from(["config", "validate", ".devrs.toml"]);
+        assert!(matches!(
+            result.unwrap().command,
+            ConfigCommand::Validate(_)
+        ));
+        let result = ConfigArgs::try_parse_from(["config", "schema", "--srv", "-o", "s.json"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Schema(_)));
+        let result = ConfigArgs::try_parse_from(["config", "migrate", "--project", "--dry-run"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Migrate(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "migrate", "a.toml", "--user"]).is_err());
+        let result = ConfigArgs::try_parse_from(["config", "lint", "--strict"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Lint(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "lint", "--strict", "--rules"]).is_err());
+    }
+
+    /// Test parsing of the `team` subcommands.
+    #[test]
+    fn test_parses_config_team() {
+        let result = ConfigArgs::try_parse_from([
+            "config",
+            "team",
+            "add",
+            "git@github.com:acme/devrs-team.git",
+            "--name",
+            "acme",
+        ]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Team(_)));
+        let result = ConfigArgs::try_parse_from(["config", "team", "sync", "acme", "infra"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Team(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "team", "add"]).is_err());
+        assert!(ConfigArgs::try_parse_from(["config", "team", "rm"]).is_err());
+    }
+
+    /// Test parsing of `import`, with and without an explicit file.
+    #[test]
+    fn test_parses_config_import() {
+        let result = ConfigArgs::try_parse_from(["config", "import", "--compose"]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Import(_)));
+        let result = ConfigArgs::try_parse_from([
+            "config",
+            "import",
+            "--compose",
+            "dev.yml",
+            "--core-service",
+            "workspace",
+            "-o",
+            "-",
+        ]);
+        assert!(matches!(result.unwrap().command, ConfigCommand::Import(_)));
+        assert!(ConfigArgs::try_parse_from(["config", "import", "dev.yml"]).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! # DevRS Config Schema Handler
+//!
+//! File: cli/src/commands/config/schema.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config schema` subcommand, which prints the
+//! JSON Schema of the DevRS config files for editors. With the schema, taplo and the
+//! Even Better TOML extension complete keys, show their documentation and flag
+//! unknown keys and wrong types while editing.
+//!
+//! ## Architecture
+//!
+//! - The schemas are generated from the config types: `config.toml` and `.devrs.toml` share one
+//!   (`core::schema`); `--srv` selects the one for `.devrs-srv.toml` (`srv::config`).
+//! - The schema is printed to stdout, or written to `--output`.
+//! - Point the editor at it with a `#:schema` comment as the first line of the file, or
+//!   with a `[[rule]]` in `.taplo.toml`.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config schema -o devrs.schema.json
+//! devrs config schema --srv -o devrs-srv.schema.json
+//! # Then, as the first line of .devrs.toml (the path is relative to the file):
+//! #:schema ./devrs.schema.json
+//! ```
+//!
+use crate::commands::srv; // The `.devrs-srv.toml` schema.
+use crate::core::{
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+    schema,                      // The config file schema.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use std::{fs, path::PathBuf}; // Writing the schema.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Schema Arguments (`SchemaArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config schema` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Print the JSON Schema of the config files, for editor completion")]
+pub struct SchemaArgs {
+    /// Print the schema of `.devrs-srv.toml` (`devrs srv`) instead of `config.toml`/`.devrs.toml`.
+    #[arg(long)]
+    srv: bool,
+
+    /// Write the schema to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// # Handle Config Schema Command (`handle_schema`)
+///
+/// ## Workflow:
+/// 1.  Builds the selected schema.
+/// 2.  Prints it as JSON, or writes it to `--output`.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `SchemaArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once printed or written.
+/// * `Err`: If the output file cannot be written.
+pub async fn handle_schema(args: SchemaArgs) -> Result<()> {
+    info!("Handling config schema command...");
+    debug!("Schema args: {:?}", args);
+    let document = if args.srv {
+        srv::config::srv_schema()
+    } else {
+        schema::config_schema()
+    };
+    let rendered = serde_json::to_string_pretty(&document).context("Failed to render JSON")?;
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, rendered + "\n").map_err(|e| {
+                anyhow!(DevrsError::FileSystem(format!(
+                    "Failed to write '{}': {}",
+                    path.display(),
+                    e
+                )))
+            })?;
+            println!("✅ Wrote {}", path.display());
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! # DevRS Config Set Handler
+//!
+//! File: cli/src/commands/config/set.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config set` subcommand, which writes a single
+//! setting into the user config file (default) or the project's `.devrs.toml`
+//! (`--project`), without disturbing the rest of the file.
+//!
+//! ## Architecture
+//!
+//! - The file is edited with `toml_edit`, so comments, ordering and formatting are
+//!   preserved; a replaced value keeps its trailing comment. Missing files and tables
+//!   are created.
+//! - The value is parsed like `--set` and `DEVRS_*` overrides (`core::layers::parse_value`):
+//!   booleans, integers, arrays and inline tables are TOML, anything else is a string.
+//!   A boolean or integer is kept as written if the config schema (`core::schema`) only
+//!   allows a string there, e.g. for `core_env.env_vars.*`.
+//! - The edited file is validated before it is written; an invalid result leaves the file untouched.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config set core_env.image_tag v3
+//! devrs config set core_env.ports '["3000:3000"]' --project
+//! devrs config set 'merge."core_env.mounts"' replace
+//! ```
+//!
+use super::file::{self, FileArgs}; // File selection and key editing.
+use crate::core::{
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+    layers,                      // Parsing values like `--set`.
+    schema,                      // The type a key expects.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use toml_edit::DocumentMut; // Format-preserving editing.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Set Arguments (`SetArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config set` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Set one setting in the user or project config file")]
+pub struct SetArgs {
+    /// The dotted key, e.g. `core_env.image_tag`.
+    key: String,
+
+    /// The value. Booleans, integers, arrays and inline tables are read as TOML,
+    /// anything else as a string (quote it, e.g. '"2"', to force a string). Keys that
+    /// only take a string, such as `core_env.env_vars.*`, take the value as written.
+    value: String,
+
+    /// The file to write (default: the user config file).
+    #[command(flatten)]
+    file: FileArgs,
+}
+
+/// # Handle Config Set Command (`handle_set`)
+///
+/// ## Workflow:
+/// 1.  Parses the key and value.
+/// 2.  Reads the selected file (empty if it does not exist yet).
+/// 3.  Sets the key, keeping the rest of the file as it is.
+/// 4.  Validates the result and writes it.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `SetArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once the file is written.
+/// * `Err`: If the key is malformed, the file cannot be parsed or written, or the
+///   new setting makes the configuration invalid.
+pub async fn handle_set(args: SetArgs) -> Result<()> {
+    info!("Handling config set command...");
+    debug!("Set args: {:?}", args);
+    let keys = file::parse_key(&args.key)?;
+    let path = args.file.path()?;
+
+    let content = file::read_or_empty(&path)?;
+    let mut doc: DocumentMut = content
+        .parse()
+        .with_context(|| format!("Failed to parse TOML from file: {}", path.display()))?;
+    let key_parts: Vec<&str> = keys.iter().map(|k| k.get()).collect();
+    let config_schema = schema::config_schema();
+    let expected = schema::value_schema(&config_schema, &key_parts)
+        .map(schema::value_types)
+        .unwrap_or_default();
+    let value = edit_value(&args.value, &expected)?;
+    file::set_key(&mut doc, &keys, value)?;
+    file::write_checked(&path, &doc.to_string())
+        .with_context(|| format!("Not setting '{}'", args.key))?;
+
+    println!("✅ Set {} in {}", args.key, path.display());
+    Ok(())
+}
+
+/// Parses a value given on the command line into an editable TOML value.
+///
+/// # Arguments
+///
+/// * `raw` - The value as given.
+/// * `expected` - The JSON Schema types the key accepts (`schema::value_types`); empty if unknown.
+fn edit_value(raw: &str, expected: &[&str]) -> Result<toml_edit::Value> {
+    let mut value = layers::parse_value(raw);
+    let scalar = matches!(value, toml::Value::Boolean(_) | toml::Value::Integer(_));
+    if scalar && expected.contains(&"string") && !expected.contains(&schema::json_type(&value)) {
+        value = toml::Value::String(raw.to_string());
+    }
+    value.to_string().parse().map_err(|e| {
+        anyhow!(DevrsError::ArgumentParsing(format!(
+            "Invalid value '{}': {}",
+            raw, e
+        )))
+    })
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that values are read the same way as `--set` overrides.
+    #[test]
+    fn test_edit_value() {
+        assert_eq!(edit_value("v3", &[]).unwrap().as_str(), Some("v3"));
+        assert_eq!(edit_value("1.0", &[]).unwrap().as_str(), Some("1.0"));
+        assert_eq!(edit_value("\"2\"", &[]).unwrap().as_str(), Some("2"));
+        assert_eq!(edit_value("true", &[]).unwrap().as_bool(), Some(true));
+        assert_eq!(edit_value("5", &[]).unwrap().as_integer(), Some(5));
+        assert_eq!(
+            edit_value("[\"3000:3000\"]", &[])
+                .unwrap()
+                .as_array()
+                .map(|a| a.len()),
+            Some(1)
+        );
+        assert!(edit_value("{ A = \"1\" }", &[]).unwrap().is_inline_table());
+    }
+
+    /// Test that a bare number or boolean stays a string where only a string is allowed.
+    #[test]
+    fn test_edit_value_string_field() {
+        let expected = ["string", "object"];
+        assert_eq!(edit_value("2", &expected).unwrap().as_str(), Some("2"));
+        assert_eq!(
+            edit_value("true", &expected).unwrap().as_str(),
+            Some("true")
+        );
+        assert!(edit_value("{ secret = \"file:~/t\" }", &expected)
+            .unwrap()
+            .is_inline_table());
+        let either = ["string", "integer"];
+        assert_eq!(edit_value("8", &either).unwrap().as_integer(), Some(8));
+    }
+}
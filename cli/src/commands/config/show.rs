@@ -0,0 +1,187 @@
+//! # DevRS Config Show Handler
+//!
+//! File: cli/src/commands/config/show.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config show` subcommand, which prints the
+//! effective configuration (what `config::load_config` resolved from every layer)
+//! together with where each value came from.
+//!
+//! ## Architecture
+//!
+//! - The configuration and its layers are loaded with `config::load_config_with_layers`;
+//!   `core::layers::provenance` attributes each field to the layers that set it. Fields
+//!   no layer sets are shown as `default`.
+//! - TOML output (the default) annotates each value, and each `[[table]]` entry, with a
+//!   trailing `# <source>` comment. Combined lists (see `[merge]`) name every contributing layer.
+//! - `--json` prints `{ "config": ..., "sources": { "<dotted.key>": ["<source>", ...] } }`.
+//! - Keys are sorted, so the output is stable across runs.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config show
+//! devrs config show --json | jq '.config.core_env.mounts'
+//! ```
+//!
+use crate::core::{
+    config,                      // Effective configuration and its layers.
+    error::Result,               // Standard Result type.
+    layers::{self, LayerSource}, // Provenance of each field.
+};
+use anyhow::Context; // For adding context to errors.
+use clap::Parser; // For parsing command-line arguments.
+use std::collections::BTreeMap; // Sources per field.
+use toml::Table; // The configuration as a table.
+use toml_edit::{DocumentMut, Item}; // Annotating the rendered TOML.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Show Arguments (`ShowArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config show` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Print the effective configuration and where each value comes from")]
+pub struct ShowArgs {
+    /// Print JSON (`config` and `sources`) instead of annotated TOML.
+    #[arg(long)]
+    json: bool,
+}
+
+/// # Handle Config Show Command (`handle_show`)
+///
+/// ## Workflow:
+/// 1.  Loads the effective configuration and the layers it was merged from.
+/// 2.  Works out the source layers of every field.
+/// 3.  Prints the configuration as annotated TOML, or as JSON with a `sources` map.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ShowArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once printed.
+/// * `Err`: If the configuration cannot be loaded or rendered.
+pub async fn handle_show(args: ShowArgs) -> Result<()> {
+    info!("Handling config show command...");
+    debug!("Show args: {:?}", args);
+    let (cfg, layers) =
+        config::load_config_with_layers().context("Failed to load DevRS configuration")?;
+    let table = Table::try_from(&cfg).context("Failed to convert the configuration")?;
+    let sources = layers::provenance(&layers);
+
+    if args.json {
+        let sources: BTreeMap<&String, Vec<String>> = sources
+            .iter()
+            .map(|(field, from)| (field, from.iter().map(ToString::to_string).collect()))
+            .collect();
+        let document = serde_json::json!({ "config": table, "sources": sources });
+        let rendered = serde_json::to_string_pretty(&document).context("Failed to render JSON")?;
+        println!("{}", rendered);
+    } else {
+        print!("{}", annotated_toml(&table, &sources)?);
+    }
+    Ok(())
+}
+
+/// Renders the configuration as TOML with a `# <source>` comment after each value.
+///
+/// # Arguments
+///
+/// * `table` - The effective configuration.
+/// * `sources` - The field sources from `layers::provenance`.
+///
+/// # Returns
+///
+/// * `Result<String>` - The annotated document.
+fn annotated_toml(table: &Table, sources: &BTreeMap<String, Vec<LayerSource>>) -> Result<String> {
+    let rendered = toml::to_string(table).context("Failed to render the configuration")?;
+    let mut doc: DocumentMut = rendered
+        .parse()
+        .context("Failed to re-parse the rendered configuration")?;
+    annotate(doc.as_table_mut(), "", sources);
+    Ok(doc.to_string())
+}
+
+/// Adds the source comments to the values of one table, recursing into sub-tables.
+fn annotate(
+    table: &mut toml_edit::Table,
+    prefix: &str,
+    sources: &BTreeMap<String, Vec<LayerSource>>,
+) {
+    for (key, item) in table.iter_mut() {
+        let path = if prefix.is_empty() {
+            key.get().to_string()
+        } else {
+            format!("{}.{}", prefix, key.get())
+        };
+        let comment = format!("  # {}", describe(layers::source_of(sources, &path)));
+        match item {
+            Item::Value(value) => value.decor_mut().set_suffix(comment),
+            Item::Table(inner) => annotate(inner, &path, sources),
+            // Lists of tables (mounts, hooks) come from their layers as a whole.
+            Item::ArrayOfTables(entries) => {
+                for entry in entries.iter_mut() {
+                    entry.decor_mut().set_suffix(comment.clone());
+                }
+            }
+            Item::None => {}
+        }
+    }
+}
+
+/// Describes the sources of one field.
+fn describe(sources: Option<&[LayerSource]>) -> String {
+    match sources {
+        Some(sources) if !sources.is_empty() => sources
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" + "),
+        _ => "default".to_string(),
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::layers::Layer;
+
+    /// Test that values are annotated with their layer, or as defaults.
+    #[test]
+    fn test_annotated_toml() {
+        let layer = |source: LayerSource, text: &str| Layer {
+            source,
+            table: toml::from_str(text).unwrap(),
+        };
+        let user = LayerSource::User("/u/config.toml".into());
+        let project = LayerSource::Project("/p/.devrs.toml".into());
+        let layers = [
+            layer(
+                user,
+                "[core_env]\nimage_tag = \"v2\"\nmounts = [{ host = \"/a\", container = \"/a\" }]\n",
+            ),
+            layer(
+                project,
+                "[core_env]\nmounts = [{ host = \"/b\", container = \"/b\" }]\n",
+            ),
+        ];
+        let table = layers::merge_layers(&layers);
+        let table: Table =
+            toml::from_str(&(toml::to_string(&table).unwrap() + "[logs]\ncollect = false\n"))
+                .unwrap();
+        let rendered = annotated_toml(&table, &layers::provenance(&layers)).unwrap();
+        assert!(rendered.contains("image_tag = \"v2\"  # user config (/u/config.toml)\n"));
+        assert!(rendered.contains(
+            "[[core_env.mounts]]  # user config (/u/config.toml) + project config (/p/.devrs.toml)\n"
+        ));
+        assert!(rendered.contains("collect = false  # default\n"));
+    }
+}
@@ -0,0 +1,367 @@
+//! # DevRS Config Team Handler
+//!
+//! File: cli/src/commands/config/team.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config team` subcommands, which share a baseline
+//! configuration across a team. The team keeps a config file (mounts, ports, apps, env
+//! vars) in a git repository; every developer adds it once and syncs it when it changes.
+//! It is loaded beneath their own `config.toml`, so personal and project settings
+//! override it.
+//!
+//! ## Architecture
+//!
+//! - `add`: clones the repository into the DevRS data directory (or registers a local
+//!   directory in place), checks its config file and registers it (`core::team`).
+//! - `sync`: pulls the clones (`git pull --ff-only`) and checks their config files again.
+//! - `ls` / `rm`: list and unregister teams; `rm` deletes clones made by `add`.
+//! - `git` runs with the terminal attached, so credential prompts work as usual.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config team add git@github.com:acme/devrs-team.git
+//! devrs config team add https://github.com/acme/platform.git --name acme --file devrs/config.toml
+//! devrs config team sync
+//! devrs config team ls
+//! devrs config team rm acme
+//! ```
+//!
+use crate::core::{
+    config,                       // Checking team config files.
+    error::{DevrsError, Result},  // Standard Result type and custom errors.
+    team::{self, Registry, Team}, // The registry.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::{Parser, Subcommand}; // For parsing command-line arguments.
+use std::{fs, path::Path, process::Command}; // Checkouts and running git.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+/// # Config Team Arguments (`TeamArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config team` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Share a base configuration across a team via a git repository")]
+pub struct TeamArgs {
+    /// The specific team subcommand to execute.
+    #[command(subcommand)]
+    command: TeamCommand,
+}
+
+/// # Team Subcommands (`TeamCommand`)
+#[derive(Subcommand, Debug)]
+enum TeamCommand {
+    /// Corresponds to `devrs config team add`. Clones and registers a team config.
+    Add {
+        /// A git URL to clone, or a local directory (or config file) to use in place.
+        source: String,
+        /// The name of the team. Defaults to the repository or directory name.
+        #[arg(long)]
+        name: Option<String>,
+        /// The config file within the repository.
+        #[arg(long, default_value = team::DEFAULT_TEAM_FILE)]
+        file: String,
+    },
+    /// Corresponds to `devrs config team sync`. Pulls the latest team configs.
+    Sync {
+        /// Teams to sync. Defaults to all.
+        names: Vec<String>,
+    },
+    /// Corresponds to `devrs config team ls`. Lists the registered teams.
+    Ls,
+    /// Corresponds to `devrs config team rm`. Unregisters a team.
+    Rm {
+        /// The team to remove.
+        name: String,
+    },
+}
+
+/// # Handle Config Team Command (`handle_team`)
+///
+/// Loads the team registry and dispatches to the subcommand handlers.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `TeamArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Propagates the `Result` from the called subcommand handler.
+pub async fn handle_team(args: TeamArgs) -> Result<()> {
+    info!("Handling config team command...");
+    debug!("Team args: {:?}", args);
+    let registry_path = team::registry_path();
+    let mut registry = Registry::load(&registry_path)?;
+    match args.command {
+        TeamCommand::Add { source, name, file } => {
+            let added = handle_add(&mut registry, &source, name, file)?;
+            registry.save(&registry_path)?;
+            println!(
+                "✅ Added team '{}'. {} is now loaded beneath your user config.",
+                added.name,
+                added.config_path().display()
+            );
+        }
+        TeamCommand::Sync { names } => handle_sync(&registry, &names)?,
+        TeamCommand::Ls => handle_ls(&registry),
+        TeamCommand::Rm { name } => {
+            handle_rm(&mut registry, &name)?;
+            registry.save(&registry_path)?;
+            println!("✅ Removed team '{}'.", name);
+        }
+    }
+    Ok(())
+}
+
+/// # Handle Add (`handle_add`)
+///
+/// ## Workflow:
+/// 1.  Determines the team name and checks it is valid and not taken.
+/// 2.  Registers an existing local directory (or file) in place; clones anything else
+///     into the data directory.
+/// 3.  Checks the team's config file; a clone is deleted again if the check fails.
+/// 4.  Adds the team to the registry (saved by the caller).
+///
+/// ## Returns
+///
+/// * `Result<Team>`: The registered team.
+/// * `Err`: If the name is invalid or taken, `git clone` fails, or the config file is
+///   missing or invalid.
+fn handle_add(
+    registry: &mut Registry,
+    source: &str,
+    name: Option<String>,
+    file: String,
+) -> Result<Team> {
+    let local = Path::new(source);
+    // A config file given directly is named after its directory.
+    let named_after = match local.parent() {
+        Some(parent) if local.is_file() => fs::canonicalize(parent)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| source.to_string()),
+        _ => source.to_string(),
+    };
+    let name = match name.or_else(|| team::name_from_source(&named_after)) {
+        Some(name) if team::is_valid_name(&name) => name,
+        Some(name) => {
+            return Err(anyhow!(DevrsError::ArgumentParsing(format!(
+                "Invalid team name '{}': use letters, digits, '.', '_' and '-'.",
+                name
+            ))))
+        }
+        None => {
+            return Err(anyhow!(DevrsError::ArgumentParsing(format!(
+                "Cannot derive a team name from '{}'; pass --name.",
+                source
+            ))))
+        }
+    };
+    if registry.get(&name).is_some() {
+        return Err(anyhow!(DevrsError::Config(format!(
+            "A team named '{}' is already registered (see `devrs config team ls`).",
+            name
+        ))));
+    }
+
+    let added = if local.exists() {
+        let local = fs::canonicalize(local).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to resolve '{}': {}",
+                source, e
+            )))
+        })?;
+        let (dir, file) = if local.is_file() {
+            let file_name = local.file_name().map(|f| f.to_string_lossy().into_owned());
+            (local.parent().map(Path::to_path_buf), file_name)
+        } else {
+            (Some(local.clone()), Some(file))
+        };
+        let (Some(dir), Some(file)) = (dir, file) else {
+            return Err(anyhow!(DevrsError::FileSystem(format!(
+                "'{}' is not a config file or directory.",
+                local.display()
+            ))));
+        };
+        Team {
+            name,
+            source: local.display().to_string(),
+            dir,
+            file,
+            cloned: false,
+        }
+    } else {
+        let dir = team::checkouts_dir().join(&name);
+        if dir.exists() {
+            return Err(anyhow!(DevrsError::FileSystem(format!(
+                "'{}' already exists; remove it or choose another --name.",
+                dir.display()
+            ))));
+        }
+        println!("Cloning {} ...", source);
+        git(&["clone".as_ref(), source.as_ref(), dir.as_os_str()])?;
+        Team {
+            name,
+            source: source.to_string(),
+            dir,
+            file,
+            cloned: true,
+        }
+    };
+
+    if let Err(e) = check_team_config(&added) {
+        if added.cloned {
+            if let Err(rm) = fs::remove_dir_all(&added.dir) {
+                warn!("Failed to remove {}: {}", added.dir.display(), rm);
+            }
+        }
+        return Err(e);
+    }
+    registry.teams.push(added.clone());
+    Ok(added)
+}
+
+/// # Handle Sync (`handle_sync`)
+///
+/// ## Workflow:
+/// 1.  Selects the named teams (all if none are named).
+/// 2.  Pulls each clone with `git pull --ff-only`; local directories are left as they are.
+/// 3.  Checks each team's config file, so a broken update is reported right away.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once every selected team is synced and valid.
+/// * `Err`: If a name is unknown, `git pull` fails, or a config file is invalid.
+fn handle_sync(registry: &Registry, names: &[String]) -> Result<()> {
+    if let Some(unknown) = names.iter().find(|name| registry.get(name).is_none()) {
+        return Err(anyhow!(DevrsError::Config(format!(
+            "No team named '{}' is registered (see `devrs config team ls`).",
+            unknown
+        ))));
+    }
+    let selected: Vec<&Team> = registry
+        .teams
+        .iter()
+        .filter(|t| names.is_empty() || names.contains(&t.name))
+        .collect();
+    if selected.is_empty() {
+        println!("No teams registered. Add one with `devrs config team add <git-url>`.");
+        return Ok(());
+    }
+    for registered in selected {
+        if registered.cloned {
+            println!("Syncing team '{}' ...", registered.name);
+            git(&[
+                "-C".as_ref(),
+                registered.dir.as_os_str(),
+                "pull".as_ref(),
+                "--ff-only".as_ref(),
+            ])
+            .with_context(|| format!("Failed to sync team '{}'", registered.name))?;
+        } else {
+            println!(
+                "Team '{}' uses {} in place; nothing to pull.",
+                registered.name,
+                registered.dir.display()
+            );
+        }
+        check_team_config(registered)?;
+        println!("✅ Team '{}' is up to date.", registered.name);
+    }
+    Ok(())
+}
+
+/// # Handle List (`handle_ls`)
+///
+/// Prints each registered team with its source and config file, in the order they are
+/// layered (later teams override earlier ones).
+fn handle_ls(registry: &Registry) {
+    if registry.teams.is_empty() {
+        println!("No teams registered. Add one with `devrs config team add <git-url>`.");
+        return;
+    }
+    for registered in &registry.teams {
+        let path = registered.config_path();
+        let missing = if path.is_file() { "" } else { " (missing)" };
+        println!("{}", registered.name);
+        println!("  source: {}", registered.source);
+        println!("  config: {}{}", path.display(), missing);
+    }
+}
+
+/// # Handle Remove (`handle_rm`)
+///
+/// Unregisters a team and deletes its clone. Local directories registered in place
+/// are kept.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once removed from the registry (saved by the caller).
+/// * `Err`: If no team has that name, or its clone cannot be deleted.
+fn handle_rm(registry: &mut Registry, name: &str) -> Result<()> {
+    let Some(index) = registry.teams.iter().position(|t| t.name == name) else {
+        return Err(anyhow!(DevrsError::Config(format!(
+            "No team named '{}' is registered (see `devrs config team ls`).",
+            name
+        ))));
+    };
+    let removed = registry.teams.remove(index);
+    if removed.cloned && removed.dir.exists() {
+        fs::remove_dir_all(&removed.dir).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to delete '{}': {}",
+                removed.dir.display(),
+                e
+            )))
+        })?;
+    }
+    Ok(())
+}
+
+/// Checks that a team's config file exists and is a valid configuration.
+fn check_team_config(registered: &Team) -> Result<()> {
+    let path = registered.config_path();
+    let content = fs::read_to_string(&path).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Team '{}' has no readable config file at {}: {} (choose it with --file).",
+            registered.name,
+            path.display(),
+            e
+        )))
+    })?;
+    config::check_config_str(&content, &path)
+        .with_context(|| format!("Invalid config in team '{}'", registered.name))?;
+    Ok(())
+}
+
+/// Runs `git` with the terminal attached.
+fn git(args: &[&std::ffi::OsStr]) -> Result<()> {
+    let command_line = format!(
+        "git {}",
+        args.iter()
+            .map(|a| a.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    debug!("Running {}", command_line);
+    let status = Command::new("git").args(args).status().map_err(|e| {
+        anyhow!(DevrsError::ExternalCommand {
+            cmd: command_line.clone(),
+            status: "failed to start".to_string(),
+            output: e.to_string(),
+        })
+    })?;
+    if !status.success() {
+        return Err(anyhow!(DevrsError::ExternalCommand {
+            cmd: command_line,
+            status: status.to_string(),
+            output: String::new(),
+        }));
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! # DevRS Config Validate Handler
+//!
+//! File: cli/src/commands/config/validate.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs config validate` subcommand, which checks a
+//! config file (e.g. before committing a `.devrs.toml`) or, without a file, every
+//! layer of the current configuration.
+//!
+//! ## Architecture
+//!
+//! - `FILE`: The file must parse as a DevRS configuration (no unknown keys, correct
+//!   types) and pass the same checks as the merged configuration
+//!   (`config::check_config_str`). A file named `.devrs-srv.toml` is checked as a
+//!   `devrs srv` configuration instead.
+//! - Problems in a file are shown with their line and column, and unknown keys with the
+//!   closest known key (see `core::diagnostics`).
+//! - No file: Loads the configuration as every command does, which checks each file
+//!   layer and the merged result, and lists the layers that were checked.
+//! - Problems are reported as an error (non-zero exit status).
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs config validate
+//! devrs config validate .devrs.toml
+//! ```
+//!
+use super::file; // Reading the file.
+use crate::commands::srv; // Checking `.devrs-srv.toml` files.
+use crate::core::{
+    config,                      // Checking files and loading layers.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use std::{ffi::OsStr, path::PathBuf}; // The file to check.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Config Validate Arguments (`ValidateArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs config validate` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "Check a config file, or every layer of the current configuration")]
+pub struct ValidateArgs {
+    /// The config file to check. Without it, checks the configuration DevRS would load here.
+    file: Option<PathBuf>,
+}
+
+/// # Handle Config Validate Command (`handle_validate`)
+///
+/// ## Workflow:
+/// 1.  With a file: reads and checks it.
+/// 2.  Without: loads the configuration from all layers and lists them.
+/// 3.  Reports success; any problem is returned as an error.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ValidateArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` if the configuration is valid.
+/// * `Err`: Describing the first problem found.
+pub async fn handle_validate(args: ValidateArgs) -> Result<()> {
+    info!("Handling config validate command...");
+    debug!("Validate args: {:?}", args);
+    match &args.file {
+        Some(path) => {
+            if !path.is_file() {
+                return Err(anyhow!(DevrsError::FileSystem(format!(
+                    "'{}' is not a file.",
+                    path.display()
+                ))));
+            }
+            let content = file::read_or_empty(path)?;
+            if path.file_name() == Some(OsStr::new(srv::config::CONFIG_FILE_NAME)) {
+                srv::config::check_srv_config_str(&content, path)?;
+                println!("✅ {} is a valid server configuration.", path.display());
+                return Ok(());
+            }
+            config::check_config_str(&content, path)?;
+            println!("✅ {} is a valid DevRS configuration.", path.display());
+        }
+        None => {
+            let (_, layers) =
+                config::load_config_with_layers().context("Failed to load DevRS configuration")?;
+            for layer in &layers {
+                println!("  checked {}", layer.source);
+            }
+            println!("✅ The configuration is valid.");
+        }
+    }
+    Ok(())
+}
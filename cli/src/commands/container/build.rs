@@ -49,6 +49,7 @@
 //! The command provides feedback during the build process by streaming Docker's output.
 //!
 use crate::common::docker; // Access shared Docker utilities (like build_image).
+use crate::common::docker::roles::DevrsRole; // Role recorded in image labels.
 use crate::core::config; // Access configuration loading.
 use crate::core::error::Result; // Standard Result type for error handling.
 use anyhow::Context; // For adding context to errors.
@@ -150,6 +151,10 @@ pub async fn handle_build(args: BuildArgs) -> Result<()> {
     let current_dir = env::current_dir().context("Failed to get current directory")?;
     // Join the CWD with the filename provided by the `--file` arg (defaults to "Dockerfile").
     let dockerfile_path = current_dir.join(&args.file);
+    // The project (directory) name is recorded as an image label.
+    let project_name = current_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase());
 
     // Check if the resolved Dockerfile path exists.
     if !dockerfile_path.exists() {
@@ -186,6 +191,9 @@ pub async fn handle_build(args: BuildArgs) -> Result<()> {
         dockerfile_arg, // The relative path to the Dockerfile within the context.
         context_dir,    // The build context path (".").
         args.no_cache,  // The boolean flag for using Docker cache.
+        // Label the image as a devrs application image for `devrs images ls/prune`.
+        &docker::roles::build_labels(DevrsRole::App, project_name.as_deref()),
+        &Default::default(), // No build arguments.
     )
     .await // Await the async build process.
     .with_context(|| format!("Failed to build Docker image '{}'", image_tag))?; // Add context on error.
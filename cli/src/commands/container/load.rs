@@ -0,0 +1,132 @@
+//! # DevRS Image Load Handler
+//!
+//! File: cli/src/commands/container/load.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs container load` subcommand, which imports
+//! images from an archive created by `devrs container save` (or `docker save`)
+//! into the local Docker image cache.
+//!
+//! ## Architecture
+//!
+//! The command flow involves these steps:
+//! 1. Parse command-line arguments (`LoadArgs`): the archive file path.
+//! 2. Verify the file exists.
+//! 3. Call `common::docker::images::load_images`, which detects gzip/zstd compression from
+//!    the file content (via `common::archive::compression`), streams the decompressed tar to
+//!    the Docker image import endpoint, and prints the daemon's progress messages.
+//! 4. Summarize the loaded image references.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Load images from an archive (compression is detected automatically)
+//! devrs container load lab-images.tar.zst
+//! devrs container load my-app.tar
+//! ```
+//!
+use crate::{
+    common::docker, // Access shared Docker utilities (images::load_images).
+    core::error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use std::path::PathBuf; // For the archive file path.
+use tracing::info; // Logging framework utilities.
+
+/// # Load Image Arguments (`LoadArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs container load` subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Load images from a (compressed) tar archive",
+    long_about = "Imports images from an archive created by 'devrs container save' or 'docker save'.\n\
+                  Uncompressed, gzip and zstd archives are detected automatically from their content."
+)]
+pub struct LoadArgs {
+    /// Required: The archive file to load (e.g., "images.tar.zst").
+    file: PathBuf,
+}
+
+/// # Handle Load Image Command (`handle_load`)
+///
+/// The main asynchronous handler function for the `devrs container load` command.
+///
+/// ## Workflow:
+/// 1.  Verifies that the archive file exists.
+/// 2.  Calls `docker::images::load_images` to stream the archive to the daemon.
+/// 3.  Prints the list of loaded image references.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `LoadArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Returns `Ok(())` if the daemon accepted the archive.
+/// * `Err`: If the file is missing or unreadable, or the daemon rejects the archive.
+pub async fn handle_load(args: LoadArgs) -> Result<()> {
+    info!(
+        "Handling container load command (File: {})",
+        args.file.display()
+    );
+
+    // 1. Check the archive exists for a clear error message.
+    if !args.file.is_file() {
+        return Err(anyhow!(DevrsError::FileSystem(format!(
+            "Archive '{}' not found.",
+            args.file.display()
+        ))));
+    }
+
+    // 2. Stream the archive to the daemon.
+    println!("Loading images from '{}'...", args.file.display());
+    let loaded = docker::images::load_images(&args.file)
+        .await
+        .with_context(|| format!("Failed to load images from '{}'", args.file.display()))?;
+
+    // 3. Summarize.
+    if loaded.is_empty() {
+        println!("Archive loaded.");
+    } else {
+        println!("Loaded {} image(s).", loaded.len());
+    }
+    Ok(())
+}
+
+// --- Unit Tests ---
+// Focus on argument parsing. Exercising the handler requires a Docker daemon.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing the archive path.
+    #[test]
+    fn test_load_args_parsing() {
+        let args = LoadArgs::try_parse_from(["load", "images.tar.gz"]).unwrap();
+        assert_eq!(args.file, PathBuf::from("images.tar.gz"));
+    }
+
+    /// Test that the archive path is required.
+    #[test]
+    fn test_load_args_requires_file() {
+        assert!(LoadArgs::try_parse_from(["load"]).is_err());
+    }
+
+    /// Test that a missing archive fails before contacting Docker.
+    #[tokio::test]
+    async fn test_handle_load_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = LoadArgs {
+            file: dir.path().join("missing.tar"),
+        };
+        let err = handle_load(args).await.unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+}
@@ -41,6 +41,11 @@
 //!
 //! # Remove a container
 //! devrs container rm my-container
+//!
+//! # Share images offline or through a local registry
+//! devrs container save myapp:1.0 -o myapp.tar.zst
+//! devrs container load myapp.tar.zst
+//! devrs container push myapp:1.0 --registry localhost:5000
 //! ```
 //!
 //! The command processing flow:
@@ -59,14 +64,20 @@ use clap::{Parser, Subcommand}; // Import necessary components from the Clap cra
 
 /// Implements the `devrs container build` command.
 mod build;
+/// Implements the `devrs container load` command (import image archives).
+mod load;
 /// Implements the `devrs container logs` command.
 mod logs;
+/// Implements the `devrs container push` command (push to a registry).
+mod push;
 /// Implements the `devrs container rm` command (remove containers).
 mod rm;
 /// Implements the `devrs container rmi` command (remove images).
 mod rmi;
 /// Implements the `devrs container run` command.
 mod run;
+/// Implements the `devrs container save` command (export image archives).
+mod save;
 /// Implements the `devrs container shell` command (debug shell).
 mod shell;
 /// Implements the `devrs container status` command.
@@ -107,11 +118,21 @@ enum ContainerCommand {
 
     // Note: The `Buildrun` subcommand was previously present but has been removed.
     // Users should now use separate `build` and `run` commands.
+    /// Corresponds to `devrs container load`.
+    /// Imports images from a (gzip/zstd compressed) tar archive created by `save`.
+    /// Holds `load::LoadArgs` for the archive file path.
+    Load(load::LoadArgs), //
+
     /// Corresponds to `devrs container logs`.
     /// Fetches and displays logs from a specified running or stopped application container.
     /// Holds `logs::LogsArgs` for options like container name/ID, `--follow`, `--lines`.
     Logs(logs::LogsArgs), //
 
+    /// Corresponds to `devrs container push`.
+    /// Tags an image for a (local) registry and pushes it with progress output.
+    /// Holds `push::PushArgs` for options like the image, `--registry` and `--tag`.
+    Push(push::PushArgs), //
+
     /// Corresponds to `devrs container rm`.
     /// Removes one or more specified application containers.
     /// Holds `rm::RmArgs` for options like container names/IDs and `--force`.
@@ -127,6 +148,11 @@ enum ContainerCommand {
     /// Holds `run::RunArgs` for options like `--image`, `--name`, `--port`, `--env`, `--detach`, `--rm`, and command overrides.
     Run(run::RunArgs), //
 
+    /// Corresponds to `devrs container save`.
+    /// Exports one or more images into a tar archive, compressed according to the file extension.
+    /// Holds `save::SaveArgs` for options like image names, `--output` and `--force`.
+    Save(save::SaveArgs), //
+
     /// Corresponds to `devrs container shell`.
     /// Starts a temporary, interactive shell within a specified application image, mainly for debugging purposes. The container is auto-removed on exit.
     /// Holds `shell::ShellArgs` for options like image name and the command/shell to run.
@@ -171,11 +197,21 @@ pub async fn handle_container(args: ContainerArgs) -> Result<()> {
             build::handle_build(args).await? // `await` the async handler and propagate errors (`?`).
         }
         // Buildrun variant removed.
+        // If the command was `load`...
+        ContainerCommand::Load(args) => {
+            // ...call the `handle_load` function from the `load` module.
+            load::handle_load(args).await?
+        }
         // If the command was `logs`...
         ContainerCommand::Logs(args) => {
             // ...call the `handle_logs` function from the `logs` module.
             logs::handle_logs(args).await?
         }
+        // If the command was `push`...
+        ContainerCommand::Push(args) => {
+            // ...call the `handle_push` function from the `push` module.
+            push::handle_push(args).await?
+        }
         // If the command was `rm`...
         ContainerCommand::Rm(args) => {
             // ...call the `handle_rm` function from the `rm` module.
@@ -191,6 +227,11 @@ pub async fn handle_container(args: ContainerArgs) -> Result<()> {
             // ...call the `handle_run` function from the `run` module.
             run::handle_run(args).await?
         }
+        // If the command was `save`...
+        ContainerCommand::Save(args) => {
+            // ...call the `handle_save` function from the `save` module.
+            save::handle_save(args).await?
+        }
         // If the command was `shell`...
         ContainerCommand::Shell(args) => {
             // ...call the `handle_shell` function from the `shell` module.
@@ -259,6 +300,26 @@ mod tests {
         }
     }
 
+    /// Test parsing of the `save`, `load` and `push` image transfer subcommands.
+    #[test]
+    fn test_parses_container_image_transfer() {
+        let result =
+            ContainerArgs::try_parse_from(["container", "save", "app:1", "-o", "app.tar.zst"]);
+        assert!(matches!(result.unwrap().command, ContainerCommand::Save(_)));
+
+        let result = ContainerArgs::try_parse_from(["container", "load", "app.tar.zst"]);
+        assert!(matches!(result.unwrap().command, ContainerCommand::Load(_)));
+
+        let result = ContainerArgs::try_parse_from([
+            "container",
+            "push",
+            "app:1",
+            "--registry",
+            "localhost:5000",
+        ]);
+        assert!(matches!(result.unwrap().command, ContainerCommand::Push(_)));
+    }
+
     /// Test that the previously removed 'buildrun' subcommand is correctly rejected.
     #[test]
     fn test_rejects_container_buildrun() {
@@ -0,0 +1,170 @@
+//! # DevRS Image Push Handler
+//!
+//! File: cli/src/commands/container/push.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs container push` subcommand, which publishes a
+//! local image to a registry — typically a local `registry:2` instance
+//! (`docker run -d -p 5000:5000 registry:2`) used to share images between machines
+//! on a lab network without internet access.
+//!
+//! ## Architecture
+//!
+//! The command flow involves these steps:
+//! 1. Parse command-line arguments (`PushArgs`): the image, the `--registry` host and an optional `--tag` override.
+//! 2. Compute the registry reference with `common::docker::images::registry_reference`
+//!    (e.g., `my-app:1.0` + `localhost:5000` → `localhost:5000/my-app:1.0`), replacing any
+//!    registry already present in the image name.
+//! 3. Tag the local image with that reference (`images::tag_image`).
+//! 4. Push the reference (`images::push_image`), rendering layer progress as it streams.
+//! 5. Unless `--keep-tag` is given, remove the temporary registry tag again so the local
+//!    image list is not cluttered (only the tag is removed; the image itself is untouched).
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Push to a local registry on the default port
+//! devrs container push my-app:1.0 --registry localhost:5000
+//!
+//! # Push under a different tag on the registry
+//! devrs container push my-app:latest --registry lab-host:5000 --tag 2024-06-01
+//!
+//! # Keep the registry tag locally after pushing
+//! devrs container push my-app:1.0 --registry localhost:5000 --keep-tag
+//! ```
+//!
+use crate::{
+    common::docker, // Access shared Docker utilities (images::{tag_image, push_image}).
+    core::error::Result, // Standard Result type for error handling.
+};
+use anyhow::Context; // For adding context to errors.
+use clap::Parser; // For parsing command-line arguments.
+use tracing::{info, warn}; // Logging framework utilities.
+
+/// # Push Image Arguments (`PushArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs container push` subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Push an image to a (local) registry",
+    long_about = "Tags a local image for the given registry and pushes it, showing progress.\n\
+                  Intended for sharing images via a local 'registry:2' instance, e.g.:\n  \
+                  docker run -d -p 5000:5000 registry:2\n  \
+                  devrs container push my-app:1.0 --registry localhost:5000"
+)]
+pub struct PushArgs {
+    /// Required: The local image to push (e.g., "my-app:1.0").
+    image: String,
+
+    /// Optional: The registry host (and port) to push to.
+    #[arg(long, default_value = "localhost:5000")] // Define as `--registry <HOST:PORT>`.
+    registry: String,
+
+    /// Optional: Push under this tag instead of the image's own tag.
+    #[arg(long)] // Define as `--tag <TAG>`.
+    tag: Option<String>,
+
+    /// Optional: Keep the registry-prefixed tag locally after the push.
+    #[arg(long)] // Define as `--keep-tag`.
+    keep_tag: bool,
+}
+
+/// # Handle Push Image Command (`handle_push`)
+///
+/// The main asynchronous handler function for the `devrs container push` command.
+///
+/// ## Workflow:
+/// 1.  Computes the target reference on the registry (applying `--tag` if given).
+/// 2.  Tags the local image with that reference.
+/// 3.  Pushes the reference, streaming progress to the terminal.
+/// 4.  Removes the temporary tag unless `--keep-tag` was given (failures here only warn).
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `PushArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Returns `Ok(())` once the push has completed.
+/// * `Err`: If the image is missing, tagging fails, or the registry rejects the push.
+pub async fn handle_push(args: PushArgs) -> Result<()> {
+    info!(
+        "Handling container push command (Image: {}, Registry: {})",
+        args.image, args.registry
+    );
+
+    // 1. Work out the reference on the registry.
+    let target = target_reference(&args);
+    let needs_tag = target != args.image;
+
+    // 2. Tag the local image (unless it already carries the exact reference).
+    if needs_tag {
+        docker::images::tag_image(&args.image, &target)
+            .await
+            .with_context(|| format!("Failed to tag '{}' as '{}'", args.image, target))?;
+    }
+
+    // 3. Push with progress.
+    println!("Pushing '{}'...", target);
+    let push_result = docker::images::push_image(&target)
+        .await
+        .with_context(|| format!("Failed to push '{}' to '{}'", args.image, args.registry));
+
+    // 4. Clean up the temporary tag even if the push failed.
+    if needs_tag && !args.keep_tag {
+        if let Err(e) = docker::images::remove_image(&target, false).await {
+            warn!("Failed to remove temporary tag '{}': {:?}", target, e);
+        }
+    }
+
+    push_result?;
+    println!("Pushed '{}'.", target);
+    Ok(())
+}
+
+/// Computes the registry reference for `args`, applying any `--tag` override.
+fn target_reference(args: &PushArgs) -> String {
+    let rewritten = docker::images::registry_reference(&args.image, &args.registry);
+    match &args.tag {
+        Some(tag) => {
+            let (repo, _) = docker::images::split_reference(&rewritten);
+            format!("{}:{}", repo, tag)
+        }
+        None => rewritten,
+    }
+}
+
+// --- Unit Tests ---
+// Focus on argument parsing and reference computation. Pushing requires a Docker daemon.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing defaults.
+    #[test]
+    fn test_push_args_defaults() {
+        let args = PushArgs::try_parse_from(["push", "my-app:1.0"]).unwrap();
+        assert_eq!(args.image, "my-app:1.0");
+        assert_eq!(args.registry, "localhost:5000");
+        assert!(args.tag.is_none());
+        assert!(!args.keep_tag);
+    }
+
+    /// Test that the target reference is rewritten and tag overrides apply.
+    #[test]
+    fn test_target_reference() {
+        let args =
+            PushArgs::try_parse_from(["push", "my-app:1.0", "--registry", "lab:5000"]).unwrap();
+        assert_eq!(target_reference(&args), "lab:5000/my-app:1.0");
+
+        let args =
+            PushArgs::try_parse_from(["push", "ghcr.io/team/app", "--tag", "nightly"]).unwrap();
+        assert_eq!(target_reference(&args), "localhost:5000/team/app:nightly");
+    }
+}
@@ -23,7 +23,10 @@
 //! 3. Determine the final image name to use (prioritizing `--image`, then generating a default based on directory name and config prefix).
 //! 4. Check if the target image exists locally using `common::docker::image_exists` (issues a warning if not found, Docker will attempt to pull).
 //! 5. Determine the container name (prioritizing `--name`, then generating a default `devrs-app-<dirname>`).
-//! 6. Parse environment variables provided via `--env KEY=VALUE` into a HashMap.
+//! 6. Read `--env-file` files (dotenv syntax, see `core::dotenv`) and then `--env KEY=VALUE`
+//!    into a HashMap; `--env` overrides the files, and later files override earlier ones.
+//!    `--secret NAME=REFERENCE` values are resolved on the host (see `core::secrets`) and
+//!    override both.
 //! 7. Prepare port mappings from `--port HOST:CONTAINER` arguments.
 //! 8. Prepare volume mount configurations (currently none supported directly via CLI args for this command).
 //! 9. Prepare any command override provided as trailing arguments.
@@ -44,6 +47,12 @@
 //! # Run with port mappings and environment variables
 //! devrs container run --image myapp:1.0 -p 8080:80 -p 8443:443 -e KEY=VALUE
 //!
+//! # Run with the variables of the project's .env files (-e still overrides them)
+//! devrs container run --image myapp:1.0 --env-file .env --env-file .env.local
+//!
+//! # Pass a token from a host file or password manager without typing its value
+//! devrs container run --image myapp:1.0 --secret NPM_TOKEN=cmd:"pass show npm"
+//!
 //! # Run in detached mode with a specific name
 //! devrs container run --image myapp:1.0 --name myapp-instance --detach
 //!
@@ -60,18 +69,23 @@
 //!
 use crate::common::docker; // Access shared Docker utilities (run_container, image_exists).
 use crate::core::config; // Access configuration loading.
-use crate::core::error::Result; // Standard Result type for error handling.
-use anyhow::Context; // For adding context to errors.
+use crate::core::dotenv; // Reading --env-file files and redacting values in logs.
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use crate::core::ports::{self, PortMapping}; // Typed port mappings and default binding.
+use crate::core::secrets; // Resolving --secret references on the host.
+use anyhow::{anyhow, Context}; // For creating errors and adding context.
 use clap::Parser; // For parsing command-line arguments.
 use std::collections::HashMap; // Required for storing parsed environment variables.
 use std::env; // For getting the current working directory.
+use std::fmt; // Debug output without environment values.
+use std::path::PathBuf; // Paths of --env-file files.
 use tracing::{debug, info, warn}; // Logging framework utilities.
 
 /// # Container Run Arguments (`RunArgs`)
 ///
 /// Defines the command-line arguments accepted by the `devrs container run` subcommand.
 /// Uses the `clap` crate for parsing and validation.
-#[derive(Parser, Debug)]
+#[derive(Parser)]
 pub struct RunArgs {
     /// Optional: Specifies the name and tag of the Docker image to use for creating the container (e.g., "myapp:latest").
     /// If omitted, a default image name is generated based on the current directory name and an optional
@@ -86,11 +100,13 @@ pub struct RunArgs {
     pub name: Option<String>,
 
     /// Optional: Specifies port mappings between the host and the container.
-    /// Format: `HOST_PORT:CONTAINER_PORT` (e.g., "8080:80", "127.0.0.1:9000:9000").
+    /// Format: `[IP:][HOST:]CONTAINER[/PROTOCOL]` with ports or ranges (e.g., "8080:80",
+    /// "127.0.0.1:9000:9000", "8000-8010:8000-8010", "53:53/udp", or "3000" for an
+    /// ephemeral host port). Without an IP, `[network] bind_address` is used.
     /// Can be specified multiple times to map multiple ports.
     #[arg(short, long = "port", action = clap::ArgAction::Append)]
     // Define as `-p` or `--port`, allowing multiple occurrences.
-    pub ports: Vec<String>,
+    pub ports: Vec<PortMapping>,
 
     /// Optional: Sets environment variables inside the container.
     /// Format: `KEY=VALUE` (e.g., "DATABASE_URL=postgres://...", "API_KEY=123").
@@ -99,6 +115,20 @@ pub struct RunArgs {
     // Define as `-e` or `--env`, allowing multiple occurrences.
     pub env_vars: Vec<String>,
 
+    /// Optional: Reads environment variables from a `.env` file (dotenv syntax: quotes,
+    /// `export`, comments, multiline values and `${VAR}` expansion). Can be specified
+    /// multiple times; later files override earlier ones, and `--env` overrides them all.
+    #[arg(long = "env-file", value_name = "FILE", action = clap::ArgAction::Append)]
+    pub env_files: Vec<PathBuf>,
+
+    /// Optional: Sets an environment variable from a secret resolved on the host when the
+    /// container is created. Format: `NAME=REFERENCE` with a `file:PATH` or `cmd:COMMAND`
+    /// reference (e.g. "NPM_TOKEN=file:~/.secrets/npm"). The value is never printed or
+    /// logged, but like `--env` it is part of the container's configuration.
+    /// Can be specified multiple times; it overrides `--env` and `--env-file`.
+    #[arg(long = "secret", value_name = "NAME=REFERENCE", action = clap::ArgAction::Append)]
+    pub secrets: Vec<String>,
+
     /// Optional: Runs the container in the background (detached mode).
     /// If not set, the command will typically wait for the container's main process to finish
     /// (although this implementation might not currently stream foreground logs effectively).
@@ -120,6 +150,28 @@ pub struct RunArgs {
     // TODO: Add volume mount (-v) arguments later.
 }
 
+/// Debug output for logs, with the values of `--env` hidden.
+impl fmt::Debug for RunArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let env_vars: Vec<String> = self
+            .env_vars
+            .iter()
+            .map(|env_str| dotenv::redact_assignment(env_str))
+            .collect();
+        f.debug_struct("RunArgs")
+            .field("image", &self.image)
+            .field("name", &self.name)
+            .field("ports", &self.ports)
+            .field("env_vars", &env_vars)
+            .field("env_files", &self.env_files)
+            .field("secrets", &self.secrets)
+            .field("detach", &self.detach)
+            .field("rm", &self.rm)
+            .field("command", &self.command)
+            .finish()
+    }
+}
+
 /// # Handle Container Run Command (`handle_run`)
 ///
 /// The main asynchronous handler function for the `devrs container run` command.
@@ -133,7 +185,8 @@ pub struct RunArgs {
 /// 3.  Determines the target image name (using `--image` or generating default).
 /// 4.  Checks if the image exists locally (logs warning if not).
 /// 5.  Determines the target container name (using `--name` or generating default).
-/// 6.  Parses `--env` arguments into a HashMap.
+/// 6.  Reads `--env-file` files, then parses `--env` arguments and resolves `--secret`
+///     references on top into a HashMap.
 /// 7.  Prepares port mappings from `--port` arguments (passed as `Vec<String>`).
 /// 8.  Prepares volume mounts (currently none are passed from CLI args).
 /// 9.  Prepares the command override (`Option<Vec<String>>`) if provided.
@@ -148,7 +201,8 @@ pub struct RunArgs {
 ///
 /// * `Result<()>`: Returns `Ok(())` on successful container start/run.
 /// * `Err`: Returns an `Err` if configuration loading, path resolution, argument parsing,
-///   or the underlying Docker operation fails (e.g., image pull fails, port conflict, container name conflict).
+///   resolving a `--secret`, or the underlying Docker operation fails (e.g., image pull
+///   fails, port conflict, container name conflict).
 pub async fn handle_run(args: RunArgs) -> Result<()> {
     info!("Handling container run command..."); // Log entry point.
     debug!("Run args: {:?}", args); // Log parsed arguments.
@@ -218,8 +272,9 @@ pub async fn handle_run(args: RunArgs) -> Result<()> {
     };
 
     // --- Prepare Environment Variables ---
-    // Parse the `KEY=VALUE` strings provided via `--env` into a HashMap.
-    let mut env_map: HashMap<String, String> = HashMap::new();
+    // Start from the `--env-file` files, then parse the `KEY=VALUE` strings provided via
+    // `--env` on top of them.
+    let mut env_map: HashMap<String, String> = dotenv::load_files(&args.env_files)?;
     for env_str in &args.env_vars {
         if let Some((key, value)) = env_str.split_once('=') {
             // Trim whitespace and insert into the map.
@@ -232,6 +287,18 @@ pub async fn handle_run(args: RunArgs) -> Result<()> {
             );
         }
     }
+    // `--secret NAME=REFERENCE` values are resolved on the host now.
+    for secret in &args.secrets {
+        let (name, reference) = secret.split_once('=').ok_or_else(|| {
+            anyhow!(DevrsError::Config(format!(
+                "Invalid --secret '{}'. Expected NAME=REFERENCE, e.g. NPM_TOKEN=file:~/.secrets/npm.",
+                secret
+            )))
+        })?;
+        let name = name.trim();
+        env_map.insert(name.to_string(), secrets::resolve(name, reference.trim())?);
+    }
+    debug!("Container environment: {}", dotenv::Redacted(&env_map));
 
     // --- Prepare Mounts ---
     // Currently, `devrs container run` doesn't accept mount arguments directly.
@@ -253,11 +320,15 @@ pub async fn handle_run(args: RunArgs) -> Result<()> {
         "Attempting to run container '{}' from image '{}'",
         container_name, image_name
     );
+    // Mappings without an explicit IP bind to the configured address (localhost by default).
+    let port_mappings = ports::bind_default(&args.ports, &cfg.network.bind_address);
     docker::run_container(
         &image_name,      // Image to use.
         &container_name,  // Name for the new container.
-        &args.ports,      // Port mappings (Vec<String>).
+        &port_mappings,   // Port mappings (Vec<PortMapping>).
         &mounts,          // Volume mounts (currently empty Vec<MountConfig>).
+        &[],              // No named volumes.
+        &[],              // No tmpfs mounts.
         &env_map,         // Environment variables (HashMap).
         None,             // workdir - use container's default (could be added as arg later).
         args.detach,      // Run in background?
@@ -306,7 +377,7 @@ mod tests {
     // Test parsing with a comprehensive set of arguments.
     #[test]
     fn test_run_args_parsing() {
-        // Simulate `devrs container run --image myimage:v2 --name my-instance -p 80:8000 --port 443:8443 -e VAR1=val1 --env VAR2=val2 --detach --rm override_cmd --arg1`
+        // Simulate `devrs container run --image myimage:v2 --name my-instance -p 80:8000 --port 443:8443 -e VAR1=val1 --env VAR2=val2 --env-file .env --secret TOKEN=file:~/.secrets/token --detach --rm override_cmd --arg1`
         let args = RunArgs::try_parse_from([
             "run", // Command name context for clap.
             "--image",
@@ -321,6 +392,10 @@ mod tests {
             "VAR1=val1",
             "--env", // Long env flag.
             "VAR2=val2",
+            "--env-file", // Env file flag.
+            ".env",
+            "--secret", // Secret flag.
+            "TOKEN=file:~/.secrets/token",
             "--detach",     // Detach flag.
             "--rm",         // Auto-remove flag.
             "--",           // Add separator before trailing command args
@@ -334,6 +409,11 @@ mod tests {
         assert_eq!(args.name, Some("my-instance".to_string()));
         assert_eq!(args.ports, vec!["80:8000", "443:8443"]);
         assert_eq!(args.env_vars, vec!["VAR1=val1", "VAR2=val2"]);
+        assert_eq!(args.env_files, vec![PathBuf::from(".env")]);
+        assert_eq!(args.secrets, vec!["TOKEN=file:~/.secrets/token"]);
+        // Logged arguments hide the values.
+        let logged = format!("{:?}", args);
+        assert!(logged.contains("VAR1=***") && !logged.contains("val1"));
         assert!(args.detach);
         assert!(args.rm);
         assert_eq!(args.command, vec!["override_cmd", "--arg1"]);
@@ -353,6 +433,7 @@ mod tests {
         assert!(args.name.is_none());
         assert!(args.ports.is_empty());
         assert!(args.env_vars.is_empty());
+        assert!(args.env_files.is_empty());
         assert!(!args.detach);
         assert!(!args.rm);
         assert!(args.command.is_empty()); // No command override.
@@ -371,8 +452,10 @@ mod tests {
         let args = RunArgs {
             image: Some("test:run".to_string()), // Explicit image.
             name: None,                          // Let name default.
-            ports: vec!["8080:80".to_string()],
+            ports: vec!["8080:80".parse().unwrap()],
             env_vars: vec!["MODE=test".to_string()],
+            env_files: vec![],
+            secrets: vec![],
             detach: true,
             rm: true,
             command: vec![], // No command override.
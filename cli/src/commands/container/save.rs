@@ -0,0 +1,151 @@
+//! # DevRS Image Save Handler
+//!
+//! File: cli/src/commands/container/save.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs container save` subcommand, which exports
+//! one or more local Docker images into a single archive file. The archive can be
+//! copied to another machine (for example, an air-gapped lab host) and imported
+//! there with `devrs container load` or `docker load`.
+//!
+//! ## Architecture
+//!
+//! The command flow involves these steps:
+//! 1. Parse command-line arguments (`SaveArgs`): one or more image names and the required `-o/--output` path.
+//! 2. Refuse to overwrite an existing output file unless `--force` is given.
+//! 3. Call `common::docker::images::save_images`, which streams the Docker image export
+//!    endpoint into the output file. Compression is chosen from the file extension via
+//!    `common::archive::compression` (`.tar` = none, `.tar.gz`/`.tgz` = gzip, `.tar.zst` = zstd).
+//! 4. Report the number of image bytes exported and the final archive size.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Save a single image to an uncompressed tarball
+//! devrs container save my-app:latest -o my-app.tar
+//!
+//! # Save several images into one zstd-compressed archive
+//! devrs container save my-app:latest my-db:1.2 -o lab-images.tar.zst
+//!
+//! # Overwrite an existing archive
+//! devrs container save my-app:latest -o my-app.tar.gz --force
+//! ```
+//!
+use crate::{
+    common::docker, // Access shared Docker utilities (images::save_images).
+    core::error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use std::path::PathBuf; // For the output file path.
+use tracing::info; // Logging framework utilities.
+
+/// # Save Image Arguments (`SaveArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs container save` subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Save one or more images to a (compressed) tar archive",
+    long_about = "Exports one or more local images into a single archive file for offline transfer.\n\
+                  Compression is selected from the output extension: .tar (none), .tar.gz/.tgz (gzip) or .tar.zst (zstd).\n\
+                  Load the archive on another machine with 'devrs container load <file>'."
+)]
+pub struct SaveArgs {
+    /// One or more image names (e.g., "my-app:latest") or IDs to include in the archive.
+    #[arg(required = true, num_args = 1..)] // Require at least one image.
+    images: Vec<String>,
+
+    /// Required: The archive file to write (e.g., "images.tar.zst").
+    #[arg(short, long)] // Define as `-o` or `--output`.
+    output: PathBuf,
+
+    /// Optional: Overwrite the output file if it already exists.
+    #[arg(long, short)] // Define as `--force` or `-f`.
+    force: bool,
+}
+
+/// # Handle Save Image Command (`handle_save`)
+///
+/// The main asynchronous handler function for the `devrs container save` command.
+///
+/// ## Workflow:
+/// 1.  Checks the output path, refusing to overwrite an existing file without `--force`.
+/// 2.  Calls `docker::images::save_images` to stream the export into the output file.
+/// 3.  Prints a summary with the exported and on-disk sizes.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `SaveArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Returns `Ok(())` if the archive was written successfully.
+/// * `Err`: If the output exists (without `--force`), an image is missing, or the export fails.
+pub async fn handle_save(args: SaveArgs) -> Result<()> {
+    info!(
+        "Handling container save command (Images: {:?}, Output: {})",
+        args.images,
+        args.output.display()
+    );
+
+    // 1. Protect existing files unless explicitly overwriting.
+    if args.output.exists() && !args.force {
+        return Err(anyhow!(DevrsError::FileSystem(format!(
+            "Output file '{}' already exists. Use --force to overwrite it.",
+            args.output.display()
+        ))));
+    }
+
+    // 2. Stream the export into the archive.
+    println!(
+        "Saving {} image(s) to '{}'...",
+        args.images.len(),
+        args.output.display()
+    );
+    let exported_bytes = docker::images::save_images(&args.images, &args.output)
+        .await
+        .with_context(|| format!("Failed to save images to '{}'", args.output.display()))?;
+
+    // 3. Report the result, including the compressed size on disk.
+    let archive_bytes = std::fs::metadata(&args.output)
+        .map(|m| m.len())
+        .unwrap_or(exported_bytes);
+    println!(
+        "Saved {} image(s) to '{}' ({:.1} MB of image data, {:.1} MB on disk).",
+        args.images.len(),
+        args.output.display(),
+        exported_bytes as f64 / 1_048_576.0,
+        archive_bytes as f64 / 1_048_576.0
+    );
+    Ok(())
+}
+
+// --- Unit Tests ---
+// Focus on argument parsing. Exercising the handler requires a Docker daemon.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing multiple images with an output path.
+    #[test]
+    fn test_save_args_parsing() {
+        let args =
+            SaveArgs::try_parse_from(["save", "app:1", "db:2", "-o", "out.tar.zst"]).unwrap();
+        assert_eq!(args.images, vec!["app:1", "db:2"]);
+        assert_eq!(args.output, PathBuf::from("out.tar.zst"));
+        assert!(!args.force);
+    }
+
+    /// Test that both an image and an output path are required.
+    #[test]
+    fn test_save_args_requires_image_and_output() {
+        assert!(SaveArgs::try_parse_from(["save", "-o", "out.tar"]).is_err());
+        assert!(SaveArgs::try_parse_from(["save", "app:1"]).is_err());
+    }
+}
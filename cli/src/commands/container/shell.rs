@@ -185,6 +185,8 @@ pub async fn handle_shell(args: ShellArgs) -> Result<()> {
         &temp_container_name,
         &[],                                                // No ports.
         &[],                                                // No mounts.
+        &[],                                                // No named volumes.
+        &[],                                                // No tmpfs mounts.
         &Default::default(),                                // No extra env vars.
         Some(&get_default_workdir(&args.image_name).await), // Default workdir (currently "/").
         false,                                              // Run in foreground (not detached).
@@ -35,12 +35,17 @@
 //! devrs container status --all
 //! # Equivalent shorthand:
 //! devrs container status -a
+//!
+//! # Include a live CPU/memory/I/O snapshot of the running containers
+//! devrs container status --stats
 //! ```
 //!
-//! The output provides key details like Container ID (short), Image, Command, Created Timestamp, Status, Names, and Ports for each application container found.
+//! The output provides key details like Container ID (short), Image, Command, Created time (local time and age), Status, Names, and Ports for each application container found.
 //!
 use crate::{
+    commands::stats,               // Shared resource usage snapshot for `--stats`.
     common::docker,                // Access shared Docker utilities (list_containers).
+    common::ui::format,            // Human-readable creation times.
     core::{config, error::Result}, // Standard config loading and Result type.
 };
 use anyhow::Context; // For adding context to errors.
@@ -64,6 +69,10 @@ pub struct StatusArgs {
     /// By default (if this flag is omitted), only currently running containers are shown.
     #[arg(long, short)] // Define as `--all` or `-a`.
     all: bool,
+    /// Optional: Also sample live resource usage (CPU, memory, network/block I/O, PIDs)
+    /// of the running containers. See `devrs stats` for a refreshing view.
+    #[arg(long)]
+    stats: bool,
 }
 
 /// # Handle Container Status Command (`handle_status`)
@@ -152,6 +161,22 @@ pub async fn handle_status(args: StatusArgs) -> Result<()> {
         }
         // Print a count of the containers found.
         println!("Found {} application container(s).", app_containers.len());
+
+        // Optionally append a resource usage snapshot of the running ones.
+        if args.stats {
+            let running: Vec<String> = app_containers
+                .iter()
+                .filter(|c| c.state.as_deref() == Some("running"))
+                .filter_map(|c| c.names.as_ref().and_then(|n| n.first()))
+                .map(|n| n.trim_start_matches('/').to_string())
+                .collect();
+            if running.is_empty() {
+                println!("\nNo running application containers to sample.");
+            } else {
+                println!("\n--- Resource Usage ---");
+                stats::print_snapshot(&running).await?;
+            }
+        }
     }
 
     Ok(()) // Indicate successful command execution.
@@ -188,18 +213,16 @@ fn print_container_summary(container: &bollard::models::ContainerSummary) {
             .join(", ")
     });
 
-    // Get the creation timestamp (as a Unix timestamp integer).
-    // Note: Displaying this as a human-readable date/time requires the `chrono` crate,
-    // which was removed for simplicity here. We just show the raw timestamp.
+    // Get the creation time as a local date/time plus its age (e.g., "... (3 days ago)").
     let created_str = container
-        .created // This is an Option<i64>.
-        .map_or("N/A".to_string(), |ts| ts.to_string()); // Convert timestamp to string if present.
+        .created // This is an Option<i64> Unix timestamp.
+        .map_or("N/A".to_string(), format::format_timestamp);
 
     // Print the extracted information with labels.
     println!("ID:      {}", id);
     println!("Image:   {}", image);
     println!("Command: {}", command);
-    println!("Created: {} (timestamp)", created_str); // Clarify that 'Created' is a timestamp.
+    println!("Created: {}", created_str);
     println!("Status:  {}", status);
     println!("Names:   {}", names_str);
 
@@ -256,6 +279,7 @@ mod tests {
         let args = StatusArgs::try_parse_from(["status"]).unwrap();
         // Default value for `all` should be false.
         assert!(!args.all);
+        assert!(!args.stats);
     }
 
     /// Test argument parsing with the `--all` flag (or `-a`).
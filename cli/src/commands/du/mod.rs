@@ -0,0 +1,210 @@
+//! # DevRS Disk Usage Command
+//!
+//! File: cli/src/commands/du/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements `devrs du`, a disk usage report across everything DevRS
+//! owns on the Docker host: core environment and profile images, application
+//! images and their (stopped) containers, snapshots, volumes and the build cache.
+//! For each category it shows how much space is used, how much could be reclaimed,
+//! and the command that would reclaim it. Usage is also broken down by project.
+//!
+//! ## Architecture
+//!
+//! 1. Load configuration (needed to classify images by role).
+//! 2. Query the Docker `system df` endpoint via `common::docker::system::disk_usage`.
+//! 3. Group the results with `report::build_report` (pure, unit tested).
+//! 4. Render tables with `common::ui::tables`, or print the report as JSON with `--json`.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Human-readable report
+//! devrs du
+//!
+//! # Machine-readable report for dashboards
+//! devrs du --json
+//! ```
+//!
+use crate::{
+    common::{
+        docker,
+        ui::{format::format_bytes, tables},
+    }, // Docker disk usage query and table output.
+    core::{config, error::Result}, // Config loading and standard Result type.
+};
+use anyhow::Context; // For adding context to errors.
+use clap::Parser; // For parsing command-line arguments.
+use tracing::info; // Logging framework utilities.
+
+/// Groups `system df` results by category and project.
+mod report;
+
+use report::UsageReport;
+
+/// # Disk Usage Arguments (`DuArgs`)
+///
+/// Defines the command-line arguments accepted by `devrs du`.
+#[derive(Parser, Debug)]
+#[command(about = "Show disk usage of DevRS images, containers, volumes and build cache")]
+pub struct DuArgs {
+    /// Optional: Print the report as JSON instead of tables.
+    #[arg(long)]
+    json: bool,
+}
+
+/// # Handle Disk Usage Command (`handle_du`)
+///
+/// ## Workflow:
+/// 1.  Loads configuration and queries Docker disk usage.
+/// 2.  Builds the grouped report.
+/// 3.  Prints it as JSON (`--json`) or as category and project tables with prune hints.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `DuArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success, or an `Err` if config loading, the Docker query or JSON serialization fails.
+pub async fn handle_du(args: DuArgs) -> Result<()> {
+    info!("Handling du command (JSON: {})", args.json);
+
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let usage = docker::system::disk_usage().await?;
+    let report = report::build_report(&usage, &cfg);
+
+    if args.json {
+        let json =
+            serde_json::to_string_pretty(&report).context("Failed to serialize usage report")?;
+        println!("{}", json);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+/// Prints the report as human-readable tables.
+fn print_report(report: &UsageReport) {
+    tables::print_table(
+        &["CATEGORY", "ITEMS", "SIZE", "RECLAIMABLE", "RECLAIM WITH"],
+        &category_rows(report),
+    );
+    if report.shared_layers > 0 {
+        println!(
+            "\nShared image layers: {} (counted once, not attributed to a category).",
+            format_bytes(report.shared_layers)
+        );
+    }
+    println!(
+        "\nTotal: {}, reclaimable: {} ({} in DevRS-managed categories).",
+        format_bytes(report.total_size),
+        format_bytes(report.total_reclaimable),
+        format_bytes(report.devrs_reclaimable())
+    );
+
+    if !report.projects.is_empty() {
+        println!();
+        tables::print_table(
+            &[
+                "PROJECT",
+                "IMAGES",
+                "CONTAINERS",
+                "VOLUMES",
+                "SIZE",
+                "RECLAIMABLE",
+            ],
+            &project_rows(report),
+        );
+    }
+}
+
+/// Builds the category table rows, skipping empty categories.
+fn category_rows(report: &UsageReport) -> Vec<Vec<String>> {
+    report
+        .categories
+        .iter()
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            vec![
+                c.category.label().to_string(),
+                c.describe_items(),
+                format_bytes(c.size),
+                format_bytes(c.reclaimable),
+                // Only point at a command when it would actually free something.
+                match c.prune_hint {
+                    Some(hint) if c.reclaimable > 0 => hint.to_string(),
+                    _ => "-".to_string(),
+                },
+            ]
+        })
+        .collect()
+}
+
+/// Builds the project table rows.
+fn project_rows(report: &UsageReport) -> Vec<Vec<String>> {
+    report
+        .projects
+        .iter()
+        .map(|p| {
+            vec![
+                p.project.clone(),
+                p.images.to_string(),
+                p.containers.to_string(),
+                p.volumes.to_string(),
+                format_bytes(p.size),
+                format_bytes(p.reclaimable),
+            ]
+        })
+        .collect()
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::Config;
+    use bollard::models::{ImageSummary, SystemDataUsageResponse};
+
+    /// Test parsing of the `--json` flag.
+    #[test]
+    fn test_du_args_parsing() {
+        assert!(!DuArgs::try_parse_from(["du"]).unwrap().json);
+        assert!(DuArgs::try_parse_from(["du", "--json"]).unwrap().json);
+    }
+
+    /// Test that empty categories are hidden and hints only shown when space is reclaimable.
+    #[test]
+    fn test_category_rows() {
+        let mut cfg = Config::default();
+        cfg.core_env.image_name = "devrs-core-env".to_string();
+        let usage = SystemDataUsageResponse {
+            images: Some(vec![ImageSummary {
+                id: "sha256:core".to_string(),
+                repo_tags: vec!["devrs-core-env:old".to_string()],
+                size: 2_000,
+                shared_size: 0,
+                containers: 0,
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let rows = category_rows(&report::build_report(&usage, &cfg));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0][0], "Core environment");
+        assert_eq!(rows[0][1], "1 image");
+        assert_eq!(rows[0][3], "2.0 kB");
+        assert!(rows[0][4].contains("devrs images prune"));
+
+        // The JSON document keeps every category for stable dashboards.
+        let json = serde_json::to_value(report::build_report(&usage, &cfg)).unwrap();
+        assert_eq!(json["categories"].as_array().unwrap().len(), 7);
+        assert_eq!(json["categories"][0]["category"], "core-env");
+    }
+}
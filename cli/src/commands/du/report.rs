@@ -0,0 +1,592 @@
+//! # DevRS Disk Usage Report
+//!
+//! File: cli/src/commands/du/report.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Turns the raw Docker `system df` response into the grouped report shown by
+//! `devrs du`: usage per category (core environment, profiles, application images,
+//! snapshots, volumes, build cache, and everything else), usage per project, and
+//! how much of each is reclaimable.
+//!
+//! ## Architecture
+//!
+//! `build_report` is a pure function over `SystemDataUsageResponse`, so the grouping
+//! rules can be unit tested without a Docker daemon:
+//!
+//! - **Images** are classified with `common::docker::roles::image_role`. Each image
+//!   is counted by its *unique* size (total size minus layers shared with other
+//!   images); shared layers are reported once as a separate total. An image is
+//!   reclaimable when no container uses it.
+//! - **Containers** inherit the labels of their image, so they are classified the
+//!   same way. Their writable layer is reclaimable when they are not running.
+//! - **Volumes** count as DevRS volumes when they carry a `devrs.*` label or are
+//!   mounted by a DevRS container. Unreferenced volumes are reclaimable.
+//! - **Build cache** cannot be attributed to an image, so it forms its own category.
+//!   Records that are neither in use nor shared are reclaimable.
+//!
+use crate::{
+    common::docker::roles::{self, DevrsRole}, // Role classification and label keys.
+    core::config::Config,                     // For naming-convention based classification.
+};
+use bollard::models::{ContainerSummary, SystemDataUsageResponse}; // Docker `system df` models.
+use serde::Serialize; // For `--json` output.
+use std::collections::{BTreeMap, HashMap, HashSet}; // For grouping and lookups.
+
+/// # Usage Category (`Category`)
+///
+/// The groups shown in the `devrs du` table, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    /// Core environment images and containers.
+    CoreEnv,
+    /// Toolchain profile images and containers.
+    Profiles,
+    /// Application images and containers.
+    App,
+    /// Snapshot images.
+    Snapshots,
+    /// Named volumes owned by DevRS.
+    Volumes,
+    /// The Docker build cache.
+    BuildCache,
+    /// Images, containers and volumes not managed by DevRS.
+    Other,
+}
+
+impl Category {
+    /// Every category, in display order.
+    pub const ALL: [Category; 7] = [
+        Category::CoreEnv,
+        Category::Profiles,
+        Category::App,
+        Category::Snapshots,
+        Category::Volumes,
+        Category::BuildCache,
+        Category::Other,
+    ];
+
+    /// Maps an image or container role to its category.
+    fn from_role(role: DevrsRole) -> Self {
+        match role {
+            DevrsRole::CoreEnv => Category::CoreEnv,
+            DevrsRole::Profile => Category::Profiles,
+            DevrsRole::App => Category::App,
+            DevrsRole::Snapshot => Category::Snapshots,
+            DevrsRole::Other => Category::Other,
+        }
+    }
+
+    /// Human-readable name for tables.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Category::CoreEnv => "Core environment",
+            Category::Profiles => "Profiles",
+            Category::App => "Applications",
+            Category::Snapshots => "Snapshots",
+            Category::Volumes => "Volumes",
+            Category::BuildCache => "Build cache",
+            Category::Other => "Not managed by DevRS",
+        }
+    }
+
+    /// The command that reclaims space in this category, if there is one.
+    pub fn prune_hint(&self) -> Option<&'static str> {
+        match self {
+            Category::CoreEnv => Some("devrs env prune -f; devrs images prune --keep-last 1 -f"),
+            Category::Profiles | Category::Snapshots => Some("devrs images prune -f"),
+            Category::App => Some("devrs container rm <name>; devrs images prune -f"),
+            Category::Volumes => Some("docker volume prune"),
+            Category::BuildCache => Some("docker builder prune"),
+            Category::Other => None, // Not ours to clean up.
+        }
+    }
+}
+
+/// # Category Usage (`CategoryUsage`)
+///
+/// Usage totals for one category.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryUsage {
+    /// The category these totals belong to.
+    pub category: Category,
+    /// Number of images.
+    pub images: usize,
+    /// Number of containers.
+    pub containers: usize,
+    /// Number of volumes.
+    pub volumes: usize,
+    /// Number of build cache records.
+    pub cache_records: usize,
+    /// Space used, in bytes.
+    pub size: u64,
+    /// Space that the prune hint would free, in bytes.
+    pub reclaimable: u64,
+    /// The command that reclaims this space, if any.
+    pub prune_hint: Option<&'static str>,
+}
+
+impl CategoryUsage {
+    /// Creates an empty entry for a category.
+    fn new(category: Category) -> Self {
+        Self {
+            category,
+            images: 0,
+            containers: 0,
+            volumes: 0,
+            cache_records: 0,
+            size: 0,
+            reclaimable: 0,
+            prune_hint: category.prune_hint(),
+        }
+    }
+
+    /// Whether the category has no resources at all.
+    pub fn is_empty(&self) -> bool {
+        self.images + self.containers + self.volumes + self.cache_records == 0
+    }
+
+    /// A short description of the counted resources, e.g. `"3 images, 1 container"`.
+    pub fn describe_items(&self) -> String {
+        let parts: Vec<String> = [
+            (self.images, "image"),
+            (self.containers, "container"),
+            (self.volumes, "volume"),
+            (self.cache_records, "cache record"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, noun)| format!("{} {}{}", count, noun, if *count == 1 { "" } else { "s" }))
+        .collect();
+        if parts.is_empty() {
+            "-".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// # Project Usage (`ProjectUsage`)
+///
+/// Usage totals for resources labelled with one `devrs.project`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProjectUsage {
+    /// The project name (from the `devrs.project` label).
+    pub project: String,
+    /// Number of images.
+    pub images: usize,
+    /// Number of containers.
+    pub containers: usize,
+    /// Number of volumes.
+    pub volumes: usize,
+    /// Space used, in bytes.
+    pub size: u64,
+    /// Space that could be reclaimed, in bytes.
+    pub reclaimable: u64,
+}
+
+/// # Usage Report (`UsageReport`)
+///
+/// The complete `devrs du` report (also the `--json` document).
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    /// Usage per category, in display order (empty categories included).
+    pub categories: Vec<CategoryUsage>,
+    /// Usage per project, sorted by name.
+    pub projects: Vec<ProjectUsage>,
+    /// Image layers shared between several images, counted once and not attributed to a category.
+    pub shared_layers: u64,
+    /// Total space used by everything reported.
+    pub total_size: u64,
+    /// Total space reclaimable across all categories.
+    pub total_reclaimable: u64,
+}
+
+impl UsageReport {
+    /// Reclaimable space in the DevRS-managed categories (everything except `Other`).
+    pub fn devrs_reclaimable(&self) -> u64 {
+        self.categories
+            .iter()
+            .filter(|c| c.category != Category::Other)
+            .map(|c| c.reclaimable)
+            .sum()
+    }
+}
+
+/// Converts an API size (`-1` when unknown) to bytes.
+fn bytes(size: i64) -> u64 {
+    size.max(0) as u64
+}
+
+/// Adds one resource to the per-category and per-project totals.
+struct Accumulator {
+    categories: HashMap<Category, CategoryUsage>,
+    projects: BTreeMap<String, ProjectUsage>,
+}
+
+/// The kind of resource being added (selects the counter to increment).
+#[derive(Clone, Copy)]
+enum Kind {
+    Image,
+    Container,
+    Volume,
+    Cache,
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        Self {
+            categories: Category::ALL
+                .iter()
+                .map(|c| (*c, CategoryUsage::new(*c)))
+                .collect(),
+            projects: BTreeMap::new(),
+        }
+    }
+
+    fn add(
+        &mut self,
+        category: Category,
+        project: Option<&String>,
+        kind: Kind,
+        size: u64,
+        reclaimable: bool,
+    ) {
+        let reclaimed = if reclaimable { size } else { 0 };
+        let entry = self
+            .categories
+            .get_mut(&category)
+            .expect("every category is pre-populated");
+        match kind {
+            Kind::Image => entry.images += 1,
+            Kind::Container => entry.containers += 1,
+            Kind::Volume => entry.volumes += 1,
+            Kind::Cache => entry.cache_records += 1,
+        }
+        entry.size += size;
+        entry.reclaimable += reclaimed;
+
+        if let Some(project) = project {
+            let usage = self
+                .projects
+                .entry(project.clone())
+                .or_insert_with(|| ProjectUsage {
+                    project: project.clone(),
+                    ..Default::default()
+                });
+            match kind {
+                Kind::Image => usage.images += 1,
+                Kind::Container => usage.containers += 1,
+                Kind::Volume => usage.volumes += 1,
+                Kind::Cache => {}
+            }
+            usage.size += size;
+            usage.reclaimable += reclaimed;
+        }
+    }
+}
+
+/// Classifies a container by its (inherited) labels and image reference.
+fn container_role(container: &ContainerSummary, cfg: &Config) -> DevrsRole {
+    let labels = container.labels.clone().unwrap_or_default();
+    let image: Vec<String> = container.image.iter().cloned().collect();
+    roles::image_role(&image, &labels, cfg)
+}
+
+/// Builds the grouped report from a `system df` response.
+///
+/// # Arguments
+///
+/// * `usage` - The response from `common::docker::system::disk_usage`.
+/// * `cfg` - The loaded DevRS configuration (for role classification).
+///
+/// # Returns
+///
+/// * `UsageReport` - Usage grouped by category and project.
+pub fn build_report(usage: &SystemDataUsageResponse, cfg: &Config) -> UsageReport {
+    let mut acc = Accumulator::new();
+    let images = usage.images.as_deref().unwrap_or_default();
+    let containers = usage.containers.as_deref().unwrap_or_default();
+    let volumes = usage.volumes.as_deref().unwrap_or_default();
+    let build_cache = usage.build_cache.as_deref().unwrap_or_default();
+
+    // Images referenced by any container (running or stopped) cannot be removed.
+    let used_images: HashSet<&str> = containers
+        .iter()
+        .filter_map(|c| c.image_id.as_deref())
+        .collect();
+
+    // --- Images ---
+    let mut unique_total = 0u64;
+    for image in images {
+        let repo_tags: Vec<String> = image
+            .repo_tags
+            .iter()
+            .filter(|t| t.as_str() != "<none>:<none>")
+            .cloned()
+            .collect();
+        let role = roles::image_role(&repo_tags, &image.labels, cfg);
+        // Shared size is -1 when the daemon did not compute it.
+        let unique = bytes(image.size).saturating_sub(bytes(image.shared_size));
+        unique_total += unique;
+        let unused = !used_images.contains(image.id.as_str()) && image.containers <= 0;
+        acc.add(
+            Category::from_role(role),
+            image.labels.get(roles::PROJECT_LABEL),
+            Kind::Image,
+            unique,
+            unused,
+        );
+    }
+
+    // --- Containers ---
+    // Named volumes mounted by DevRS containers belong to DevRS.
+    let mut devrs_volumes: HashSet<&str> = HashSet::new();
+    for container in containers {
+        let role = container_role(container, cfg);
+        if role.is_devrs() {
+            for mount in container.mounts.iter().flatten() {
+                if let Some(name) = mount.name.as_deref() {
+                    devrs_volumes.insert(name);
+                }
+            }
+        }
+        let stopped = container.state.as_deref() != Some("running");
+        acc.add(
+            Category::from_role(role),
+            container
+                .labels
+                .as_ref()
+                .and_then(|l| l.get(roles::PROJECT_LABEL)),
+            Kind::Container,
+            bytes(container.size_rw.unwrap_or(0)),
+            stopped,
+        );
+    }
+
+    // --- Volumes ---
+    for volume in volumes {
+        let labelled = volume.labels.keys().any(|k| k.starts_with("devrs."));
+        let category = if labelled || devrs_volumes.contains(volume.name.as_str()) {
+            Category::Volumes
+        } else {
+            Category::Other
+        };
+        let (size, ref_count) = volume
+            .usage_data
+            .as_ref()
+            .map(|u| (bytes(u.size), u.ref_count))
+            .unwrap_or((0, -1));
+        acc.add(
+            category,
+            volume.labels.get(roles::PROJECT_LABEL),
+            Kind::Volume,
+            size,
+            ref_count == 0,
+        );
+    }
+
+    // --- Build cache ---
+    for record in build_cache {
+        let reclaimable = !record.in_use.unwrap_or(false) && !record.shared.unwrap_or(false);
+        acc.add(
+            Category::BuildCache,
+            None,
+            Kind::Cache,
+            bytes(record.size.unwrap_or(0)),
+            reclaimable,
+        );
+    }
+
+    let shared_layers = usage
+        .layers_size
+        .map(bytes)
+        .unwrap_or(0)
+        .saturating_sub(unique_total);
+    let categories: Vec<CategoryUsage> = Category::ALL
+        .iter()
+        .filter_map(|c| acc.categories.remove(c))
+        .collect();
+    let total_size = categories.iter().map(|c| c.size).sum::<u64>() + shared_layers;
+    let total_reclaimable = categories.iter().map(|c| c.reclaimable).sum();
+
+    UsageReport {
+        categories,
+        projects: acc.projects.into_values().collect(),
+        shared_layers,
+        total_size,
+        total_reclaimable,
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::{BuildCache, ImageSummary, MountPoint, Volume, VolumeUsageData};
+
+    /// Helper: a config using the default core image name.
+    fn test_config() -> Config {
+        let mut cfg = Config::default();
+        cfg.core_env.image_name = "devrs-core-env".to_string();
+        cfg
+    }
+
+    /// Helper: an image summary with optional devrs labels.
+    fn image(id: &str, tag: &str, size: i64, shared: i64, labels: &[(&str, &str)]) -> ImageSummary {
+        ImageSummary {
+            id: id.to_string(),
+            repo_tags: vec![tag.to_string()],
+            size,
+            shared_size: shared,
+            containers: -1,
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Helper: a container summary.
+    fn container(image_id: &str, image: &str, state: &str, size_rw: i64) -> ContainerSummary {
+        ContainerSummary {
+            image_id: Some(image_id.to_string()),
+            image: Some(image.to_string()),
+            state: Some(state.to_string()),
+            size_rw: Some(size_rw),
+            ..Default::default()
+        }
+    }
+
+    /// Helper: find a category in the report.
+    fn category(report: &UsageReport, category: Category) -> &CategoryUsage {
+        report
+            .categories
+            .iter()
+            .find(|c| c.category == category)
+            .unwrap()
+    }
+
+    /// Test grouping of images and containers by role, with reclaimable space.
+    #[test]
+    fn test_build_report_images_and_containers() {
+        let app_labels = [(roles::ROLE_LABEL, "app"), (roles::PROJECT_LABEL, "webapp")];
+        let usage = SystemDataUsageResponse {
+            layers_size: Some(1_000),
+            images: Some(vec![
+                image("sha256:core", "devrs-core-env:latest", 500, 100, &[]),
+                image("sha256:app1", "webapp:1", 200, 100, &app_labels),
+                image("sha256:app2", "webapp:2", 150, 100, &app_labels),
+                image("sha256:pg", "postgres:16", 50, 0, &[]),
+            ]),
+            containers: Some(vec![
+                container("sha256:core", "devrs-core-env:latest", "running", 30),
+                ContainerSummary {
+                    labels: Some(
+                        app_labels
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect(),
+                    ),
+                    ..container("sha256:app2", "webapp:2", "exited", 20)
+                },
+            ]),
+            ..Default::default()
+        };
+        let report = build_report(&usage, &test_config());
+
+        let core = category(&report, Category::CoreEnv);
+        assert_eq!((core.images, core.containers), (1, 1));
+        assert_eq!(core.size, 400 + 30);
+        assert_eq!(core.reclaimable, 0); // Image in use, container running.
+
+        let app = category(&report, Category::App);
+        assert_eq!((app.images, app.containers), (2, 1));
+        assert_eq!(app.size, 100 + 50 + 20);
+        assert_eq!(app.reclaimable, 100 + 20); // Unused app1 image + stopped container.
+
+        let other = category(&report, Category::Other);
+        assert_eq!(other.reclaimable, 50);
+        assert!(other.prune_hint.is_none());
+
+        // 1000 layers - (400 + 100 + 50 + 50) unique = 400 shared.
+        assert_eq!(report.shared_layers, 400);
+        assert_eq!(report.total_reclaimable, 170);
+        assert_eq!(report.devrs_reclaimable(), 120);
+
+        assert_eq!(report.projects.len(), 1);
+        let webapp = &report.projects[0];
+        assert_eq!(webapp.project, "webapp");
+        assert_eq!((webapp.images, webapp.containers), (2, 1));
+        assert_eq!(webapp.size, 170);
+    }
+
+    /// Test volume ownership and build cache accounting.
+    #[test]
+    fn test_build_report_volumes_and_cache() {
+        let volume = |name: &str, labels: &[(&str, &str)], size: i64, refs: i64| Volume {
+            name: name.to_string(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            usage_data: Some(VolumeUsageData {
+                size,
+                ref_count: refs,
+            }),
+            ..Default::default()
+        };
+        let usage = SystemDataUsageResponse {
+            containers: Some(vec![ContainerSummary {
+                mounts: Some(vec![MountPoint {
+                    name: Some("cargo-cache".to_string()),
+                    ..Default::default()
+                }]),
+                ..container("sha256:core", "devrs-core-env:latest", "running", 0)
+            }]),
+            volumes: Some(vec![
+                volume("cargo-cache", &[], 300, 1),
+                volume("old-data", &[(roles::PROJECT_LABEL, "api")], 200, 0),
+                volume("random", &[], 100, 0),
+            ]),
+            build_cache: Some(vec![
+                BuildCache {
+                    size: Some(40),
+                    in_use: Some(false),
+                    shared: Some(false),
+                    ..Default::default()
+                },
+                BuildCache {
+                    size: Some(60),
+                    in_use: Some(true),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+        let report = build_report(&usage, &test_config());
+
+        let volumes = category(&report, Category::Volumes);
+        assert_eq!(volumes.volumes, 2);
+        assert_eq!(volumes.size, 500);
+        assert_eq!(volumes.reclaimable, 200);
+        assert_eq!(category(&report, Category::Other).volumes, 1);
+
+        let cache = category(&report, Category::BuildCache);
+        assert_eq!(
+            (cache.cache_records, cache.size, cache.reclaimable),
+            (2, 100, 40)
+        );
+        assert_eq!(cache.describe_items(), "2 cache records");
+
+        assert_eq!(report.projects[0].project, "api");
+        assert_eq!(report.projects[0].volumes, 1);
+    }
+}
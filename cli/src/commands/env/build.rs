@@ -23,9 +23,11 @@
 //! 3. Construct the full image tag (e.g., "my-custom-env:beta", "devrs-core-env:latest"). Validate that the resulting name and tag are not empty.
 //! 4. Define the expected location of the Dockerfile (`presets/Dockerfile.devrs`) relative to the repository root and the build context (repository root, represented as ".").
 //! 5. Validate that the `Dockerfile.devrs` exists at the expected path (`presets/Dockerfile.devrs`). **Assumption:** This command is run from the DevRS repository root directory.
-//! 6. Call the shared Docker utility function `common::docker::build_image` with the final image tag, relative Dockerfile path, context path ("."), and cache options.
-//! 7. Stream build output from Docker to the console.
-//! 8. Report final success or failure.
+//!    A Dockerfile in `[core_env.build]` replaces it; otherwise `core_env.base_image` replaces it with a generated `FROM <base_image>` Dockerfile (`base_image_context`).
+//! 6. Label the image with its role and provenance (`common::docker::provenance`: content hash of the Dockerfile and the files it COPYs, git commit, build time, checkout location), so stale images can be detected later.
+//! 7. Call the shared Docker utility function `common::docker::build_image` with the final image tag, relative Dockerfile path, context path ("."), labels and cache options.
+//! 8. Stream build output from Docker to the console.
+//! 9. Report final success or failure.
 //!
 //! ## Examples
 //!
@@ -44,17 +46,25 @@
 //!
 //! The command builds the single, shared core development environment image, not project-specific application images (which are handled by `devrs container build`).
 //!
+use crate::common::docker::roles::DevrsRole; // Role recorded in image labels.
 use crate::{
     common::docker, // Access shared Docker utilities (specifically build_image).
-    core::{config, error::Result}, // Standard Result type for error handling & config loading.
+    core::{
+        config::{self, CoreEnvBuildConfig}, // Config loading and `[core_env.build]`.
+        error::Result,                      // Standard Result type for error handling.
+    },
 };
 use anyhow::{bail, Context}; // For adding context to errors & early returns.
 use clap::Parser; // For parsing command-line arguments.
-use std::path::Path; // For working with filesystem paths (Dockerfile location).
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+}; // Build arguments and filesystem paths (Dockerfile location).
 use tracing::{debug, info, warn}; // Logging framework utilities.
 
 // Define the relative path to the core environment Dockerfile within the repository.
-const CORE_DOCKERFILE_PATH: &str = "presets/Dockerfile.devrs";
+pub(super) const CORE_DOCKERFILE_PATH: &str = "presets/Dockerfile.devrs";
 // Define the default image name if config is missing or empty.
 const DEFAULT_CORE_IMAGE_NAME: &str = "devrs-core-env";
 // Define the default image tag if config is missing or empty.
@@ -85,6 +95,190 @@ pub struct BuildArgs {
     stage: Option<String>,
 }
 
+/// # Core Image Build Source (`CoreBuildSource`)
+///
+/// Where the core environment image is built from: `presets/Dockerfile.devrs` in a
+/// DevRS checkout by default, the Dockerfile configured in `[core_env.build]`, or a
+/// generated `FROM core_env.base_image` Dockerfile (e.g. imported from a project's
+/// devcontainer.json).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CoreBuildSource {
+    /// The build context directory.
+    pub context: PathBuf,
+    /// The Dockerfile, relative to `context`.
+    pub dockerfile: String,
+    /// Build arguments.
+    pub args: HashMap<String, String>,
+}
+
+impl CoreBuildSource {
+    /// Resolves the build source from the configuration.
+    ///
+    /// ## Arguments
+    ///
+    /// * `build`: The `[core_env.build]` settings.
+    /// * `base_image`: `core_env.base_image`, used when no Dockerfile is configured.
+    /// * `checkout`: The DevRS checkout to use when neither is configured.
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<CoreBuildSource>`: The resolved source.
+    /// * `Err`: If the configured Dockerfile is not inside the build context, or the
+    ///   Dockerfile for the base image cannot be written.
+    pub fn resolve(
+        build: &CoreEnvBuildConfig,
+        base_image: Option<&str>,
+        checkout: &Path,
+    ) -> Result<Self> {
+        let Some(dockerfile) = build.dockerfile.as_deref() else {
+            let context = match base_image {
+                Some(base_image) => base_image_context(base_image)?,
+                None => checkout.to_path_buf(),
+            };
+            let dockerfile = match base_image {
+                Some(_) => "Dockerfile",
+                None => CORE_DOCKERFILE_PATH,
+            };
+            return Ok(CoreBuildSource {
+                context,
+                dockerfile: dockerfile.to_string(),
+                args: build.args.clone(),
+            });
+        };
+        let absolute = |p: &Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+        let dockerfile = absolute(Path::new(dockerfile));
+        let context = match build.context.as_deref() {
+            Some(context) => absolute(Path::new(context)),
+            None => dockerfile
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("/")),
+        };
+        let Ok(relative) = dockerfile.strip_prefix(&context) else {
+            bail!(
+                "core_env.build.dockerfile '{}' must be inside the build context '{}'.",
+                dockerfile.display(),
+                context.display()
+            );
+        };
+        let relative = relative.to_string_lossy().into_owned();
+        Ok(CoreBuildSource {
+            context,
+            dockerfile: relative,
+            args: build.args.clone(),
+        })
+    }
+}
+
+/// Writes the build context of an image built `FROM` an upstream image: a directory
+/// holding only `Dockerfile`, under `~/.local/share/devrs/base-images/`. It stays in
+/// place so the provenance labels of the image keep pointing at it and staleness
+/// checks (`env status`, `env shell`) can hash it again.
+///
+/// ## Arguments
+///
+/// * `base_image`: The upstream image reference.
+///
+/// ## Returns
+///
+/// * `Result<PathBuf>`: The context directory.
+/// * `Err`: If the directory or the Dockerfile cannot be written.
+fn base_image_context(base_image: &str) -> Result<PathBuf> {
+    let slug: String = base_image
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let context = dirs::data_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("devrs")
+        .join("base-images")
+        .join(slug.to_lowercase());
+    fs::create_dir_all(&context)
+        .with_context(|| format!("Failed to create '{}'", context.display()))?;
+    let dockerfile = context.join("Dockerfile");
+    let content = format!(
+        "# Generated by DevRS from core_env.base_image.\nFROM {}\n",
+        base_image
+    );
+    fs::write(&dockerfile, content)
+        .with_context(|| format!("Failed to write '{}'", dockerfile.display()))?;
+    Ok(context)
+}
+
+/// # Build Core Environment Image (`build_core_image`)
+///
+/// Builds the core environment image from its `CoreBuildSource` (by default
+/// `presets/Dockerfile.devrs` inside a repository checkout), labelled with its DevRS
+/// role and its provenance (content hash of the Dockerfile and the files it copies,
+/// git commit, build time and the context location). The provenance labels let
+/// `env status`, `env shell` and `env exec` detect a stale image later. Shared by
+/// `env build`, `env rebuild` and `env shell --rebuild-if-stale`.
+///
+/// ## Arguments
+///
+/// * `full_image_tag`: The image to build (e.g., `devrs-core-env:latest`).
+/// * `source`: The build context, Dockerfile and build arguments.
+/// * `no_cache`: Build without Docker's layer cache.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` once the image is built.
+/// * `Err`: If the Dockerfile is missing, the labels cannot be computed, or the build fails.
+pub(super) async fn build_core_image(
+    full_image_tag: &str,
+    source: &CoreBuildSource,
+    no_cache: bool,
+) -> Result<()> {
+    let context_dir = source.context.as_path();
+    // --- Validate Dockerfile Existence ---
+    let dockerfile_path = context_dir.join(&source.dockerfile);
+    if !dockerfile_path.is_file() {
+        if source.dockerfile == CORE_DOCKERFILE_PATH {
+            bail!(
+                "Core environment Dockerfile not found at expected path: '{}'. Please run this command from the root of the devrs repository.",
+                dockerfile_path.display()
+            );
+        }
+        bail!(
+            "Dockerfile configured in core_env.build not found: '{}'.",
+            dockerfile_path.display()
+        );
+    }
+    info!("Using Dockerfile: {}", dockerfile_path.display()); // Log path used.
+    info!("Using build context: {}", context_dir.display()); // Log context used.
+
+    // --- Labels: role for `devrs images`, provenance for staleness checks ---
+    let mut labels = docker::roles::build_labels(DevrsRole::CoreEnv, None);
+    labels.extend(
+        docker::provenance::provenance_labels(context_dir, &source.dockerfile)
+            .context("Failed to compute image provenance labels")?,
+    );
+    debug!("Image labels: {:?}", labels);
+
+    // --- Execute Docker Build ---
+    println!(
+        "Building core environment image: {} (Using {}) (No Cache: {})...",
+        full_image_tag, source.dockerfile, no_cache
+    );
+    docker::build_image(
+        full_image_tag,                 // The final image tag.
+        &source.dockerfile,             // Relative path to Dockerfile within context.
+        &context_dir.to_string_lossy(), // Build context path.
+        no_cache,                       // Pass the no-cache flag.
+        &labels,
+        &source.args, // Build arguments from `[core_env.build]`.
+    )
+    .await // Await the async build process.
+    .with_context(|| {
+        // Add context if the build function returns an error.
+        format!(
+            "Failed to build core environment image '{}'",
+            full_image_tag
+        )
+    })
+}
+
 /// # Handle Environment Build Command (`handle_build`)
 ///
 /// The main asynchronous handler function for the `devrs env build` command.
@@ -97,12 +291,11 @@ pub struct BuildArgs {
 /// 3.  Attempts to load the DevRS configuration using `core::config::load_config`.
 /// 4.  Determines the final image name and tag, using config values if present and non-empty, otherwise falling back to defaults.
 /// 5.  Constructs the full image tag string (e.g., `image_name:image_tag`). Validates it's not empty.
-/// 6.  Defines the relative path to the Dockerfile (`presets/Dockerfile.devrs`) and the build context directory (".").
-/// 7.  Validates that the Dockerfile exists and is a file in the current working directory (assumed to be the repository root).
-/// 8.  Calls the shared `common::docker::build_image` function, providing the full image tag, relative Dockerfile path,
-///     context path ("."), and the `no_cache` flag.
-/// 9.  Streams Docker build output to the console.
-/// 10. Logs and prints a success message upon completion.
+/// 6.  Calls `build_core_image` with the current directory (assumed to be the repository root) as context, which
+///     validates that `presets/Dockerfile.devrs` exists, computes the role and provenance labels, and calls the
+///     shared `common::docker::build_image` function with the `no_cache` flag.
+/// 7.  Streams Docker build output to the console.
+/// 8.  Logs and prints a success message upon completion.
 ///
 /// ## Arguments
 ///
@@ -131,11 +324,15 @@ pub async fn handle_build(args: BuildArgs) -> Result<()> {
     // Initialize with defaults.
     let mut image_name = DEFAULT_CORE_IMAGE_NAME.to_string();
     let mut image_tag = DEFAULT_CORE_IMAGE_TAG.to_string();
+    let mut build_config = CoreEnvBuildConfig::default();
+    let mut base_image = None;
 
     // Attempt to load configuration.
     match config::load_config() {
         Ok(cfg) => {
             info!("Successfully loaded configuration.");
+            build_config = cfg.core_env.build.clone();
+            base_image = cfg.core_env.base_image.clone();
             // Use configured name if not empty, else keep default.
             if !cfg.core_env.image_name.trim().is_empty() {
                 image_name = cfg.core_env.image_name;
@@ -177,49 +374,10 @@ pub async fn handle_build(args: BuildArgs) -> Result<()> {
         );
     }
 
-    // --- Define Build Paths ---
-    // Define the relative path to the Dockerfile.
-    let dockerfile_path_str = CORE_DOCKERFILE_PATH;
-    // Define the build context directory (always the current directory, represented as ".").
-    let context_dir_str = ".";
-    // Create Path objects for validation.
-    let dockerfile_path = Path::new(dockerfile_path_str);
-    let context_dir = Path::new(context_dir_str); // Represents current directory.
-
-    // --- Validate Dockerfile Existence ---
-    // Check if the Dockerfile exists and is a file in the expected location relative to CWD.
-    // Assumes the command is run from the repository root.
-    if !dockerfile_path.is_file() {
-        // Use is_file() for better check
-        bail!(
-            "Core environment Dockerfile not found at expected path: '{}'. Please run this command from the root of the devrs repository.",
-            dockerfile_path.display()
-        );
-    }
-    info!("Using Dockerfile: {}", dockerfile_path.display()); // Log path used.
-    info!("Using build context: {}", context_dir.display()); // Log context used (".").
-
-    // --- Execute Docker Build ---
-    // Print message to user indicating the start of the build.
-    println!(
-        "Building core environment image: {} (Using {}) (No Cache: {})...",
-        full_image_tag, dockerfile_path_str, args.no_cache
-    );
-    // Call the shared Docker build utility function.
-    docker::build_image(
-        &full_image_tag,     // The final image tag.
-        dockerfile_path_str, // Relative path to Dockerfile within context.
-        context_dir_str,     // Build context path (".").
-        args.no_cache,       // Pass the no-cache flag.
-    )
-    .await // Await the async build process.
-    .with_context(|| {
-        // Add context if the build function returns an error.
-        format!(
-            "Failed to build core environment image '{}'",
-            full_image_tag
-        )
-    })?;
+    // --- Build from the configured Dockerfile, or from the current directory
+    // (assumed to be the repository root) ---
+    let source = CoreBuildSource::resolve(&build_config, base_image.as_deref(), Path::new("."))?;
+    build_core_image(&full_image_tag, &source, args.no_cache).await?;
 
     // --- Report Success ---
     // Log and print success message.
@@ -275,6 +433,33 @@ mod tests {
         Ok(temp_dir) // Return the guard to manage the temp directory's lifetime.
     }
 
+    /// Test resolving the build source: the DevRS checkout by default, or a configured
+    /// Dockerfile relative to its context.
+    #[test]
+    fn test_core_build_source_resolve() {
+        let checkout = Path::new("/src/devrs");
+        let source =
+            CoreBuildSource::resolve(&CoreEnvBuildConfig::default(), None, checkout).unwrap();
+        assert_eq!(source.context, checkout);
+        assert_eq!(source.dockerfile, CORE_DOCKERFILE_PATH);
+
+        let build = CoreEnvBuildConfig {
+            dockerfile: Some("/proj/.devcontainer/Dockerfile".to_string()),
+            context: Some("/proj".to_string()),
+            args: [("VARIANT".to_string(), "1".to_string())].into(),
+        };
+        let source = CoreBuildSource::resolve(&build, Some("ignored:1"), checkout).unwrap();
+        assert_eq!(source.context, Path::new("/proj"));
+        assert_eq!(source.dockerfile, ".devcontainer/Dockerfile");
+        assert_eq!(source.args.len(), 1);
+
+        let outside = CoreEnvBuildConfig {
+            context: Some("/other".to_string()),
+            ..build
+        };
+        assert!(CoreBuildSource::resolve(&outside, None, checkout).is_err());
+    }
+
     /// Test parsing of arguments like --no-cache and --stage.
     #[test]
     fn test_build_args_parsing() {
@@ -21,10 +21,11 @@
 //! 1. Parse command-line arguments (`ExecArgs`) using `clap`, capturing flags like `-i` (interactive), `-t` (TTY), `--name`, `--user`, `--workdir`, and the command itself.
 //! 2. Load the DevRS configuration (`core::config`) to determine the core environment settings (like image name to derive the default container name).
 //! 3. Determine the target core environment container name (using `--name` if provided, otherwise generating the default `<image_name>-instance`).
-//! 4. Call the shared `common::docker::lifecycle::ensure_core_env_running` utility function to guarantee the target container exists and is in a running state, starting or creating it if necessary.
-//! 5. Validate that the user provided a command to execute.
-//! 6. Call the shared `common::docker::interaction::exec_in_container` utility function, passing the container name, command, and all relevant flags (`interactive`, `tty`, `user`, `workdir`). This function handles the Docker `exec` API call and manages I/O streaming.
-//! 7. Check the exit code returned by `exec_in_container`. If the exit code is 0, return `Ok(())`. If it's non-zero, return an `Err` of type `DevrsError::ExternalCommand` containing the command and exit code.
+//! 4. Warn on stderr if the core image or container is stale (`freshness::check`).
+//! 5. Call the shared `common::docker::lifecycle::ensure_core_env_running` utility function to guarantee the target container exists and is in a running state, starting or creating it if necessary.
+//! 6. Validate that the user provided a command to execute.
+//! 7. Call the shared `common::docker::interaction::exec_in_container` utility function, passing the container name, command, and all relevant flags (`interactive`, `tty`, `user`, `workdir`). This function handles the Docker `exec` API call and manages I/O streaming. With `--host-paths`, `exec_in_container_with_host_paths` is used instead, which rewrites container paths in the output to host paths.
+//! 8. Check the exit code returned by `exec_in_container`. If the exit code is 0, return `Ok(())`. If it's non-zero, return an `Err` of type `DevrsError::ExternalCommand` containing the command and exit code.
 //!
 //! ## Usage
 //!
@@ -44,17 +45,22 @@
 //! # Run a command in a specific working directory inside the container
 //! devrs env exec -w /home/me/code/my-project git status
 //!
+//! # Show compiler errors with host paths (e.g. for clickable links in the host terminal)
+//! devrs env exec --host-paths cargo build
+//!
 //! # Run in a specifically named core environment container
 //! devrs env exec --name my-custom-env-instance make all
 //! ```
 //!
 //! The `-i` and `-t` flags mimic the behavior of `docker exec -it`, enabling interactive sessions when needed.
 //!
+use super::{freshness, hooks}; // Staleness warnings and lifecycle hooks.
 use crate::{
-    common::docker::{self}, // Access shared Docker utilities (ensure_running, exec_in_container).
+    common::docker::{self, paths::PathMapper}, // Docker utilities (exec_in_container) and mount path mapping.
     core::{
         config,                      // Access configuration loading.
         error::{DevrsError, Result}, // Standard Result type and custom errors.
+        secrets,                     // `[core_env.secrets]` for the command.
     },
 };
 use anyhow::{anyhow, Context}; // For easy error creation and adding context.
@@ -89,7 +95,7 @@ pub struct ExecArgs {
 
     /// Optional: Specifies the username or UID to run the command as inside the container
     /// (e.g., 'root', '1000', 'vscode').
-    /// If omitted, the command runs as the container's default configured user.
+    /// If omitted, the command runs as `core_env.user`, or the container's default user.
     #[arg(long, short)] // Define as `--user` or `-u`.
     user: Option<String>,
 
@@ -99,6 +105,23 @@ pub struct ExecArgs {
     #[arg(long, short = 'w')] // Define as `--workdir` or `-w`.
     workdir: Option<String>,
 
+    /// Optional: Runs the command in the container path corresponding to the current host
+    /// directory, translated through `core_env.mounts` (falls back to the default working
+    /// directory with a warning if the current directory is not mounted). Used by host shims.
+    #[arg(long, conflicts_with = "workdir")] // Define as `--map-cwd`.
+    map_cwd: bool,
+
+    /// Optional: Exits `devrs` with the command's exit code instead of reporting a non-zero
+    /// exit as an error, so scripts and shims see the tool's own status.
+    #[arg(long)] // Define as `--exit-code`.
+    exit_code: bool,
+
+    /// Optional: Rewrites container paths in the command's output to the corresponding
+    /// host paths (through `core_env.mounts`), so errors like `/home/me/code/app/src/lib.rs:3:5`
+    /// can be opened on the host. Binary output is passed through untouched.
+    #[arg(long)] // Define as `--host-paths`.
+    host_paths: bool,
+
     /// The command and its arguments to execute inside the core environment container.
     /// All arguments provided after the options (or after `--`) are captured into this vector.
     /// Example: `devrs env exec ls -la /tmp` -> `command` will be `vec!["ls", "-la", "/tmp"]`.
@@ -116,12 +139,13 @@ pub struct ExecArgs {
 /// 1.  Logs the start and parsed arguments.
 /// 2.  Loads the DevRS configuration (`core::config`) to get core environment details.
 /// 3.  Determines the target container name using `get_core_env_container_name` (uses `--name` override or generates default from config).
-/// 4.  Calls `common::docker::lifecycle::ensure_core_env_running` to make sure the target container exists and is running, starting/creating it if necessary.
-/// 5.  Validates that `args.command` is not empty (Clap's `required=true` should normally prevent this, but added check for safety).
-/// 6.  Calls `common::docker::interaction::exec_in_container` with the container name, command vector, and the `interactive`, `tty`, `workdir`, and `user` arguments. This function handles the underlying Docker `exec` call and I/O streaming.
-/// 7.  Checks the integer exit code returned by `exec_in_container`.
-/// 8.  If the exit code is 0, logs success and returns `Ok(())`.
-/// 9.  If the exit code is non-zero, logs a warning and returns an `Err` of type `DevrsError::ExternalCommand`, including the command string and exit code for clear error reporting.
+/// 4.  Prints staleness warnings to stderr if the image or container is out of date (`freshness::check`).
+/// 5.  Calls `hooks::ensure_running_with_hooks` (wrapping `common::docker::lifecycle::ensure_core_env_running`, plus the `on_create`/`on_start` hooks) to make sure the target container exists and is running, starting/creating it if necessary. Forwarded agent sockets are re-linked on the way.
+/// 6.  Validates that `args.command` is not empty (Clap's `required=true` should normally prevent this, but added check for safety).
+/// 7.  Calls `common::docker::interaction::exec_in_container` with the container name, command vector, and the `interactive`, `tty`, `workdir`, and `user` arguments. This function handles the underlying Docker `exec` call and I/O streaming. With `--host-paths`, calls `exec_in_container_with_host_paths` so container paths in the output are shown as host paths.
+/// 8.  Checks the integer exit code returned by `exec_in_container`.
+/// 9.  If the exit code is 0, logs success and returns `Ok(())`.
+/// 10. If the exit code is non-zero, logs a warning and returns an `Err` of type `DevrsError::ExternalCommand`, including the command string and exit code for clear error reporting.
 ///
 /// ## Arguments
 ///
@@ -147,17 +171,28 @@ pub async fn handle_exec(args: ExecArgs) -> Result<()> {
         default_name
     });
 
-    // 3. Ensure the core environment container is ready (exists and is running).
+    // 3. Warn (on stderr, so command output stays clean) if the environment is out of date.
+    match freshness::check(&cfg, &container_name).await {
+        Ok(Some(report)) => {
+            for warning in report.warnings() {
+                eprintln!("⚠️  {}", warning);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => debug!("Skipping staleness check: {:#}", e),
+    }
+
+    // 4. Ensure the core environment container is ready (exists and is running).
     // This function will start or create the container if needed.
     let _ =
-        docker::lifecycle::ensure_core_env_running(&container_name, &cfg) //
+        hooks::ensure_running_with_hooks(&container_name, &cfg) // Also runs on_create/on_start hooks.
             .await // Await the async check/preparation.
             .with_context(|| {
                 format!("Failed to prepare container '{}' for exec", container_name)
             })?;
     // We don't need the return value (bool indicating creation) here.
 
-    // 4. Validate that a command was actually provided.
+    // 5. Validate that a command was actually provided.
     // Clap's `required=true` should prevent this, but check defensively.
     if args.command.is_empty() {
         return Err(anyhow!(DevrsError::ArgumentParsing(
@@ -165,22 +200,46 @@ pub async fn handle_exec(args: ExecArgs) -> Result<()> {
         )));
     }
 
-    // 5. Execute the command inside the container using the shared utility.
+    // 6. Execute the command inside the container using the shared utility.
     info!(
         "Executing command {:?} in container '{}' (Interactive: {}, TTY: {})",
         args.command, container_name, args.interactive, args.tty
     );
-    let exit_code = docker::interaction::exec_in_container(
-        //
-        &container_name,         // Target container.
-        &args.command,           // Command and arguments vector.
-        args.interactive,        // Pass interactive flag.
-        args.tty,                // Pass TTY flag.
-        args.workdir.as_deref(), // Pass optional working directory.
-        args.user.as_deref(),    // Pass optional user.
-    )
-    .await // Await the async execution.
-    .with_context(|| {
+    let workdir = if args.map_cwd {
+        Some(container_cwd(&cfg))
+    } else {
+        args.workdir.clone()
+    };
+    // `--user` wins over `core_env.user`.
+    let user = args.user.as_deref().or(cfg.core_env.user.as_deref());
+    // Secrets are resolved on the host now and only passed to this exec.
+    let secret_env = secrets::exec_env(&cfg.core_env)?;
+    let exec_result = if args.host_paths {
+        docker::interaction::exec_in_container_with_host_paths(
+            &container_name,
+            &args.command,
+            args.interactive,
+            args.tty,
+            workdir.as_deref(),
+            user,
+            &secret_env,
+            PathMapper::from_mounts(&cfg.core_env.mounts), // Container → host output paths.
+        )
+        .await
+    } else {
+        docker::interaction::exec_in_container(
+            //
+            &container_name,    // Target container.
+            &args.command,      // Command and arguments vector.
+            args.interactive,   // Pass interactive flag.
+            args.tty,           // Pass TTY flag.
+            workdir.as_deref(), // Pass optional working directory.
+            user,               // Pass optional user.
+            &secret_env,        // `inject = "env"` secrets.
+        )
+        .await // Await the async execution.
+    };
+    let exit_code = exec_result.with_context(|| {
         // Add context to potential errors during execution.
         format!(
             "Failed to execute command {:?} in container '{}'",
@@ -196,6 +255,10 @@ pub async fn handle_exec(args: ExecArgs) -> Result<()> {
             args.command, container_name
         );
         Ok(()) // Return Ok for overall success.
+    } else if args.exit_code {
+        // Pass the tool's status through unchanged (e.g. for shims); nothing to report.
+        debug!("Passing through exit code {}.", exit_code);
+        std::process::exit(i32::try_from(exit_code).unwrap_or(1));
     } else {
         // Command failed (non-zero exit code).
         warn!(
@@ -216,6 +279,38 @@ pub async fn handle_exec(args: ExecArgs) -> Result<()> {
     }
 }
 
+/// # Container Working Directory (`container_cwd`)
+/// Translates the current host directory into the container through `core_env.mounts`
+/// (`--map-cwd`). Falls back to `core_env.default_workdir` with a warning if the current
+/// directory cannot be determined or is not under any mount.
+fn container_cwd(cfg: &config::Config) -> String {
+    let fallback = || cfg.core_env.default_workdir.clone();
+    let host_dir = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!(
+                "Cannot determine current directory ({}); running in '{}'.",
+                e, cfg.core_env.default_workdir
+            );
+            return fallback();
+        }
+    };
+    let mapped = PathMapper::from_mounts(&cfg.core_env.mounts).to_container(&host_dir);
+    debug!(
+        "Host directory '{}' maps to {:?}",
+        host_dir.display(),
+        mapped
+    );
+    mapped.unwrap_or_else(|| {
+        eprintln!(
+            "⚠️  '{}' is not mounted into the core environment; running in '{}'.",
+            host_dir.display(),
+            cfg.core_env.default_workdir
+        );
+        fallback()
+    })
+}
+
 /// # Get Core Environment Container Name (`get_core_env_container_name`)
 ///
 /// A helper function to consistently derive the default name for the core development
@@ -298,6 +393,28 @@ mod tests {
         assert!(args.workdir.is_none());
     }
 
+    /// Test the shim-oriented flags, and that `--map-cwd` conflicts with `--workdir`.
+    #[test]
+    fn test_exec_args_shim_flags() {
+        let args =
+            ExecArgs::try_parse_from(["exec", "--map-cwd", "--exit-code", "--", "cargo", "test"])
+                .unwrap();
+        assert!(args.map_cwd);
+        assert!(args.exit_code);
+        assert_eq!(args.command, vec!["cargo", "test"]);
+        assert!(!args.host_paths);
+
+        let args =
+            ExecArgs::try_parse_from(["exec", "--host-paths", "--", "cargo", "build"]).unwrap();
+        assert!(args.host_paths);
+
+        let result = ExecArgs::try_parse_from(["exec", "--map-cwd", "-w", "/app", "--", "ls"]);
+        assert!(
+            result.is_err(),
+            "--map-cwd and --workdir are mutually exclusive"
+        );
+    }
+
     /// Test that the command fails parsing if no command is provided after the options.
     #[test]
     fn test_exec_args_requires_command() {
@@ -0,0 +1,256 @@
+//! # DevRS Core Environment Freshness Checks
+//!
+//! File: cli/src/commands/env/freshness.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Detects when the core environment is out of date, so that `env status`,
+//! `env shell` and `env exec` can warn about it:
+//!
+//! - **Stale image:** `presets/Dockerfile.devrs` (or a file it copies) changed since
+//!   the local image was built. Detected by recomputing the content hash recorded in
+//!   the image's `devrs.content-hash` label, in the checkout recorded in
+//!   `devrs.build-context`.
+//! - **Outdated container:** the container was created from an older image than the
+//!   one the configured tag points at now (e.g. after `env build` without recreating
+//!   the container).
+//!
+//! ## Architecture
+//!
+//! - `assess` is the pure decision over labels, hashes and image IDs (unit tested).
+//! - `check` gathers the inputs from Docker and the filesystem.
+//! - Checks are advisory: callers log and ignore errors from `check`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! # use crate::core::config::Config;
+//! # async fn example(cfg: &Config) -> crate::core::error::Result<()> {
+//! if let Some(freshness) = freshness::check(cfg, "devrs-core-env-instance").await? {
+//!     for warning in freshness.warnings() {
+//!         eprintln!("⚠️  {}", warning);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::{
+    common::docker::{self, provenance}, // Image/container inspection and provenance labels.
+    core::{
+        config::Config,              // Core environment image name and tag.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+    },
+};
+use std::{collections::HashMap, path::PathBuf}; // Labels and the recorded checkout.
+use tracing::debug; // Logging framework utilities.
+
+/// # Stale Reason (`StaleReason`)
+///
+/// One way in which the core environment is out of date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum StaleReason {
+    /// The Dockerfile or the files it copies changed since the image was built.
+    SourcesChanged,
+    /// The container runs an older image than the configured tag.
+    ContainerOutdated,
+}
+
+/// # Freshness Report (`Freshness`)
+///
+/// The result of `check`: the image's provenance and what, if anything, is stale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Freshness {
+    /// The image reference checked (`name:tag`).
+    pub image: String,
+    /// When the image was built (from `devrs.build-time`), if recorded.
+    pub built_at: Option<String>,
+    /// The git commit the image was built from (from `devrs.git-commit`), if recorded.
+    pub commit: Option<String>,
+    /// The checkout the image was built from, if recorded and still present.
+    pub context: Option<PathBuf>,
+    /// Whether the image carries a content hash at all (older images do not).
+    pub tracked: bool,
+    /// What is out of date (empty if the environment is fresh).
+    pub reasons: Vec<StaleReason>,
+}
+
+impl Freshness {
+    /// Whether the image was built from outdated sources.
+    pub fn image_stale(&self) -> bool {
+        self.reasons.contains(&StaleReason::SourcesChanged)
+    }
+
+    /// Whether the container needs to be recreated to use the current image.
+    pub fn container_outdated(&self) -> bool {
+        self.reasons.contains(&StaleReason::ContainerOutdated)
+    }
+
+    /// Human-readable warnings, one per stale reason.
+    pub fn warnings(&self) -> Vec<String> {
+        self.reasons
+            .iter()
+            .map(|reason| match reason {
+                StaleReason::SourcesChanged => format!(
+                    "Image '{}' is stale: presets/Dockerfile.devrs or the files it copies changed since it was built{}.",
+                    self.image,
+                    self.built_at
+                        .as_deref()
+                        .map(|t| format!(" ({})", t))
+                        .unwrap_or_default()
+                ),
+                StaleReason::ContainerOutdated => format!(
+                    "The container runs an older image than '{}'; recreate it to pick up the new image.",
+                    self.image
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Decides what is stale from the gathered inputs.
+///
+/// # Arguments
+///
+/// * `labels` - The image's labels.
+/// * `current_hash` - The content hash of the sources as they are now, if computable.
+/// * `image_id` - The ID the configured tag points at.
+/// * `container_image_id` - The image ID the container was created from, if a container exists.
+///
+/// # Returns
+///
+/// * `Vec<StaleReason>` - The stale reasons, empty if everything is current or unknown.
+pub(super) fn assess(
+    labels: &HashMap<String, String>,
+    current_hash: Option<&str>,
+    image_id: Option<&str>,
+    container_image_id: Option<&str>,
+) -> Vec<StaleReason> {
+    let mut reasons = Vec::new();
+    if let (Some(built), Some(current)) = (labels.get(provenance::CONTENT_HASH_LABEL), current_hash)
+    {
+        if built != current {
+            reasons.push(StaleReason::SourcesChanged);
+        }
+    }
+    if let (Some(image), Some(container)) = (image_id, container_image_id) {
+        if image != container {
+            reasons.push(StaleReason::ContainerOutdated);
+        }
+    }
+    reasons
+}
+
+/// Checks the core environment image (and container, if it exists) for staleness.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration (`core_env.image_name`/`image_tag`).
+/// * `container_name` - The core environment container.
+///
+/// # Returns
+///
+/// * `Result<Option<Freshness>>` - The report, or `None` if the image does not exist locally.
+///
+/// # Errors
+///
+/// Returns an error if Docker cannot be queried (other than "not found").
+pub(super) async fn check(cfg: &Config, container_name: &str) -> Result<Option<Freshness>> {
+    let image = format!("{}:{}", cfg.core_env.image_name, cfg.core_env.image_tag);
+    let details = match docker::images::inspect_image(&image).await {
+        Ok(details) => details,
+        Err(e)
+            if e.downcast_ref::<DevrsError>()
+                .is_some_and(|de| matches!(de, DevrsError::ImageNotFound { .. })) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let labels = details.config.and_then(|c| c.labels).unwrap_or_default();
+
+    // Recompute the content hash in the checkout the image was built from, if it still exists.
+    let context = labels
+        .get(provenance::BUILD_CONTEXT_LABEL)
+        .map(PathBuf::from)
+        .filter(|p| p.is_dir());
+    let current_hash = match (&context, labels.get(provenance::DOCKERFILE_LABEL)) {
+        (Some(context), Some(dockerfile)) => provenance::content_hash(context, dockerfile)
+            .map_err(|e| debug!("Cannot recompute content hash: {:#}", e))
+            .ok(),
+        _ => None,
+    };
+
+    let container_image_id = match docker::state::inspect_container(container_name).await {
+        Ok(container) => container.image,
+        Err(e) => {
+            debug!("No container to compare against: {:#}", e);
+            None
+        }
+    };
+
+    let reasons = assess(
+        &labels,
+        current_hash.as_deref(),
+        details.id.as_deref(),
+        container_image_id.as_deref(),
+    );
+    debug!("Freshness of '{}': {:?}", image, reasons);
+    Ok(Some(Freshness {
+        image,
+        built_at: labels.get(provenance::BUILD_TIME_LABEL).cloned(),
+        commit: labels.get(provenance::GIT_COMMIT_LABEL).cloned(),
+        context,
+        tracked: labels.contains_key(provenance::CONTENT_HASH_LABEL),
+        reasons,
+    }))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the staleness decision for each combination of inputs.
+    #[test]
+    fn test_assess() {
+        let labels = HashMap::from([(
+            provenance::CONTENT_HASH_LABEL.to_string(),
+            "sha256:aaa".to_string(),
+        )]);
+        assert!(assess(&labels, Some("sha256:aaa"), Some("id1"), Some("id1")).is_empty());
+        assert_eq!(
+            assess(&labels, Some("sha256:bbb"), Some("id1"), None),
+            vec![StaleReason::SourcesChanged]
+        );
+        assert_eq!(
+            assess(&labels, None, Some("id2"), Some("id1")),
+            vec![StaleReason::ContainerOutdated]
+        );
+        // Images built before provenance labels existed cannot be checked for source changes.
+        assert!(assess(&HashMap::new(), Some("sha256:bbb"), None, None).is_empty());
+    }
+
+    /// Test the warning texts.
+    #[test]
+    fn test_freshness_warnings() {
+        let freshness = Freshness {
+            image: "devrs-core-env:latest".to_string(),
+            built_at: Some("2024-03-01T10:00:00Z".to_string()),
+            commit: None,
+            context: None,
+            tracked: true,
+            reasons: vec![StaleReason::SourcesChanged, StaleReason::ContainerOutdated],
+        };
+        assert!(freshness.image_stale());
+        assert!(freshness.container_outdated());
+        let warnings = freshness.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("(2024-03-01T10:00:00Z)"));
+    }
+}
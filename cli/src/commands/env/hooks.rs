@@ -0,0 +1,520 @@
+//! # DevRS Core Environment Lifecycle Hooks
+//!
+//! File: cli/src/commands/env/hooks.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Runs the `[core_env.hooks]` commands inside the core environment container, so
+//! per-developer setup (`rustup component add ...`, `npm ci`, git config tweaks)
+//! happens automatically instead of by hand after every recreate:
+//!
+//! - **`on_create`**: once, after `ensure_core_env_running` creates the container.
+//! - **`on_start`**: every time the container is started (including after creation).
+//! - **`on_shell`**: before each `devrs env shell` session attaches.
+//!
+//! Each hook runs as `sh -c <command>` with its own user, working directory, timeout and
+//! failure policy (`warn` or `abort`), and with the `[core_env.secrets]` variables.
+//! Output is echoed and appended to a log file, and the result of the latest run of
+//! each hook is recorded for `devrs env status`.
+//!
+//! ## Architecture
+//!
+//! - `ensure_running_with_hooks` wraps `docker::lifecycle::ensure_core_env_running` and
+//!   runs `on_create`/`on_start` depending on what it had to do.
+//! - `run_stage` runs one stage's hooks via `docker::interaction::exec_in_container_captured`.
+//!   The timeout is enforced inside the container by running the hook under coreutils
+//!   `timeout`, which signals the hook's whole process group, so nothing keeps running after
+//!   the deadline.
+//! - Records live in `~/.local/state/devrs/hooks/<container>.json` and output in
+//!   `<container>.log` next to it. A stage's records are replaced on each run.
+//!
+//! ## Usage
+//!
+//! ```toml
+//! [[core_env.hooks.on_create]]
+//! command = "rustup component add clippy rustfmt"
+//!
+//! [[core_env.hooks.on_start]]
+//! command = "npm ci"
+//! workdir = "/code/web"
+//! timeout = "5m"
+//! on_failure = "abort"
+//! ```
+//!
+use crate::{
+    common::{docker, ui::format}, // Container lifecycle, exec, and time formatting.
+    core::{
+        config::{Config, HookConfig, HookFailurePolicy}, // Hook definitions.
+        error::{DevrsError, Result},                     // Standard Result type and custom errors.
+        secrets,                                         // `[core_env.secrets]` for hook commands.
+    },
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use serde::{Deserialize, Serialize}; // Hook records are stored as JSON.
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+}; // Record/log files and timing.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+/// How long a hook may run when it sets no `timeout`.
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How long a timed-out hook gets after `SIGTERM` before `timeout` sends `SIGKILL`.
+const KILL_GRACE_SECS: u64 = 5;
+
+/// Exit codes `timeout` reports when it had to stop the command (`SIGTERM`, then `SIGKILL`).
+const TIMEOUT_EXIT_CODES: [i64; 2] = [124, 137];
+
+/// # Hook Stage (`HookStage`)
+///
+/// The lifecycle event a hook list belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HookStage {
+    /// After the container is created.
+    Create,
+    /// After the container is started.
+    Start,
+    /// Before a shell session attaches.
+    Shell,
+}
+
+impl HookStage {
+    /// The configuration key of the stage (e.g. `on_create`).
+    pub fn key(self) -> &'static str {
+        match self {
+            HookStage::Create => "on_create",
+            HookStage::Start => "on_start",
+            HookStage::Shell => "on_shell",
+        }
+    }
+
+    /// The configured hooks of this stage.
+    fn hooks(self, cfg: &Config) -> &[HookConfig] {
+        match self {
+            HookStage::Create => &cfg.core_env.hooks.on_create,
+            HookStage::Start => &cfg.core_env.hooks.on_start,
+            HookStage::Shell => &cfg.core_env.hooks.on_shell,
+        }
+    }
+}
+
+/// # Hook Status (`HookStatus`)
+///
+/// The outcome of one hook run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum HookStatus {
+    /// Exited with code 0.
+    Succeeded,
+    /// Exited with a non-zero code.
+    Failed,
+    /// Did not finish within its timeout.
+    TimedOut,
+    /// Could not be run (e.g. Docker error).
+    Error,
+}
+
+/// # Hook Record (`HookRecord`)
+///
+/// The result of the latest run of one hook, as shown by `devrs env status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct HookRecord {
+    /// The stage key (`on_create`, `on_start`, `on_shell`).
+    pub stage: String,
+    /// The hook's command.
+    pub command: String,
+    /// The outcome.
+    pub status: HookStatus,
+    /// The exit code, if the command finished.
+    pub exit_code: Option<i64>,
+    /// When the hook started (Unix seconds).
+    pub started_at: i64,
+    /// How long it ran, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// The directory holding hook records and logs (`~/.local/state/devrs/hooks`).
+fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("devrs")
+        .join("hooks")
+}
+
+/// The file holding the hook records of a container.
+fn records_path(dir: &Path, container: &str) -> PathBuf {
+    dir.join(format!("{}.json", container))
+}
+
+/// The file receiving the hook output of a container.
+pub(super) fn log_path(container: &str) -> PathBuf {
+    state_dir().join(format!("{}.log", container))
+}
+
+/// Loads the stored hook records of a container (empty if none or unreadable).
+fn load_records(dir: &Path, container: &str) -> Vec<HookRecord> {
+    fs::read_to_string(records_path(dir, container))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Replaces the records of `stage` in `existing` with `runs`, keeping other stages.
+fn replace_stage(
+    existing: Vec<HookRecord>,
+    stage: HookStage,
+    runs: Vec<HookRecord>,
+) -> Vec<HookRecord> {
+    existing
+        .into_iter()
+        .filter(|r| r.stage != stage.key())
+        .chain(runs)
+        .collect()
+}
+
+/// Stores the records of one stage run, keeping the records of other stages.
+fn save_records(
+    dir: &Path,
+    container: &str,
+    stage: HookStage,
+    runs: Vec<HookRecord>,
+) -> Result<()> {
+    let records = replace_stage(load_records(dir, container), stage, runs);
+    let path = records_path(dir, container);
+    let json =
+        serde_json::to_string_pretty(&records).context("Failed to serialize hook records")?;
+    fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&path, json))
+        .map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to write '{}': {}",
+                path.display(),
+                e
+            )))
+        })
+}
+
+/// Returns the latest hook records of a container, for `devrs env status`.
+pub(super) fn latest_records(container: &str) -> Vec<HookRecord> {
+    load_records(&state_dir(), container)
+}
+
+/// Appends one hook run (header line plus output) to the container's hook log.
+fn append_log(container: &str, record: &HookRecord, output: &[u8]) {
+    let path = log_path(container);
+    let result = fs::create_dir_all(state_dir()).and_then(|_| {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        writeln!(
+            file,
+            "=== {} {} `{}`: {:?} (exit {}, {} ms)",
+            chrono::DateTime::from_timestamp(record.started_at, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+            record.stage,
+            record.command,
+            record.status,
+            record
+                .exit_code
+                .map_or_else(|| "-".to_string(), |c| c.to_string()),
+            record.duration_ms
+        )?;
+        file.write_all(output)?;
+        if !output.ends_with(b"\n") && !output.is_empty() {
+            writeln!(file)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!("Failed to write hook log '{}': {}", path.display(), e);
+    }
+}
+
+/// Ensures the core environment container is running and runs the matching hooks.
+///
+/// `on_create` runs if the container had to be created, then `on_start` runs if it was
+/// not running before (created or started from stopped).
+/// Forwarded agents are pointed at the host's current sockets first
+/// (`docker::agents::relink`), so hooks and the session that follows use the current
+/// ssh-agent/gpg-agent.
+///
+/// # Arguments
+///
+/// * `container_name` - The core environment container.
+/// * `cfg` - The loaded configuration (container settings and hooks).
+///
+/// # Returns
+///
+/// * `Result<bool>` - Whether the container was newly created, like `ensure_core_env_running`.
+///
+/// # Errors
+///
+/// Returns an error if the container cannot be prepared, or if a hook with
+/// `on_failure = "abort"` fails.
+pub(super) async fn ensure_running_with_hooks(container_name: &str, cfg: &Config) -> Result<bool> {
+    let was_running = docker::state::container_running(container_name)
+        .await
+        .unwrap_or(false);
+    let created = docker::lifecycle::ensure_core_env_running(container_name, cfg).await?;
+    docker::agents::relink(container_name, cfg).await;
+    if created {
+        run_stage(cfg, container_name, HookStage::Create).await?;
+    }
+    if !was_running {
+        run_stage(cfg, container_name, HookStage::Start).await?;
+    }
+    Ok(created)
+}
+
+/// Runs the hooks of one stage in order.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration (hooks and default working directory).
+/// * `container_name` - The running core environment container.
+/// * `stage` - The stage to run.
+///
+/// # Returns
+///
+/// * `Result<()>` - `Ok(())` if all hooks succeeded or only `warn` hooks failed.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ExternalCommand` for the first failing hook with `on_failure = "abort"`.
+/// Later hooks of the stage are not run in that case.
+pub(super) async fn run_stage(cfg: &Config, container_name: &str, stage: HookStage) -> Result<()> {
+    let hooks = stage.hooks(cfg);
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    println!(
+        "Running {} {} hook(s) in '{}'...",
+        hooks.len(),
+        stage.key(),
+        container_name
+    );
+
+    // Resolved once per stage; the values are only passed to the hook execs.
+    let env = secrets::exec_env(&cfg.core_env)?;
+    let mut runs = Vec::new();
+    let mut abort = None;
+    for hook in hooks {
+        let (record, output) = run_hook(cfg, container_name, stage, hook, &env).await;
+        append_log(container_name, &record, &output);
+        let failed = record.status != HookStatus::Succeeded;
+        runs.push(record);
+        if failed {
+            let last = runs.last().expect("just pushed");
+            let message = format!("{} hook `{}` {}", stage.key(), hook.command, describe(last));
+            match hook.on_failure {
+                HookFailurePolicy::Warn => eprintln!("⚠️  {} (continuing).", message),
+                HookFailurePolicy::Abort => {
+                    abort = Some(
+                        anyhow!(DevrsError::ExternalCommand {
+                            cmd: hook.command.clone(),
+                            status: describe(last),
+                            output: String::from_utf8_lossy(&output).into_owned(),
+                        })
+                        .context(format!(
+                            "{}; see '{}'",
+                            message,
+                            log_path(container_name).display()
+                        )),
+                    );
+                    break;
+                }
+            }
+        }
+    }
+    if let Err(e) = save_records(&state_dir(), container_name, stage, runs) {
+        warn!("{:#}", e);
+    }
+    abort.map_or(Ok(()), Err)
+}
+
+/// Runs one hook, returning its record and captured output.
+async fn run_hook(
+    cfg: &Config,
+    container_name: &str,
+    stage: HookStage,
+    hook: &HookConfig,
+    env: &[String],
+) -> (HookRecord, Vec<u8>) {
+    let timeout = hook
+        .timeout
+        .as_deref()
+        .and_then(|t| humantime::parse_duration(t).ok())
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+    let workdir = hook
+        .workdir
+        .as_deref()
+        .unwrap_or(&cfg.core_env.default_workdir);
+    let user = hook.user.as_deref().or(cfg.core_env.user.as_deref());
+    let cmd = hook_command(&hook.command, timeout);
+    info!(
+        "Running {} hook {:?} (workdir {}, user {:?}, timeout {:?})",
+        stage.key(),
+        hook.command,
+        workdir,
+        user,
+        timeout
+    );
+
+    let started_at = chrono::Utc::now().timestamp();
+    let started = Instant::now();
+    // `timeout` stops the hook in the container; this is only a backstop for a hung exec.
+    let result = tokio::time::timeout(
+        timeout + Duration::from_secs(KILL_GRACE_SECS + 5),
+        docker::interaction::exec_in_container_captured(
+            container_name,
+            &cmd,
+            Some(workdir),
+            user,
+            env,
+        ),
+    )
+    .await;
+    let (status, exit_code, output) = match result {
+        Ok(Ok((0, output))) => (HookStatus::Succeeded, Some(0), output),
+        Ok(Ok((code, output)))
+            if TIMEOUT_EXIT_CODES.contains(&code) && started.elapsed() >= timeout =>
+        {
+            (HookStatus::TimedOut, None, output)
+        }
+        Ok(Ok((code, output))) => (HookStatus::Failed, Some(code), output),
+        Ok(Err(e)) => (HookStatus::Error, None, format!("{:#}\n", e).into_bytes()),
+        Err(_) => (HookStatus::TimedOut, None, Vec::new()),
+    };
+    let record = HookRecord {
+        stage: stage.key().to_string(),
+        command: hook.command.clone(),
+        status,
+        exit_code,
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    debug!("Hook finished: {:?}", record);
+    (record, output)
+}
+
+/// Builds the exec command for a hook: `sh -c <command>` under `timeout`.
+///
+/// `timeout` runs the hook in its own process group and signals the whole group, so
+/// background jobs and children of the hook are stopped too. The deadline is rounded up
+/// to whole seconds.
+fn hook_command(command: &str, timeout: Duration) -> Vec<String> {
+    let secs = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+    vec![
+        "timeout".to_string(),
+        "-k".to_string(),
+        KILL_GRACE_SECS.to_string(),
+        secs.max(1).to_string(),
+        "sh".to_string(),
+        "-c".to_string(),
+        command.to_string(),
+    ]
+}
+
+/// Describes a hook outcome, e.g. `"failed with exit code 1"`.
+pub(super) fn describe(record: &HookRecord) -> String {
+    match record.status {
+        HookStatus::Succeeded => "succeeded".to_string(),
+        HookStatus::Failed => format!("failed with exit code {}", record.exit_code.unwrap_or(-1)),
+        HookStatus::TimedOut => format!(
+            "timed out after {}",
+            format::format_duration(Duration::from_millis(record.duration_ms))
+        ),
+        HookStatus::Error => "could not be run".to_string(),
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn record(stage: HookStage, command: &str, status: HookStatus) -> HookRecord {
+        HookRecord {
+            stage: stage.key().to_string(),
+            command: command.to_string(),
+            status,
+            exit_code: (status == HookStatus::Succeeded).then_some(0),
+            started_at: 1_700_000_000,
+            duration_ms: 1_500,
+        }
+    }
+
+    /// Test that a stage run replaces only that stage's records, and that they persist.
+    #[test]
+    fn test_save_records_replaces_stage() {
+        let dir = tempdir().unwrap();
+        assert!(load_records(dir.path(), "env").is_empty());
+
+        let create = vec![record(HookStage::Create, "npm ci", HookStatus::Succeeded)];
+        save_records(dir.path(), "env", HookStage::Create, create.clone()).unwrap();
+        let start = vec![record(HookStage::Start, "cargo fetch", HookStatus::Failed)];
+        save_records(dir.path(), "env", HookStage::Start, start).unwrap();
+        let restart = vec![record(
+            HookStage::Start,
+            "cargo fetch",
+            HookStatus::Succeeded,
+        )];
+        save_records(dir.path(), "env", HookStage::Start, restart.clone()).unwrap();
+
+        let stored = load_records(dir.path(), "env");
+        assert_eq!(stored, [create, restart].concat());
+    }
+
+    /// Test that a hook's command, including its background children, is stopped at the
+    /// deadline rather than left running.
+    #[test]
+    fn test_hook_command_stops_at_deadline() {
+        let dir = tempdir().unwrap();
+        let pid_file = dir.path().join("child.pid");
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let cmd = hook_command(&script, Duration::from_millis(200));
+        assert_eq!(cmd[..4], ["timeout", "-k", "5", "1"]);
+
+        let started = Instant::now();
+        let status = std::process::Command::new(&cmd[0])
+            .args(&cmd[1..])
+            .status()
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(TIMEOUT_EXIT_CODES.contains(&i64::from(status.code().unwrap())));
+
+        // The backgrounded `sleep` must be gone too (or at most a zombie awaiting reaping).
+        let pid = fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        let stopped = (0..50).any(|_| {
+            let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+            let done = stat.is_empty() || stat.contains(") Z ");
+            if !done {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            done
+        });
+        assert!(stopped, "hook child {} still running", pid);
+    }
+
+    /// Test the outcome descriptions.
+    #[test]
+    fn test_describe() {
+        let mut failed = record(HookStage::Shell, "false", HookStatus::Failed);
+        failed.exit_code = Some(2);
+        assert_eq!(describe(&failed), "failed with exit code 2");
+        let timed_out = record(HookStage::Shell, "sleep 9", HookStatus::TimedOut);
+        assert_eq!(describe(&timed_out), "timed out after 1 second");
+    }
+}
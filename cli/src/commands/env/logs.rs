@@ -24,6 +24,9 @@
 //! 5. Call the shared Docker utility function `common::docker::interaction::get_container_logs`, passing the container name, follow flag, and tail option. This function handles the Docker API call and streams the log output to the console.
 //! 6. If not following logs (`-f` was not used), print a concluding message after the logs are displayed.
 //!
+//! With `--archived`, steps 4-6 are replaced by reading the history persisted on the
+//! host by the log collector (`common::logstore`), which survives container removal.
+//!
 //! ## Usage
 //!
 //! ```bash
@@ -41,6 +44,9 @@
 //! # Equivalent:
 //! devrs env logs --lines all
 //!
+//! # Show the last 500 archived lines, including output of removed containers
+//! devrs env logs --archived -n 500
+//!
 //! # Show logs from a specifically named core env container (if using non-default name)
 //! devrs env logs --name my-custom-core-env-instance
 //! ```
@@ -49,7 +55,10 @@
 //! `devrs container logs` which targets application-specific containers.
 //!
 use crate::{
-    common::docker::{self}, // Access shared Docker utilities (interaction::get_container_logs).
+    common::{
+        docker::{self}, // Access shared Docker utilities (interaction::get_container_logs).
+        logstore,       // Archived history for `--archived`.
+    },
     core::{
         config,        // Access configuration loading.
         error::Result, // Standard Result type for error handling.
@@ -83,6 +92,11 @@ pub struct LogsArgs {
     /// typically `<image_name>-instance`) is used.
     #[arg(long)] // Define as `--name <NAME>`.
     name: Option<String>,
+
+    /// Optional: Show the history persisted by the log collector (`devrs collector`) instead
+    /// of the logs Docker still holds. Includes output of previous (removed) containers.
+    #[arg(long, conflicts_with = "follow")]
+    archived: bool,
 }
 
 /// # Handle Environment Logs Command (`handle_logs`)
@@ -123,6 +137,11 @@ pub async fn handle_logs(args: LogsArgs) -> Result<()> {
         default_name
     });
 
+    // With --archived, read the host-side history instead of asking Docker.
+    if args.archived {
+        return print_archived(&cfg, &container_name, &args.lines);
+    }
+
     // 3. Check if the container exists before attempting to get logs.
     // While `get_container_logs` also checks, checking here provides a slightly earlier and potentially clearer warning.
     if !docker::state::container_exists(&container_name).await? {
@@ -178,6 +197,49 @@ pub async fn handle_logs(args: LogsArgs) -> Result<()> {
     Ok(()) // Indicate overall success.
 }
 
+/// Prints the last lines of a container's archived history from the log store.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration (for the log store location).
+/// * `container_name` - The container whose history to show.
+/// * `lines` - Number of lines from the end, or "all".
+///
+/// # Returns
+///
+/// * `Result<()>` - `Ok(())` once printed (also when nothing is archived).
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be read.
+fn print_archived(cfg: &config::Config, container_name: &str, lines: &str) -> Result<()> {
+    let root = logstore::root_dir(cfg);
+    let history = logstore::reader::read_history(&root, container_name)?;
+    if history.is_empty() {
+        println!(
+            "No archived logs for '{}' in '{}'. Is the log collector running? (devrs collector status)",
+            container_name,
+            root.display()
+        );
+        return Ok(());
+    }
+    let skip = match lines.trim().parse::<usize>() {
+        Ok(n) => history.len().saturating_sub(n),
+        Err(_) => 0, // "all" (or invalid): show everything.
+    };
+    for line in &history[skip..] {
+        match line.timestamp {
+            Some(ts) => println!(
+                "{} {}",
+                ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                line.message
+            ),
+            None => println!("{}", line.message),
+        }
+    }
+    Ok(())
+}
+
 /// # Get Core Environment Container Name (`get_core_env_container_name`)
 ///
 /// A helper function to consistently derive the default name for the core development
@@ -215,6 +277,7 @@ mod tests {
         assert!(!args.follow); // Default is false.
         assert_eq!(args.lines, "100"); // Default is "100".
         assert!(args.name.is_none()); // Default is None.
+        assert!(!args.archived); // Default is live logs.
     }
 
     /// Test parsing with all optional flags and arguments provided.
@@ -248,6 +311,14 @@ mod tests {
         assert!(args.name.is_none());
     }
 
+    /// Test that `--archived` cannot be combined with `--follow`.
+    #[test]
+    fn test_logs_args_parsing_archived() {
+        let args = LogsArgs::try_parse_from(["logs", "--archived", "-n", "all"]).unwrap();
+        assert!(args.archived);
+        assert!(LogsArgs::try_parse_from(["logs", "--archived", "-f"]).is_err());
+    }
+
     // Note: Testing the `handle_logs` function's internal logic (like the `tail` validation,
     // container name generation, or the call to the Docker utility) would require mocking
     // `config::load_config` and `docker::interaction::get_container_logs`.
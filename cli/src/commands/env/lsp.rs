@@ -0,0 +1,553 @@
+//! # DevRS Environment Language Server Bridge
+//!
+//! File: cli/src/commands/env/lsp.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs env lsp` subcommand. It runs a language server
+//! (e.g. `rust-analyzer`, `gopls`, `clangd`) **inside** the core environment and
+//! bridges it to an editor on the host over stdio, so host editors get completions
+//! and diagnostics from the exact toolchain the container uses.
+//!
+//! The editor and the server see the same files at different paths (host checkout
+//! vs. bind mount), so every JSON-RPC message is rewritten on the way through:
+//! `file://` URIs and absolute paths under a `core_env.mounts` entry are translated
+//! host → container for requests from the editor and container → host for
+//! everything the server sends back.
+//!
+//! ## Architecture
+//!
+//! - `MessageReader` splits a byte stream into LSP messages (`Content-Length`
+//!   framing). Anything that is not a well-formed frame is passed through untouched.
+//! - `rewrite_message` parses a body as JSON and rewrites strings (and object keys,
+//!   e.g. `WorkspaceEdit.changes`) that are file URIs or absolute paths. Document
+//!   content (`text`, `newText`) is never rewritten. Non-JSON bodies pass through.
+//! - `handle_lsp` starts the server with `docker::interaction::exec_attached` (no TTY,
+//!   so the byte stream is exact) and pumps both directions concurrently. The server's
+//!   stderr is copied to the host's stderr unchanged.
+//! - The container is prepared with `ensure_core_env_running` directly rather than
+//!   through the lifecycle hooks: stdout is the protocol channel here, and hook output
+//!   would corrupt it.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Point the editor's server command at devrs instead of the server binary
+//! devrs env lsp rust-analyzer
+//!
+//! # Server arguments follow the server name
+//! devrs env lsp clangd --background-index
+//! ```
+//!
+use crate::{
+    common::docker::{self, paths::PathMapper}, // Docker utilities (exec_attached) and mount path mapping.
+    core::{
+        config,                      // Access configuration loading.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+        secrets,                     // `[core_env.secrets]` for the server.
+    },
+};
+use anyhow::{anyhow, Context}; // For easy error creation and adding context.
+use bollard::container::LogOutput; // Output chunks of the attached exec.
+use clap::Parser; // For parsing command-line arguments.
+use futures_util::StreamExt; // Iterating the exec output stream.
+use serde_json::Value; // JSON-RPC message bodies.
+use std::path::Path; // Host path handling.
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt}; // Async stdio.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+/// Keys whose string values are document content and must never be rewritten.
+const CONTENT_KEYS: &[&str] = &["text", "newText"];
+
+/// # Environment LSP Arguments (`LspArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs env lsp` subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Run a language server inside the core environment over stdio",
+    long_about = "Starts a language server inside the core environment container and bridges it to the \
+                  editor over stdio, rewriting file URIs and paths between host and container using core_env.mounts."
+)]
+pub struct LspArgs {
+    /// Optional: Specifies the exact name of the core environment container to run the server in.
+    /// If omitted, the default name (`<core_env.image_name>-instance`) derived from the configuration is used.
+    #[arg(long)] // Define as `--name <NAME>`.
+    name: Option<String>,
+
+    /// The language server executable inside the container (e.g. `rust-analyzer`).
+    #[arg(required = true)]
+    server: String,
+
+    /// Arguments passed through to the language server unchanged.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+/// # Translation Direction (`Direction`)
+///
+/// Which way paths are translated in a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Editor → server: host paths become container paths.
+    ToContainer,
+    /// Server → editor: container paths become host paths.
+    ToHost,
+}
+
+/// # LSP Frame (`Frame`)
+///
+/// One unit split off a byte stream by `MessageReader`.
+#[derive(Debug, PartialEq, Eq)]
+enum Frame {
+    /// The body of a `Content-Length` framed message.
+    Message(Vec<u8>),
+    /// Bytes that are not a well-formed frame; forwarded as-is.
+    Raw(Vec<u8>),
+}
+
+/// # Message Reader (`MessageReader`)
+///
+/// Incrementally splits a byte stream into LSP messages. Bytes are fed in as they
+/// arrive; complete frames are returned as soon as their body is available.
+#[derive(Debug, Default)]
+struct MessageReader {
+    buf: Vec<u8>,
+}
+
+impl MessageReader {
+    /// Appends bytes read from the stream.
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete frame, or `None` if more bytes are needed.
+    fn next_frame(&mut self) -> Option<Frame> {
+        let header_end = self.buf.windows(4).position(|w| w == b"\r\n\r\n")?;
+        let headers = String::from_utf8_lossy(&self.buf[..header_end]);
+        let length = headers.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())
+                .flatten()
+        });
+        let body_start = header_end + 4;
+        match length {
+            Some(length) if self.buf.len() >= body_start + length => {
+                let body = self.buf[body_start..body_start + length].to_vec();
+                self.buf.drain(..body_start + length);
+                Some(Frame::Message(body))
+            }
+            Some(_) => None,
+            // Not an LSP header block; hand it on untouched rather than stalling.
+            None => Some(Frame::Raw(self.buf.drain(..body_start).collect())),
+        }
+    }
+}
+
+/// Frames a message body with a `Content-Length` header.
+fn encode_frame(body: &[u8]) -> Vec<u8> {
+    let mut out = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    out.extend_from_slice(body);
+    out
+}
+
+/// Rewrites the paths in one message body. Bodies that are not JSON are returned unchanged.
+///
+/// # Arguments
+///
+/// * `body` - The message body.
+/// * `mapper` - The mount mapping.
+/// * `direction` - Which way to translate.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - The (possibly) rewritten body.
+fn rewrite_message(body: &[u8], mapper: &PathMapper, direction: Direction) -> Vec<u8> {
+    let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+        return body.to_vec();
+    };
+    if !rewrite_value(&mut value, mapper, direction) {
+        return body.to_vec();
+    }
+    serde_json::to_vec(&value).unwrap_or_else(|_| body.to_vec())
+}
+
+/// Recursively rewrites paths in a JSON value. Returns whether anything changed.
+fn rewrite_value(value: &mut Value, mapper: &PathMapper, direction: Direction) -> bool {
+    match value {
+        Value::String(s) => match rewrite_str(s, mapper, direction) {
+            Some(rewritten) => {
+                *s = rewritten;
+                true
+            }
+            None => false,
+        },
+        Value::Array(items) => {
+            let mut changed = false;
+            for item in items.iter_mut() {
+                changed |= rewrite_value(item, mapper, direction);
+            }
+            changed
+        }
+        Value::Object(map) => {
+            let mut changed = false;
+            for (key, mut item) in std::mem::take(map) {
+                if !CONTENT_KEYS.contains(&key.as_str()) {
+                    changed |= rewrite_value(&mut item, mapper, direction);
+                }
+                let key = match rewrite_str(&key, mapper, direction) {
+                    Some(rewritten) => {
+                        changed = true;
+                        rewritten
+                    }
+                    None => key,
+                };
+                map.insert(key, item);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+/// Rewrites a single string if it is a `file://` URI or an absolute path under a mount.
+fn rewrite_str(s: &str, mapper: &PathMapper, direction: Direction) -> Option<String> {
+    if let Some(encoded) = s.strip_prefix("file://") {
+        let (path, suffix) = match encoded.find(['?', '#']) {
+            Some(i) => encoded.split_at(i),
+            None => (encoded, ""),
+        };
+        let mapped = map_path(&percent_decode(path), mapper, direction)?;
+        return Some(format!("file://{}{}", percent_encode(&mapped), suffix));
+    }
+    if s.starts_with('/') && !s.contains('\n') {
+        return map_path(s, mapper, direction);
+    }
+    None
+}
+
+/// Translates an absolute path in the given direction.
+fn map_path(path: &str, mapper: &PathMapper, direction: Direction) -> Option<String> {
+    match direction {
+        Direction::ToContainer => mapper.map_to_container(Path::new(path)),
+        Direction::ToHost => mapper
+            .to_host(path)
+            .map(|p| p.to_string_lossy().into_owned()),
+    }
+}
+
+/// Decodes `%XX` escapes in a URI path. Invalid escapes are kept literally.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Encodes a path for use in a `file://` URI (unreserved characters and `/` are kept).
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Splits `bytes` into frames, rewrites each message and returns the bytes to forward.
+fn translate(
+    reader: &mut MessageReader,
+    bytes: &[u8],
+    mapper: &PathMapper,
+    direction: Direction,
+) -> Vec<u8> {
+    reader.push(bytes);
+    let mut out = Vec::new();
+    while let Some(frame) = reader.next_frame() {
+        match frame {
+            Frame::Message(body) => {
+                out.extend(encode_frame(&rewrite_message(&body, mapper, direction)))
+            }
+            Frame::Raw(raw) => out.extend(raw),
+        }
+    }
+    out
+}
+
+/// # Handle Environment LSP Command (`handle_lsp`)
+///
+/// The main asynchronous handler function for the `devrs env lsp` command.
+///
+/// ## Workflow:
+/// 1.  Loads the configuration and determines the container name (`--name` or `<image_name>-instance`).
+/// 2.  Calls `common::docker::lifecycle::ensure_core_env_running` (no hooks: stdout belongs to the protocol).
+/// 3.  Builds a `PathMapper` from `core_env.mounts` and picks the working directory: the
+///     current host directory mapped into the container, else `core_env.default_workdir`.
+/// 4.  Starts the server with `exec_attached` (stdin attached, no TTY).
+/// 5.  Pumps host stdin → server (rewriting host → container) in a background task and
+///     server stdout → host stdout (rewriting container → host) in the foreground;
+///     server stderr goes to host stderr unchanged.
+/// 6.  When the editor closes stdin, closes the server's stdin. When the server's output
+///     ends, retrieves its exit code.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `LspArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` if the server exits with code 0.
+/// * `Err`: If the container cannot be prepared, the server cannot be started, stdio fails,
+///   or the server exits with a non-zero code (`DevrsError::ExternalCommand`).
+pub async fn handle_lsp(args: LspArgs) -> Result<()> {
+    info!("Handling env lsp command...");
+    debug!("LSP args: {:?}", args);
+
+    // 1. Load configuration and resolve the container.
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let container_name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}-instance", cfg.core_env.image_name));
+
+    // 2. Ensure the container is running (hooks would write to the protocol channel).
+    docker::lifecycle::ensure_core_env_running(&container_name, &cfg)
+        .await
+        .with_context(|| format!("Failed to prepare container '{}' for lsp", container_name))?;
+
+    // 3. Path mapping and working directory.
+    let mapper = PathMapper::from_mounts(&cfg.core_env.mounts);
+    let workdir = std::env::current_dir()
+        .ok()
+        .and_then(|dir| mapper.to_container(&dir))
+        .unwrap_or_else(|| {
+            warn!(
+                "Current directory is not mounted; starting server in '{}'.",
+                cfg.core_env.default_workdir
+            );
+            cfg.core_env.default_workdir.clone()
+        });
+
+    // 4. Start the server.
+    let mut cmd = vec![args.server.clone()];
+    cmd.extend(args.args.iter().cloned());
+    info!(
+        "Starting language server {:?} in container '{}' (workdir: {})",
+        cmd, container_name, workdir
+    );
+    let secret_env = secrets::exec_env(&cfg.core_env)?;
+    let exec = docker::interaction::exec_attached(
+        &container_name,
+        &cmd,
+        Some(&workdir),
+        cfg.core_env.user.as_deref(),
+        &secret_env,
+    )
+    .await
+    .with_context(|| format!("Failed to start language server '{}'", args.server))?;
+    let docker::interaction::AttachedExec {
+        id: exec_id,
+        mut output,
+        mut input,
+    } = exec;
+
+    // 5a. Editor → server.
+    let to_server_mapper = mapper.clone();
+    let stdin_task = tokio::spawn(async move {
+        let mut stdin = io::stdin();
+        let mut reader = MessageReader::default();
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            let n = stdin.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            let out = translate(
+                &mut reader,
+                &chunk[..n],
+                &to_server_mapper,
+                Direction::ToContainer,
+            );
+            input.write_all(&out).await?;
+            input.flush().await?;
+        }
+        debug!("Editor closed stdin; closing server stdin.");
+        input.shutdown().await
+    });
+
+    // 5b. Server → editor.
+    let mut stdout = io::stdout();
+    let mut stderr = io::stderr();
+    let mut reader = MessageReader::default();
+    while let Some(chunk) = output.next().await {
+        match chunk {
+            Ok(LogOutput::StdOut { message }) => {
+                let out = translate(&mut reader, &message, &mapper, Direction::ToHost);
+                stdout.write_all(&out).await?;
+                stdout.flush().await?;
+            }
+            Ok(LogOutput::StdErr { message }) => {
+                stderr.write_all(&message).await?;
+                stderr.flush().await?;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Language server output stream failed: {}", e);
+                break;
+            }
+        }
+    }
+    stdin_task.abort();
+
+    // 6. Report the server's exit status.
+    let exit_code = docker::interaction::exec_exit_code(&exec_id).await?;
+    if exit_code == 0 {
+        info!("Language server '{}' exited cleanly.", args.server);
+        Ok(())
+    } else {
+        Err(anyhow!(DevrsError::ExternalCommand {
+            cmd: cmd.join(" "),
+            status: exit_code.to_string(),
+            output: format!(
+                "Language server in container '{}' exited with code {}",
+                container_name, exit_code
+            ),
+        }))
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::MountConfig;
+    use serde_json::json;
+
+    fn mapper() -> PathMapper {
+        PathMapper::from_mounts(&[MountConfig {
+            host: "/nonexistent/Users/me/code".to_string(),
+            container: "/home/me/code".to_string(),
+            readonly: false,
+        }])
+    }
+
+    /// Test parsing of the server name and pass-through arguments.
+    #[test]
+    fn test_lsp_args_parsing() {
+        let args =
+            LspArgs::try_parse_from(["lsp", "--name", "env1", "clangd", "--background-index"])
+                .unwrap();
+        assert_eq!(args.name.as_deref(), Some("env1"));
+        assert_eq!(args.server, "clangd");
+        assert_eq!(args.args, vec!["--background-index"]);
+        assert!(LspArgs::try_parse_from(["lsp"]).is_err());
+    }
+
+    /// Test framing across partial reads and back-to-back messages.
+    #[test]
+    fn test_message_reader() {
+        let mut reader = MessageReader::default();
+        reader.push(b"Content-Length: 2\r\n");
+        assert_eq!(reader.next_frame(), None);
+        reader.push(b"Content-Type: application/vscode-jsonrpc\r\n\r\n{");
+        assert_eq!(reader.next_frame(), None);
+        reader.push(b"}Content-Length: 4\r\n\r\nnull");
+        assert_eq!(reader.next_frame(), Some(Frame::Message(b"{}".to_vec())));
+        assert_eq!(reader.next_frame(), Some(Frame::Message(b"null".to_vec())));
+        assert_eq!(reader.next_frame(), None);
+
+        reader.push(b"garbage\r\n\r\n");
+        assert_eq!(
+            reader.next_frame(),
+            Some(Frame::Raw(b"garbage\r\n\r\n".to_vec()))
+        );
+    }
+
+    /// Test URI and path rewriting in both directions, including object keys,
+    /// percent-encoding, content fields and paths outside any mount.
+    #[test]
+    fn test_rewrite_message() {
+        let mapper = mapper();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "rootPath": "/nonexistent/Users/me/code/app",
+                "textDocument": {
+                    "uri": "file:///nonexistent/Users/me/code/app/my%20file.rs",
+                    "text": "/nonexistent/Users/me/code/app"
+                },
+                "other": "file:///etc/hosts"
+            }
+        });
+        let body = serde_json::to_vec(&request).unwrap();
+        let out: Value =
+            serde_json::from_slice(&rewrite_message(&body, &mapper, Direction::ToContainer))
+                .unwrap();
+        assert_eq!(out["params"]["rootPath"], "/home/me/code/app");
+        assert_eq!(
+            out["params"]["textDocument"]["uri"],
+            "file:///home/me/code/app/my%20file.rs"
+        );
+        assert_eq!(
+            out["params"]["textDocument"]["text"],
+            "/nonexistent/Users/me/code/app"
+        );
+        assert_eq!(out["params"]["other"], "file:///etc/hosts");
+
+        let edit = json!({
+            "result": {
+                "changes": {
+                    "file:///home/me/code/app/src/lib.rs": [{ "newText": "x" }]
+                }
+            }
+        });
+        let body = serde_json::to_vec(&edit).unwrap();
+        let out: Value =
+            serde_json::from_slice(&rewrite_message(&body, &mapper, Direction::ToHost)).unwrap();
+        assert!(out["result"]["changes"]
+            .get("file:///nonexistent/Users/me/code/app/src/lib.rs")
+            .is_some());
+
+        // Non-JSON and unchanged bodies are forwarded byte for byte.
+        assert_eq!(
+            rewrite_message(b"not json", &mapper, Direction::ToHost),
+            b"not json"
+        );
+        assert_eq!(
+            rewrite_message(b"{ \"id\" : 1 }", &mapper, Direction::ToHost),
+            b"{ \"id\" : 1 }"
+        );
+    }
+
+    /// Test that a framed stream is rewritten with corrected Content-Length headers.
+    #[test]
+    fn test_translate_reframes() {
+        let mapper = mapper();
+        let body = br#"{"uri":"file:///home/me/code/a.rs"}"#;
+        let mut reader = MessageReader::default();
+        let out = translate(&mut reader, &encode_frame(body), &mapper, Direction::ToHost);
+        let expected = br#"{"uri":"file:///nonexistent/Users/me/code/a.rs"}"#;
+        assert_eq!(out, encode_frame(expected));
+    }
+}
@@ -44,6 +44,12 @@
 //! # Check the status of the core environment container
 //! devrs env status
 //!
+//! # Run a language server in the core environment for a host editor
+//! devrs env lsp rust-analyzer
+//!
+//! # List the directories persisted across rebuilds
+//! devrs env persist ls
+//!
 //! # Stop the core environment container
 //! devrs env stop
 //! ```
@@ -54,6 +60,7 @@
 //! 3. The corresponding handler function (e.g., `build::handle_build`) is called.
 //! 4. Results or errors are returned up the call stack.
 //!
+use crate::common::docker::agents; // The agent proxy behind `devrs env agent-proxy`.
 use crate::core::error::Result; // Use the standard Result type for error handling.
 use clap::{Parser, Subcommand}; // Import necessary components from the Clap crate for argument parsing.
 
@@ -65,8 +72,16 @@ use clap::{Parser, Subcommand}; // Import necessary components from the Clap cra
 mod build;
 /// Implements the `devrs env exec` command (executes commands in the core env).
 mod exec;
+/// Detects stale core env images and containers (used by `shell`, `exec` and `status`).
+mod freshness;
+/// Runs the `[core_env.hooks]` lifecycle hooks (on_create, on_start, on_shell).
+mod hooks;
 /// Implements the `devrs env logs` command (views logs from the core env container).
 mod logs;
+/// Implements the `devrs env lsp` command (bridges a language server in the core env to a host editor).
+mod lsp;
+/// Implements the `devrs env persist` commands (lists and clears persisted directories).
+mod persist;
 /// Implements the `devrs env prune` command (cleans up unused core env resources).
 mod prune;
 /// Implements the `devrs env rebuild` command (stops, removes, and rebuilds the core env).
@@ -114,6 +129,12 @@ enum EnvCommand {
     /// Corresponds to `devrs env logs`. Fetches and displays logs from the core environment container.
     /// Holds `logs::LogsArgs` for options like `--follow`, `--lines`, `--name`.
     Logs(logs::LogsArgs),
+    /// Corresponds to `devrs env lsp`. Runs a language server inside the core environment over stdio,
+    /// rewriting file URIs and paths between host and container. Holds `lsp::LspArgs`.
+    Lsp(lsp::LspArgs),
+    /// Corresponds to `devrs env persist`. Lists or clears the directories persisted in named volumes
+    /// (`[core_env.persist]`). Holds `persist::PersistArgs` for the `ls` and `clear` actions.
+    Persist(persist::PersistArgs),
     /// Corresponds to `devrs env prune`. Removes stopped core environment containers.
     /// Holds `prune::PruneArgs` for the `--force` flag.
     Prune(prune::PruneArgs),
@@ -129,6 +150,15 @@ enum EnvCommand {
     /// Corresponds to `devrs env stop`. Stops the running core environment container.
     /// Holds `stop::StopArgs` for options like `--name` and `--time`.
     Stop(stop::StopArgs),
+    /// Corresponds to `devrs env agent-proxy` (internal). Runs the proxy that forwards a host
+    /// agent socket into the core environment; `shell` and `exec` start it as needed
+    /// (see `common::docker::agents`).
+    #[command(name = "agent-proxy", hide = true)]
+    AgentProxy {
+        /// The agent to proxy.
+        #[arg(value_enum)]
+        agent: agents::Agent,
+    },
 }
 
 /// # Handle Environment Command (`handle_env`)
@@ -157,11 +187,14 @@ pub async fn handle_env(args: EnvArgs) -> Result<()> {
         EnvCommand::Build(args) => build::handle_build(args).await?, // Call build handler.
         EnvCommand::Exec(args) => exec::handle_exec(args).await?,    // Call exec handler.
         EnvCommand::Logs(args) => logs::handle_logs(args).await?,    // Call logs handler.
+        EnvCommand::Lsp(args) => lsp::handle_lsp(args).await?,       // Call lsp handler.
+        EnvCommand::Persist(args) => persist::handle_persist(args).await?, // Call persist handler.
         EnvCommand::Prune(args) => prune::handle_prune(args).await?, // Call prune handler.
         EnvCommand::Rebuild(args) => rebuild::handle_rebuild(args).await?, // Call rebuild handler.
         EnvCommand::Shell(args) => shell::handle_shell(args).await?, // Call shell handler.
         EnvCommand::Status(args) => status::handle_status(args).await?, // Call status handler.
         EnvCommand::Stop(args) => stop::handle_stop(args).await?,    // Call stop handler.
+        EnvCommand::AgentProxy { agent } => agents::run_proxy(agent).await?, // Serve until replaced.
     }
     // If the matched handler completed successfully, return Ok.
     Ok(())
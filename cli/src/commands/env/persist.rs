@@ -0,0 +1,301 @@
+//! # DevRS Environment Persist Handler
+//!
+//! File: cli/src/commands/env/persist.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs env persist` subcommands, which inspect and reset
+//! the directories listed under `[core_env.persist]`. Each of those directories is
+//! backed by a DevRS-managed named volume per profile (`core_env.image_name`), so its
+//! contents survive `devrs env rebuild` and `devrs env prune`.
+//!
+//! ## Architecture
+//!
+//! - `ls`: Lists the configured directories with their volume names and whether the
+//!   volume exists yet, followed by volumes of the profile whose directory is no
+//!   longer configured (so they can be cleared).
+//! - `clear <PATH>...` / `clear --all`: Removes the volumes. A volume still mounted by
+//!   the core env container (running or stopped) cannot be removed, so its contents are
+//!   deleted from inside the container instead (`find <PATH> -mindepth 1 -delete`, as root).
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Show persisted directories and their volumes
+//! devrs env persist ls
+//!
+//! # Forget the persisted cargo registry
+//! devrs env persist clear /home/me/.cargo/registry
+//!
+//! # Forget everything persisted for this profile
+//! devrs env persist clear --all
+//! ```
+//!
+use super::hooks; // Starts the container (with hooks) before clearing in place.
+use crate::{
+    common::docker::{
+        self,
+        volumes::{self, PersistVolume},
+    }, // Volume and exec utilities.
+    core::{
+        config,                      // Access configuration loading.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+    },
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::{ArgGroup, Parser, Subcommand}; // For parsing command-line arguments.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// # Environment Persist Arguments (`PersistArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs env persist` subcommand group.
+#[derive(Parser, Debug)]
+#[command(about = "Inspect and clear the core env directories persisted in named volumes")]
+pub struct PersistArgs {
+    /// The persist action to perform.
+    #[command(subcommand)]
+    command: PersistCommand,
+}
+
+/// # Persist Subcommands (`PersistCommand`)
+#[derive(Subcommand, Debug)]
+enum PersistCommand {
+    /// List the persisted directories and their volumes.
+    Ls,
+    /// Delete the persisted contents of directories (the volume is recreated empty on next start).
+    #[command(group(ArgGroup::new("target").required(true).args(["paths", "all"])))]
+    Clear {
+        /// The container directories to clear, as listed by `devrs env persist ls`.
+        paths: Vec<String>,
+        /// Clear every persisted directory of the profile, including ones no longer configured.
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+/// # Handle Environment Persist Command (`handle_persist`)
+///
+/// ## Workflow:
+/// 1.  Loads the configuration to get the profile and the persisted directories.
+/// 2.  Lists the profile's existing volumes.
+/// 3.  Prints them (`ls`), or removes/empties the selected ones (`clear`).
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `PersistArgs`.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success.
+/// * `Err`: If configuration loading or a Docker call fails, or `clear` names a
+///   directory that is neither configured nor backed by a volume.
+pub async fn handle_persist(args: PersistArgs) -> Result<()> {
+    info!("Handling env persist command...");
+    debug!("Persist args: {:?}", args);
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let profile = cfg.core_env.image_name.clone();
+    let configured = volumes::persist_volumes(&cfg);
+    let existing: Vec<PersistVolume> = volumes::list_persist_volumes(Some(&profile))
+        .await?
+        .into_iter()
+        .filter_map(|v| {
+            let path = v.labels.get(volumes::PATH_LABEL)?.clone();
+            Some(PersistVolume {
+                name: v.name,
+                path,
+                profile: profile.clone(),
+            })
+        })
+        .collect();
+
+    match args.command {
+        PersistCommand::Ls => {
+            print_volumes(&profile, &configured, &existing);
+            Ok(())
+        }
+        PersistCommand::Clear { paths, all } => {
+            let targets = select_targets(&configured, &existing, &paths, all)?;
+            let container_name = format!("{}-instance", profile);
+            for volume in &targets {
+                if !existing.iter().any(|v| v.name == volume.name) {
+                    println!("'{}' has no persisted data.", volume.path);
+                    continue;
+                }
+                clear_volume(&container_name, &cfg, volume).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Prints the configured directories and the profile's orphaned volumes.
+fn print_volumes(profile: &str, configured: &[PersistVolume], existing: &[PersistVolume]) {
+    if configured.is_empty() && existing.is_empty() {
+        println!(
+            "No persisted directories for '{}'. Add paths under [core_env.persist].",
+            profile
+        );
+        return;
+    }
+    println!("Persisted directories for '{}':", profile);
+    for volume in configured {
+        let state = if existing.iter().any(|v| v.name == volume.name) {
+            "exists"
+        } else {
+            "created on next start"
+        };
+        println!("  {:<40} {} ({})", volume.path, volume.name, state);
+    }
+    let orphaned: Vec<_> = existing
+        .iter()
+        .filter(|v| !configured.iter().any(|c| c.name == v.name))
+        .collect();
+    if !orphaned.is_empty() {
+        println!("No longer configured (remove with `devrs env persist clear`):");
+        for volume in orphaned {
+            println!("  {:<40} {}", volume.path, volume.name);
+        }
+    }
+}
+
+/// Resolves the directories named on the command line (or `--all`) to volumes.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` for a path that is neither configured nor has a volume.
+fn select_targets(
+    configured: &[PersistVolume],
+    existing: &[PersistVolume],
+    paths: &[String],
+    all: bool,
+) -> Result<Vec<PersistVolume>> {
+    let known = || configured.iter().chain(existing);
+    if all {
+        let mut targets: Vec<PersistVolume> = Vec::new();
+        for volume in known() {
+            if !targets.iter().any(|t| t.name == volume.name) {
+                targets.push(volume.clone());
+            }
+        }
+        return Ok(targets);
+    }
+    paths
+        .iter()
+        .map(|path| {
+            let path = match path.trim_end_matches('/') {
+                "" => "/",
+                trimmed => trimmed,
+            };
+            known().find(|v| v.path == path).cloned().ok_or_else(|| {
+                anyhow!(DevrsError::Config(format!(
+                    "'{}' is not a persisted directory (see `devrs env persist ls`).",
+                    path
+                )))
+            })
+        })
+        .collect()
+}
+
+/// Removes a volume, or empties it through the core env container if that still mounts it.
+async fn clear_volume(
+    container_name: &str,
+    cfg: &config::Config,
+    volume: &PersistVolume,
+) -> Result<()> {
+    match volumes::remove_volume(&volume.name).await {
+        Ok(()) => {
+            println!("✅ Cleared '{}' (removed {}).", volume.path, volume.name);
+            Ok(())
+        }
+        Err(e)
+            if e.downcast_ref::<DevrsError>()
+                .is_some_and(|de| matches!(de, DevrsError::VolumeInUse { .. }))
+                && docker::state::container_exists(container_name).await? =>
+        {
+            info!(
+                "Volume '{}' is mounted by '{}', clearing it in place.",
+                volume.name, container_name
+            );
+            hooks::ensure_running_with_hooks(container_name, cfg).await?;
+            let cmd: Vec<String> = ["find", &volume.path, "-mindepth", "1", "-delete"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            let (code, _) = docker::interaction::exec_in_container_captured(
+                container_name,
+                &cmd,
+                None,
+                Some("root"),
+                &[],
+            )
+            .await?;
+            if code != 0 {
+                return Err(anyhow!(DevrsError::DockerOperation(format!(
+                    "Clearing '{}' in '{}' failed with exit code {}",
+                    volume.path, container_name, code
+                ))));
+            }
+            println!(
+                "✅ Cleared '{}' in the running container '{}'.",
+                volume.path, container_name
+            );
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// --- Unit Tests ---
+// Focus on argument parsing and target selection. Docker calls require mocking.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing the `ls` and `clear` subcommands.
+    #[test]
+    fn test_persist_args_parsing() {
+        let args = PersistArgs::try_parse_from(["persist", "ls"]).unwrap();
+        assert!(matches!(args.command, PersistCommand::Ls));
+
+        let args = PersistArgs::try_parse_from(["persist", "clear", "/a", "/b"]).unwrap();
+        match args.command {
+            PersistCommand::Clear { paths, all } => {
+                assert_eq!(paths, vec!["/a", "/b"]);
+                assert!(!all);
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        }
+
+        assert!(PersistArgs::try_parse_from(["persist", "clear", "--all"]).is_ok());
+        // A target is required, and paths and --all are exclusive.
+        assert!(PersistArgs::try_parse_from(["persist", "clear"]).is_err());
+        assert!(PersistArgs::try_parse_from(["persist", "clear", "/a", "--all"]).is_err());
+    }
+
+    /// Test resolving paths and `--all` to configured and orphaned volumes.
+    #[test]
+    fn test_select_targets() {
+        let configured = vec![
+            PersistVolume::new("env", "/a"),
+            PersistVolume::new("env", "/b"),
+        ];
+        let existing = vec![
+            PersistVolume::new("env", "/a"),
+            PersistVolume::new("env", "/old"),
+        ];
+
+        let all = select_targets(&configured, &existing, &[], true).unwrap();
+        let paths: Vec<&str> = all.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["/a", "/b", "/old"]);
+
+        let picked = select_targets(&configured, &existing, &["/old/".to_string()], false).unwrap();
+        assert_eq!(picked, vec![PersistVolume::new("env", "/old")]);
+
+        assert!(select_targets(&configured, &existing, &["/c".to_string()], false).is_err());
+    }
+}
@@ -30,7 +30,9 @@
 //!    * Spawn asynchronous Tokio tasks for each container identified for pruning.
 //!    * Each task calls `common::docker::lifecycle::remove_container` with `force=false` (since the containers are already verified to be stopped).
 //!    * Collect results using `join_all`.
-//! 9. Report overall success or list any containers that failed removal.
+//! 9. With `--purge`, also remove the named volumes holding the profile's `[core_env.persist]`
+//!    directories (listed in the dry run as well). A volume still mounted by a running container is reported as a failure.
+//! 10. Report overall success or list any containers (or volumes) that failed removal.
 //!
 //! ## Usage
 //!
@@ -42,9 +44,12 @@
 //! devrs env prune --force
 //! # Shorthand:
 //! devrs env prune -f
+//!
+//! # Also remove the persisted directories ([core_env.persist]) of this profile
+//! devrs env prune --purge --force
 //! ```
 //!
-//! **Important:** This command currently only targets stopped core environment *containers*. It does not prune the core environment image or associated volumes.
+//! **Important:** This command targets stopped core environment *containers*. It does not prune the core environment image, and it keeps the persisted-directory volumes unless `--purge` is given.
 //!
 use crate::{
     common::docker::{self}, // Access shared Docker utilities (list_containers, remove_container).
@@ -63,7 +68,7 @@ use tracing::{debug, error, info, warn}; // Logging framework utilities.
 #[command(
     about = "Clean up unused core DevRS environment Docker resources",
     long_about = "Removes stopped containers associated with the core DevRS environment.\n\
-                  WARNING: This currently only removes containers, not images or volumes."
+                  Persisted directories ([core_env.persist]) are kept unless --purge is given."
 )]
 pub struct PruneArgs {
     /// Confirms the removal operation. Without this flag, the command performs a dry run,
@@ -72,8 +77,11 @@ pub struct PruneArgs {
     /// this flag primarily acts as a confirmation rather than enabling removal of running containers.
     #[arg(long, short)] // Define as `--force` or `-f`.
     force: bool,
-    // TODO: Consider adding flags like `--images` or `--volumes` in the future if
-    //       env-specific pruning of these resources is desired.
+
+    /// Also remove the named volumes holding this profile's `[core_env.persist]` directories.
+    #[arg(long)] // Define as `--purge`.
+    purge: bool,
+    // TODO: Consider adding an `--images` flag in the future if env-specific image pruning is desired.
 }
 
 /// # Handle Environment Prune Command (`handle_prune`)
@@ -87,14 +95,16 @@ pub struct PruneArgs {
 /// 2.  Loads the DevRS configuration to get the `core_env.image_name` used to derive the container naming pattern (e.g., `<image_name>-instance`).
 /// 3.  Lists *all* Docker containers (running and stopped) using `common::docker::state::list_containers`.
 /// 4.  Filters the list to find containers that match the naming pattern AND are in a non-running state ('created', 'exited', 'dead', etc.).
-/// 5.  If the filtered list is empty, prints a message and exits successfully.
-/// 6.  If containers are found, prints the names/IDs of those targeted for removal.
+/// 5.  With `--purge`, lists the profile's persisted-directory volumes as well.
+///     If nothing was found, prints a message and exits successfully.
+/// 6.  Prints the names/IDs of the containers (and volumes) targeted for removal.
 /// 7.  Checks the `args.force` flag. If `false`, prints a warning explaining that `--force` is needed to proceed and exits successfully (simulating a dry run).
 /// 8.  If `args.force` is `true`, proceeds to remove the targeted containers concurrently:
 ///     * Spawns a Tokio task for each container ID.
 ///     * Each task calls `common::docker::lifecycle::remove_container` with `force=false` (since we've already filtered for stopped containers).
 ///     * Collects results using `join_all`.
-/// 9.  Processes the results, reporting overall success or listing any containers that failed removal.
+/// 9.  Removes the targeted volumes one by one (after the containers that mounted them are gone).
+/// 10. Processes the results, reporting overall success or listing any containers or volumes that failed removal.
 ///
 /// ## Arguments
 ///
@@ -105,7 +115,10 @@ pub struct PruneArgs {
 /// * `Result<()>`: Returns `Ok(())` if the prune operation completes successfully (either nothing to prune, dry run completed, or forced removal succeeded for all targets).
 /// * `Err`: Returns an `Err` if configuration loading fails, listing containers fails, or if forced removal fails for any targeted container.
 pub async fn handle_prune(args: PruneArgs) -> Result<()> {
-    info!("Handling env prune command (Force: {})...", args.force); // Log entry.
+    info!(
+        "Handling env prune command (Force: {}, Purge: {})...",
+        args.force, args.purge
+    ); // Log entry.
 
     // 1. Load config to get the core env image name for pattern matching.
     let cfg = config::load_config().context("Failed to load DevRS configuration")?;
@@ -142,14 +155,23 @@ pub async fn handle_prune(args: PruneArgs) -> Result<()> {
         })
         .collect();
 
-    // 4. Handle cases based on whether containers were found.
-    if containers_to_prune.is_empty() {
+    // 4. With --purge, find the profile's persisted-directory volumes too.
+    let volumes_to_prune = if args.purge {
+        docker::volumes::list_persist_volumes(Some(&cfg.core_env.image_name)).await?
+    } else {
+        Vec::new()
+    };
+
+    // Handle cases based on whether anything was found.
+    if containers_to_prune.is_empty() && volumes_to_prune.is_empty() {
         println!("No stopped core DevRS environment containers found to prune.");
         return Ok(()); // Nothing to do, successful exit.
     }
 
-    // 5. List containers identified for pruning.
-    println!("The following stopped core environment containers will be removed:");
+    // 5. List containers (and volumes) identified for pruning.
+    if !containers_to_prune.is_empty() {
+        println!("The following stopped core environment containers will be removed:");
+    }
     for c in &containers_to_prune {
         // Extract short ID and names for display.
         let id = c.id.as_deref().unwrap_or("unknown-id")[..12].to_string();
@@ -159,6 +181,16 @@ pub async fn handle_prune(args: PruneArgs) -> Result<()> {
             .map_or_else(|| "N/A".to_string(), |n| n.join(", "));
         println!("  - {} ({})", id, names);
     }
+    if !volumes_to_prune.is_empty() {
+        println!("The following persisted-directory volumes will be removed:");
+    }
+    for v in &volumes_to_prune {
+        let path = v
+            .labels
+            .get(docker::volumes::PATH_LABEL)
+            .map_or("?", |p| p.as_str());
+        println!("  - {} ({})", v.name, path);
+    }
 
     // 6. Check for confirmation via --force flag.
     // Currently, this acts purely as confirmation, as we only target stopped containers.
@@ -202,7 +234,7 @@ pub async fn handle_prune(args: PruneArgs) -> Result<()> {
     // Wait for all removal tasks to complete.
     let results = join_all(removal_tasks).await;
 
-    // 8. Collect and report results.
+    // 8. Collect results.
     let mut failed_removals = Vec::new();
     for result in results {
         match result {
@@ -215,6 +247,18 @@ pub async fn handle_prune(args: PruneArgs) -> Result<()> {
         }
     }
 
+    // 9. Remove the volumes now that the stopped containers using them are gone.
+    for volume in volumes_to_prune {
+        match docker::volumes::remove_volume(&volume.name).await {
+            Ok(()) => println!("Removed volume '{}'", volume.name),
+            Err(e) => {
+                // Typically DevrsError::VolumeInUse: the running core env still mounts it.
+                error!("Failed to remove volume '{}': {:?}", volume.name, e);
+                failed_removals.push((volume.name, e));
+            }
+        }
+    }
+
     // Check if any removals failed.
     if failed_removals.is_empty() {
         info!("Successfully pruned all targeted containers.");
@@ -222,14 +266,14 @@ pub async fn handle_prune(args: PruneArgs) -> Result<()> {
         Ok(()) // Overall success.
     } else {
         // Report failures.
-        eprintln!("\nErrors occurred during container prune:");
+        eprintln!("\nErrors occurred during prune:");
         for (id, err) in &failed_removals {
             eprintln!("- {}: {}", id, err);
         }
         // Return the first error encountered, adding context.
         let first_error = failed_removals.remove(0).1;
         Err(first_error).context(format!(
-            "Failed to prune {} container(s)",
+            "Failed to prune {} container(s) or volume(s)",
             failed_removals.len() + 1 // Original number of failures.
         ))
     }
@@ -246,8 +290,18 @@ mod tests {
     fn test_prune_args_parsing() {
         // Simulate `devrs env prune`
         let args = PruneArgs::try_parse_from(["prune"]).unwrap();
-        // Default value for `force` should be false.
+        // Default values for `force` and `purge` should be false.
         assert!(!args.force);
+        assert!(!args.purge);
+    }
+
+    /// Test parsing with the `--purge` flag.
+    #[test]
+    fn test_prune_args_parsing_purge() {
+        // Simulate `devrs env prune --purge -f`
+        let args = PruneArgs::try_parse_from(["prune", "--purge", "-f"]).unwrap();
+        assert!(args.purge);
+        assert!(args.force);
     }
 
     /// Test parsing with the `--force` flag (or `-f`).
@@ -22,10 +22,10 @@
 //! 3. Determine the target core environment container name (using `--name` override or generating the default).
 //! 4. Attempt to gracefully **stop** the existing container using `common::docker::lifecycle::stop_container`, handling "not found" errors non-fatally.
 //! 5. Attempt to **remove** the existing container using `common::docker::lifecycle::remove_container`, handling "not found" errors non-fatally.
-//! 6. Determine the image tag, Dockerfile path ("Dockerfile"), and build context (".") based on configuration and the assumption that the command is run from the repository root.
-//! 7. Validate that the `Dockerfile` exists.
-//! 8. **Build** the new core environment image using `common::docker::build_image`, passing the `no_cache` flag if specified.
-//! 9. Report success and suggest the next step (`devrs env shell`).
+//!    Named volumes backing `[core_env.persist]` directories are kept, unless `--purge` is given.
+//! 6. Determine the image tag from configuration; the build context (".") assumes the command is run from the repository root.
+//! 7. **Build** the new core environment image from `presets/Dockerfile.devrs` via the shared `build::build_core_image` (the same path and labels as `devrs env build`), passing the `no_cache` flag if specified.
+//! 8. Report success and suggest the next step (`devrs env shell`).
 //!
 //! ## Usage
 //!
@@ -41,10 +41,14 @@
 //!
 //! # Rebuild a specifically named core environment container/image
 //! devrs env rebuild --name my-custom-env-instance
+//!
+//! # Rebuild and also drop the persisted directories ([core_env.persist])
+//! devrs env rebuild --purge
 //! ```
 //!
 //! This command essentially combines `devrs env stop`, `devrs env prune` (implicitly, by removing the specific container), and `devrs env build` into a single operation for the core environment.
 //!
+use super::build; // Shared core image build (Dockerfile location, labels).
 use crate::{
     common::docker, // Access shared Docker utilities (stop, remove, build).
     core::{config, error::Result}, // Standard config loading and Result type.
@@ -87,6 +91,11 @@ pub struct RebuildArgs {
     /// to work with the default Dockerfile.
     #[arg(long)] // Define as `--with-plugins`.
     with_plugins: bool,
+
+    /// Optional: Also remove the named volumes holding the `[core_env.persist]` directories
+    /// of this profile, so the new container starts with them empty.
+    #[arg(long)] // Define as `--purge`.
+    purge: bool,
 }
 
 /// # Handle Environment Rebuild Command (`handle_rebuild`)
@@ -101,12 +110,13 @@ pub struct RebuildArgs {
 /// 3. Determines the target container name using `get_core_env_container_name` (honoring the `--name` override or using the default derived from config).
 /// 4. Attempts to stop the determined container using `docker::lifecycle::stop_container` with a short timeout. Logs warnings on failure but continues (e.g., if container wasn't running or didn't exist).
 /// 5. Attempts to remove the determined container using `docker::lifecycle::remove_container` with `force=true` (to handle stopped/exited states). Logs warnings on failure but continues (e.g., if container didn't exist).
-/// 6. Retrieves the configured image name and tag from the loaded config.
-/// 7. Constructs the full image tag string (e.g., `image_name:tag`).
-/// 8. Defines the Dockerfile path ("Dockerfile") and context path (".") assuming execution from the repository root.
-/// 9. Validates that the `Dockerfile` exists at the expected path.
-/// 10. Calls the shared `common::docker::build_image` function with the full image tag, Dockerfile path, context path, and the `no_cache` flag.
-/// 11. Prints a success message and suggests running `devrs env shell`.
+/// 6. With `--purge`, removes the profile's persisted-directory volumes (failures are fatal, since
+///    the user asked for the data to be gone). Without it they are kept for the new container.
+/// 7. Retrieves the configured image name and tag from the loaded config.
+/// 8. Constructs the full image tag string (e.g., `image_name:tag`).
+/// 9. Calls `build::build_core_image` with the current directory (assumed to be the repository root), which validates
+///    `presets/Dockerfile.devrs`, applies the role and provenance labels and runs the build with the `no_cache` flag.
+/// 10. Prints a success message and suggests running `devrs env shell`.
 ///
 /// ## Arguments
 ///
@@ -120,8 +130,8 @@ pub async fn handle_rebuild(args: RebuildArgs) -> Result<()> {
     info!("Handling env rebuild command..."); // Log entry point.
                                               // Log the specific arguments received.
     info!(
-        "(Name: {:?}, NoCache: {}, WithPlugins: {})",
-        args.name, args.no_cache, args.with_plugins
+        "(Name: {:?}, NoCache: {}, WithPlugins: {}, Purge: {})",
+        args.name, args.no_cache, args.with_plugins, args.purge
     );
 
     // Warn if the experimental --with-plugins flag is used.
@@ -201,51 +211,33 @@ pub async fn handle_rebuild(args: RebuildArgs) -> Result<()> {
         Err(e) => warn!("Remove command failed: {}", e),
     }
 
-    // 5. Build the new image. This step *should* fail the command if it errors out.
+    // 5. Drop the persisted directories if asked to; otherwise the new container reuses them.
+    if args.purge {
+        let persisted =
+            docker::volumes::list_persist_volumes(Some(&cfg.core_env.image_name)).await?;
+        for volume in persisted {
+            docker::volumes::remove_volume(&volume.name).await?;
+            println!("Removed persisted volume '{}'.", volume.name);
+        }
+    }
+
+    // 6. Build the new image. This step *should* fail the command if it errors out.
     println!("Building the core environment image...");
     // Get image name and tag from the loaded (or default) config.
     let image_name = &cfg.core_env.image_name;
     let image_tag = &cfg.core_env.image_tag;
     let full_image_tag = format!("{}:{}", image_name, image_tag);
 
-    // Define paths, assuming execution from repository root.
-    let dockerfile_path_str = "Dockerfile";
-    let context_dir_str = ".";
-    let dockerfile_path = Path::new(dockerfile_path_str);
+    // Build from the current directory (assumed to be the repository root), with
+    // role and provenance labels so later staleness checks see a fresh image.
+    let source = build::CoreBuildSource::resolve(
+        &cfg.core_env.build,
+        cfg.core_env.base_image.as_deref(),
+        Path::new("."),
+    )?;
+    build::build_core_image(&full_image_tag, &source, args.no_cache).await?;
 
-    // Validate Dockerfile existence.
-    if !dockerfile_path.exists() || !dockerfile_path.is_file() {
-        anyhow::bail!(
-            "Core environment Dockerfile not found at expected path: '{}'. Please run this command from the root of the devrs repository.",
-            dockerfile_path.display()
-        );
-    }
-    info!("Using Dockerfile: {}", dockerfile_path.display());
-    // Try to get canonical path for context logging, but don't fail if it errors.
-    let context_display_path = Path::new(context_dir_str).canonicalize().map_or_else(
-        |_| context_dir_str.to_string(), // Fallback to "." if canonicalize fails
-        |p| p.display().to_string(),
-    );
-    info!("Using build context: {}", context_display_path);
-
-    // Call the shared build function.
-    docker::build_image(
-        //
-        &full_image_tag,
-        dockerfile_path_str, // Pass relative path string.
-        context_dir_str,     // Pass context path string (".").
-        args.no_cache,       // Pass no-cache flag.
-    )
-    .await // Await the async build.
-    .with_context(|| {
-        // Add context on error.
-        format!(
-            "Failed to build core environment image '{}'",
-            full_image_tag
-        )
-    })?;
-
-    // 6. Report overall success.
+    // 7. Report overall success.
     println!(
         "✅ Environment rebuild process completed for '{}'.",
         container_name // Report using the container name determined earlier.
@@ -298,6 +290,10 @@ mod tests {
         assert_eq!(args.name, Some("my-env".to_string()));
         assert!(args.no_cache);
         assert!(args.with_plugins);
+        assert!(!args.purge);
+
+        let args = RebuildArgs::try_parse_from(["rebuild", "--purge"]).unwrap();
+        assert!(args.purge);
     }
 
     /// Test parsing with only default flags.
@@ -333,6 +329,7 @@ mod tests {
             no_cache: true,
             name: Some("test-env".to_string()),
             with_plugins: false,
+            purge: false,
         };
         let result = handle_rebuild(args).await;
 
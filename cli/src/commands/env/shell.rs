@@ -24,15 +24,19 @@
 //!    created in memory by `create_minimal_default_config`.
 //! 3. Determine the target core environment container name using `get_core_env_container_name`
 //!    (honoring the `--name` override or using the name derived from the loaded/default config).
-//! 4. Call the shared `common::docker::lifecycle::ensure_core_env_running` utility function.
+//! 4. Check the image and container for staleness (`freshness::check`): warn, or with
+//!    `--rebuild-if-stale` rebuild the image and remove the outdated container.
+//! 5. Call the shared `common::docker::lifecycle::ensure_core_env_running` utility function.
 //!    This function checks if the target container exists and is running. If not, it creates
 //!    and/or starts the container using the image name, mounts, ports, etc., from the loaded/default config.
-//!    It returns a boolean indicating if the container was newly created.
-//! 5. Determine the shell command to run inside the container (defaults to `/bin/bash`).
-//! 6. Retrieve the working directory path from the loaded/default configuration (`core_env.default_workdir`).
-//! 7. Call the shared `common::docker::interaction::exec_in_container` utility function to start an
+//!    It returns a boolean indicating if the container was newly created. The call goes through
+//!    `hooks::ensure_running_with_hooks`, which also runs the `on_create`/`on_start` hooks, and the
+//!    `on_shell` hooks run right before the shell attaches.
+//! 6. Determine the shell command to run inside the container (defaults to `/bin/bash`).
+//! 7. Retrieve the working directory path from the loaded/default configuration (`core_env.default_workdir`).
+//! 8. Call the shared `common::docker::interaction::exec_in_container` utility function to start an
 //!    interactive (`-it`) shell session inside the container, using the determined shell command and working directory.
-//! 8. Handle the exit code returned by the shell session.
+//! 9. Handle the exit code returned by the shell session.
 //!
 //! ## Usage
 //!
@@ -42,15 +46,20 @@
 //!
 //! # Start a shell in a specifically named core environment container
 //! devrs env shell --name my-custom-env-instance
+//!
+//! # Rebuild the image and recreate the container first if they are out of date
+//! devrs env shell --rebuild-if-stale
 //! ```
 //!
 //! This command ensures a seamless entry into the consistent development environment provided by DevRS.
 //!
+use super::{build, freshness, hooks}; // Core image builds, staleness checks, lifecycle hooks.
 use crate::{
     common::docker::{self}, // Access shared Docker utilities (ensure_running, exec).
     core::{
-        config,        // Access configuration loading and structures.
-        error::Result, // Standard Result type for error handling.
+        config,                      // Access configuration loading and structures.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+        secrets,                     // `[core_env.secrets]` for the shell session.
     },
 };
 use anyhow::Context; // For adding context to errors.
@@ -78,6 +87,12 @@ pub struct ShellArgs {
     /// environment instances if needed, although typically only one is used.
     #[arg(long)] // Define as `--name <NAME>`.
     name: Option<String>,
+
+    /// Optional: If the core image was built from outdated sources (or the container runs an
+    /// older image than the configured tag), rebuild the image and recreate the container
+    /// before opening the shell instead of only printing a warning.
+    #[arg(long)] // Define as `--rebuild-if-stale`.
+    rebuild_if_stale: bool,
     // TODO: Consider adding `--user` or `--command` overrides later if specific use cases arise,
     // similar to `devrs env exec`. Currently, it defaults to `/bin/bash` as the container's default user.
 }
@@ -97,19 +112,23 @@ pub struct ShellArgs {
 ///     A warning is logged, and a message is printed informing the user about the defaults being used.
 /// 4.  Determines the target container name using `get_core_env_container_name`, honoring the `--name`
 ///     argument or using the name derived from the (loaded or default) configuration.
-/// 5.  Calls `common::docker::lifecycle::ensure_core_env_running`, passing the container name and the
+/// 5.  Checks the image and container for staleness (`freshness::check`). With `--rebuild-if-stale`
+///     a stale image is rebuilt and an outdated container removed; otherwise warnings are printed.
+/// 6.  Calls `hooks::ensure_running_with_hooks` (wrapping `common::docker::lifecycle::ensure_core_env_running`,
+///     and running the `on_create`/`on_start` hooks as needed), passing the container name and the
 ///     active configuration (loaded or default). This crucial step handles container creation
-///     (using config mounts, ports, image) and starting as needed.
-/// 6.  Prints informational messages if the container was newly created, especially if default settings were used.
-/// 7.  Defines the shell command to run inside the container (currently hardcoded to `/bin/bash`).
-/// 8.  Retrieves the target working directory from the active configuration (`core_env.default_workdir`).
-/// 9.  Calls `common::docker::interaction::exec_in_container` to start the interactive (`-it`) shell session
+///     (using config mounts, ports, image) and starting as needed, and re-links forwarded agent sockets.
+/// 7.  Prints informational messages if the container was newly created, especially if default settings were used.
+/// 8.  Runs the `on_shell` hooks (a failing `abort` hook prevents the shell from opening).
+/// 9.  Defines the shell command to run inside the container (currently hardcoded to `/bin/bash`).
+/// 10. Retrieves the target working directory from the active configuration (`core_env.default_workdir`).
+/// 11. Calls `common::docker::interaction::exec_in_container` to start the interactive (`-it`) shell session
 ///     using the determined shell command and working directory.
-/// 10. Logs the exit code of the shell session upon completion.
+/// 12. Logs the exit code of the shell session upon completion.
 ///
 /// ## Arguments
 ///
-/// * `args`: The parsed `ShellArgs` struct containing the optional container `name`.
+/// * `args`: The parsed `ShellArgs` struct containing the optional container `name` and the `--rebuild-if-stale` flag.
 ///
 /// ## Returns
 ///
@@ -174,8 +193,12 @@ pub async fn handle_shell(args: ShellArgs) -> Result<()> {
 
     // 3. Ensure the container exists and is running. This handles creation/starting automatically.
     // Pass the active config (`cfg`) which contains image name, mounts, ports etc. needed for creation.
+    // Warn about (or fix) a stale image or outdated container before (re)using it.
+    refresh_if_stale(&cfg, &container_name, args.rebuild_if_stale).await?;
+
+    // Runs the `on_create`/`on_start` hooks if the container had to be created or started.
     let needs_creation =
-        docker::lifecycle::ensure_core_env_running(&container_name, &cfg) //
+        hooks::ensure_running_with_hooks(&container_name, &cfg) //
             .await // Await the async operation.
             .with_context(|| {
                 format!("Failed to prepare container '{}' for shell", container_name)
@@ -197,6 +220,18 @@ pub async fn handle_shell(args: ShellArgs) -> Result<()> {
         }
     }
 
+    // Optionally make sure the log collector archives this session's output.
+    if cfg.logs.collect.unwrap_or(false) {
+        match crate::commands::collector::ensure_running(&cfg) {
+            Ok((pid, true)) => info!("Started log collector (PID {}).", pid),
+            Ok(_) => {}
+            Err(e) => warn!("Could not start the log collector: {:#}", e),
+        }
+    }
+
+    // Run the `on_shell` hooks before attaching.
+    hooks::run_stage(&cfg, &container_name, hooks::HookStage::Shell).await?;
+
     // 4. Execute the interactive shell inside the prepared container.
     println!(
         "Starting interactive shell in core environment container '{}'...",
@@ -212,15 +247,19 @@ pub async fn handle_shell(args: ShellArgs) -> Result<()> {
     let workdir_to_use = cfg.core_env.default_workdir.clone();
     info!("Using working directory: {}", workdir_to_use);
 
+    // Secrets are resolved on the host now and only passed to this exec.
+    let secret_env = secrets::exec_env(&cfg.core_env)?;
+
     // Use the shared `exec_in_container` utility to run the shell interactively.
     let exit_code = docker::interaction::exec_in_container(
         //
-        &container_name,       // Target container name.
-        &shell_cmd,            // Command to run (the shell).
-        true,                  // interactive = true: Attach stdin.
-        true,                  // tty = true: Allocate a pseudo-terminal.
-        Some(&workdir_to_use), // Set the working directory inside the container.
-        None,                  // user = None: Run as container's default user.
+        &container_name,              // Target container name.
+        &shell_cmd,                   // Command to run (the shell).
+        true,                         // interactive = true: Attach stdin.
+        true,                         // tty = true: Allocate a pseudo-terminal.
+        Some(&workdir_to_use),        // Set the working directory inside the container.
+        cfg.core_env.user.as_deref(), // `core_env.user`, else the container's default user.
+        &secret_env,                  // `inject = "env"` secrets.
     )
     .await // Await the async execution.
     .with_context(|| {
@@ -249,6 +288,76 @@ pub async fn handle_shell(args: ShellArgs) -> Result<()> {
     Ok(()) // Return Ok, indicating the `devrs env shell` command itself completed.
 }
 
+/// Checks the core environment for staleness before the shell attaches to it.
+///
+/// Without `rebuild` this only prints warnings. With `rebuild`, a stale image is rebuilt from
+/// the checkout recorded in its labels, and the container is removed if it runs an older image
+/// (or the image was just rebuilt) so that `ensure_core_env_running` recreates it.
+///
+/// # Errors
+///
+/// Failing to *check* is never an error (it is logged at debug level). Returns an error only if
+/// a requested rebuild fails.
+async fn refresh_if_stale(cfg: &config::Config, container_name: &str, rebuild: bool) -> Result<()> {
+    let report = match freshness::check(cfg, container_name).await {
+        Ok(Some(report)) => report,
+        Ok(None) => return Ok(()), // No local image yet; it is pulled or built on creation.
+        Err(e) => {
+            debug!("Skipping staleness check: {:#}", e);
+            return Ok(());
+        }
+    };
+    if report.reasons.is_empty() {
+        return Ok(());
+    }
+    if !rebuild {
+        for warning in report.warnings() {
+            eprintln!("⚠️  {}", warning);
+        }
+        eprintln!("   Run `devrs env rebuild` or `devrs env shell --rebuild-if-stale` to update.");
+        return Ok(());
+    }
+
+    // A container created from an older image ID is recreated even if the image itself
+    // cannot be rebuilt here.
+    let mut recreate = report.container_outdated();
+    if report.image_stale() {
+        match report.context.as_deref() {
+            None => eprintln!("⚠️  Image '{}' is stale, but its build context is unknown; run `devrs env rebuild` from the DevRS checkout.", report.image),
+            Some(context) => {
+                println!(
+                    "Image '{}' is stale; rebuilding from '{}'...",
+                    report.image,
+                    context.display()
+                );
+                let source = build::CoreBuildSource::resolve(
+                    &cfg.core_env.build,
+                    cfg.core_env.base_image.as_deref(),
+                    context,
+                )?;
+                build::build_core_image(&report.image, &source, false)
+                    .await
+                    .context("Failed to rebuild stale core environment image")?;
+                recreate = true;
+            }
+        }
+    }
+    if recreate {
+        println!(
+            "Recreating container '{}' with the current image...",
+            container_name
+        );
+        match docker::lifecycle::remove_container(container_name, true).await {
+            Ok(()) => info!("Removed outdated container '{}'.", container_name),
+            Err(e)
+                if e.downcast_ref::<DevrsError>()
+                    .is_some_and(|de| matches!(de, DevrsError::ContainerNotFound { .. })) => {}
+            Err(e) => return Err(e.context("Failed to remove outdated core environment container")),
+        }
+    }
+    Ok(())
+}
+
 /// # Get Core Environment Container Name (`get_core_env_container_name`)
 ///
 /// Helper function to consistently derive the default name for the core development
@@ -308,12 +417,29 @@ async fn create_minimal_default_config() -> Result<config::Config> {
             }],
             ports: vec![],                // No default port mappings.
             env_vars: Default::default(), // No default environment variables.
+            env_files: vec![],            // No env files.
+            secrets: Default::default(),  // No secrets.
             // Set the default working directory inside the container to match the mount point.
             default_workdir: "/code".to_string(),
+            hooks: Default::default(),   // No lifecycle hooks.
+            persist: Default::default(), // Nothing persisted.
+            build: Default::default(),   // Build from presets/Dockerfile.devrs.
+            base_image: None,            // Not built from an upstream image.
+            user: None,                  // Run as the image's default user.
+            forward_ssh_agent: None,     // No agent forwarding.
+            forward_gpg_agent: None,
         },
         // Use default (empty) settings for other config sections.
         blueprints: Default::default(),
         application_defaults: Default::default(),
+        images: Default::default(),
+        logs: Default::default(),
+        shims: Default::default(),
+        devcontainer: Default::default(),
+        apps: Default::default(),
+        network: Default::default(),
+        lint: Default::default(),
+        merge: Default::default(),
     })
 }
 
@@ -335,6 +461,10 @@ mod tests {
         let args_named = ShellArgs::try_parse_from(["shell", "--name", "custom-core-env"]).unwrap();
         // Name should be parsed correctly.
         assert_eq!(args_named.name, Some("custom-core-env".to_string()));
+        assert!(!args_named.rebuild_if_stale);
+
+        let args_rebuild = ShellArgs::try_parse_from(["shell", "--rebuild-if-stale"]).unwrap();
+        assert!(args_rebuild.rebuild_if_stale);
     }
 
     /// Test the logic for creating the minimal default configuration fallback.
@@ -376,7 +506,10 @@ mod tests {
         // Mock `exec_in_container` -> Ok(0) // Simulate successful shell exit.
 
         // --- Execution ---
-        let args = ShellArgs { name: None }; // Use default container name.
+        let args = ShellArgs {
+            name: None,
+            rebuild_if_stale: false,
+        }; // Use default container name.
         let result = handle_shell(args).await;
 
         // --- Assertions ---
@@ -395,7 +528,10 @@ mod tests {
         // Mock `env::current_dir` if needed for precise default config verification.
 
         // --- Execution ---
-        let args = ShellArgs { name: None }; // Use default container name.
+        let args = ShellArgs {
+            name: None,
+            rebuild_if_stale: false,
+        }; // Use default container name.
         let result = handle_shell(args).await;
 
         // --- Assertions ---
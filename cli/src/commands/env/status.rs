@@ -21,10 +21,11 @@
 //! 2. Load the DevRS configuration (`core::config`) to get core environment settings (image name/tag, default container name).
 //! 3. Determine the target core environment container name (using `--name` or the default derived from config).
 //! 4. Check if the configured core environment *image* exists locally using `common::docker::images::image_exists`, logging a warning if not found.
-//! 5. Attempt to inspect the target *container* using `common::docker::state::inspect_container`.
-//! 6. If inspection is successful, pass the detailed container information (`ContainerInspectResponse`) to the `print_container_details` function for formatted output.
-//! 7. If inspection fails with a `ContainerNotFound` error, print a user-friendly message indicating the container doesn't exist and suggest next steps (`devrs env shell` or `devrs env build`). Treat this as a successful command execution (the status *is* "not found").
-//! 8. If inspection fails with any other error, propagate it up the call stack.
+//! 5. Print the image's provenance labels and any staleness warnings (`freshness::check`).
+//! 6. Attempt to inspect the target *container* using `common::docker::state::inspect_container`.
+//! 7. If inspection is successful, pass the detailed container information (`ContainerInspectResponse`) to the `print_container_details` function for formatted output, followed by the latest lifecycle hook results (`print_hooks`).
+//! 8. If inspection fails with a `ContainerNotFound` error, print a user-friendly message indicating the container doesn't exist and suggest next steps (`devrs env shell` or `devrs env build`). Treat this as a successful command execution (the status *is* "not found").
+//! 9. If inspection fails with any other error, propagate it up the call stack.
 //!
 //! ## Usage
 //!
@@ -34,12 +35,18 @@
 //!
 //! # Show status of a specifically named core environment container
 //! devrs env status --name my-custom-env-instance
+//!
+//! # Include a live CPU/memory/I/O snapshot
+//! devrs env status --stats
 //! ```
 //!
 //! The output provides a comprehensive overview of the core environment's current state.
 //!
+use super::{freshness, hooks}; // Staleness checks and lifecycle hook results.
 use crate::{
+    commands::stats,        // Shared resource usage snapshot for `--stats`.
     common::docker::{self}, // Access shared Docker utilities (image_exists, inspect_container).
+    common::ui::format,     // Human-readable creation time and uptime.
     core::{
         config,                      // Access configuration loading.
         error::{DevrsError, Result}, // Standard Result type and custom errors.
@@ -61,6 +68,10 @@ pub struct StatusArgs {
     /// typically `<image_name>-instance`) is used.
     #[arg(long)] // Defines the `--name <NAME>` option.
     name: Option<String>,
+    /// Optional: Also sample live resource usage (CPU, memory, network/block I/O, PIDs)
+    /// if the container is running. See `devrs stats` for a refreshing view.
+    #[arg(long)]
+    stats: bool,
     // TODO: Consider adding flags like `--json` in the future for machine-readable output.
 }
 
@@ -73,9 +84,11 @@ pub struct StatusArgs {
 /// 2. Loads the DevRS configuration.
 /// 3. Determines the target container name (using `--name` or default).
 /// 4. Checks if the configured core environment *image* exists locally, logging a warning if not.
-/// 5. Calls `common::docker::state::inspect_container` to get detailed information about the target container.
-/// 6. Processes the result of the inspection:
-///    - If `Ok(details)`, calls `print_container_details` to display the information.
+/// 5. Prints the image's provenance (build time, git commit) and staleness warnings via `print_freshness`.
+/// 6. Calls `common::docker::state::inspect_container` to get detailed information about the target container.
+/// 7. Processes the result of the inspection:
+///    - If `Ok(details)`, calls `print_container_details` and `print_hooks` to display the information, followed by
+///      a live resource usage snapshot (`commands::stats::print_snapshot`) when `--stats` is given.
 ///    - If `Err` is `DevrsError::ContainerNotFound`, prints a helpful "not found" message and returns `Ok(())`.
 ///    - If any other `Err` occurs, propagates the error.
 ///
@@ -117,18 +130,30 @@ pub async fn handle_status(args: StatusArgs) -> Result<()> {
         Err(e) => warn!("Could not check image existence: {}", e), // Warn on error checking image.
     }
 
+    // 4. Report where the image came from and whether it (or the container) is stale.
+    print_freshness(&cfg, &container_name).await;
+
     println!(
         // Inform user which container status is being checked.
         "Checking status for core environment container '{}'...",
         container_name
     );
 
-    // 4. Inspect the target container.
+    // 5. Inspect the target container.
     match docker::state::inspect_container(&container_name).await {
         //
         Ok(details) => {
-            // 5. If inspection succeeded, print the details.
+            // 6. If inspection succeeded, print the details and the latest hook results.
             print_container_details(&container_name, &details);
+            print_hooks(&cfg, &container_name);
+            // 7. Optionally append a live resource usage snapshot.
+            let running = details.state.as_ref().and_then(|s| s.running);
+            if args.stats && running == Some(true) {
+                println!("\n--- Resource Usage ---");
+                stats::print_snapshot(std::slice::from_ref(&container_name)).await?;
+            } else if args.stats {
+                println!("\nResource usage unavailable: container is not running.");
+            }
         }
         Err(e) => {
             // Check if the error was specifically 'ContainerNotFound'.
@@ -150,6 +175,91 @@ pub async fn handle_status(args: StatusArgs) -> Result<()> {
     Ok(()) // Overall command success.
 }
 
+/// # Print Hooks (`print_hooks`)
+/// Prints the result of the latest run of each configured lifecycle hook (`[core_env.hooks]`).
+/// Prints nothing if no hooks are configured or have run yet.
+///
+/// ## Arguments
+/// * `cfg`: The loaded configuration (to tell whether any hooks are configured).
+/// * `container_name`: The core environment container the hooks ran in.
+fn print_hooks(cfg: &config::Config, container_name: &str) {
+    let configured = &cfg.core_env.hooks;
+    let records = hooks::latest_records(container_name);
+    if records.is_empty()
+        && configured.on_create.is_empty()
+        && configured.on_start.is_empty()
+        && configured.on_shell.is_empty()
+    {
+        return;
+    }
+    println!("\n--- Lifecycle Hooks ---");
+    if records.is_empty() {
+        println!("  No hooks have run in this container yet.");
+        return;
+    }
+    for record in &records {
+        let icon = if record.status == hooks::HookStatus::Succeeded {
+            "✅"
+        } else {
+            "❌"
+        };
+        println!(
+            "  {:<10} {} {} ({}, {})",
+            record.stage,
+            icon,
+            record.command,
+            hooks::describe(record),
+            format::format_ago(record.started_at)
+        );
+    }
+    println!(
+        "  Output log:  {}",
+        hooks::log_path(container_name).display()
+    );
+}
+
+/// # Print Freshness (`print_freshness`)
+/// Prints the core image's build provenance and warns if the image was built from outdated
+/// sources or the container runs an older image. Best effort: failures are logged at debug level.
+///
+/// ## Arguments
+/// * `cfg`: The loaded configuration (image name and tag).
+/// * `container_name`: The core environment container to compare against.
+async fn print_freshness(cfg: &config::Config, container_name: &str) {
+    let report = match freshness::check(cfg, container_name).await {
+        Ok(Some(report)) => report,
+        Ok(None) => return, // Image missing; already reported above.
+        Err(e) => {
+            debug!("Skipping staleness check: {:#}", e);
+            return;
+        }
+    };
+    if let Some(built_at) = &report.built_at {
+        println!(
+            "Image '{}' built {}{}.",
+            report.image,
+            built_at,
+            report
+                .commit
+                .as_deref()
+                .map(|c| format!(" from commit {}", c))
+                .unwrap_or_default()
+        );
+    }
+    if !report.tracked {
+        println!(
+            "Note: Image '{}' has no content hash (built before provenance labels); rebuild it to enable staleness checks.",
+            report.image
+        );
+    }
+    for warning in report.warnings() {
+        println!("⚠️  {}", warning);
+    }
+    if !report.reasons.is_empty() {
+        println!("   Run `devrs env rebuild` or `devrs env shell --rebuild-if-stale` to update.");
+    }
+}
+
 /// # Get Core Environment Container Name (`get_core_env_container_name`)
 /// Helper function to derive the default container name based on the configured image name.
 /// Appends "-instance" to the image name.
@@ -209,6 +319,25 @@ fn print_container_details(name: &str, details: &bollard::models::ContainerInspe
             Err(_) => println!("  Started At:  {} (Could not parse)", started_at_str), // Handle parse failure.
         }
     }
+    // Print creation time and, for running containers, how long they have been up.
+    if let Some(created) = details
+        .created
+        .as_deref()
+        .and_then(format::parse_docker_time)
+    {
+        println!("  Created:     {}", format::format_timestamp(created));
+    }
+    if state.and_then(|s| s.running) == Some(true) {
+        if let Some(started) = state
+            .and_then(|s| s.started_at.as_deref())
+            .and_then(format::parse_docker_time)
+        {
+            println!(
+                "  Uptime:      {}",
+                format::format_duration(format::age_since(started))
+            );
+        }
+    }
     // Format and print 'FinishedAt' timestamp if the container has exited.
     if let Some(finished_at_str) = state.and_then(|s| s.finished_at.as_deref()) {
         // Docker often uses a zero-value timestamp for non-exited containers; ignore those.
@@ -353,6 +482,7 @@ mod tests {
         let args = StatusArgs::try_parse_from(["status"]).unwrap();
         // The optional `--name` should be None by default.
         assert!(args.name.is_none());
+        assert!(!args.stats);
     }
 
     /// Test parsing with the optional `--name` flag.
@@ -0,0 +1,178 @@
+//! # DevRS Image Inventory
+//!
+//! File: cli/src/commands/images/inventory.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Collects the local image list and annotates each image with the information
+//! the `devrs images` subcommands need: its devrs role, its size and creation
+//! time, whether it is dangling, and whether any container (running or stopped)
+//! still references it.
+//!
+//! ## Architecture
+//!
+//! - `collect_images` performs the Docker calls (`images::list_images` and
+//!   `state::list_containers`).
+//! - `build_entries` is the pure transformation from API models to `ImageEntry`
+//!   values, kept separate so it can be unit tested without a daemon.
+//!
+use crate::{
+    common::docker::{
+        self,
+        roles::{self, DevrsRole},
+    }, // Docker listing helpers and role classification.
+    core::{config::Config, error::Result}, // Config and standard Result type.
+};
+use anyhow::Context; // For adding context to errors.
+use bollard::models::ImageSummary; // Image list model from the Docker API.
+use std::collections::HashSet; // For the set of in-use image IDs.
+
+/// Placeholder tag Docker reports for untagged images.
+const NONE_TAG: &str = "<none>:<none>";
+
+/// # Image Entry (`ImageEntry`)
+///
+/// A local image annotated for display and pruning.
+#[derive(Debug, Clone)]
+pub struct ImageEntry {
+    /// Full image ID (`sha256:...`).
+    pub id: String,
+    /// Real `repository:tag` references (placeholder `<none>` tags removed).
+    pub repo_tags: Vec<String>,
+    /// Creation time as a Unix timestamp.
+    pub created: i64,
+    /// Image size in bytes (including shared layers).
+    pub size: u64,
+    /// The devrs role of the image.
+    pub role: DevrsRole,
+    /// Whether any container (running or stopped) uses this image.
+    pub in_use: bool,
+}
+
+impl ImageEntry {
+    /// Whether the image has no tags (a leftover from rebuilds).
+    pub fn is_dangling(&self) -> bool {
+        self.repo_tags.is_empty()
+    }
+
+    /// The 12-character short ID, as shown by the Docker CLI.
+    pub fn short_id(&self) -> &str {
+        let id = self.id.trim_start_matches("sha256:");
+        &id[..id.len().min(12)]
+    }
+}
+
+/// Lists local images and annotates them (role, dangling, in-use).
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded DevRS configuration (used for role classification).
+///
+/// # Returns
+///
+/// * `Result<Vec<ImageEntry>>` - One entry per image.
+///
+/// # Errors
+///
+/// Returns an error if listing images or containers fails.
+pub async fn collect_images(cfg: &Config) -> Result<Vec<ImageEntry>> {
+    let images = docker::images::list_images(false, None)
+        .await
+        .context("Failed to list Docker images")?;
+    // Any container, running or stopped, keeps its image from being removed.
+    let containers = docker::state::list_containers(true, None)
+        .await
+        .context("Failed to list Docker containers")?;
+    let used_ids: HashSet<String> = containers.into_iter().filter_map(|c| c.image_id).collect();
+    Ok(build_entries(images, &used_ids, cfg))
+}
+
+/// Converts Docker image summaries into annotated entries.
+///
+/// # Arguments
+///
+/// * `images` - Image summaries from `list_images`.
+/// * `used_ids` - IDs of images referenced by at least one container.
+/// * `cfg` - The loaded DevRS configuration.
+///
+/// # Returns
+///
+/// * `Vec<ImageEntry>` - The annotated entries, in input order.
+pub fn build_entries(
+    images: Vec<ImageSummary>,
+    used_ids: &HashSet<String>,
+    cfg: &Config,
+) -> Vec<ImageEntry> {
+    images
+        .into_iter()
+        .map(|image| {
+            let repo_tags: Vec<String> = image
+                .repo_tags
+                .into_iter()
+                .filter(|t| t != NONE_TAG)
+                .collect();
+            let role = roles::image_role(&repo_tags, &image.labels, cfg);
+            ImageEntry {
+                in_use: used_ids.contains(&image.id) || image.containers > 0,
+                id: image.id,
+                repo_tags,
+                created: image.created,
+                size: image.size.max(0) as u64,
+                role,
+            }
+        })
+        .collect()
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Helper: a minimal image summary for tests.
+    pub fn summary(id: &str, tags: &[&str], created: i64, size: i64) -> ImageSummary {
+        ImageSummary {
+            id: id.to_string(),
+            repo_tags: tags.iter().map(|t| t.to_string()).collect(),
+            created,
+            size,
+            containers: -1, // Docker reports -1 when not computed.
+            labels: HashMap::new(),
+            ..Default::default()
+        }
+    }
+
+    /// Helper: a config using the default core image name.
+    pub fn test_config() -> Config {
+        let mut cfg = Config::default();
+        cfg.core_env.image_name = "devrs-core-env".to_string();
+        cfg.core_env.image_tag = "latest".to_string();
+        cfg
+    }
+
+    /// Test role, dangling and in-use annotation.
+    #[test]
+    fn test_build_entries_annotations() {
+        let images = vec![
+            summary("sha256:aaa", &["devrs-core-env:latest"], 100, 10),
+            summary("sha256:bbb", &["<none>:<none>"], 50, 5),
+            summary("sha256:ccc", &["postgres:16"], 10, 1),
+        ];
+        let used = HashSet::from(["sha256:ccc".to_string()]);
+        let entries = build_entries(images, &used, &test_config());
+
+        assert_eq!(entries[0].role, DevrsRole::CoreEnv);
+        assert!(!entries[0].is_dangling());
+        assert!(entries[1].is_dangling());
+        assert_eq!(entries[1].role, DevrsRole::Other);
+        assert!(entries[2].in_use);
+        assert!(!entries[0].in_use);
+        assert_eq!(entries[0].short_id(), "aaa");
+    }
+}
@@ -0,0 +1,179 @@
+//! # DevRS Images List Handler
+//!
+//! File: cli/src/commands/images/ls.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs images ls` subcommand, which prints an
+//! inventory of local images in a table: tag, short ID, devrs role, size, age and
+//! whether a container still uses the image.
+//!
+//! ## Architecture
+//!
+//! 1. Load configuration (needed to classify images by role).
+//! 2. Collect annotated images via `inventory::collect_images`.
+//! 3. Keep DevRS-managed and dangling images (or everything with `--all`).
+//! 4. Sort by role, repository and age, then render with `common::ui::tables`.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # DevRS-managed images (core env, application images) and dangling leftovers
+//! devrs images ls
+//!
+//! # Every image on the Docker host
+//! devrs images ls --all
+//! ```
+//!
+use super::inventory::{self, ImageEntry}; // Shared image collection.
+use crate::{
+    common::ui::{format, tables}, // Human-readable sizes/ages and table output.
+    core::{config, error::Result}, // Config loading and standard Result type.
+};
+use anyhow::Context; // For adding context to errors.
+use clap::Parser; // For parsing command-line arguments.
+use tracing::info; // Logging framework utilities.
+
+/// # Images List Arguments (`LsArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs images ls` subcommand.
+#[derive(Parser, Debug)]
+#[command(about = "List images with size, age, devrs role and usage")]
+pub struct LsArgs {
+    /// Optional: Include images not managed by DevRS.
+    #[arg(long, short)] // Define as `--all` or `-a`.
+    all: bool,
+}
+
+/// # Handle Images List Command (`handle_ls`)
+///
+/// ## Workflow:
+/// 1.  Loads configuration and collects the annotated image inventory.
+/// 2.  Filters and sorts the entries.
+/// 3.  Prints one table row per tag (dangling images get a single `<none>` row).
+/// 4.  Prints a summary line with the image count and total size.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `LsArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success, or an `Err` if config loading or Docker calls fail.
+pub async fn handle_ls(args: LsArgs) -> Result<()> {
+    info!("Handling images ls command (All: {})", args.all);
+
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let mut entries: Vec<ImageEntry> = inventory::collect_images(&cfg)
+        .await?
+        .into_iter()
+        .filter(|e| args.all || e.role.is_devrs() || e.is_dangling())
+        .collect();
+
+    if entries.is_empty() {
+        println!(
+            "No {}images found.",
+            if args.all { "" } else { "DevRS-managed " }
+        );
+        if !args.all {
+            println!("Use --all to include images not managed by DevRS.");
+        }
+        return Ok(());
+    }
+
+    sort_entries(&mut entries);
+    tables::print_table(
+        &[
+            "REPOSITORY:TAG",
+            "IMAGE ID",
+            "ROLE",
+            "SIZE",
+            "CREATED",
+            "IN USE",
+        ],
+        &table_rows(&entries),
+    );
+
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    println!(
+        "\n{} image(s), {} total (shared layers are counted once per image).",
+        entries.len(),
+        format::format_bytes(total)
+    );
+    Ok(())
+}
+
+/// Sorts entries by role, then repository, then newest first.
+fn sort_entries(entries: &mut [ImageEntry]) {
+    entries.sort_by(|a, b| {
+        a.role
+            .cmp(&b.role)
+            .then_with(|| a.repo_tags.first().cmp(&b.repo_tags.first()))
+            .then_with(|| b.created.cmp(&a.created))
+    });
+}
+
+/// Builds table rows, one per tag (or a single `<none>` row for dangling images).
+fn table_rows(entries: &[ImageEntry]) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    for entry in entries {
+        let tags = if entry.is_dangling() {
+            vec!["<none>".to_string()]
+        } else {
+            entry.repo_tags.clone()
+        };
+        for tag in tags {
+            rows.push(vec![
+                tag,
+                entry.short_id().to_string(),
+                entry.role.to_string(),
+                format::format_bytes(entry.size),
+                format::format_ago(entry.created),
+                if entry.in_use { "yes" } else { "no" }.to_string(),
+            ]);
+        }
+    }
+    rows
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::super::inventory::{build_entries, tests as inv};
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Test parsing the `--all` flag.
+    #[test]
+    fn test_ls_args_parsing() {
+        assert!(!LsArgs::try_parse_from(["ls"]).unwrap().all);
+        assert!(LsArgs::try_parse_from(["ls", "-a"]).unwrap().all);
+    }
+
+    /// Test that multi-tag images produce one row per tag and dangling images one row.
+    #[test]
+    fn test_table_rows() {
+        let images = vec![
+            inv::summary(
+                "sha256:aaa",
+                &["devrs-core-env:latest", "devrs-core-env:v2"],
+                0,
+                1,
+            ),
+            inv::summary("sha256:bbb", &[], 0, 1),
+        ];
+        let mut entries = build_entries(images, &HashSet::new(), &inv::test_config());
+        sort_entries(&mut entries);
+        let rows = table_rows(&entries);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "devrs-core-env:latest");
+        assert_eq!(rows[0][2], "core-env");
+        assert_eq!(rows[2][0], "<none>");
+        assert_eq!(rows[2][5], "no");
+    }
+}
@@ -0,0 +1,128 @@
+//! # DevRS Images Command Group
+//!
+//! File: cli/src/commands/images/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module serves as the entry point and router for the `devrs images`
+//! command group. Where `devrs container rmi` removes images one at a time, this
+//! group gives an inventory of the images DevRS has produced (old core environment
+//! tags, per-project application images, dangling build leftovers) and removes them
+//! in bulk according to retention policies.
+//!
+//! ## Architecture
+//!
+//! The module uses Clap's derive macros to define the command structure:
+//! - `ImagesArgs`: Top-level arguments struct for the `devrs images` group.
+//! - `ImagesCommand`: Enum defining the available subcommands (`ls`, `prune`).
+//! - `handle_images`: The main handler function that routes to the subcommand handlers.
+//!
+//! The shared `inventory` submodule collects local images (via `common::docker::images::list_images`)
+//! and annotates them with their devrs role (`common::docker::roles`) and whether any
+//! container still uses them.
+//!
+//! ## Examples
+//!
+//! ```bash
+//! # List DevRS-managed images
+//! devrs images ls
+//!
+//! # Include every image on the host
+//! devrs images ls --all
+//!
+//! # Preview removing all but the 2 newest images of each repository
+//! devrs images prune --keep-last 2
+//!
+//! # Remove devrs images older than 30 days plus dangling images
+//! devrs images prune --older-than 30d --dangling --force
+//! ```
+//!
+use crate::core::error::Result; // Use the standard Result type for error handling.
+use clap::{Parser, Subcommand}; // Import necessary components from the Clap crate for argument parsing.
+
+// --- Subcommand Module Declarations ---
+
+/// Shared image inventory collection (roles, in-use flags) used by `ls` and `prune`.
+mod inventory;
+/// Implements the `devrs images ls` command (image inventory table).
+mod ls;
+/// Implements the `devrs images prune` command (policy-based cleanup).
+mod prune;
+
+/// # Images Command Group Arguments (`ImagesArgs`)
+///
+/// This struct represents the top-level command group `devrs images`.
+/// Its purpose is to capture which specific subcommand the user intends to execute.
+#[derive(Parser, Debug)]
+pub struct ImagesArgs {
+    /// The specific images subcommand to execute (`ls` or `prune`).
+    #[command(subcommand)]
+    command: ImagesCommand,
+}
+
+/// # Images Subcommands (`ImagesCommand`)
+///
+/// This enum enumerates all valid subcommands available under `devrs images`.
+/// Each variant holds the arguments struct defined within that subcommand's module.
+#[derive(Subcommand, Debug)]
+enum ImagesCommand {
+    /// Corresponds to `devrs images ls`. Lists images with size, age, tag, devrs role and in-use flag.
+    /// Holds `ls::LsArgs` for the `--all` flag.
+    #[command(alias = "list")]
+    Ls(ls::LsArgs),
+    /// Corresponds to `devrs images prune`. Removes images according to retention policies.
+    /// Holds `prune::PruneArgs` for `--keep-last`, `--older-than`, `--dangling` and `--force`.
+    Prune(prune::PruneArgs),
+}
+
+/// # Handle Images Command (`handle_images`)
+///
+/// The main entry point function for the `devrs images` command group.
+/// It acts as a **dispatcher**, matching on the subcommand and calling the
+/// corresponding asynchronous handler.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ImagesArgs` struct containing the specific `ImagesCommand` variant.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Propagates the `Result` from the called subcommand handler.
+pub async fn handle_images(args: ImagesArgs) -> Result<()> {
+    // Match on the specific subcommand variant provided in the parsed arguments.
+    match args.command {
+        ImagesCommand::Ls(args) => ls::handle_ls(args).await?, // Call ls handler.
+        ImagesCommand::Prune(args) => prune::handle_prune(args).await?, // Call prune handler.
+    }
+    // If the matched handler completed successfully, return Ok.
+    Ok(())
+}
+
+// --- Unit Tests ---
+// Verify that clap routes the subcommands correctly.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing of `ls` (and its `list` alias).
+    #[test]
+    fn test_parses_images_ls() {
+        let result = ImagesArgs::try_parse_from(["images", "ls", "--all"]);
+        assert!(matches!(result.unwrap().command, ImagesCommand::Ls(_)));
+        let result = ImagesArgs::try_parse_from(["images", "list"]);
+        assert!(matches!(result.unwrap().command, ImagesCommand::Ls(_)));
+    }
+
+    /// Test parsing of `prune` with policy flags.
+    #[test]
+    fn test_parses_images_prune() {
+        let result =
+            ImagesArgs::try_parse_from(["images", "prune", "--keep-last", "2", "--dangling"]);
+        assert!(matches!(result.unwrap().command, ImagesCommand::Prune(_)));
+    }
+}
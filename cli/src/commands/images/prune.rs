@@ -0,0 +1,506 @@
+//! # DevRS Images Prune Handler
+//!
+//! File: cli/src/commands/images/prune.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the `devrs images prune` subcommand, which removes
+//! DevRS-managed images (old core environment tags, stale per-project application
+//! images) and dangling images according to a retention policy:
+//!
+//! - **`--keep-last N`**: keep the N newest images of every repository.
+//! - **`--older-than DURATION`**: only remove images older than the duration (e.g., `30d`, `2weeks`).
+//! - **`--dangling`**: also remove untagged images left behind by rebuilds.
+//!
+//! When both `--keep-last` and `--older-than` are given, an image must satisfy both
+//! (be outside the newest N *and* older than the duration) to be removed.
+//! If no policy flag is given, the `[images.prune]` section of the configuration is used.
+//!
+//! Like `devrs env prune`, the command is a **dry run** unless `--force` is given.
+//! Images used by any container, and the configured core environment image
+//! (`core_env.image_name:core_env.image_tag`), are never removed.
+//!
+//! ## Architecture
+//!
+//! 1. Resolve the effective `PrunePolicy` from flags or configuration.
+//! 2. Collect the annotated image inventory (`inventory::collect_images`).
+//! 3. Select candidates with the pure `select_candidates` function.
+//! 4. Print the plan (and the images skipped because they are in use).
+//! 5. With `--force`, remove candidates concurrently via `common::docker::images::remove_image`
+//!    and report the reclaimed bytes.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Preview: keep the 2 newest images per repository
+//! devrs images prune --keep-last 2
+//!
+//! # Remove devrs images older than 30 days (except the newest per repo) and dangling images
+//! devrs images prune --keep-last 1 --older-than 30d --dangling --force
+//!
+//! # Use the default policy from [images.prune] in config.toml
+//! devrs images prune --force
+//! ```
+//!
+use super::inventory::{self, ImageEntry}; // Shared image collection.
+use crate::{
+    common::{
+        docker::{self, images::split_reference}, // Image removal and reference parsing.
+        ui::format,                              // Human-readable sizes and ages.
+    },
+    core::{
+        config::{self, Config},      // Config loading and default policy.
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+    },
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::Parser; // For parsing command-line arguments.
+use futures_util::future::join_all; // For running removal tasks concurrently.
+use std::{
+    collections::{HashMap, HashSet}, // For per-repository grouping.
+    time::Duration,                  // For the age threshold.
+};
+use tracing::{debug, error, info, warn}; // Logging framework utilities.
+
+/// # Images Prune Arguments (`PruneArgs`)
+///
+/// Defines the command-line arguments accepted by the `devrs images prune` subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    about = "Remove old devrs images using retention policies",
+    long_about = "Removes DevRS-managed images (core env and application images) and, optionally, dangling images.\n\
+                  Policies: --keep-last N per repository, --older-than DURATION, --dangling.\n\
+                  Without policy flags, the [images.prune] section of the configuration is used.\n\
+                  Performs a dry run unless --force is given. Images used by containers are never removed."
+)]
+pub struct PruneArgs {
+    /// Keep the N most recent images of each repository.
+    #[arg(long, value_name = "N")] // Define as `--keep-last <N>`.
+    keep_last: Option<usize>,
+
+    /// Only remove images older than this duration (e.g., "30d", "2weeks", "12h").
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    older_than: Option<Duration>,
+
+    /// Also remove dangling (untagged) images.
+    #[arg(long)] // Define as `--dangling`.
+    dangling: bool,
+
+    /// Confirms the removal. Without this flag, the command only lists what would be removed.
+    #[arg(long, short)] // Define as `--force` or `-f`.
+    force: bool,
+}
+
+/// # Prune Policy (`PrunePolicy`)
+///
+/// The effective retention policy after combining flags and configuration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrunePolicy {
+    /// Keep the N newest images of each repository.
+    pub keep_last: Option<usize>,
+    /// Only remove images older than this.
+    pub older_than: Option<Duration>,
+    /// Remove dangling images.
+    pub dangling: bool,
+}
+
+impl PrunePolicy {
+    /// Whether the policy would select anything at all.
+    fn is_empty(&self) -> bool {
+        self.keep_last.is_none() && self.older_than.is_none() && !self.dangling
+    }
+
+    /// Short description for messages, e.g., "keep-last 2, older-than 30days, dangling".
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(n) = self.keep_last {
+            parts.push(format!("keep-last {}", n));
+        }
+        if let Some(d) = self.older_than {
+            parts.push(format!("older-than {}", humantime::format_duration(d)));
+        }
+        if self.dangling {
+            parts.push("dangling".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// A selected image together with the reason it was selected.
+#[derive(Debug)]
+struct Candidate<'a> {
+    /// The image to remove.
+    entry: &'a ImageEntry,
+    /// Human-readable reason (e.g., "dangling", "older than 30days").
+    reason: String,
+}
+
+/// # Handle Images Prune Command (`handle_prune`)
+///
+/// ## Workflow:
+/// 1.  Loads configuration and resolves the effective policy (flags override config).
+/// 2.  Collects the image inventory and selects candidates.
+/// 3.  Prints candidates and in-use skips; stops here unless `--force` was given.
+/// 4.  Removes candidates concurrently, then reports reclaimed bytes and any failures.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `PruneArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success (including dry runs).
+/// * `Err`: If no policy is configured, Docker calls fail, or any removal fails.
+pub async fn handle_prune(args: PruneArgs) -> Result<()> {
+    info!("Handling images prune command: {:?}", args);
+
+    // 1. Resolve the policy.
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let policy = resolve_policy(&args, &cfg)?;
+    if policy.is_empty() {
+        return Err(anyhow!(DevrsError::ArgumentParsing(
+            "No prune policy given. Use --keep-last, --older-than and/or --dangling, \
+             or set a default in the [images.prune] section of config.toml."
+                .to_string()
+        )));
+    }
+    println!("Prune policy: {}", policy.describe());
+
+    // 2. Select candidates.
+    let entries = inventory::collect_images(&cfg).await?;
+    let protected = format!("{}:{}", cfg.core_env.image_name, cfg.core_env.image_tag);
+    let now = chrono::Utc::now().timestamp();
+    let (candidates, in_use) = select_candidates(&entries, &policy, &[protected], now);
+
+    for entry in &in_use {
+        println!("Skipping {} (in use by a container)", display_name(entry));
+    }
+    if candidates.is_empty() {
+        println!("No images match the prune policy.");
+        return Ok(());
+    }
+
+    // 3. Show the plan.
+    let planned_bytes: u64 = candidates.iter().map(|c| c.entry.size).sum();
+    println!(
+        "{} the following {} image(s):",
+        if args.force {
+            "Removing"
+        } else {
+            "Would remove"
+        },
+        candidates.len()
+    );
+    for candidate in &candidates {
+        println!(
+            "  - {} [{}] {} created {} ({})",
+            display_name(candidate.entry),
+            candidate.entry.role,
+            format::format_bytes(candidate.entry.size),
+            format::format_ago(candidate.entry.created),
+            candidate.reason
+        );
+    }
+    if !args.force {
+        println!(
+            "\nDry run: would reclaim up to {}. Re-run with --force to remove.",
+            format::format_bytes(planned_bytes)
+        );
+        return Ok(());
+    }
+
+    // 4. Remove concurrently (same pattern as `container rmi`).
+    let mut removal_tasks = Vec::new();
+    for candidate in &candidates {
+        let id = candidate.entry.id.clone();
+        let name = display_name(candidate.entry);
+        let size = candidate.entry.size;
+        // Removing by ID needs force when the image carries several tags.
+        let force = candidate.entry.repo_tags.len() > 1;
+        removal_tasks.push(tokio::spawn(async move {
+            match docker::images::remove_image(&id, force).await {
+                Ok(()) => Ok((name, size)),
+                Err(e)
+                    if e.downcast_ref::<DevrsError>()
+                        .is_some_and(|de| matches!(de, DevrsError::ImageNotFound { .. })) =>
+                {
+                    warn!("Image '{}' already removed.", name);
+                    Ok((name, 0))
+                }
+                Err(e) => Err((name, e)),
+            }
+        }));
+    }
+
+    let mut reclaimed: u64 = 0;
+    let mut failed_removals = Vec::new();
+    for result in join_all(removal_tasks).await {
+        match result {
+            Ok(Ok((name, size))) => {
+                debug!("Removed '{}' ({} bytes)", name, size);
+                reclaimed += size;
+            }
+            Ok(Err((name, e))) => failed_removals.push((name, e)),
+            Err(e) => error!("Image removal task failed unexpectedly: {}", e),
+        }
+    }
+
+    println!(
+        "\nReclaimed up to {} from {} image(s).",
+        format::format_bytes(reclaimed),
+        candidates.len() - failed_removals.len()
+    );
+
+    if failed_removals.is_empty() {
+        Ok(())
+    } else {
+        eprintln!("\nErrors occurred during image prune:");
+        for (name, err) in &failed_removals {
+            eprintln!("- {}: {}", name, err);
+        }
+        let count = failed_removals.len();
+        let first_error = failed_removals.remove(0).1;
+        Err(first_error).context(format!("Failed to remove {} image(s)", count))
+    }
+}
+
+/// Combines command-line flags and the configured default policy.
+///
+/// Any policy flag on the command line replaces the configured policy entirely,
+/// so a one-off `--dangling` does not silently pick up a configured `keep_last`.
+fn resolve_policy(args: &PruneArgs, cfg: &Config) -> Result<PrunePolicy> {
+    if args.keep_last.is_some() || args.older_than.is_some() || args.dangling {
+        return Ok(PrunePolicy {
+            keep_last: args.keep_last,
+            older_than: args.older_than,
+            dangling: args.dangling,
+        });
+    }
+    let defaults = &cfg.images.prune;
+    let older_than = match &defaults.older_than {
+        Some(s) => Some(humantime::parse_duration(s).map_err(|e| {
+            anyhow!(DevrsError::Config(format!(
+                "Invalid images.prune.older_than '{}': {}",
+                s, e
+            )))
+        })?),
+        None => None,
+    };
+    Ok(PrunePolicy {
+        keep_last: defaults.keep_last,
+        older_than,
+        dangling: defaults.dangling.unwrap_or(false),
+    })
+}
+
+/// Selects images to remove under `policy`.
+///
+/// # Arguments
+///
+/// * `entries` - The annotated image inventory.
+/// * `policy` - The effective policy.
+/// * `protected` - References that must never be removed (e.g., the configured core env tag).
+/// * `now` - The current Unix time (injected for testability).
+///
+/// # Returns
+///
+/// * `(Vec<Candidate>, Vec<&ImageEntry>)` - The images to remove, and the images that matched
+///   the policy but are skipped because a container uses them.
+fn select_candidates<'a>(
+    entries: &'a [ImageEntry],
+    policy: &PrunePolicy,
+    protected: &[String],
+    now: i64,
+) -> (Vec<Candidate<'a>>, Vec<&'a ImageEntry>) {
+    // Rank images within each repository (newest first) for keep-last.
+    let mut by_repo: HashMap<String, Vec<&ImageEntry>> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.role.is_devrs()) {
+        let repos: HashSet<String> = entry
+            .repo_tags
+            .iter()
+            .map(|t| split_reference(t).0)
+            .collect();
+        for repo in repos {
+            by_repo.entry(repo).or_default().push(entry);
+        }
+    }
+    let mut kept: HashSet<&str> = HashSet::new();
+    if let Some(n) = policy.keep_last {
+        for images in by_repo.values_mut() {
+            images.sort_by_key(|e| std::cmp::Reverse(e.created));
+            kept.extend(images.iter().take(n).map(|e| e.id.as_str()));
+        }
+    }
+
+    let mut candidates = Vec::new();
+    let mut in_use = Vec::new();
+    for entry in entries {
+        let reason = if entry.is_dangling() {
+            policy.dangling.then(|| "dangling".to_string())
+        } else if !entry.role.is_devrs()
+            || entry.repo_tags.iter().any(|t| protected.contains(t))
+            || (policy.keep_last.is_none() && policy.older_than.is_none())
+        {
+            None
+        } else {
+            let outside_keep = policy.keep_last.map(|n| {
+                (!kept.contains(entry.id.as_str())).then(|| format!("beyond newest {}", n))
+            });
+            let old_enough = policy.older_than.map(|d| {
+                let age = (now - entry.created).max(0) as u64;
+                (age > d.as_secs()).then(|| format!("older than {}", humantime::format_duration(d)))
+            });
+            // Every configured criterion must match.
+            match (outside_keep, old_enough) {
+                (Some(None), _) | (_, Some(None)) => None,
+                (a, b) => {
+                    let reasons: Vec<String> = a.flatten().into_iter().chain(b.flatten()).collect();
+                    Some(reasons.join(", "))
+                }
+            }
+        };
+        if let Some(reason) = reason {
+            if entry.in_use {
+                in_use.push(entry);
+            } else {
+                candidates.push(Candidate { entry, reason });
+            }
+        }
+    }
+    (candidates, in_use)
+}
+
+/// Display name for an image: its first tag, or the short ID for dangling images.
+fn display_name(entry: &ImageEntry) -> String {
+    entry
+        .repo_tags
+        .first()
+        .cloned()
+        .unwrap_or_else(|| format!("<none> ({})", entry.short_id()))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::super::inventory::{build_entries, tests as inv};
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const NOW: i64 = 100 * DAY;
+
+    /// Helper: a small inventory of core env tags, app images and other images.
+    fn sample_entries() -> Vec<ImageEntry> {
+        let mut cfg = inv::test_config();
+        cfg.application_defaults.default_image_prefix = Some("acme".to_string());
+        let images = vec![
+            inv::summary("sha256:core1", &["devrs-core-env:latest"], NOW - DAY, 10),
+            inv::summary("sha256:core2", &["devrs-core-env:v1"], NOW - 40 * DAY, 10),
+            inv::summary("sha256:core3", &["devrs-core-env:v0"], NOW - 60 * DAY, 10),
+            inv::summary("sha256:app1", &["acme-web:latest"], NOW - 2 * DAY, 5),
+            inv::summary("sha256:app2", &["acme-web:old"], NOW - 50 * DAY, 5),
+            inv::summary("sha256:dang", &["<none>:<none>"], NOW - 90 * DAY, 3),
+            inv::summary("sha256:pg", &["postgres:16"], NOW - 99 * DAY, 50),
+        ];
+        let used = HashSet::from(["sha256:app2".to_string()]);
+        build_entries(images, &used, &cfg)
+    }
+
+    /// Helper: IDs of selected candidates, sorted.
+    fn ids(candidates: &[Candidate]) -> Vec<String> {
+        let mut ids: Vec<String> = candidates.iter().map(|c| c.entry.id.clone()).collect();
+        ids.sort();
+        ids
+    }
+
+    const PROTECTED: &str = "devrs-core-env:latest";
+
+    /// Test keep-last per repository, with the in-use image skipped.
+    #[test]
+    fn test_select_keep_last() {
+        let entries = sample_entries();
+        let policy = PrunePolicy {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        let (candidates, in_use) =
+            select_candidates(&entries, &policy, &[PROTECTED.to_string()], NOW);
+        assert_eq!(ids(&candidates), vec!["sha256:core2", "sha256:core3"]);
+        assert_eq!(in_use.len(), 1);
+        assert_eq!(in_use[0].id, "sha256:app2");
+    }
+
+    /// Test that keep-last and older-than must both match.
+    #[test]
+    fn test_select_keep_last_and_older_than() {
+        let entries = sample_entries();
+        let policy = PrunePolicy {
+            keep_last: Some(1),
+            older_than: Some(Duration::from_secs(50 * DAY as u64)),
+            ..Default::default()
+        };
+        let (candidates, _) = select_candidates(&entries, &policy, &[PROTECTED.to_string()], NOW);
+        assert_eq!(ids(&candidates), vec!["sha256:core3"]);
+        assert!(candidates[0].reason.contains("older than"));
+    }
+
+    /// Test that dangling images are only removed with the dangling policy, and
+    /// that non-devrs images are never selected.
+    #[test]
+    fn test_select_dangling_only() {
+        let entries = sample_entries();
+        let policy = PrunePolicy {
+            dangling: true,
+            ..Default::default()
+        };
+        let (candidates, _) = select_candidates(&entries, &policy, &[], NOW);
+        assert_eq!(ids(&candidates), vec!["sha256:dang"]);
+    }
+
+    /// Test that the protected core env tag survives an aggressive age policy.
+    #[test]
+    fn test_select_protects_current_core_image() {
+        let entries = sample_entries();
+        let policy = PrunePolicy {
+            older_than: Some(Duration::from_secs(0)),
+            ..Default::default()
+        };
+        let (candidates, _) = select_candidates(&entries, &policy, &[PROTECTED.to_string()], NOW);
+        assert!(!ids(&candidates).contains(&"sha256:core1".to_string()));
+        assert!(!ids(&candidates).contains(&"sha256:pg".to_string()));
+    }
+
+    /// Test policy resolution: flags win, otherwise config defaults apply.
+    #[test]
+    fn test_resolve_policy() {
+        let mut cfg = Config::default();
+        cfg.images.prune.keep_last = Some(3);
+        cfg.images.prune.older_than = Some("7d".to_string());
+
+        let args = PruneArgs::try_parse_from(["prune"]).unwrap();
+        let policy = resolve_policy(&args, &cfg).unwrap();
+        assert_eq!(policy.keep_last, Some(3));
+        assert_eq!(policy.older_than, Some(Duration::from_secs(7 * DAY as u64)));
+        assert!(!policy.dangling);
+
+        let args = PruneArgs::try_parse_from(["prune", "--dangling"]).unwrap();
+        let policy = resolve_policy(&args, &cfg).unwrap();
+        assert_eq!(
+            policy,
+            PrunePolicy {
+                dangling: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    /// Test that an invalid duration is rejected by the argument parser.
+    #[test]
+    fn test_prune_args_invalid_duration() {
+        assert!(PruneArgs::try_parse_from(["prune", "--older-than", "soon"]).is_err());
+    }
+}
@@ -0,0 +1,706 @@
+//! # DevRS Aggregated Logs Command
+//!
+//! File: cli/src/commands/logs/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements `devrs logs`, which shows the logs of several containers
+//! at once. Where `devrs env logs` and `devrs container logs` copy one container's
+//! raw output, `devrs logs` merges the streams of every selected container in
+//! timestamp order and prefixes each line with the container name in its own color.
+//!
+//! Containers are selected by name, with `--all-devrs` (every container whose
+//! image has a DevRS role), or with `--project <name>` (the `devrs.project` label).
+//! When following, containers that start later and match the selector are picked
+//! up automatically.
+//!
+//! ## Architecture
+//!
+//! 1. Resolve the initial containers from the selector.
+//! 2. Spawn one task per container that forwards `common::docker::logs::log_lines`
+//!    over a channel.
+//! 3. Lines passing the `--grep` filter are buffered. Without `--follow` the buffer
+//!    is sorted and printed once every stream has finished. With `--follow` it is
+//!    sorted and flushed on a short interval, so lines from different containers
+//!    arriving close together still come out in time order.
+//! 4. While following, `common::docker::logs::container_starts` reports new
+//!    containers; matching ones get their own forwarding task.
+//!
+//! With `--archived`, lines come from the host-side log store written by
+//! `devrs collector` (`common::logstore`) instead of Docker, so the history of
+//! removed containers can be searched too.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Last 100 lines of two containers, merged
+//! devrs logs api worker
+//!
+//! # Follow everything DevRS manages, including containers started later
+//! devrs logs --all-devrs -f
+//!
+//! # Errors from one project in the last 15 minutes
+//! devrs logs --project webapp --since 15m --stream stderr --grep 'ERROR|WARN'
+//!
+//! # Search the archived history, including removed containers
+//! devrs logs --all-devrs --archived --since 7d --grep panic -n all
+//!
+//! # JSON lines for other tools
+//! devrs logs --all-devrs --since 2024-03-01T09:00:00Z --until 1h --json
+//! ```
+//!
+use crate::{
+    common::{
+        docker::{
+            self,
+            logs::{LogLine, LogQuery, LogStreamKind},
+            roles,
+        }, // Log streams, event stream and role classification.
+        logstore, // Archived history for `--archived`.
+    },
+    core::{
+        config::{self, Config},      // Config loading (role classification).
+        error::{DevrsError, Result}, // Standard Result type and custom errors.
+    },
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use chrono::{DateTime, Utc}; // For `--since`/`--until` parsing and timestamp display.
+use clap::{ArgGroup, Parser, ValueEnum}; // For parsing command-line arguments.
+use futures_util::StreamExt; // For consuming log and event streams.
+use regex::Regex; // For `--grep`.
+use std::{
+    collections::{HashMap, HashSet},
+    io::{IsTerminal, Write},
+    time::Duration,
+}; // Bookkeeping, color detection and output.
+use tokio::sync::mpsc; // Channel from per-container tasks to the printer.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+/// How long lines are buffered while following, so that lines from different
+/// containers arriving close together can be printed in timestamp order.
+const FOLLOW_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// ANSI colors for container name prefixes (red is avoided; it reads as an error).
+const PREFIX_COLORS: [&str; 10] = ["36", "33", "32", "35", "34", "96", "93", "92", "95", "94"];
+
+/// # Stream Selection (`StreamSelection`)
+///
+/// Which container output streams to show.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamSelection {
+    /// Both standard output and standard error.
+    All,
+    /// Only standard output.
+    Stdout,
+    /// Only standard error.
+    Stderr,
+}
+
+/// # Logs Arguments (`LogsArgs`)
+///
+/// Defines the command-line arguments accepted by `devrs logs`.
+#[derive(Parser, Debug)]
+#[command(about = "Show merged, color-coded logs of several containers")]
+#[command(group(
+    ArgGroup::new("selector")
+        .required(true)
+        .args(["containers", "all_devrs", "project"])
+))]
+pub struct LogsArgs {
+    /// Names of the containers to show.
+    containers: Vec<String>,
+
+    /// Select every container whose image has a DevRS role (core environment, applications, ...).
+    #[arg(long)]
+    all_devrs: bool,
+
+    /// Select the containers of one project (the `devrs.project` label set by `devrs container build`).
+    #[arg(long, value_name = "NAME")]
+    project: Option<String>,
+
+    /// Keep streaming new lines, and attach to matching containers that start later.
+    #[arg(short, long)]
+    follow: bool,
+
+    /// Number of lines to show from the end of each container's logs, or "all".
+    /// Defaults to 100, or to "all" when `--since` is given.
+    #[arg(long, short = 'n')]
+    lines: Option<String>,
+
+    /// Only show lines after this time: a duration ago (`15m`, `2h`) or an RFC 3339 timestamp.
+    #[arg(long, value_name = "TIME")]
+    since: Option<String>,
+
+    /// Only show lines before this time: a duration ago (`15m`, `2h`) or an RFC 3339 timestamp.
+    #[arg(long, value_name = "TIME")]
+    until: Option<String>,
+
+    /// Only show lines matching this regular expression.
+    #[arg(long, value_name = "REGEX", value_parser = parse_regex)]
+    grep: Option<Regex>,
+
+    /// Which output streams to show.
+    #[arg(long, value_enum, default_value = "all")]
+    stream: StreamSelection,
+
+    /// Do not print timestamps (text output only).
+    #[arg(long)]
+    no_timestamps: bool,
+
+    /// Print one JSON object per line (`container`, `stream`, `timestamp`, `message`).
+    #[arg(long)]
+    json: bool,
+
+    /// Search the history persisted by the log collector (`devrs collector`) instead of
+    /// asking Docker. Includes removed containers; `--lines` then counts matching lines.
+    #[arg(long, conflicts_with = "follow")]
+    archived: bool,
+}
+
+/// Validates and compiles the `--grep` expression.
+fn parse_regex(value: &str) -> std::result::Result<Regex, String> {
+    Regex::new(value).map_err(|e| e.to_string())
+}
+
+/// # Container Selector (`Selector`)
+///
+/// Which containers `devrs logs` shows.
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    /// Containers given by name.
+    Names(Vec<String>),
+    /// Containers whose image has a DevRS role.
+    AllDevrs,
+    /// Containers labelled with a `devrs.project`.
+    Project(String),
+}
+
+impl Selector {
+    /// Builds the selector from the (mutually exclusive) arguments.
+    fn from_args(args: &LogsArgs) -> Self {
+        if args.all_devrs {
+            Selector::AllDevrs
+        } else if let Some(project) = &args.project {
+            Selector::Project(project.clone())
+        } else {
+            Selector::Names(args.containers.clone())
+        }
+    }
+
+    /// Whether a container matches the selector.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Container name (without leading `/`).
+    /// * `image` - Image reference the container runs.
+    /// * `labels` - Container labels (including those inherited from the image).
+    /// * `cfg` - The loaded configuration (for role classification).
+    fn matches(
+        &self,
+        name: &str,
+        image: &str,
+        labels: &HashMap<String, String>,
+        cfg: &Config,
+    ) -> bool {
+        match self {
+            Selector::Names(names) => names.iter().any(|n| n == name),
+            Selector::AllDevrs => roles::container_role(name, image, labels, cfg).is_devrs(),
+            Selector::Project(project) => {
+                labels.get(roles::PROJECT_LABEL).map(String::as_str) == Some(project.as_str())
+            }
+        }
+    }
+}
+
+/// Parses a `--since`/`--until` value into a Unix timestamp.
+///
+/// # Arguments
+///
+/// * `value` - A duration before `now` (humantime syntax, e.g. `15m`) or an RFC 3339 timestamp.
+/// * `now` - The reference time for durations.
+///
+/// # Returns
+///
+/// * `Result<i64>` - Seconds since the Unix epoch.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ArgumentParsing` if the value is neither a duration nor a timestamp.
+fn parse_time_bound(value: &str, now: DateTime<Utc>) -> Result<i64> {
+    if let Ok(duration) = humantime::parse_duration(value) {
+        return Ok(now.timestamp() - duration.as_secs() as i64);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp())
+        .map_err(|_| {
+            anyhow!(DevrsError::ArgumentParsing(format!(
+                "Invalid time '{}': expected a duration (e.g. 15m, 2h) or an RFC 3339 timestamp",
+                value
+            )))
+        })
+}
+
+/// Messages from the per-container tasks to the printer.
+enum LogEvent {
+    /// A line of output.
+    Line(LogLine),
+    /// A container's stream failed.
+    Failed(String, anyhow::Error),
+    /// A container's stream ended (it stopped, or all requested lines were sent).
+    Ended(String),
+}
+
+/// # Handle Logs Command (`handle_logs`)
+///
+/// ## Workflow:
+/// 1.  Loads configuration and builds the selector and Docker log query.
+/// 2.  Resolves the matching containers and starts one forwarding task per container.
+/// 3.  Buffers, filters and prints lines (sorted at the end, or periodically with `--follow`).
+/// 4.  With `--follow`, attaches to matching containers as they start, until Ctrl+C.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `LogsArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success, or an `Err` if arguments are invalid or Docker calls fail.
+pub async fn handle_logs(args: LogsArgs) -> Result<()> {
+    info!("Handling logs command...");
+    debug!("Logs args: {:?}", args);
+
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let selector = Selector::from_args(&args);
+    let now = Utc::now();
+    let query = LogQuery {
+        stdout: args.stream != StreamSelection::Stderr,
+        stderr: args.stream != StreamSelection::Stdout,
+        follow: args.follow,
+        tail: args
+            .lines
+            .clone()
+            .unwrap_or_else(|| if args.since.is_some() { "all" } else { "100" }.to_string()),
+        since: args
+            .since
+            .as_deref()
+            .map(|v| parse_time_bound(v, now))
+            .transpose()?
+            .unwrap_or(0),
+        until: args
+            .until
+            .as_deref()
+            .map(|v| parse_time_bound(v, now))
+            .transpose()?
+            .unwrap_or(0),
+    };
+
+    if args.archived {
+        return print_archived(&args, &selector, &query, &cfg);
+    }
+
+    // Subscribe to start events before listing, so no container slips through in between.
+    let mut starts = if args.follow {
+        Some(Box::pin(docker::logs::container_starts().await?))
+    } else {
+        None
+    };
+
+    let initial = initial_containers(&selector, &cfg).await?;
+    if initial.is_empty() && !args.follow {
+        println!("No matching containers found.");
+        return Ok(());
+    }
+
+    let mut printer = Printer::new(args.json, !args.no_timestamps);
+    let (tx, mut rx) = mpsc::channel::<LogEvent>(1024);
+    let mut active: HashSet<String> = HashSet::new();
+    for name in initial {
+        printer.register(&name);
+        spawn_forwarder(name.clone(), query.clone(), tx.clone());
+        active.insert(name);
+    }
+    // Without --follow the channel must close once all forwarders finish.
+    let follow_tx = if args.follow { Some(tx) } else { None };
+
+    let mut pending: Vec<LogLine> = Vec::new();
+    let mut ticker = tokio::time::interval(FOLLOW_FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Some(LogEvent::Line(line)) => {
+                    if args.grep.as_ref().is_none_or(|re| re.is_match(&line.message)) {
+                        pending.push(line);
+                    }
+                }
+                Some(LogEvent::Failed(name, e)) => {
+                    eprintln!("Error reading logs of '{}': {:#}", name, e);
+                    active.remove(&name);
+                }
+                Some(LogEvent::Ended(name)) => {
+                    debug!("Log stream of '{}' ended.", name);
+                    active.remove(&name);
+                }
+                None => break, // All forwarders done (only without --follow).
+            },
+            _ = ticker.tick(), if args.follow => printer.flush(&mut pending),
+            Some(start) = async {
+                match starts.as_mut() {
+                    Some(s) => s.next().await,
+                    None => None,
+                }
+            }, if starts.is_some() => match start {
+                Ok(start) => {
+                    if !active.contains(&start.name)
+                        && selector.matches(&start.name, &start.image, &start.labels, &cfg)
+                    {
+                        info!("Attaching to newly started container '{}'.", start.name);
+                        printer.register(&start.name);
+                        // Only new output: the container just started.
+                        let query = LogQuery {
+                            tail: "all".to_string(),
+                            since: Utc::now().timestamp() - 1,
+                            ..query.clone()
+                        };
+                        if let Some(tx) = &follow_tx {
+                            spawn_forwarder(start.name.clone(), query, tx.clone());
+                        }
+                        active.insert(start.name);
+                    }
+                }
+                Err(e) => {
+                    warn!("Stopped watching for new containers: {:#}", e);
+                    starts = None;
+                }
+            },
+            _ = tokio::signal::ctrl_c(), if args.follow => break,
+        }
+    }
+    printer.flush(&mut pending);
+    Ok(())
+}
+
+/// Lists the containers matching the selector.
+///
+/// Named containers are used as given (errors for unknown names surface when their
+/// logs are read). For the other selectors, all containers (running or stopped) are
+/// listed and classified.
+async fn initial_containers(selector: &Selector, cfg: &Config) -> Result<Vec<String>> {
+    if let Selector::Names(names) = selector {
+        return Ok(names.clone());
+    }
+    let containers = docker::state::list_containers(true, None)
+        .await
+        .context("Failed to list containers")?;
+    Ok(containers
+        .into_iter()
+        .filter_map(|c| {
+            let name = c
+                .names
+                .as_ref()?
+                .first()?
+                .trim_start_matches('/')
+                .to_string();
+            let labels = c.labels.clone().unwrap_or_default();
+            let image = c.image.clone().unwrap_or_default();
+            selector
+                .matches(&name, &image, &labels, cfg)
+                .then_some(name)
+        })
+        .collect())
+}
+
+/// Prints the archived history of the stored containers matching the selector.
+///
+/// # Arguments
+///
+/// * `args` - The parsed arguments (`--grep`, output format).
+/// * `selector` - Which containers to include (matched against stored metadata).
+/// * `query` - Streams, time bounds and per-container line count.
+/// * `cfg` - The loaded configuration (store location, role classification).
+///
+/// # Errors
+///
+/// Returns an error if the log store cannot be read.
+fn print_archived(
+    args: &LogsArgs,
+    selector: &Selector,
+    query: &LogQuery,
+    cfg: &Config,
+) -> Result<()> {
+    let root = logstore::root_dir(cfg);
+    let mut printer = Printer::new(args.json, !args.no_timestamps);
+    let mut pending = Vec::new();
+    for meta in logstore::reader::stored_containers(&root)?
+        .iter()
+        .filter(|m| selector.matches(&m.name, &m.image, &m.labels, cfg))
+    {
+        let history = logstore::reader::read_history(&root, &meta.name)?;
+        let lines = select_archived(history, query, args.grep.as_ref());
+        if !lines.is_empty() {
+            printer.register(&meta.name);
+            pending.extend(lines);
+        }
+    }
+    if pending.is_empty() {
+        println!(
+            "No archived log lines match (log store: '{}').",
+            root.display()
+        );
+        return Ok(());
+    }
+    printer.flush(&mut pending);
+    Ok(())
+}
+
+/// Applies the stream, time, `--grep` and line-count filters to one container's history.
+///
+/// Unlike live logs, the line count is applied after `--grep`, so that a search
+/// returns the last N *matching* lines.
+fn select_archived(history: Vec<LogLine>, query: &LogQuery, grep: Option<&Regex>) -> Vec<LogLine> {
+    let mut lines: Vec<LogLine> = history
+        .into_iter()
+        .filter(|l| match l.stream {
+            LogStreamKind::Stdout => query.stdout,
+            LogStreamKind::Stderr => query.stderr,
+        })
+        .filter(|l| {
+            let secs = l.timestamp.map_or(0, |ts| ts.timestamp());
+            (query.since == 0 || secs >= query.since) && (query.until == 0 || secs <= query.until)
+        })
+        .filter(|l| grep.is_none_or(|re| re.is_match(&l.message)))
+        .collect();
+    if let Ok(n) = query.tail.parse::<usize>() {
+        lines.drain(..lines.len().saturating_sub(n));
+    }
+    lines
+}
+
+/// Spawns a task forwarding one container's log lines to the printer.
+fn spawn_forwarder(name: String, query: LogQuery, tx: mpsc::Sender<LogEvent>) {
+    tokio::spawn(async move {
+        let stream = match docker::logs::log_lines(&name, &query).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = tx.send(LogEvent::Failed(name, e)).await;
+                return;
+            }
+        };
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            let event = match item {
+                Ok(line) => LogEvent::Line(line),
+                Err(e) => {
+                    let _ = tx.send(LogEvent::Failed(name, e)).await;
+                    return;
+                }
+            };
+            if tx.send(event).await.is_err() {
+                return; // Printer has exited.
+            }
+        }
+        let _ = tx.send(LogEvent::Ended(name)).await;
+    });
+}
+
+/// # Line Printer (`Printer`)
+///
+/// Formats lines as colored text or JSON and writes them to stdout.
+struct Printer {
+    /// Print JSON objects instead of text.
+    json: bool,
+    /// Include timestamps in text output.
+    timestamps: bool,
+    /// Whether to emit ANSI colors.
+    color: bool,
+    /// Color assigned to each container, in order of first appearance.
+    colors: HashMap<String, &'static str>,
+    /// Width of the name column (longest registered name).
+    name_width: usize,
+}
+
+impl Printer {
+    /// Creates a printer. Colors are used only on a terminal and when `NO_COLOR` is unset.
+    fn new(json: bool, timestamps: bool) -> Self {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Self {
+            json,
+            timestamps,
+            color,
+            colors: HashMap::new(),
+            name_width: 0,
+        }
+    }
+
+    /// Assigns a color to a container and widens the name column if needed.
+    fn register(&mut self, name: &str) {
+        let next = PREFIX_COLORS[self.colors.len() % PREFIX_COLORS.len()];
+        self.colors.entry(name.to_string()).or_insert(next);
+        self.name_width = self.name_width.max(name.chars().count());
+    }
+
+    /// Formats one line for output.
+    fn format(&self, line: &LogLine) -> String {
+        if self.json {
+            return serde_json::to_string(line).unwrap_or_else(|_| line.message.clone());
+        }
+        let name = format!("{:<width$}", line.container, width = self.name_width);
+        let prefix = match (self.color, self.colors.get(&line.container)) {
+            (true, Some(code)) => format!("\x1b[{}m{} |\x1b[0m", code, name),
+            _ => format!("{} |", name),
+        };
+        match (self.timestamps, line.timestamp) {
+            (true, Some(ts)) => format!(
+                "{} {} {}",
+                prefix,
+                ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                line.message
+            ),
+            _ => format!("{} {}", prefix, line.message),
+        }
+    }
+
+    /// Sorts the buffered lines by timestamp (stable) and prints them.
+    fn flush(&self, pending: &mut Vec<LogLine>) {
+        if pending.is_empty() {
+            return;
+        }
+        pending.sort_by_key(|l| l.timestamp);
+        let mut out = std::io::stdout().lock();
+        for line in pending.drain(..) {
+            let _ = writeln!(out, "{}", self.format(&line));
+        }
+        let _ = out.flush();
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper: a log line at a given second.
+    fn line(container: &str, secs: i64, message: &str) -> LogLine {
+        LogLine {
+            container: container.to_string(),
+            stream: LogStreamKind::Stdout,
+            timestamp: DateTime::from_timestamp(secs, 0),
+            message: message.to_string(),
+        }
+    }
+
+    /// Test selector parsing and mutual exclusion.
+    #[test]
+    fn test_logs_args_selectors() {
+        let args = LogsArgs::try_parse_from(["logs", "api", "worker"]).unwrap();
+        assert_eq!(
+            Selector::from_args(&args),
+            Selector::Names(vec!["api".to_string(), "worker".to_string()])
+        );
+        let args = LogsArgs::try_parse_from(["logs", "--project", "webapp", "-f"]).unwrap();
+        assert_eq!(
+            Selector::from_args(&args),
+            Selector::Project("webapp".to_string())
+        );
+        assert!(LogsArgs::try_parse_from(["logs"]).is_err());
+        assert!(LogsArgs::try_parse_from(["logs", "api", "--all-devrs"]).is_err());
+        assert!(LogsArgs::try_parse_from(["logs", "--all-devrs", "--grep", "("]).is_err());
+        assert!(LogsArgs::try_parse_from(["logs", "--all-devrs", "--archived", "-f"]).is_err());
+        let args = LogsArgs::try_parse_from(["logs", "--all-devrs", "--stream", "stderr"]).unwrap();
+        assert_eq!(args.stream, StreamSelection::Stderr);
+    }
+
+    /// Test `--since`/`--until` parsing of durations and timestamps.
+    #[test]
+    fn test_parse_time_bound() {
+        let now = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        assert_eq!(parse_time_bound("15m", now).unwrap(), 1_000_000 - 900);
+        assert_eq!(
+            parse_time_bound("2024-03-01T10:00:00Z", now).unwrap(),
+            1_709_287_200
+        );
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+
+    /// Test selector matching for projects and DevRS roles.
+    #[test]
+    fn test_selector_matches() {
+        let mut cfg = Config::default();
+        cfg.core_env.image_name = "devrs-core-env".to_string();
+        let labels = HashMap::from([
+            (roles::ROLE_LABEL.to_string(), "app".to_string()),
+            (roles::PROJECT_LABEL.to_string(), "webapp".to_string()),
+        ]);
+        let none = HashMap::new();
+
+        let project = Selector::Project("webapp".to_string());
+        assert!(project.matches("web-1", "webapp:latest", &labels, &cfg));
+        assert!(!project.matches("db", "postgres:16", &none, &cfg));
+
+        assert!(Selector::AllDevrs.matches("web-1", "webapp:latest", &labels, &cfg));
+        assert!(Selector::AllDevrs.matches(
+            "devrs-core-env-instance",
+            "devrs-core-env:latest",
+            &none,
+            &cfg
+        ));
+        assert!(!Selector::AllDevrs.matches("db", "postgres:16", &none, &cfg));
+    }
+
+    /// Test filtering of archived history.
+    #[test]
+    fn test_select_archived() {
+        let mut history: Vec<LogLine> = (0..10)
+            .map(|i| line("api", i * 10, &format!("msg {}", i)))
+            .collect();
+        history[9].stream = LogStreamKind::Stderr;
+        let query = LogQuery {
+            stderr: false,
+            since: 20,
+            tail: "2".to_string(),
+            ..Default::default()
+        };
+        let selected = select_archived(history.clone(), &query, None);
+        let messages: Vec<&str> = selected.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, vec!["msg 7", "msg 8"]);
+
+        // The line count applies to matching lines.
+        let re = Regex::new("msg [13]").unwrap();
+        let query = LogQuery {
+            tail: "5".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(select_archived(history, &query, Some(&re)).len(), 2);
+    }
+
+    /// Test text and JSON formatting.
+    #[test]
+    fn test_printer_format_and_order() {
+        let mut printer = Printer {
+            json: false,
+            timestamps: false,
+            color: false,
+            colors: HashMap::new(),
+            name_width: 0,
+        };
+        printer.register("api");
+        printer.register("worker");
+        assert_eq!(printer.format(&line("api", 0, "hello")), "api    | hello");
+
+        printer.timestamps = true;
+        assert_eq!(
+            printer.format(&line("worker", 0, "hi")),
+            "worker | 1970-01-01T00:00:00.000Z hi"
+        );
+
+        printer.json = true;
+        let json: serde_json::Value =
+            serde_json::from_str(&printer.format(&line("api", 0, "x"))).unwrap();
+        assert_eq!(json["stream"], "stdout");
+        assert_eq!(json["container"], "api");
+    }
+}
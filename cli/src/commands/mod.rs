@@ -23,9 +23,16 @@
 //! ## Command Groups
 //!
 //! - `blueprint`: Project template management commands
+//! - `collector`: Background log collector persisting container logs to rotating files
+//! - `config`: Configuration conversion (e.g. `devcontainer.json` export)
 //! - `container`: Application container management commands
 //! - `env`: Core development environment commands
+//! - `images`: Image inventory and retention-based cleanup commands
+//! - `du`: Disk usage report grouped by DevRS role and project
+//! - `logs`: Merged, color-coded logs of several containers
+//! - `stats`: Live container resource usage (CPU, memory, network and block I/O)
 //! - `setup`: Host system configuration commands
+//! - `shims`: Host command wrappers that run tools inside the core environment
 //! - `srv`: HTTP file server commands
 //!
 //! Each command group defines its own arguments structure and handler function
@@ -34,14 +41,28 @@
 
 /// Command group for managing project templates (blueprints). Includes subcommands like `list`, `create`, `info`.
 pub mod blueprint;
+/// Command group for the background log collector. Includes subcommands `start`, `stop`, `status` and `run`.
+pub mod collector;
+/// Command group for working with the configuration itself. Includes the `export` subcommand.
+pub mod config;
 /// Command group for managing application-specific Docker containers. Includes subcommands like `build`, `run`, `logs`, `rm`.
 pub mod container;
+/// Disk usage report (`devrs du`) across DevRS images, containers, volumes and build cache.
+pub mod du;
 /// Command group for managing the core development environment container. Includes subcommands like `build`, `shell`, `exec`, `status`.
 pub mod env;
+/// Command group for image inventory and cleanup. Includes subcommands like `ls` and `prune`.
+pub mod images;
+/// Merged, color-coded logs of several containers (`devrs logs`).
+pub mod logs;
 /// Command group for setting up the host system with necessary dependencies and configurations. Includes subcommands like `all`, `nvim`, `shell`.
 pub mod setup;
+/// Command group for host command shims. Includes subcommands `install`, `ls` and `rm`.
+pub mod shims;
 /// Command group for the static HTTP file server. Includes configuration and server logic.
 pub mod srv;
+/// Live resource usage of containers (`devrs stats`), also used by the `--stats` flag of the status commands.
+pub mod stats;
 
 // Note regarding subcommand declarations:
 // Subcommands (like `build` within `container`, or `list` within `blueprint`)
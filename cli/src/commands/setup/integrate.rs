@@ -23,6 +23,7 @@
 //!     the DevRS repository installation using the `find_repo_root` helper from the `setup` module.
 //! 3.  Prints clear instructions to the console, including:
 //!     * The exact `source "<path_to_shell_functions>"` command to add.
+//!     * An `export PATH=...` line putting the command shim directory (`devrs shims`) first on `PATH`.
 //!     * Which file (`~/.bashrc` or `~/.zshrc`) the user should add it to based on the detected shell.
 //!     * How to reload the shell configuration afterwards (by running `source` again or opening a new terminal).
 //!
@@ -43,6 +44,8 @@
 //! shell configuration file.
 //!
 use crate::commands::setup::find_repo_root; // Use shared helper from setup::mod
+use crate::commands::shims; // Shim directory to put on PATH
+use crate::core::config; // Configuration (for a custom shim directory)
 use crate::core::error::Result; // Standard Result type
 use anyhow::{bail, Context}; // Error handling utilities
 use clap::Parser; // Argument parsing
@@ -130,20 +133,29 @@ pub async fn handle_integrate(_args: IntegrateArgs) -> Result<()> {
         canonical_functions_path.display()
     );
 
+    // The shim directory; fall back to the default location if the configuration cannot be loaded.
+    let shim_dir = shims::shim_dir(&config::load_config().unwrap_or_default());
+    debug!("Shim directory for PATH: {}", shim_dir.display());
+
     // --- 3. Print Instructions ---
     // Print clear, multi-line instructions for the user.
     println!("\n--- DevRS Integration Instructions ---");
-    println!("\nTo make DevRS shell functions available, please **manually add** the following");
-    println!("line to your shell configuration file ({})", rc_filename);
+    println!("\nTo make DevRS shell functions and command shims available, please");
+    println!(
+        "**manually add** the following lines to your shell configuration file ({})",
+        rc_filename
+    );
     println!("and then reload your shell or open a new terminal:");
     // Add visual separators for clarity.
-    println!("\n# --- Start: Add this line to {} ---", rc_filename);
+    println!("\n# --- Start: Add these lines to {} ---", rc_filename);
     // Print the exact command the user needs, using quotes for safety,
     // especially if paths might contain spaces or special characters.
     println!("source \"{}\"", canonical_functions_path.display());
-    println!("# --- End: Add this line ---");
+    // Put the command shims (`devrs shims install`) ahead of host tools on PATH.
+    println!("export PATH=\"{}:$PATH\"", shim_dir.display());
+    println!("# --- End: Add these lines ---");
 
-    println!("\nAfter adding the line, reload your configuration by running:");
+    println!("\nAfter adding the lines, reload your configuration by running:");
     println!("  source {}", rc_filename);
     println!("Or simply open a new terminal window.");
     println!("------------------------------------------");
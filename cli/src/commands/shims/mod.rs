@@ -0,0 +1,435 @@
+//! # DevRS Host Command Shims
+//!
+//! File: cli/src/commands/shims/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Shims let developers type `cargo test` or `go build` on the host and have the tool
+//! run inside the core environment, in the container directory matching the current
+//! host directory. A shim is a small `sh` wrapper script named after the tool, placed in
+//! a DevRS bin directory that `devrs setup integrate` puts at the front of `PATH`.
+//!
+//! Each shim runs `devrs env exec --map-cwd --exit-code`, which:
+//! - translates the host working directory through `core_env.mounts`,
+//! - passes the tool's exit code through unchanged,
+//! - optionally (`shims.host_paths = true`) rewrites container paths in the output to host paths,
+//! - and gets `-i`/`-t` from the shim, which checks `isatty` on stdin/stdout
+//!   (`-i -t` in a terminal, `-i` when input is piped, neither otherwise).
+//!
+//! ## Architecture
+//!
+//! - **`install <tool...>`**: Writes shims (all of `shims.allow` if no tools are given).
+//!   With a non-empty `shims.allow`, other tools are refused.
+//! - **`ls`**: Lists installed shims.
+//! - **`rm <tool...>` / `rm --all`**: Removes shims.
+//!
+//! Shims carry a `# devrs-shim: <tool>` marker line; files without it are never
+//! overwritten (without `--force`) or removed.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! devrs shims install cargo rustc go
+//! devrs shims ls
+//! devrs shims rm go
+//! ```
+//!
+use crate::core::{
+    config::{self, Config},      // Shim directory and allowlist.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use clap::{Parser, Subcommand}; // Argument parsing.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+}; // Shim files and PATH inspection.
+use tracing::{debug, info}; // Logging framework utilities.
+
+/// The marker line identifying a file as a DevRS shim.
+const SHIM_MARKER: &str = "# devrs-shim: ";
+
+/// # Shims Command Group Arguments (`ShimsArgs`)
+///
+/// This struct represents the top-level command group `devrs shims`.
+#[derive(Parser, Debug)]
+#[command(about = "Manage host command shims that run tools inside the core environment")]
+pub struct ShimsArgs {
+    /// The specific shims subcommand to execute.
+    #[command(subcommand)]
+    command: ShimsCommand,
+}
+
+/// # Shims Subcommands (`ShimsCommand`)
+#[derive(Subcommand, Debug)]
+enum ShimsCommand {
+    /// Corresponds to `devrs shims install`. Writes shims for the given tools.
+    Install {
+        /// Tools to shim (e.g. `cargo go`). Defaults to all tools in `shims.allow`.
+        tools: Vec<String>,
+        /// Overwrite existing files in the shim directory that are not DevRS shims.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Corresponds to `devrs shims ls`. Lists installed shims.
+    Ls,
+    /// Corresponds to `devrs shims rm`. Removes shims.
+    Rm {
+        /// Tools whose shims to remove.
+        #[arg(required_unless_present = "all")]
+        tools: Vec<String>,
+        /// Remove all installed shims.
+        #[arg(long, conflicts_with = "tools")]
+        all: bool,
+    },
+}
+
+/// # Handle Shims Command (`handle_shims`)
+///
+/// Loads the configuration and dispatches to the subcommand handlers.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `ShimsArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: Propagates the `Result` from the called subcommand handler.
+pub async fn handle_shims(args: ShimsArgs) -> Result<()> {
+    let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+    let dir = shim_dir(&cfg);
+    debug!("Shim directory: {}", dir.display());
+    match args.command {
+        ShimsCommand::Install { tools, force } => handle_install(&cfg, &dir, tools, force),
+        ShimsCommand::Ls => handle_ls(&dir),
+        ShimsCommand::Rm { tools, all } => handle_rm(&dir, tools, all),
+    }
+}
+
+/// The directory holding the shims (`shims.directory`, default `~/.local/share/devrs/bin`).
+///
+/// Also used by `devrs setup integrate` to print the `PATH` line.
+pub fn shim_dir(cfg: &Config) -> PathBuf {
+    if let Some(dir) = cfg.shims.directory.as_deref().filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    dirs::data_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("devrs")
+        .join("bin")
+}
+
+/// # Handle Install (`handle_install`)
+///
+/// ## Workflow:
+/// 1.  Resolves the tool list (arguments, or `shims.allow` if none were given).
+/// 2.  Validates every tool name and checks it against `shims.allow` (if configured).
+/// 3.  Writes the shims, pointing at the running `devrs` executable (with `--host-paths`
+///     if `shims.host_paths` is set).
+/// 4.  Prints a `PATH` hint if the shim directory is not on `PATH`.
+fn handle_install(cfg: &Config, dir: &Path, tools: Vec<String>, force: bool) -> Result<()> {
+    let tools = if tools.is_empty() {
+        if cfg.shims.allow.is_empty() {
+            return Err(anyhow!(DevrsError::ArgumentParsing(
+                "No tools given and `shims.allow` is empty. Usage: devrs shims install <tool...>"
+                    .to_string()
+            )));
+        }
+        cfg.shims.allow.clone()
+    } else {
+        tools
+    };
+    for tool in &tools {
+        check_allowed(tool, &cfg.shims.allow)?;
+    }
+
+    let devrs = env::current_exe().context("Failed to locate the devrs executable")?;
+    let host_paths = cfg.shims.host_paths.unwrap_or(false);
+    for tool in &tools {
+        let path = install_shim(dir, tool, &devrs, host_paths, force)?;
+        println!("Installed shim '{}' -> {}", tool, path.display());
+    }
+    if !on_path(dir) {
+        println!(
+            "\nNote: '{}' is not on your PATH. Run `devrs setup integrate` for the line to add to your shell configuration.",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// # Handle List (`handle_ls`)
+///
+/// Prints the installed shims and whether the shim directory is on `PATH`.
+fn handle_ls(dir: &Path) -> Result<()> {
+    let shims = installed_shims(dir)?;
+    if shims.is_empty() {
+        println!("No shims installed in '{}'.", dir.display());
+        return Ok(());
+    }
+    println!("Shims in '{}':", dir.display());
+    for (tool, _) in &shims {
+        println!("  {}", tool);
+    }
+    if !on_path(dir) {
+        println!("\nNote: this directory is not on your PATH, so the shims are inactive.");
+    }
+    Ok(())
+}
+
+/// # Handle Remove (`handle_rm`)
+///
+/// Removes the named shims (or all with `--all`). Files that are not DevRS shims are left alone.
+fn handle_rm(dir: &Path, tools: Vec<String>, all: bool) -> Result<()> {
+    let installed = installed_shims(dir)?;
+    let targets: Vec<&(String, PathBuf)> = if all {
+        installed.iter().collect()
+    } else {
+        let mut targets = Vec::new();
+        for tool in &tools {
+            match installed.iter().find(|(name, _)| name == tool) {
+                Some(shim) => targets.push(shim),
+                None => {
+                    return Err(anyhow!(DevrsError::ArgumentParsing(format!(
+                        "No shim named '{}' in '{}'.",
+                        tool,
+                        dir.display()
+                    ))))
+                }
+            }
+        }
+        targets
+    };
+    for (tool, path) in targets {
+        fs::remove_file(path).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to remove '{}': {}",
+                path.display(),
+                e
+            )))
+        })?;
+        println!("Removed shim '{}'.", tool);
+    }
+    Ok(())
+}
+
+/// Checks a tool name and, if an allowlist is configured, that the tool is on it.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ArgumentParsing` for invalid names and `DevrsError::Config`
+/// for tools missing from a non-empty allowlist.
+fn check_allowed(tool: &str, allow: &[String]) -> Result<()> {
+    if !config::is_valid_tool_name(tool) {
+        return Err(anyhow!(DevrsError::ArgumentParsing(format!(
+            "Invalid tool name '{}'. Expected a bare command name like 'cargo'.",
+            tool
+        ))));
+    }
+    if !allow.is_empty() && !allow.iter().any(|a| a == tool) {
+        return Err(anyhow!(DevrsError::Config(format!(
+            "'{}' is not in shims.allow ({}).",
+            tool,
+            allow.join(", ")
+        ))));
+    }
+    Ok(())
+}
+
+/// Renders the wrapper script for `tool`. With `host_paths`, the shim also passes
+/// `--host-paths` so container paths in the tool's output are shown as host paths.
+fn render_shim(tool: &str, devrs: &Path, host_paths: bool) -> String {
+    format!(
+        r#"#!/bin/sh
+{marker}{tool}
+# Generated by `devrs shims install`: runs `{tool}` inside the DevRS core environment.
+if [ -t 0 ] && [ -t 1 ]; then
+  set -- -i -t -- {tool} "$@"
+elif [ ! -t 0 ]; then
+  set -- -i -- {tool} "$@"
+else
+  set -- -- {tool} "$@"
+fi
+exec {devrs} env exec --map-cwd --exit-code{extra} "$@"
+"#,
+        marker = SHIM_MARKER,
+        tool = tool,
+        devrs = sh_quote(&devrs.to_string_lossy()),
+        extra = if host_paths { " --host-paths" } else { "" },
+    )
+}
+
+/// Quotes a string for `sh` using single quotes.
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'\''"#))
+}
+
+/// Returns the tool a shim file wraps, or `None` if it is not a DevRS shim.
+fn shim_tool(content: &str) -> Option<String> {
+    content
+        .lines()
+        .take(3)
+        .find_map(|line| line.strip_prefix(SHIM_MARKER))
+        .map(|tool| tool.trim().to_string())
+}
+
+/// Writes one shim, making it executable.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the directory or file cannot be written, or if a
+/// file that is not a DevRS shim exists at the target and `force` is not set.
+fn install_shim(
+    dir: &Path,
+    tool: &str,
+    devrs: &Path,
+    host_paths: bool,
+    force: bool,
+) -> Result<PathBuf> {
+    let path = dir.join(tool);
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if shim_tool(&existing).is_none() && !force {
+            return Err(anyhow!(DevrsError::FileSystem(format!(
+                "'{}' exists and is not a DevRS shim; use --force to overwrite it.",
+                path.display()
+            ))));
+        }
+    }
+    let write = || -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(&path, render_shim(tool, devrs, host_paths))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    };
+    write().map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to write shim '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+    info!("Wrote shim {}", path.display());
+    Ok(path)
+}
+
+/// Lists the DevRS shims in `dir`, sorted by tool name.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the directory exists but cannot be read.
+fn installed_shims(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to read shim directory '{}': {}",
+            dir.display(),
+            e
+        )))
+    })?;
+    let mut shims: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let tool = shim_tool(&fs::read_to_string(&path).ok()?)?;
+            Some((tool, path))
+        })
+        .collect();
+    shims.sort();
+    Ok(shims)
+}
+
+/// Whether `dir` is one of the entries of `PATH`.
+fn on_path(dir: &Path) -> bool {
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|p| p == dir))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test subcommand parsing, including `rm` requiring tools or `--all`.
+    #[test]
+    fn test_parses_shims_subcommands() {
+        let args = ShimsArgs::try_parse_from(["shims", "install", "cargo", "go"]).unwrap();
+        assert!(matches!(
+            args.command,
+            ShimsCommand::Install { ref tools, force: false } if tools == &["cargo", "go"]
+        ));
+        assert!(ShimsArgs::try_parse_from(["shims", "ls"]).is_ok());
+        assert!(ShimsArgs::try_parse_from(["shims", "rm", "--all"]).is_ok());
+        assert!(ShimsArgs::try_parse_from(["shims", "rm"]).is_err());
+        assert!(ShimsArgs::try_parse_from(["shims", "rm", "go", "--all"]).is_err());
+    }
+
+    /// Test the allowlist and tool name checks.
+    #[test]
+    fn test_check_allowed() {
+        assert!(check_allowed("cargo", &[]).is_ok());
+        assert!(check_allowed("sh -c", &[]).is_err());
+        let allow = vec!["cargo".to_string()];
+        assert!(check_allowed("cargo", &allow).is_ok());
+        assert!(check_allowed("go", &allow).is_err());
+    }
+
+    /// Test the rendered script: marker, TTY detection and exec flags.
+    #[test]
+    fn test_render_shim() {
+        let script = render_shim("cargo", Path::new("/opt/it's/devrs"), false);
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert_eq!(shim_tool(&script).as_deref(), Some("cargo"));
+        assert!(script.contains("[ -t 0 ] && [ -t 1 ]"));
+        assert!(script.contains(r#"exec '/opt/it'\''s/devrs' env exec --map-cwd --exit-code "$@""#));
+        assert_eq!(shim_tool("#!/bin/sh\necho hi\n"), None);
+
+        let script = render_shim("cargo", Path::new("/usr/bin/devrs"), true);
+        assert!(script.contains("env exec --map-cwd --exit-code --host-paths \"$@\""));
+    }
+
+    /// Test installing, listing and removing shims, and that foreign files are protected.
+    #[test]
+    fn test_install_ls_rm() {
+        let dir = tempdir().unwrap();
+        let devrs = Path::new("/usr/local/bin/devrs");
+        install_shim(dir.path(), "go", devrs, false, false).unwrap();
+        install_shim(dir.path(), "cargo", devrs, false, false).unwrap();
+        install_shim(dir.path(), "cargo", devrs, false, false).unwrap(); // Reinstall is fine.
+        fs::write(dir.path().join("make"), "#!/bin/sh\n").unwrap();
+        assert!(install_shim(dir.path(), "make", devrs, false, false).is_err());
+
+        let tools: Vec<String> = installed_shims(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(tool, _)| tool)
+            .collect();
+        assert_eq!(tools, ["cargo", "go"]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join("go"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+
+        assert!(handle_rm(dir.path(), vec!["make".to_string()], false).is_err());
+        handle_rm(dir.path(), Vec::new(), true).unwrap();
+        assert!(installed_shims(dir.path()).unwrap().is_empty());
+        assert!(dir.path().join("make").exists());
+    }
+}
@@ -55,9 +55,15 @@
 //! The module ensures that all paths are resolved to absolute paths and
 //! that the directory to serve actually exists and is accessible.
 //!
-use crate::core::error::Result;
-use anyhow::Context;
+//! Type errors in the file are reported with their line and column. Unknown keys
+//! are ignored with a warning that suggests the intended key (see `core::schema`).
+//!
+use crate::core::diagnostics::{self, Diagnostic};
+use crate::core::error::{DevrsError, Result};
+use crate::core::schema;
+use anyhow::{anyhow, Context};
 use clap::Parser;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::net::IpAddr;
 use std::{
@@ -67,7 +73,7 @@ use std::{
 use tracing::{debug, info, warn};
 
 /// The expected name for the server-specific configuration file.
-const CONFIG_FILE_NAME: &str = ".devrs-srv.toml";
+pub const CONFIG_FILE_NAME: &str = ".devrs-srv.toml";
 
 /// # Server Command Arguments (`SrvArgs`)
 ///
@@ -144,16 +150,88 @@ pub struct ServerConfig {
 /// All fields are optional (`Option<T>`) to allow users to specify only the settings
 /// they wish to override from the defaults. This struct is then merged with defaults
 /// and command-line arguments to produce the final `ServerConfig`.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, JsonSchema)]
+#[schemars(deny_unknown_fields)] // Unknown keys are warnings, but editors should flag them.
 struct FileConfig {
+    /// Port to listen on.
+    #[schemars(range(min = 1), extend("default" = 8000))]
     port: Option<u16>,
+    /// IP address to bind to ("0.0.0.0" for all interfaces).
+    #[schemars(extend("default" = "127.0.0.1"))]
     host: Option<String>, // Read as string to handle potential parsing errors gracefully
+    /// Directory to serve, relative to this file.
+    #[schemars(extend("default" = "."))]
     directory: Option<String>,
+    /// Send CORS headers.
+    #[schemars(extend("default" = true))]
     enable_cors: Option<bool>,
+    /// Serve files starting with a dot.
+    #[schemars(extend("default" = false))]
     show_hidden: Option<bool>,
+    /// File served for directory requests.
+    #[schemars(extend("default" = "index.html"))]
     index_file: Option<String>,
 }
 
+/// The JSON Schema of `.devrs-srv.toml`, generated from `FileConfig` (`devrs config
+/// schema --srv`, unknown-key checks).
+pub fn srv_schema() -> serde_json::Value {
+    schema::document::<FileConfig>("DevRS static file server configuration (.devrs-srv.toml)")
+}
+
+/// Parses the contents of a `.devrs-srv.toml` file.
+///
+/// Type errors are returned with their location. Unknown keys are not an error (they
+/// never were), but each is logged as a warning with its location and a suggestion.
+///
+/// # Arguments
+///
+/// * `content` - The TOML text.
+/// * `path` - The file, for messages.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if the file is not valid TOML or a value has the wrong type.
+fn parse_file_config(content: &str, path: &Path) -> Result<FileConfig> {
+    let file_config = toml::from_str::<FileConfig>(content).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Invalid server configuration file:\n{}",
+            diagnostics::render(path, content, &[diagnostics::from_toml_error(&e)])
+        )))
+    })?;
+    if let Ok(table) = toml::from_str::<toml::Table>(content) {
+        for problem in schema::check_keys(&srv_schema(), &table, content) {
+            warn!(
+                "Ignoring setting:\n{}",
+                diagnostics::render(path, content, &[problem])
+            );
+        }
+    }
+    Ok(file_config)
+}
+
+/// Checks the contents of a `.devrs-srv.toml` file, treating unknown keys as errors
+/// (`devrs config validate .devrs-srv.toml`).
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` listing every problem with its location.
+pub fn check_srv_config_str(content: &str, path: &Path) -> Result<()> {
+    let invalid = |problems: &[Diagnostic]| {
+        anyhow!(DevrsError::Config(format!(
+            "Invalid server configuration file:\n{}",
+            diagnostics::render(path, content, problems)
+        )))
+    };
+    let table = toml::from_str::<toml::Table>(content)
+        .map_err(|e| invalid(&[diagnostics::from_toml_error(&e)]))?;
+    let unknown = schema::check_keys(&srv_schema(), &table, content);
+    if !unknown.is_empty() {
+        return Err(invalid(&unknown));
+    }
+    parse_file_config(content, path).map(|_| ())
+}
+
 /// # Load and Merge Server Configuration (`load_and_merge_config`)
 ///
 /// Orchestrates the process of determining the final server configuration.
@@ -310,8 +388,7 @@ fn load_config_from_dir(search_dir: &Path) -> Result<Option<ServerConfig>> {
         .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
     // Parse the TOML content using the temporary FileConfig struct.
-    let file_config: FileConfig = toml::from_str(&content)
-        .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+    let file_config = parse_file_config(&content, &config_path)?;
 
     // Get default values to fill in unspecified fields from the TOML file.
     let defaults = ServerConfig::default();
@@ -729,4 +806,19 @@ mod tests {
 
         Ok(())
     }
+
+    /// Test that the generated schema documents the file's keys and flags unknown ones.
+    #[test]
+    fn test_srv_schema() {
+        let schema = srv_schema();
+        assert_eq!(schema["properties"]["port"]["type"], "integer");
+        assert_eq!(schema["properties"]["port"]["default"], 8000);
+        assert_eq!(schema["additionalProperties"], false);
+
+        let content = "port = 1\nprot = 2\n";
+        let table: toml::Table = toml::from_str(content).unwrap();
+        let problems = schema::check_keys(&schema, &table, content);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].help.as_deref(), Some("did you mean `port`?"));
+    }
 }
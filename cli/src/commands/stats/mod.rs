@@ -0,0 +1,402 @@
+//! # DevRS Container Stats Command
+//!
+//! File: cli/src/commands/stats/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements `devrs stats`, which shows live resource usage for the
+//! core environment and application containers: CPU %, memory usage and limit,
+//! network I/O, block I/O, PIDs and uptime. By default the table refreshes in place
+//! until interrupted; `--no-stream` prints a single snapshot.
+//!
+//! The snapshot renderer (`print_snapshot`) is also used by the `--stats` flag of
+//! `devrs env status` and `devrs container status`.
+//!
+//! ## Architecture
+//!
+//! 1. Resolve the target containers (the given names, or every running container that
+//!    `roles::container_role` identifies as DevRS-managed) and inspect each one for its
+//!    start time.
+//! 2. **One-shot mode:** sample every container concurrently with
+//!    `common::docker::stats::sample_once` and print one table.
+//! 3. **Refreshing mode:** spawn one task per container that forwards samples from
+//!    `common::docker::stats::sample_stream` over a channel. The main loop keeps the
+//!    latest sample per container and redraws the table on a fixed interval until
+//!    Ctrl+C is pressed or every container has stopped.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Live view of the running core environment and app containers
+//! devrs stats
+//!
+//! # Single snapshot of specific containers
+//! devrs stats --no-stream devrs-core-env-instance my-app
+//!
+//! # Refresh every 5 seconds
+//! devrs stats --interval 5s
+//! ```
+//!
+use crate::{
+    common::{
+        docker::{self, roles, stats::StatsSample}, // Container listing/inspection, DevRS roles and stats sampling.
+        ui::{format, tables},                      // Human-readable values and table output.
+    },
+    core::{
+        config,        // Naming conventions for the default targets.
+        error::Result, // Standard Result type.
+    },
+};
+use anyhow::Context; // For adding context to errors.
+use clap::Parser; // For parsing command-line arguments.
+use futures_util::{future::join_all, StreamExt}; // For concurrent sampling and stream consumption.
+use std::{collections::HashMap, io::Write, time::Duration}; // Latest-sample map, screen output, intervals.
+use tokio::sync::mpsc; // Channel from per-container sampling tasks to the render loop.
+use tracing::{debug, info, warn}; // Logging framework utilities.
+
+/// Column headers of the stats table.
+const HEADERS: [&str; 8] = [
+    "NAME",
+    "CPU %",
+    "MEM USAGE / LIMIT",
+    "MEM %",
+    "NET I/O",
+    "BLOCK I/O",
+    "PIDS",
+    "UPTIME",
+];
+
+/// # Stats Arguments (`StatsArgs`)
+///
+/// Defines the command-line arguments accepted by `devrs stats`.
+#[derive(Parser, Debug)]
+#[command(about = "Show live CPU, memory, network and disk usage of containers")]
+pub struct StatsArgs {
+    /// Optional: Names or IDs of the containers to show. Defaults to the running core
+    /// environment and application containers.
+    containers: Vec<String>,
+
+    /// Optional: Print a single snapshot instead of a refreshing table.
+    #[arg(long)]
+    no_stream: bool,
+
+    /// Optional: How often to redraw the refreshing table (e.g. `2s`, `500ms`).
+    #[arg(long, default_value = "2s", value_parser = humantime::parse_duration)]
+    interval: Duration,
+}
+
+/// A container to show, with the information needed for the uptime column.
+#[derive(Debug, Clone)]
+struct StatsTarget {
+    /// Container name (without the leading `/`).
+    name: String,
+    /// When the container was started (Unix seconds), if known.
+    started_at: Option<i64>,
+}
+
+/// # Handle Stats Command (`handle_stats`)
+///
+/// ## Workflow:
+/// 1.  Resolves the target containers (only running containers produce stats).
+/// 2.  With `--no-stream`, prints a single snapshot via `print_snapshot`.
+/// 3.  Otherwise, runs the refreshing table until Ctrl+C or until all targets stop.
+///
+/// ## Arguments
+///
+/// * `args`: The parsed `StatsArgs` struct.
+///
+/// ## Returns
+///
+/// * `Result<()>`: `Ok(())` on success, or an `Err` if listing, inspecting or sampling containers fails.
+pub async fn handle_stats(args: StatsArgs) -> Result<()> {
+    info!(
+        "Handling stats command (Containers: {:?}, No stream: {}, Interval: {:?})",
+        args.containers, args.no_stream, args.interval
+    );
+
+    let targets = resolve_targets(&args.containers).await?;
+    if targets.is_empty() {
+        println!("No running DevRS containers found.");
+        return Ok(());
+    }
+
+    if args.no_stream {
+        print_targets_once(&targets).await;
+        Ok(())
+    } else {
+        run_live(targets, args.interval).await
+    }
+}
+
+/// Prints a one-shot stats table for the given containers.
+///
+/// Containers that are not running are skipped with a note instead of figures.
+/// Used by `devrs stats --no-stream` and the `--stats` flag of the status commands.
+///
+/// # Arguments
+///
+/// * `names` - Names or IDs of the containers.
+///
+/// # Returns
+///
+/// * `Result<()>` - `Ok(())` once the table is printed.
+///
+/// # Errors
+///
+/// Returns an error if a container cannot be inspected.
+pub async fn print_snapshot(names: &[String]) -> Result<()> {
+    if names.is_empty() {
+        return Ok(());
+    }
+    let targets = resolve_targets(names).await?;
+    if targets.is_empty() {
+        println!("No running containers to sample.");
+        return Ok(());
+    }
+    print_targets_once(&targets).await;
+    Ok(())
+}
+
+/// Resolves container names into running targets with their start times.
+///
+/// With no names, the running containers DevRS manages (the core environment and
+/// application containers, see `roles::container_role`) are used; other containers on
+/// the host are left out. Named containers that are not running are skipped with a warning.
+async fn resolve_targets(names: &[String]) -> Result<Vec<StatsTarget>> {
+    let names: Vec<String> = if names.is_empty() {
+        let cfg = config::load_config().context("Failed to load DevRS configuration")?;
+        docker::state::list_containers(false, None)
+            .await
+            .context("Failed to list running containers")?
+            .into_iter()
+            .filter_map(|c| {
+                let name = c.names?.into_iter().next()?;
+                let name = name.trim_start_matches('/').to_string();
+                let image = c.image.unwrap_or_default();
+                let labels = c.labels.unwrap_or_default();
+                roles::container_role(&name, &image, &labels, &cfg)
+                    .is_devrs()
+                    .then_some(name)
+            })
+            .collect()
+    } else {
+        names.to_vec()
+    };
+
+    let mut targets = Vec::new();
+    for name in names {
+        let details = docker::state::inspect_container(&name)
+            .await
+            .with_context(|| format!("Failed to inspect container '{}'", name))?;
+        let state = details.state.as_ref();
+        if !state.and_then(|s| s.running).unwrap_or(false) {
+            warn!("Container '{}' is not running; skipping stats.", name);
+            println!("Container '{}' is not running.", name);
+            continue;
+        }
+        targets.push(StatsTarget {
+            name: details
+                .name
+                .as_deref()
+                .map(|n| n.trim_start_matches('/').to_string())
+                .unwrap_or(name),
+            started_at: state
+                .and_then(|s| s.started_at.as_deref())
+                .and_then(format::parse_docker_time),
+        });
+    }
+    Ok(targets)
+}
+
+/// Samples all targets concurrently and prints one table.
+async fn print_targets_once(targets: &[StatsTarget]) {
+    let results = join_all(targets.iter().map(|t| docker::stats::sample_once(&t.name))).await;
+    let samples: Vec<Option<StatsSample>> = results
+        .into_iter()
+        .zip(targets)
+        .map(|(result, target)| match result {
+            Ok(sample) => Some(sample),
+            Err(e) => {
+                eprintln!("Failed to read stats for '{}': {:#}", target.name, e);
+                None
+            }
+        })
+        .collect();
+    tables::print_table(&HEADERS, &stats_rows(targets, &samples));
+}
+
+/// Runs the refreshing table until Ctrl+C or until every container has stopped.
+async fn run_live(targets: Vec<StatsTarget>, interval: Duration) -> Result<()> {
+    let (tx, mut rx) = mpsc::channel::<(usize, Result<StatsSample>)>(64);
+
+    // One forwarding task per container; each ends when its container stops.
+    for (index, target) in targets.iter().enumerate() {
+        let stream = docker::stats::sample_stream(&target.name).await?;
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut stream = Box::pin(stream);
+            while let Some(sample) = stream.next().await {
+                if tx.send((index, sample)).await.is_err() {
+                    break; // Render loop has exited.
+                }
+            }
+        });
+    }
+    drop(tx); // The channel closes once every forwarding task has finished.
+
+    let mut latest: HashMap<usize, StatsSample> = HashMap::new();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some((index, Ok(sample))) => {
+                    latest.insert(index, sample);
+                }
+                Some((index, Err(e))) => {
+                    debug!("Stats error for '{}': {:#}", targets[index].name, e);
+                    latest.remove(&index);
+                }
+                None => {
+                    // All containers stopped: draw the final state and exit.
+                    redraw(&targets, &latest, interval);
+                    println!("\nAll containers have stopped.");
+                    return Ok(());
+                }
+            },
+            _ = ticker.tick() => redraw(&targets, &latest, interval),
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Clears the terminal and draws the current table.
+fn redraw(targets: &[StatsTarget], latest: &HashMap<usize, StatsSample>, interval: Duration) {
+    let samples: Vec<Option<StatsSample>> = (0..targets.len())
+        .map(|i| latest.get(&i).cloned())
+        .collect();
+    // ANSI: clear screen and move the cursor to the top-left corner.
+    print!("\x1b[2J\x1b[H");
+    println!(
+        "Refreshing every {} (Ctrl+C to exit)\n",
+        humantime::format_duration(interval)
+    );
+    print!(
+        "{}",
+        tables::render_table(&HEADERS, &stats_rows(targets, &samples))
+    );
+    let _ = std::io::stdout().flush();
+}
+
+/// Builds table rows; containers without a sample yet show `--` placeholders.
+fn stats_rows(targets: &[StatsTarget], samples: &[Option<StatsSample>]) -> Vec<Vec<String>> {
+    targets
+        .iter()
+        .zip(samples)
+        .map(|(target, sample)| {
+            let uptime = target.started_at.map_or("-".to_string(), |t| {
+                format::format_duration(format::age_since(t))
+            });
+            match sample {
+                Some(s) => vec![
+                    target.name.clone(),
+                    format::format_percent(s.cpu_percent),
+                    format!(
+                        "{} / {}",
+                        format::format_bytes(s.memory_usage),
+                        format::format_bytes(s.memory_limit)
+                    ),
+                    format::format_percent(s.memory_percent()),
+                    format!(
+                        "{} / {}",
+                        format::format_bytes(s.net_rx),
+                        format::format_bytes(s.net_tx)
+                    ),
+                    format!(
+                        "{} / {}",
+                        format::format_bytes(s.block_read),
+                        format::format_bytes(s.block_write)
+                    ),
+                    s.pids.to_string(),
+                    uptime,
+                ],
+                None => {
+                    let mut row = vec![target.name.clone()];
+                    row.extend(std::iter::repeat_n("--".to_string(), HEADERS.len() - 2));
+                    row.push(uptime);
+                    row
+                }
+            }
+        })
+        .collect()
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test argument parsing and defaults.
+    #[test]
+    fn test_stats_args_parsing() {
+        let args = StatsArgs::try_parse_from(["stats"]).unwrap();
+        assert!(args.containers.is_empty());
+        assert!(!args.no_stream);
+        assert_eq!(args.interval, Duration::from_secs(2));
+
+        let args =
+            StatsArgs::try_parse_from(["stats", "--no-stream", "--interval", "500ms", "app"])
+                .unwrap();
+        assert!(args.no_stream);
+        assert_eq!(args.interval, Duration::from_millis(500));
+        assert_eq!(args.containers, vec!["app".to_string()]);
+    }
+
+    /// Test row formatting with and without a sample.
+    #[test]
+    fn test_stats_rows() {
+        let targets = vec![
+            StatsTarget {
+                name: "app".to_string(),
+                started_at: None,
+            },
+            StatsTarget {
+                name: "pending".to_string(),
+                started_at: None,
+            },
+        ];
+        let sample = StatsSample {
+            cpu_percent: 12.5,
+            memory_usage: 250_000_000,
+            memory_limit: 1_000_000_000,
+            net_rx: 1_500,
+            net_tx: 0,
+            block_read: 0,
+            block_write: 2_000_000,
+            pids: 7,
+        };
+        let rows = stats_rows(&targets, &[Some(sample), None]);
+        assert_eq!(
+            rows[0],
+            vec![
+                "app",
+                "12.50%",
+                "250.0 MB / 1.0 GB",
+                "25.00%",
+                "1.5 kB / 0 B",
+                "0 B / 2.0 MB",
+                "7",
+                "-"
+            ]
+        );
+        assert_eq!(rows[1].len(), HEADERS.len());
+        assert_eq!(rows[1][1], "--");
+    }
+}
@@ -1,54 +1,384 @@
-//! # DevRS Compression Utilities (`common::archive::compression`) - Placeholder
+//! # DevRS Compression Utilities (`common::archive::compression`)
 //!
 //! File: cli/src/common/archive/compression.rs
 //! Author: Christi Mahu
 //! Repository: https://github.com/christimahu/devrs
 //!
 //! **DISCLAIMER:** This repository is in the early phases of being rewritten
-//! and is not suitable for production development yet. This module is currently
-//! a placeholder.
+//! and is not suitable for production development yet.
 //!
 //! ## Overview
 //!
-//! This module is **intended** to provide shared utilities for data compression
-//! and decompression within the DevRS CLI. It would support algorithms like gzip,
-//! potentially others (bzip2, zstd) in the future, operating on byte streams or files.
+//! This module provides shared utilities for streaming compression and
+//! decompression within the DevRS CLI. It currently supports **gzip** and
+//! **zstd**, plus a pass-through "none" mode so callers can treat plain and
+//! compressed files uniformly.
 //!
-//! **Currently, this module contains no implemented functionality.**
+//! The primary consumers are the `devrs container save` and `devrs container load`
+//! commands, which write and read Docker image archives (`.tar`, `.tar.gz`, `.tar.zst`).
+//! Everything here works on `std::io::Read` / `std::io::Write` streams, so multi-gigabyte
+//! image archives never need to be held in memory.
 //!
-//! ## Planned Architecture
+//! ## Architecture
 //!
-//! When implemented, this module would likely include:
+//! - **`Compression`**: Enum describing the supported algorithms. It can be derived from
+//!   a file name (`Compression::from_path`) when writing, or sniffed from the leading
+//!   magic bytes of existing data (`Compression::detect`) when reading.
+//! - **`CompressedWriter`**: A `Write` implementation wrapping an inner writer with the
+//!   selected encoder. It must be explicitly `finish`ed so encoder trailers are flushed
+//!   and any error surfaces to the caller (rather than being swallowed on drop).
+//! - **`open_decompressed`**: Opens a file, detects its compression from the magic
+//!   bytes (ignoring the file extension), and returns a reader yielding the
+//!   decompressed bytes.
 //!
-//! - Functions like `compress_gzip(data: &[u8]) -> Result<Vec<u8>>`
-//! - Functions like `decompress_gzip(compressed_data: &[u8]) -> Result<Vec<u8>>`
-//! - Similar functions for other supported algorithms.
-//! - Potential streaming compression/decompression utilities.
-//! - Integration with the `common::archive` module for compressed archives.
-//!
-//! ## Planned Usage
+//! ## Usage
 //!
 //! ```rust
-//! # // This is conceptual usage for when the module is implemented.
-//! # use crate::core::error::Result;
-//! # mod compression { // Mock implementation for example
-//! #   pub fn compress_gzip(_data: &[u8]) -> Result<Vec<u8>> { Ok(vec![]) }
-//! #   pub fn decompress_gzip(_compressed_data: &[u8]) -> Result<Vec<u8>> { Ok(vec![]) }
-//! # }
-//! # fn main() -> Result<()> {
-//! let data = b"Some data to compress";
-//!
-//! // Compress data using gzip (conceptual)
-//! let compressed = compression::compress_gzip(data)?;
+//! use crate::common::archive::compression::{self, Compression, CompressedWriter};
+//! use std::io::{Read, Write};
+//! use std::path::Path;
 //!
-//! // Decompress data using gzip (conceptual)
-//! let decompressed = compression::decompress_gzip(&compressed)?;
+//! # fn run() -> anyhow::Result<()> {
+//! // Compression is chosen from the output file extension.
+//! let out_path = Path::new("images.tar.zst");
+//! let file = std::fs::File::create(out_path)?;
+//! let mut writer = CompressedWriter::new(file, Compression::from_path(out_path))?;
+//! writer.write_all(b"tar bytes...")?;
+//! writer.finish()?; // Flush the zstd frame trailer.
 //!
-//! assert_eq!(data.to_vec(), decompressed);
+//! // When reading back, compression is detected from the content itself.
+//! let mut reader = compression::open_decompressed(out_path)?;
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data)?;
 //! # Ok(())
 //! # }
 //! ```
 //!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::{anyhow, Context}; // For error creation and context wrapping.
+use flate2::{read::GzDecoder, write::GzEncoder}; // Gzip streaming encoder/decoder.
+use std::{
+    fs::File,                                    // For opening archive files.
+    io::{self, BufRead, BufReader, Read, Write}, // Core I/O traits and buffering.
+    path::Path,                                  // For file path handling.
+};
+use tracing::debug; // Logging utilities.
+
+/// Magic bytes at the start of every gzip stream (RFC 1952).
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Magic bytes at the start of every zstd frame (RFC 8878).
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Default zstd compression level. Level 3 is zstd's own default and offers a good
+/// speed/ratio trade-off for image layers, which are often already compressed.
+const ZSTD_LEVEL: i32 = 3;
+
+/// # Compression Algorithm (`Compression`)
+///
+/// Identifies the compression applied to a stream or file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// No compression; bytes are passed through unchanged.
+    None,
+    /// Gzip (deflate) compression, typically `.gz` / `.tgz`.
+    Gzip,
+    /// Zstandard compression, typically `.zst` / `.zstd`.
+    Zstd,
+}
+
+impl Compression {
+    /// Determines the compression to use for a file based on its extension.
+    ///
+    /// Recognizes `.gz` and `.tgz` as gzip, and `.zst`, `.zstd` and `.tzst` as zstd.
+    /// Any other extension (including `.tar` or none) maps to `Compression::None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file path whose extension should be inspected.
+    ///
+    /// # Returns
+    ///
+    /// * `Compression` - The compression implied by the file name.
+    pub fn from_path(path: &Path) -> Self {
+        // Compare extensions case-insensitively ("IMAGES.TAR.GZ" is still gzip).
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("gz") | Some("tgz") => Compression::Gzip,
+            Some("zst") | Some("zstd") | Some("tzst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Detects the compression of existing data from its leading magic bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The first bytes of the data (at least 4 bytes for reliable zstd detection).
+    ///
+    /// # Returns
+    ///
+    /// * `Compression` - The detected compression, or `Compression::None` if no known magic matched.
+    pub fn detect(header: &[u8]) -> Self {
+        if header.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if header.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Returns a short human-readable label for messages (e.g., "gzip").
+    pub fn label(&self) -> &'static str {
+        match self {
+            Compression::None => "uncompressed",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+/// # Compressed Writer (`CompressedWriter`)
+///
+/// A `Write` adapter that compresses everything written to it with the selected
+/// algorithm before forwarding it to the inner writer.
+///
+/// Call `finish` once all data has been written. Dropping the writer without
+/// finishing may leave a truncated archive behind.
+pub enum CompressedWriter<W: Write> {
+    /// Pass-through writer (no compression).
+    Plain(W),
+    /// Gzip encoder wrapping the inner writer.
+    Gzip(GzEncoder<W>),
+    /// Zstd encoder wrapping the inner writer.
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// Wraps `inner` with an encoder for the given compression.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The destination writer (e.g., a `File`).
+    /// * `compression` - The algorithm to apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - The ready-to-use writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::FileSystem` if the zstd encoder cannot be initialized.
+    pub fn new(inner: W, compression: Compression) -> Result<Self> {
+        debug!("Creating {} writer", compression.label());
+        Ok(match compression {
+            Compression::None => CompressedWriter::Plain(inner),
+            Compression::Gzip => {
+                CompressedWriter::Gzip(GzEncoder::new(inner, flate2::Compression::default()))
+            }
+            Compression::Zstd => {
+                CompressedWriter::Zstd(zstd::Encoder::new(inner, ZSTD_LEVEL).map_err(|e| {
+                    anyhow!(DevrsError::FileSystem(format!(
+                        "Failed to initialize zstd encoder: {}",
+                        e
+                    )))
+                })?)
+            }
+        })
+    }
+
+    /// Flushes any buffered data and writes the encoder trailer, returning the inner writer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<W>` - The inner writer after all compressed data has been written to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the final flush or trailer write fails.
+    pub fn finish(self) -> Result<W> {
+        let mut inner = match self {
+            CompressedWriter::Plain(w) => w,
+            CompressedWriter::Gzip(enc) => enc.finish().context("Failed to finish gzip stream")?,
+            CompressedWriter::Zstd(enc) => enc.finish().context("Failed to finish zstd stream")?,
+        };
+        // Make sure everything reaches the underlying sink (e.g., file buffers).
+        inner.flush().context("Failed to flush compressed output")?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(w) => w.write(buf),
+            CompressedWriter::Gzip(w) => w.write(buf),
+            CompressedWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedWriter::Plain(w) => w.flush(),
+            CompressedWriter::Gzip(w) => w.flush(),
+            CompressedWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Wraps a buffered reader with the decoder matching its leading magic bytes.
+///
+/// The magic bytes are only peeked (via `fill_buf`), not consumed, so the decoder
+/// sees the complete stream.
+///
+/// # Arguments
+///
+/// * `reader` - A buffered reader positioned at the start of the data.
+///
+/// # Returns
+///
+/// * `Result<(Compression, Box<dyn Read + Send>)>` - The detected compression and a reader
+///   yielding decompressed bytes.
+///
+/// # Errors
+///
+/// Returns an error if the header cannot be read or the zstd decoder fails to initialize.
+pub fn decompressed_reader<R: Read + Send + 'static>(
+    mut reader: BufReader<R>,
+) -> Result<(Compression, Box<dyn Read + Send>)> {
+    // Peek at the buffered header without consuming it.
+    let compression = Compression::detect(reader.fill_buf().context("Failed to read header")?);
+    debug!("Detected {} input", compression.label());
+    let decoded: Box<dyn Read + Send> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(GzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to initialize zstd decoder: {}",
+                e
+            )))
+        })?),
+    };
+    Ok((compression, decoded))
+}
+
+/// Opens a file and returns a reader yielding its decompressed contents.
+///
+/// Compression is detected from the file's content, not its extension, so a
+/// misnamed archive (e.g., a gzip file saved as `.tar`) still loads correctly.
+///
+/// # Arguments
+///
+/// * `path` - The file to open.
+///
+/// # Returns
+///
+/// * `Result<(Compression, Box<dyn Read + Send>)>` - The detected compression and the decompressing reader.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the file cannot be opened, or an error if the header cannot be read.
+pub fn open_decompressed(path: &Path) -> Result<(Compression, Box<dyn Read + Send>)> {
+    let file = File::open(path).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to open '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+    decompressed_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to read '{}'", path.display()))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    /// Helper: compress `data` in memory with the given algorithm.
+    fn compress(data: &[u8], compression: Compression) -> Vec<u8> {
+        let mut writer = CompressedWriter::new(Vec::new(), compression).unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap()
+    }
+
+    /// Helper: decompress `data` in memory using magic-byte detection.
+    fn decompress(data: Vec<u8>) -> (Compression, Vec<u8>) {
+        let (detected, mut reader) =
+            decompressed_reader(BufReader::new(Cursor::new(data))).unwrap();
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        (detected, out)
+    }
+
+    /// Test compression selection from file extensions.
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(
+            Compression::from_path(Path::new("a.tar")),
+            Compression::None
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.tar.gz")),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.TGZ")),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("a.tar.zst")),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("noext")),
+            Compression::None
+        );
+    }
+
+    /// Test that gzip and zstd round-trip and are detected from magic bytes.
+    #[test]
+    fn test_round_trip_all_algorithms() {
+        let data = b"devrs image archive payload ".repeat(100);
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let encoded = compress(&data, compression);
+            if compression != Compression::None {
+                // Repetitive input must actually shrink.
+                assert!(
+                    encoded.len() < data.len(),
+                    "{:?} did not compress",
+                    compression
+                );
+            }
+            let (detected, decoded) = decompress(encoded);
+            assert_eq!(detected, compression);
+            assert_eq!(decoded, data);
+        }
+    }
+
+    /// Test that `open_decompressed` ignores a misleading file extension.
+    #[test]
+    fn test_open_decompressed_detects_by_content() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("misnamed.tar"); // Actually zstd.
+        std::fs::write(&path, compress(b"hello", Compression::Zstd)).unwrap();
+
+        let (detected, mut reader) = open_decompressed(&path).unwrap();
+        let mut out = String::new();
+        reader.read_to_string(&mut out).unwrap();
+        assert_eq!(detected, Compression::Zstd);
+        assert_eq!(out, "hello");
+    }
 
-// No code or comments below this line in the current file content provided.
-// If functions were present, they would have `///` doc comments above them.
+    /// Test that opening a missing file yields an error.
+    #[test]
+    fn test_open_decompressed_missing_file() {
+        let dir = tempdir().unwrap();
+        assert!(open_decompressed(&dir.path().join("missing.tar")).is_err());
+    }
+}
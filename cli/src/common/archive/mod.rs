@@ -21,9 +21,9 @@
 //!
 //! - **`tar`**: (Implemented) Provides functions specifically for creating TAR archives,
 //!   particularly gzipped tarballs commonly used for Docker contexts.
-//! - **`compression`**: (Placeholder) Intended to house utilities for various compression
-//!   and decompression algorithms (e.g., gzip, bzip2, zstd) which might be used
-//!   in conjunction with archiving or independently.
+//! - **`compression`**: (Implemented) Streaming gzip/zstd compression and decompression
+//!   with detection from file extensions and magic bytes. Used for image archives
+//!   written by `devrs container save` and read by `devrs container load`.
 //!
 //! ## Usage
 //!
@@ -47,6 +47,5 @@
 //! ```
 //!
 
+pub mod compression;
 pub mod tar;
-// Placeholder for future compression utilities
-// pub mod compression;
@@ -0,0 +1,546 @@
+//! # DevRS Agent Forwarding
+//!
+//! File: cli/src/common/docker/agents.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module forwards the host's ssh-agent (`core_env.forward_ssh_agent`) and
+//! gpg-agent (`core_env.forward_gpg_agent`) into the core environment container, so
+//! Git over SSH and commit signing work without mounting private keys into it.
+//!
+//! ## Architecture
+//!
+//! An agent socket's path changes whenever the host session does (ssh-agent creates a
+//! new `/tmp/ssh-XXXX/` directory per login), but bind mounts are fixed when the
+//! container is created, and a host-wide directory such as `/tmp` must never be
+//! mounted. So the container only sees one stable directory owned by devrs:
+//!
+//! - `agents_dir` is `$XDG_RUNTIME_DIR/devrs/agents/` (mode 0700; the local data
+//!   directory without a runtime directory). `agent_mounts` mounts it read-only at
+//!   `/run/devrs/agents` and sets `SSH_AUTH_SOCK` to the ssh proxy socket in it.
+//! - For each agent, a small proxy (`devrs env agent-proxy <agent>`, see `run_proxy`)
+//!   listens on `<agent>-agent.sock` in that directory and connects every client to the
+//!   socket named in `<agent>-agent.target`, read per connection.
+//! - `relink`, called by `devrs env shell`, `devrs env exec` and hooks, writes the host's
+//!   *current* socket to the target file and starts the proxy if it is not running, so
+//!   a new socket after logging in again works without recreating the container. For
+//!   gpg, it also links the container user's standard `S.gpg-agent` socket
+//!   (`gpgconf --list-dirs`) to the proxy, so `gpg` talks to the host agent instead of
+//!   starting its own.
+//! - On macOS, Docker Desktop exposes the host ssh-agent at the stable
+//!   `/run/host-services/ssh-auth.sock`, which is mounted as `SSH_AUTH_SOCK` directly.
+//!   gpg-agent forwarding is not available there.
+//!
+//! The container user must be able to connect to the socket, i.e. have the same UID as
+//! the host user (as the default `me` user does with a UID of 1000).
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::agents;
+//! # use crate::core::config::Config;
+//! # async fn example(cfg: &Config) {
+//! let (mounts, env) = agents::agent_mounts(cfg); // At container creation.
+//! agents::relink("devrs-core-env-instance", cfg).await; // Before each shell/exec.
+//! # }
+//! ```
+//!
+use crate::core::config::{Config, MountConfig}; // For the forwarding flags and bind mounts.
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::{anyhow, Context}; // For creating errors and adding context.
+use std::{
+    collections::HashMap,
+    env, fs,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+}; // Host environment, the agents directory and gpgconf.
+use tokio::net::{UnixListener, UnixStream}; // The proxy sockets.
+use tracing::{debug, info, warn}; // Logging utilities.
+
+// Use the exec and inspect functions from sibling modules.
+use super::{interaction::exec_in_container_captured, state::inspect_container};
+
+/// In-container path of the devrs agents directory (`agents_dir`).
+const CONTAINER_AGENTS_DIR: &str = "/run/devrs/agents";
+/// Stable in-container path of the forwarded ssh-agent socket (`SSH_AUTH_SOCK`).
+pub const SSH_AUTH_SOCK: &str = "/run/devrs/agents/ssh-agent.sock";
+/// Host ssh-agent socket provided by Docker Desktop on macOS.
+const DOCKER_DESKTOP_SSH_SOCK: &str = "/run/host-services/ssh-auth.sock";
+/// How often a proxy checks that its socket file is still its own.
+const PROXY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// # Forwarded Agent (`Agent`)
+///
+/// The host agents that can be forwarded into the core environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Agent {
+    /// ssh-agent, found through `$SSH_AUTH_SOCK`.
+    Ssh,
+    /// gpg-agent, through its restricted "extra" socket meant for remote use.
+    Gpg,
+}
+
+impl Agent {
+    /// The base name of the agent's files in the agents directory.
+    fn file_stem(self) -> &'static str {
+        match self {
+            Agent::Ssh => "ssh-agent",
+            Agent::Gpg => "gpg-agent",
+        }
+    }
+
+    /// The proxy socket in an agents directory.
+    fn proxy_socket(self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}.sock", self.file_stem()))
+    }
+
+    /// The file naming the host socket the proxy connects to.
+    fn target_file(self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}.target", self.file_stem()))
+    }
+
+    /// Whether forwarding is enabled in the configuration.
+    fn enabled(self, cfg: &Config) -> bool {
+        match self {
+            Agent::Ssh => cfg.core_env.forward_ssh_agent,
+            Agent::Gpg => cfg.core_env.forward_gpg_agent,
+        }
+        .unwrap_or(false)
+    }
+
+    /// Finds the host agent socket of the current session, if the agent is running.
+    fn host_socket(self) -> Option<PathBuf> {
+        match self {
+            Agent::Ssh if cfg!(target_os = "macos") => Some(PathBuf::from(DOCKER_DESKTOP_SSH_SOCK)),
+            Agent::Ssh => env::var_os("SSH_AUTH_SOCK")
+                .map(PathBuf::from)
+                .filter(|p| p.exists()),
+            Agent::Gpg if cfg!(target_os = "macos") => None,
+            Agent::Gpg => {
+                let output = Command::new("gpgconf")
+                    .args(["--list-dirs", "agent-extra-socket"])
+                    .output()
+                    .ok()
+                    .filter(|o| o.status.success())?;
+                let socket = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                Some(PathBuf::from(socket)).filter(|p| p.exists())
+            }
+        }
+    }
+}
+
+/// The host directory holding the proxy sockets: `$XDG_RUNTIME_DIR/devrs/agents`, or
+/// `devrs/agents` in the local data directory without a runtime directory.
+fn agents_dir() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("devrs").join("agents"))
+}
+
+/// Creates a directory (mode 0700) that only the host user can enter.
+fn create_private_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .and_then(|()| fs::set_permissions(dir, fs::Permissions::from_mode(0o700)))
+        .map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to create the agents directory '{}': {}",
+                dir.display(),
+                e
+            )))
+        })
+}
+
+/// The bind mounts and environment variables for the enabled agents, for creating
+/// the core environment container. The agents directory is mounted even if no agent
+/// runs yet, so an agent started later can be forwarded by `relink`.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration.
+///
+/// # Returns
+///
+/// * `(Vec<MountConfig>, HashMap<String, String>)` - The read-only mount of the agents
+///   directory (or, on macOS, Docker Desktop's ssh-agent socket) and, for ssh-agent,
+///   `SSH_AUTH_SOCK`.
+pub fn agent_mounts(cfg: &Config) -> (Vec<MountConfig>, HashMap<String, String>) {
+    let mut mounts = Vec::new();
+    let mut env_vars = HashMap::new();
+    let agents: Vec<Agent> = [Agent::Ssh, Agent::Gpg]
+        .into_iter()
+        .filter(|a| a.enabled(cfg))
+        .collect();
+    if agents.is_empty() {
+        return (mounts, env_vars);
+    }
+    if agents.contains(&Agent::Ssh) {
+        env_vars.insert("SSH_AUTH_SOCK".to_string(), SSH_AUTH_SOCK.to_string());
+    }
+    if cfg!(target_os = "macos") {
+        // The Docker Desktop socket never moves; gpg forwarding is not available.
+        if agents.contains(&Agent::Ssh) {
+            mounts.push(MountConfig {
+                host: DOCKER_DESKTOP_SSH_SOCK.to_string(),
+                container: SSH_AUTH_SOCK.to_string(),
+                readonly: true,
+            });
+        }
+        return (mounts, env_vars);
+    }
+    let Some(dir) = agents_dir() else {
+        warn!("Agent forwarding is enabled, but there is no runtime or data directory for the agent sockets.");
+        return (mounts, HashMap::new());
+    };
+    if let Err(e) = create_private_dir(&dir) {
+        warn!("Agent forwarding is disabled: {:#}", e);
+        return (mounts, HashMap::new());
+    }
+    for agent in agents {
+        if let Err(e) = point_proxy(&dir, agent) {
+            warn!("Could not forward the {:?} agent: {:#}", agent, e);
+        }
+    }
+    mounts.push(MountConfig {
+        host: dir.to_string_lossy().into_owned(),
+        container: CONTAINER_AGENTS_DIR.to_string(),
+        readonly: true, // Connecting to a socket does not need a writable mount.
+    });
+    (mounts, env_vars)
+}
+
+/// Points an agent's proxy at the host's current socket and starts the proxy if it is
+/// not running. Without a host socket, nothing changes.
+///
+/// # Arguments
+///
+/// * `dir` - The agents directory.
+/// * `agent` - The agent.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the target file cannot be written, or
+/// `DevrsError::ExternalCommand` if the proxy cannot be started.
+fn point_proxy(dir: &Path, agent: Agent) -> Result<()> {
+    let Some(socket) = agent.host_socket() else {
+        debug!(
+            "No {:?} agent socket on the host, nothing to forward.",
+            agent
+        );
+        return Ok(());
+    };
+    // Written next to the target and renamed, so the proxy never reads half a path.
+    let target = agent.target_file(dir);
+    let partial = target.with_extension("target.new");
+    fs::write(&partial, socket.to_string_lossy().as_bytes())
+        .and_then(|()| fs::rename(&partial, &target))
+        .map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to write '{}': {}",
+                target.display(),
+                e
+            )))
+        })?;
+    debug!(
+        "{:?} agent proxy now points at '{}'.",
+        agent,
+        socket.display()
+    );
+
+    let proxy = agent.proxy_socket(dir);
+    if std::os::unix::net::UnixStream::connect(&proxy).is_ok() {
+        return Ok(());
+    }
+    let exe = env::current_exe().context("Failed to locate the devrs executable")?;
+    let mut command = Command::new(exe);
+    command
+        .args(["env", "agent-proxy"])
+        .arg(match agent {
+            Agent::Ssh => "ssh",
+            Agent::Gpg => "gpg",
+        })
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Own process group: the proxy outlives the shell that started it.
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let child = command.spawn().map_err(|e| {
+        anyhow!(DevrsError::ExternalCommand {
+            cmd: "devrs env agent-proxy".to_string(),
+            status: "not started".to_string(),
+            output: e.to_string(),
+        })
+    })?;
+    info!("Started the {:?} agent proxy (PID {}).", agent, child.id());
+    // Give it a moment to listen, so a command run right away finds the socket.
+    for _ in 0..20 {
+        if std::os::unix::net::UnixStream::connect(&proxy).is_ok() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+/// Runs an agent's proxy in the foreground (`devrs env agent-proxy`): listens on its
+/// socket in the agents directory and forwards each connection to the host socket the
+/// target file names at that moment. It exits when another proxy already serves the
+/// socket, or when its socket file is removed or replaced.
+///
+/// # Arguments
+///
+/// * `agent` - The agent to proxy.
+///
+/// # Errors
+///
+/// Returns an error if there is no agents directory or the socket cannot be bound.
+pub async fn run_proxy(agent: Agent) -> Result<()> {
+    let dir = agents_dir().ok_or_else(|| {
+        anyhow!(DevrsError::FileSystem(
+            "No runtime or data directory for the agent sockets.".to_string()
+        ))
+    })?;
+    create_private_dir(&dir)?;
+    let path = agent.proxy_socket(&dir);
+    if UnixStream::connect(&path).await.is_ok() {
+        debug!("The {:?} agent proxy is already running.", agent);
+        return Ok(());
+    }
+    let _ = fs::remove_file(&path); // A stale socket of a proxy that has exited.
+    let listener = UnixListener::bind(&path)
+        .with_context(|| format!("Failed to listen on '{}'", path.display()))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("Failed to restrict '{}'", path.display()))?;
+    serve(
+        listener,
+        &path,
+        &agent.target_file(&dir),
+        PROXY_CHECK_INTERVAL,
+    )
+    .await;
+    Ok(())
+}
+
+/// Accepts proxy connections until the socket file at `path` is no longer the one
+/// `listener` is bound to, checked every `check_interval`.
+async fn serve(listener: UnixListener, path: &Path, target_file: &Path, check_interval: Duration) {
+    let inode = fs::metadata(path).map(|m| m.ino()).ok();
+    let mut check = tokio::time::interval(check_interval);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((client, _)) => {
+                    let target_file = target_file.to_path_buf();
+                    tokio::spawn(async move { forward(client, &target_file).await });
+                }
+                Err(e) => warn!("Agent proxy failed to accept a connection: {}", e),
+            },
+            _ = check.tick() => {
+                if fs::metadata(path).map(|m| m.ino()).ok() != inode {
+                    debug!("Agent proxy socket '{}' was replaced; exiting.", path.display());
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Connects one client to the host socket named in the target file.
+async fn forward(mut client: UnixStream, target_file: &Path) {
+    let target = match fs::read_to_string(target_file) {
+        Ok(target) => PathBuf::from(target.trim()),
+        Err(e) => {
+            warn!(
+                "No agent socket to forward to ({}): {}",
+                target_file.display(),
+                e
+            );
+            return;
+        }
+    };
+    match UnixStream::connect(&target).await {
+        Ok(mut agent) => {
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut agent).await;
+        }
+        Err(e) => warn!(
+            "Could not connect to the agent socket '{}': {}",
+            target.display(),
+            e
+        ),
+    }
+}
+
+/// Points the forwarded agents at the host's current sockets, so agents keep working
+/// after the host session (and socket path) changes without recreating the container.
+/// Problems are logged as warnings; they never fail the command.
+///
+/// # Arguments
+///
+/// * `name` - The running core environment container.
+/// * `cfg` - The loaded configuration (forwarding flags and `core_env.user`).
+pub async fn relink(name: &str, cfg: &Config) {
+    let agents: Vec<Agent> = [Agent::Ssh, Agent::Gpg]
+        .into_iter()
+        .filter(|a| a.enabled(cfg))
+        .collect();
+    if agents.is_empty() || cfg!(target_os = "macos") {
+        return; // Docker Desktop's socket is mounted directly and never moves.
+    }
+    let mounted = match inspect_container(name).await {
+        Ok(details) => details
+            .mounts
+            .unwrap_or_default()
+            .iter()
+            .any(|m| m.destination.as_deref() == Some(CONTAINER_AGENTS_DIR)),
+        Err(e) => {
+            warn!("Could not inspect '{}' to forward agents: {}", name, e);
+            return;
+        }
+    };
+    if !mounted {
+        warn!(
+            "'{}' has no agents directory at {}. It was created by an older DevRS or without \
+             agent forwarding; recreate it with `devrs env rebuild`.",
+            name, CONTAINER_AGENTS_DIR
+        );
+        return;
+    }
+    let Some(dir) = agents_dir() else {
+        return;
+    };
+    if let Err(e) = create_private_dir(&dir) {
+        warn!("Could not forward agents: {:#}", e);
+        return;
+    }
+
+    for agent in agents {
+        if let Err(e) = point_proxy(&dir, agent) {
+            warn!("Could not forward the {:?} agent: {:#}", agent, e);
+            continue;
+        }
+        if agent != Agent::Gpg {
+            continue; // `SSH_AUTH_SOCK` names the proxy socket itself.
+        }
+        // Run as the env user so the link lands in that user's gnupg directory.
+        // `sh -c SCRIPT sh TARGET`: the target is passed as $1 to avoid quoting issues.
+        let script = "dir=$(gpgconf --list-dirs socketdir 2>/dev/null) && [ -n \"$dir\" ] && \
+                      mkdir -p \"$dir\" && chmod 700 \"$dir\" && ln -sfn \"$1\" \"$dir/S.gpg-agent\"";
+        let target = format!(
+            "{}/{}",
+            CONTAINER_AGENTS_DIR,
+            agent.proxy_socket(Path::new("")).display()
+        );
+        let cmd: Vec<String> = vec!["sh".into(), "-c".into(), script.into(), "sh".into(), target];
+        match exec_in_container_captured(name, &cmd, None, cfg.core_env.user.as_deref(), &[]).await
+        {
+            Ok((0, _)) => debug!("Linked {:?} agent socket in '{}'.", agent, name),
+            Ok((code, _)) => warn!(
+                "Could not link the {:?} agent socket in '{}' (exit code {}).",
+                agent, name, code
+            ),
+            Err(e) => warn!("Could not link the {:?} agent socket: {}", agent, e),
+        }
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the proxy follows the target file: after the host socket moves (a new
+    /// login's `/tmp/ssh-XXXX/`), new connections reach the new socket through the same
+    /// proxy socket, and the proxy exits once its socket file is removed.
+    #[tokio::test]
+    async fn test_proxy_follows_moved_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        // A fake agent that answers each connection with its name.
+        let agent = |name: &'static str| {
+            let path = dir.path().join(name).join("agent.sock");
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let listener = UnixListener::bind(&path).unwrap();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    stream.write_all(name.as_bytes()).await.unwrap();
+                }
+            });
+            path
+        };
+        let target_file = Agent::Ssh.target_file(dir.path());
+        let proxy = Agent::Ssh.proxy_socket(dir.path());
+        let listener = UnixListener::bind(&proxy).unwrap();
+        let server = {
+            let (proxy, target_file) = (proxy.clone(), target_file.clone());
+            tokio::spawn(async move {
+                serve(listener, &proxy, &target_file, Duration::from_millis(20)).await
+            })
+        };
+        let ask = || async {
+            let mut stream = UnixStream::connect(&proxy).await.unwrap();
+            let mut reply = vec![0; 9];
+            stream.read_exact(&mut reply).await.unwrap();
+            String::from_utf8(reply).unwrap()
+        };
+
+        fs::write(
+            &target_file,
+            agent("ssh-AbCd1").to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(ask().await, "ssh-AbCd1");
+        fs::write(
+            &target_file,
+            agent("ssh-XyZw2").to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(ask().await, "ssh-XyZw2");
+
+        fs::remove_file(&proxy).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("the proxy exits without its socket")
+            .unwrap();
+    }
+
+    /// Test the file names in the agents directory and the in-container socket.
+    #[test]
+    fn test_agent_files() {
+        let dir = Path::new("/run/user/1000/devrs/agents");
+        assert_eq!(
+            Agent::Ssh.proxy_socket(dir),
+            PathBuf::from("/run/user/1000/devrs/agents/ssh-agent.sock")
+        );
+        assert_eq!(
+            Agent::Gpg.target_file(dir),
+            PathBuf::from("/run/user/1000/devrs/agents/gpg-agent.target")
+        );
+        assert_eq!(
+            format!(
+                "{}/{}",
+                CONTAINER_AGENTS_DIR,
+                Agent::Ssh.proxy_socket(Path::new("")).display()
+            ),
+            SSH_AUTH_SOCK
+        );
+    }
+
+    /// Test that nothing is forwarded unless enabled.
+    #[test]
+    fn test_agent_mounts_disabled() {
+        let cfg: Config = toml::from_str("").unwrap();
+        let (mounts, env_vars) = agent_mounts(&cfg);
+        assert!(mounts.is_empty());
+        assert!(env_vars.is_empty());
+    }
+}
@@ -22,6 +22,11 @@
 //! - **`inspect_image`**: Retrieves detailed metadata about a specific image (layers, config, etc.).
 //! - **`list_images`**: Fetches a list of local images, optionally including intermediate layers or applying filters.
 //! - **`remove_image`**: Attempts to remove one or more specified images from the local cache.
+//! - **`save_images`**: Exports one or more images into a (optionally gzip/zstd compressed) tar archive on disk.
+//! - **`load_images`**: Imports images from a tar archive on disk, detecting compression automatically.
+//! - **`tag_image`**: Adds a new repository/tag reference to an existing local image.
+//! - **`push_image`**: Pushes a tagged image to a registry, rendering progress as it goes.
+//! - **`registry_reference`**: Pure helper that rewrites an image reference to point at another registry.
 //!
 //! All functions handle communication with the Docker daemon via the `connect_docker` helper
 //! and map potential Docker API errors (e.g., image not found, image in use) to
@@ -65,14 +70,25 @@
 //! # }
 //! ```
 //!
+use crate::common::archive::compression::{self, CompressedWriter, Compression}; // Archive compression helpers
 use crate::core::error::{DevrsError, Result}; // Use standard Result and custom Error
-use anyhow::anyhow; // For error context wrapping
+use anyhow::{anyhow, Context}; // For error context wrapping
 use bollard::{
-    image::{ListImagesOptions, RemoveImageOptions}, // Specific options structs for image operations
-    models::ImageInspect,                           // Response struct for inspect_image
-    models::ImageSummary,                           // Response struct element for list_images
+    image::{
+        ImportImageOptions, ListImagesOptions, PushImageOptions, RemoveImageOptions,
+        TagImageOptions,
+    }, // Specific options structs for image operations
+    models::ImageInspect,               // Response struct for inspect_image
+    models::ImageSummary,               // Response struct element for list_images
+    models::{BuildInfo, PushImageInfo}, // Progress events for load and push streams
+};
+use futures_util::stream::{self, StreamExt}; // For consuming and building byte streams
+use std::{
+    collections::HashMap,                 // For list_images filters
+    fs::{self, File},                     // For writing/renaming archive files
+    io::{stdout, BufWriter, Read, Write}, // For streaming archive bytes and progress output
+    path::Path,                           // For archive file paths
 };
-use std::collections::HashMap; // For list_images filters
 use tracing::{debug, error, info, instrument, warn}; // Logging utilities
 
 // Use the shared connection helper from the sibling module.
@@ -303,3 +319,445 @@ pub async fn remove_image(name_or_id: &str, force: bool) -> Result<()> {
         }
     }
 }
+
+/// Size of the chunks read from an archive file and streamed to the Docker daemon during `load_images`.
+const LOAD_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Exports one or more local images into a tar archive on disk.
+///
+/// The archive is the standard `docker save` format, so it can be consumed by
+/// `devrs container load` or plain `docker load`. Compression is chosen from the
+/// output file extension (`.tar`, `.tar.gz`, `.tar.zst`). Data is streamed from the
+/// daemon straight into the encoder, and written to a temporary `<output>.partial`
+/// file that is only renamed into place once the export completes, so an interrupted
+/// save never leaves a truncated archive under the requested name.
+///
+/// # Arguments
+///
+/// * `names` - The image names or IDs to include in the archive.
+/// * `output` - The destination file path.
+///
+/// # Returns
+///
+/// * `Result<u64>` - The number of (uncompressed) tar bytes received from the daemon.
+///
+/// # Errors
+///
+/// * `DevrsError::ImageNotFound` - If one of the images does not exist locally.
+/// * `DevrsError::FileSystem` - If the output file cannot be created or renamed.
+/// * `DevrsError::DockerApi` - For other errors during communication with the Docker daemon.
+#[instrument(skip(names, output), fields(output = %output.display()))] // Tracing span
+pub async fn save_images(names: &[String], output: &Path) -> Result<u64> {
+    // Verify every image up front; the export endpoint reports missing images poorly mid-stream.
+    for name in names {
+        if !image_exists(name).await? {
+            return Err(anyhow!(DevrsError::ImageNotFound { name: name.clone() }));
+        }
+    }
+
+    // Establish connection to Docker daemon.
+    let docker = connect_docker().await?;
+    let compression = Compression::from_path(output);
+    info!(
+        "Saving {} image(s) to '{}' ({})...",
+        names.len(),
+        output.display(),
+        compression.label()
+    );
+
+    // Write into a sibling temporary file first.
+    let partial_path = output.with_file_name(format!(
+        "{}.partial",
+        output
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "images.tar".to_string())
+    ));
+    let file = File::create(&partial_path).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to create '{}': {}",
+            partial_path.display(),
+            e
+        )))
+    })?;
+    let mut writer = CompressedWriter::new(BufWriter::new(file), compression)?;
+
+    // Stream the export into the (compressing) writer.
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut export_stream = docker.export_images(&name_refs);
+    let mut total_bytes: u64 = 0;
+    let stream_result: Result<()> = async {
+        while let Some(chunk) = export_stream.next().await {
+            let chunk = chunk.map_err(|e| {
+                anyhow!(DevrsError::DockerApi { source: e }).context("Failed to export images")
+            })?;
+            writer
+                .write_all(&chunk)
+                .with_context(|| format!("Failed to write '{}'", partial_path.display()))?;
+            total_bytes += chunk.len() as u64;
+        }
+        Ok(())
+    }
+    .await;
+
+    // Finish the encoder (writes trailers) only if the export succeeded; clean up otherwise.
+    let finish_result = stream_result.and_then(|_| writer.finish().map(|_| ()));
+    if let Err(e) = finish_result {
+        error!("Image save failed: {:?}", e);
+        let _ = fs::remove_file(&partial_path); // Best effort cleanup.
+        return Err(e);
+    }
+
+    fs::rename(&partial_path, output).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to move '{}' to '{}': {}",
+            partial_path.display(),
+            output.display(),
+            e
+        )))
+    })?;
+    info!("Saved {} bytes of image data.", total_bytes);
+    Ok(total_bytes)
+}
+
+/// Imports images from a tar archive on disk (the inverse of `save_images`).
+///
+/// Compression is detected from the archive's magic bytes, so `.tar`, `.tar.gz` and
+/// `.tar.zst` files (or misnamed ones) are all accepted. The file is read on a blocking
+/// thread and streamed to the daemon in chunks, and the daemon's progress messages
+/// (e.g., `Loaded image: my-app:latest`) are printed to stdout.
+///
+/// # Arguments
+///
+/// * `input` - The archive file to load.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - The image references reported as loaded by the daemon.
+///
+/// # Errors
+///
+/// * `DevrsError::FileSystem` - If the archive cannot be opened or read.
+/// * `DevrsError::Docker` - If the daemon rejects the archive.
+/// * `DevrsError::DockerApi` - For other errors during communication with the Docker daemon.
+#[instrument(skip(input), fields(input = %input.display()))] // Tracing span
+pub async fn load_images(input: &Path) -> Result<Vec<String>> {
+    // Open and sniff the archive before touching the daemon.
+    let (compression, mut reader) = compression::open_decompressed(input)?;
+    info!(
+        "Loading images from '{}' ({})...",
+        input.display(),
+        compression.label()
+    );
+
+    // Establish connection to Docker daemon.
+    let docker = connect_docker().await?;
+
+    // Read the archive on a blocking thread, forwarding chunks through a bounded channel.
+    let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(4);
+    let reader_task = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        loop {
+            let mut buf = vec![0u8; LOAD_CHUNK_SIZE];
+            let read = reader.read(&mut buf)?;
+            if read == 0 {
+                return Ok(()); // End of archive.
+            }
+            buf.truncate(read);
+            if tx.blocking_send(buf).is_err() {
+                return Ok(()); // Receiver dropped (daemon stream failed); stop quietly.
+            }
+        }
+    });
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk.into(), rx))
+    });
+
+    // Send the archive and process the daemon's response stream.
+    let mut load_stream =
+        docker.import_image_stream(ImportImageOptions { quiet: false }, body, None);
+    let mut loaded = Vec::new();
+    let mut stream_error = None;
+    while let Some(event) = load_stream.next().await {
+        match event {
+            Ok(info) => {
+                if let Some(line) = describe_load_event(&info) {
+                    if let Some(reference) = line.strip_prefix("Loaded image: ") {
+                        loaded.push(reference.to_string());
+                    } else if let Some(reference) = line.strip_prefix("Loaded image ID: ") {
+                        loaded.push(reference.to_string());
+                    }
+                    println!("{}", line);
+                }
+            }
+            Err(bollard::errors::Error::DockerStreamError { error }) => {
+                stream_error = Some(anyhow!(DevrsError::Docker(format!(
+                    "Failed to load images: {}",
+                    error
+                ))));
+                break;
+            }
+            Err(e) => {
+                stream_error = Some(
+                    anyhow!(DevrsError::DockerApi { source: e }).context("Failed to load images"),
+                );
+                break;
+            }
+        }
+    }
+    drop(load_stream); // Release the body stream so a blocked reader can exit.
+
+    // A read error is the root cause of any daemon error, so report it first.
+    match reader_task.await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            return Err(anyhow!(DevrsError::FileSystem(format!(
+                "Failed to read '{}': {}",
+                input.display(),
+                e
+            ))))
+        }
+        Err(e) => return Err(anyhow!("Archive reader task failed: {}", e)),
+    }
+    if let Some(e) = stream_error {
+        return Err(e);
+    }
+    info!("Loaded {} image reference(s).", loaded.len());
+    Ok(loaded)
+}
+
+/// Extracts the human-readable line from a load progress event, if any.
+fn describe_load_event(info: &BuildInfo) -> Option<String> {
+    info.stream
+        .as_deref()
+        .or(info.status.as_deref())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Adds a new reference (`repo:tag`) to an existing local image.
+///
+/// # Arguments
+///
+/// * `source` - The existing image name or ID.
+/// * `target` - The new full reference, e.g., `localhost:5000/my-app:1.0`. A missing tag defaults to `latest`.
+///
+/// # Returns
+///
+/// * `Result<()>` - Returns `Ok(())` once the tag has been created.
+///
+/// # Errors
+///
+/// * `DevrsError::ImageNotFound` - If the source image does not exist locally (maps Docker 404).
+/// * `DevrsError::DockerApi` - For other errors during communication with the Docker daemon.
+#[instrument(skip(source, target), fields(source = %source, target = %target))] // Tracing span
+pub async fn tag_image(source: &str, target: &str) -> Result<()> {
+    // Establish connection to Docker daemon.
+    let docker = connect_docker().await?;
+    let (repo, tag) = split_reference(target);
+    info!("Tagging '{}' as '{}:{}'", source, repo, tag);
+
+    docker
+        .tag_image(source, Some(TagImageOptions { repo, tag }))
+        .await
+        .map_err(|e| match e {
+            bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            } => anyhow!(DevrsError::ImageNotFound {
+                name: source.to_string()
+            }),
+            _ => anyhow!(DevrsError::DockerApi { source: e })
+                .context(format!("Failed to tag '{}' as '{}'", source, target)),
+        })
+}
+
+/// Pushes a local image reference to its registry, printing progress to stdout.
+///
+/// The registry is taken from the reference itself (e.g., `localhost:5000/my-app:1.0`
+/// pushes to `localhost:5000`). No credentials are sent, which suits a local
+/// `registry:2` instance. Per-layer progress bars reported by the daemon are
+/// redrawn in place on the current line; status changes are printed on new lines.
+///
+/// # Arguments
+///
+/// * `reference` - The full image reference to push (must already exist locally).
+///
+/// # Returns
+///
+/// * `Result<()>` - Returns `Ok(())` once the daemon reports the push as finished.
+///
+/// # Errors
+///
+/// * `DevrsError::ImageNotFound` - If the reference does not exist locally (maps Docker 404).
+/// * `DevrsError::Docker` - If the daemon reports a push failure (e.g., registry unreachable).
+/// * `DevrsError::DockerApi` - For other errors during communication with the Docker daemon.
+#[instrument(skip(reference), fields(image = %reference))] // Tracing span
+pub async fn push_image(reference: &str) -> Result<()> {
+    // Establish connection to Docker daemon.
+    let docker = connect_docker().await?;
+    let (repo, tag) = split_reference(reference);
+    info!("Pushing '{}:{}'...", repo, tag);
+
+    let mut push_stream = docker.push_image(&repo, Some(PushImageOptions { tag }), None);
+    let mut on_progress_line = false; // Whether the cursor sits on a redrawable progress line.
+    while let Some(event) = push_stream.next().await {
+        match event {
+            Ok(PushImageInfo {
+                status: Some(status),
+                progress: Some(progress),
+                ..
+            }) => {
+                // Redraw the progress bar in place.
+                print!("\r\x1b[2K{} {}", status, progress);
+                on_progress_line = true;
+            }
+            Ok(PushImageInfo {
+                status: Some(status),
+                ..
+            }) => {
+                if on_progress_line {
+                    println!(); // Keep the last progress bar visible.
+                    on_progress_line = false;
+                }
+                println!("{}", status);
+            }
+            Ok(other) => debug!("Received unhandled push info: {:?}", other),
+            Err(e) => {
+                if on_progress_line {
+                    println!();
+                }
+                return Err(match e {
+                    bollard::errors::Error::DockerResponseServerError {
+                        status_code: 404, ..
+                    } => anyhow!(DevrsError::ImageNotFound {
+                        name: reference.to_string()
+                    }),
+                    bollard::errors::Error::DockerStreamError { error } => anyhow!(
+                        DevrsError::Docker(format!("Push of '{}' failed: {}", reference, error))
+                    ),
+                    _ => anyhow!(DevrsError::DockerApi { source: e })
+                        .context(format!("Failed to push '{}'", reference)),
+                });
+            }
+        }
+        // Flush so progress is visible in real time.
+        let _ = stdout().flush();
+    }
+    if on_progress_line {
+        println!();
+    }
+    info!("Push of '{}' finished.", reference);
+    Ok(())
+}
+
+/// Splits an image reference into its repository and tag (defaulting to `latest`).
+///
+/// A colon only denotes a tag when it appears after the last `/`, so registry ports
+/// (`localhost:5000/app`) are not mistaken for tags. Digests (`@sha256:...`) are dropped.
+///
+/// # Arguments
+///
+/// * `reference` - An image reference such as `app`, `app:1.0` or `localhost:5000/team/app:1.0`.
+///
+/// # Returns
+///
+/// * `(String, String)` - The repository and the tag.
+pub fn split_reference(reference: &str) -> (String, String) {
+    // Ignore any digest suffix.
+    let without_digest = reference.split('@').next().unwrap_or(reference);
+    let last_slash = without_digest.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match without_digest[last_slash..].rfind(':') {
+        Some(colon) => {
+            let split_at = last_slash + colon;
+            (
+                without_digest[..split_at].to_string(),
+                without_digest[split_at + 1..].to_string(),
+            )
+        }
+        None => (without_digest.to_string(), "latest".to_string()),
+    }
+}
+
+/// Rewrites an image reference so it points at a different registry.
+///
+/// Any existing registry host in the reference (a first path component containing
+/// `.` or `:`, or `localhost`) is replaced; otherwise the registry is prepended.
+/// The tag is preserved (or defaults to `latest`).
+///
+/// # Arguments
+///
+/// * `reference` - The local image reference, e.g., `my-app:1.0` or `ghcr.io/team/app:2`.
+/// * `registry` - The target registry host, e.g., `localhost:5000`.
+///
+/// # Returns
+///
+/// * `String` - The rewritten reference, e.g., `localhost:5000/my-app:1.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use crate::common::docker::images::registry_reference;
+/// assert_eq!(registry_reference("my-app", "localhost:5000"), "localhost:5000/my-app:latest");
+/// assert_eq!(registry_reference("ghcr.io/team/app:2", "lab:5000"), "lab:5000/team/app:2");
+/// ```
+pub fn registry_reference(reference: &str, registry: &str) -> String {
+    let (repo, tag) = split_reference(reference);
+    // Strip an existing registry host from the repository path.
+    let path = match repo.split_once('/') {
+        Some((first, rest))
+            if first.contains('.') || first.contains(':') || first == "localhost" =>
+        {
+            rest.to_string()
+        }
+        _ => repo,
+    };
+    format!("{}/{}:{}", registry.trim_end_matches('/'), path, tag)
+}
+
+// --- Unit Tests ---
+// Docker-dependent functions require a daemon; these tests cover the pure reference helpers.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test splitting references with and without tags, registries and digests.
+    #[test]
+    fn test_split_reference() {
+        assert_eq!(
+            split_reference("my-app"),
+            ("my-app".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            split_reference("my-app:1.0"),
+            ("my-app".to_string(), "1.0".to_string())
+        );
+        assert_eq!(
+            split_reference("localhost:5000/team/app"),
+            ("localhost:5000/team/app".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            split_reference("localhost:5000/app:dev@sha256:abc"),
+            ("localhost:5000/app".to_string(), "dev".to_string())
+        );
+    }
+
+    /// Test rewriting references onto another registry.
+    #[test]
+    fn test_registry_reference() {
+        assert_eq!(
+            registry_reference("my-app", "localhost:5000"),
+            "localhost:5000/my-app:latest"
+        );
+        assert_eq!(
+            registry_reference("team/app:2.1", "localhost:5000/"),
+            "localhost:5000/team/app:2.1"
+        );
+        assert_eq!(
+            registry_reference("ghcr.io/team/app:2", "lab:5000"),
+            "lab:5000/team/app:2"
+        );
+        assert_eq!(
+            registry_reference("localhost:5000/app:1", "lab.local:5000"),
+            "lab.local:5000/app:1"
+        );
+    }
+}
@@ -21,8 +21,11 @@
 //! - **`exec_in_container`**: Executes a specified command inside a container.
 //!   - Automatically starts the container if it's stopped.
 //!   - Handles attaching `stdin`, `stdout`, and `stderr` based on `interactive` and `tty` flags, enabling interactive sessions.
-//!   - Allows specifying the user and working directory for the command execution context.
+//!   - Allows specifying the user, working directory and extra variables (e.g., resolved
+//!     secrets, which never reach the container's configuration) for the command.
 //!   - Waits for the command to complete and returns its exit code.
+//! - **`exec_in_container_captured`**: Non-interactive variant that also returns the command's
+//!   output, for callers that log it (e.g., core environment lifecycle hooks).
 //! - **`get_container_logs`**: Streams logs (stdout/stderr) from a specified container.
 //!   - Supports following logs in real-time (`follow` flag).
 //!   - Allows specifying the number of trailing lines to fetch (`tail` option).
@@ -49,7 +52,8 @@
 //!     false, // interactive
 //!     false, // tty
 //!     None,  // workdir
-//!     None   // user
+//!     None,  // user
+//!     &[],   // env
 //! ).await?;
 //! println!("'ls -la /app' exited with code: {}", exit_code);
 //!
@@ -61,7 +65,8 @@
 //!     true, // interactive
 //!     true, // tty
 //!     Some("/app"), // workdir
-//!     None // user
+//!     None, // user
+//!     &[], // env
 //! ).await?; // Returns when shell exits
 //!
 //! // Example 3: Get the last 20 lines of logs
@@ -77,16 +82,19 @@ use crate::core::error::{DevrsError, Result}; // Use Result/Error from core modu
 use anyhow::{anyhow, Context}; // For error context
 use bollard::{
     container::{LogOutput, LogsOptions}, // Use specific container types for logs
+    errors::Error as BollardError,       // Raw API errors carried by exec streams
     exec::{CreateExecOptions, StartExecResults}, // Types for exec operations
 };
-use futures_util::StreamExt; // Required for processing streams (like logs or exec output)
+use futures_util::{Stream, StreamExt}; // Required for processing streams (like logs or exec output)
 use std::{
-    default::Default,  // For default struct initializers
-    io::{self, Write}, // Standard IO traits (used for stdout flushing)
-    time::Duration,    // For specifying delays (e.g., after starting container)
+    default::Default,   // For default struct initializers
+    io::{self, Write},  // Standard IO traits (used for stdout flushing)
+    pin::Pin,           // Boxed stream types of attached exec instances
+    sync::{Arc, Mutex}, // Shared buffer for captured exec output
+    time::Duration,     // For specifying delays (e.g., after starting container)
 };
 use tokio::{
-    io::{copy, stderr, stdin, stdout, AsyncWriteExt}, // Async IO operations
+    io::{copy, stderr, stdin, stdout, AsyncWrite, AsyncWriteExt}, // Async IO operations
     task, // For spawning concurrent tasks (handling stdin/stdout/stderr for exec)
 };
 use tracing::{debug, error, info, instrument, warn}; // Logging framework utilities
@@ -94,6 +102,7 @@ use tracing::{debug, error, info, instrument, warn}; // Logging framework utilit
 // Import functions from sibling modules needed for exec/logs prerequisites.
 use super::connect::connect_docker; // Get Docker client connection
 use super::lifecycle::start_container; // Start container if stopped
+use super::paths::{OutputRewriter, PathMapper, IDLE_FLUSH}; // Optional host path rewriting of exec output
 use super::state::{container_exists, container_running}; // Check container status
 
 /// Executes a command inside a specified container, handling interactivity.
@@ -111,6 +120,7 @@ use super::state::{container_exists, container_running}; // Check container stat
 /// * `tty` - If `true`, a pseudo-terminal (TTY) is allocated for the exec instance. This is typically required for interactive shell sessions.
 /// * `workdir` - An optional string slice specifying the working directory inside the container where the command should be executed. If `None`, the container's default working directory is used.
 /// * `user` - An optional string slice specifying the username or UID to run the command as inside the container. If `None`, the container's default user is used.
+/// * `env` - Extra `KEY=VALUE` variables for this command only (e.g. resolved secrets). They are not logged.
 ///
 /// # Returns
 ///
@@ -121,7 +131,7 @@ use super::state::{container_exists, container_running}; // Check container stat
 /// * `DevrsError::ContainerNotFound` - If the specified container does not exist.
 /// * `DevrsError::DockerOperation` - If the container exists but is stopped and fails to start.
 /// * `DevrsError::DockerApi` - For errors during communication with the Docker daemon (e.g., creating or starting the exec instance, inspecting the result).
-#[instrument(skip(name_or_id, cmd, interactive, tty, workdir, user), fields(container = %name_or_id))] // Tracing span
+#[instrument(skip(name_or_id, cmd, interactive, tty, workdir, user, env), fields(container = %name_or_id))] // Tracing span
 #[allow(clippy::too_many_arguments)] // Necessary due to the number of options for exec
 pub async fn exec_in_container(
     name_or_id: &str,
@@ -130,6 +140,133 @@ pub async fn exec_in_container(
     tty: bool,
     workdir: Option<&str>,
     user: Option<&str>,
+    env: &[String],
+) -> Result<i64> {
+    run_exec(
+        name_or_id,
+        cmd,
+        interactive,
+        tty,
+        workdir,
+        user,
+        env,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Executes a command like `exec_in_container`, rewriting container paths in its output.
+///
+/// Container-absolute paths under one of `mapper`'s mounts (e.g. in compiler errors)
+/// are shown as the corresponding host paths, so host terminals and editors can open
+/// them. See `paths::OutputRewriter` for how partial lines, ANSI escape codes and
+/// binary output are handled.
+///
+/// # Arguments
+///
+/// * `name_or_id` - The name or ID of the target container.
+/// * `cmd` - The command and its arguments.
+/// * `interactive` - Whether to attach the host's stdin.
+/// * `tty` - Whether to allocate a pseudo-TTY.
+/// * `workdir` - Optional working directory inside the container.
+/// * `user` - Optional user to run the command as.
+/// * `env` - Extra `KEY=VALUE` variables for this command only.
+/// * `mapper` - The mount mapping used to translate paths.
+///
+/// # Returns
+///
+/// * `Result<i64>` - The exit code (`-1` if unknown).
+///
+/// # Errors
+///
+/// Same as `exec_in_container`.
+#[instrument(skip(name_or_id, cmd, interactive, tty, workdir, user, env, mapper), fields(container = %name_or_id))] // Tracing span
+#[allow(clippy::too_many_arguments)] // Necessary due to the number of options for exec
+pub async fn exec_in_container_with_host_paths(
+    name_or_id: &str,
+    cmd: &[String],
+    interactive: bool,
+    tty: bool,
+    workdir: Option<&str>,
+    user: Option<&str>,
+    env: &[String],
+    mapper: PathMapper,
+) -> Result<i64> {
+    run_exec(
+        name_or_id,
+        cmd,
+        interactive,
+        tty,
+        workdir,
+        user,
+        env,
+        None,
+        Some(mapper),
+    )
+    .await
+}
+
+/// Executes a non-interactive command inside a container, echoing and capturing its output.
+///
+/// Behaves like `exec_in_container` with `interactive` and `tty` disabled, but also
+/// collects everything the command writes to stdout and stderr (interleaved, in
+/// arrival order) so the caller can log it.
+///
+/// # Arguments
+///
+/// * `name_or_id` - The name or ID of the target container.
+/// * `cmd` - The command and its arguments.
+/// * `workdir` - Optional working directory inside the container.
+/// * `user` - Optional user to run the command as.
+/// * `env` - Extra `KEY=VALUE` variables for this command only.
+///
+/// # Returns
+///
+/// * `Result<(i64, Vec<u8>)>` - The exit code (`-1` if unknown) and the captured output.
+///
+/// # Errors
+///
+/// Same as `exec_in_container`.
+#[instrument(skip(name_or_id, cmd, workdir, user, env), fields(container = %name_or_id))] // Tracing span
+pub async fn exec_in_container_captured(
+    name_or_id: &str,
+    cmd: &[String],
+    workdir: Option<&str>,
+    user: Option<&str>,
+    env: &[String],
+) -> Result<(i64, Vec<u8>)> {
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let exit_code = run_exec(
+        name_or_id,
+        cmd,
+        false,
+        false,
+        workdir,
+        user,
+        env,
+        Some(Arc::clone(&captured)),
+        None,
+    )
+    .await?;
+    let output = std::mem::take(&mut *captured.lock().unwrap_or_else(|e| e.into_inner()));
+    Ok((exit_code, output))
+}
+
+/// Shared implementation of the `exec_in_container*` functions.
+/// When `capture` is given, stdout/stderr chunks are also appended to it; when
+/// `host_paths` is given, container paths in the echoed output are rewritten to host paths.
+#[allow(clippy::too_many_arguments)] // Internal plumbing for the public variants.
+async fn run_exec(
+    name_or_id: &str,
+    cmd: &[String],
+    interactive: bool,
+    tty: bool,
+    workdir: Option<&str>,
+    user: Option<&str>,
+    env: &[String],
+    capture: Option<Arc<Mutex<Vec<u8>>>>,
+    host_paths: Option<PathMapper>,
 ) -> Result<i64> {
     // Establish connection to Docker daemon.
     let docker = connect_docker().await?;
@@ -185,14 +322,15 @@ pub async fn exec_in_container(
     // --- Create Exec Instance ---
     // Define options for the Docker `exec_create` API call.
     let exec_options = CreateExecOptions {
-        attach_stdout: Some(true),              // Always attach stdout.
-        attach_stderr: Some(true),              // Always attach stderr.
-        attach_stdin: Some(interactive),        // Attach stdin only if interactive flag is true.
-        tty: Some(tty),                         // Allocate TTY if tty flag is true.
-        cmd: Some(cmd.to_vec()),                // The command and arguments to run.
+        attach_stdout: Some(true),                    // Always attach stdout.
+        attach_stderr: Some(true),                    // Always attach stderr.
+        attach_stdin: Some(interactive), // Attach stdin only if interactive flag is true.
+        tty: Some(tty),                  // Allocate TTY if tty flag is true.
+        cmd: Some(cmd.to_vec()),         // The command and arguments to run.
         working_dir: workdir.map(String::from), // Optional working directory.
-        user: user.map(String::from),           // Optional user.
-        ..Default::default()                    // Use defaults for other options (e.g., Env).
+        user: user.map(String::from),    // Optional user.
+        env: (!env.is_empty()).then(|| env.to_vec()), // Extra variables (never logged).
+        ..Default::default()             // Use defaults for other options.
     };
 
     // Make the API call to create the exec instance.
@@ -267,41 +405,75 @@ pub async fn exec_in_container(
             let output_handle = task::spawn(async move {
                 let mut host_stdout = stdout(); // Handle to host stdout.
                 let mut host_stderr = stderr(); // Handle to host stderr.
+                                                // Optional path rewriting, one filter per stream.
+                let mut stdout_filter = host_paths.clone().map(OutputRewriter::new);
+                let mut stderr_filter = host_paths.map(OutputRewriter::new);
 
                 // Loop while the output stream has data.
-                while let Some(result) = output.next().await {
+                loop {
+                    // While a filter holds back a partial word, wait only briefly: if no
+                    // more output comes, it is likely a prompt and is emitted as is.
+                    let holding = [&stdout_filter, &stderr_filter]
+                        .iter()
+                        .any(|f| f.as_ref().is_some_and(OutputRewriter::has_pending));
+                    let next = if holding {
+                        match tokio::time::timeout(IDLE_FLUSH, output.next()).await {
+                            Ok(next) => next,
+                            Err(_) => {
+                                flush_filter(&mut stdout_filter, &mut host_stdout).await;
+                                flush_filter(&mut stderr_filter, &mut host_stderr).await;
+                                continue;
+                            }
+                        }
+                    } else {
+                        output.next().await
+                    };
+                    let Some(result) = next else {
+                        break;
+                    };
                     match result {
                         // Successfully received a chunk of output.
-                        Ok(log_output) => match log_output {
-                            // Demultiplex the stream based on type.
-                            LogOutput::StdOut { message } => {
-                                // Write stdout chunk to host stdout.
-                                if let Err(e) = host_stdout.write_all(&message).await {
-                                    warn!("Error writing exec stdout to host stdout: {}", e);
-                                    break; // Stop processing on write error.
-                                }
-                                // Flush to ensure visibility.
-                                if let Err(e) = host_stdout.flush().await {
-                                    warn!("Error flushing host stdout: {}", e);
-                                }
+                        Ok(log_output) => {
+                            // Keep a copy of the output if the caller wants it.
+                            if let (
+                                Some(buf),
+                                LogOutput::StdOut { message } | LogOutput::StdErr { message },
+                            ) = (&capture, &log_output)
+                            {
+                                buf.lock()
+                                    .unwrap_or_else(|e| e.into_inner())
+                                    .extend_from_slice(message);
                             }
-                            LogOutput::StdErr { message } => {
-                                // Write stderr chunk to host stderr.
-                                if let Err(e) = host_stderr.write_all(&message).await {
-                                    warn!("Error writing exec stderr to host stderr: {}", e);
-                                    break; // Stop processing on write error.
+                            match log_output {
+                                // Demultiplex the stream based on type.
+                                // TTY sessions deliver their output as `Console` chunks.
+                                LogOutput::StdOut { message } | LogOutput::Console { message } => {
+                                    let message = filter_chunk(&mut stdout_filter, &message);
+                                    // Write stdout chunk to host stdout.
+                                    if let Err(e) = host_stdout.write_all(&message).await {
+                                        warn!("Error writing exec stdout to host stdout: {}", e);
+                                        break; // Stop processing on write error.
+                                    }
+                                    // Flush to ensure visibility.
+                                    if let Err(e) = host_stdout.flush().await {
+                                        warn!("Error flushing host stdout: {}", e);
+                                    }
                                 }
-                                // Flush to ensure visibility.
-                                if let Err(e) = host_stderr.flush().await {
-                                    warn!("Error flushing host stderr: {}", e);
+                                LogOutput::StdErr { message } => {
+                                    let message = filter_chunk(&mut stderr_filter, &message);
+                                    // Write stderr chunk to host stderr.
+                                    if let Err(e) = host_stderr.write_all(&message).await {
+                                        warn!("Error writing exec stderr to host stderr: {}", e);
+                                        break; // Stop processing on write error.
+                                    }
+                                    // Flush to ensure visibility.
+                                    if let Err(e) = host_stderr.flush().await {
+                                        warn!("Error flushing host stderr: {}", e);
+                                    }
                                 }
+                                LogOutput::StdIn { .. } => { /* Ignore stdin echoes */ }
                             }
-                            // Handle other potential stream types (usually not expected for exec).
-                            LogOutput::Console { message } => {
-                                warn!("Exec Console Output: {}", String::from_utf8_lossy(&message));
-                            }
-                            LogOutput::StdIn { .. } => { /* Ignore stdin echoes */ }
-                        },
+                        }
                         // Error occurred while reading from the stream.
                         Err(e) => {
                             warn!("Error receiving output from exec stream: {}", e);
@@ -310,6 +482,13 @@ pub async fn exec_in_container(
                     }
                 }
                 debug!("Exec output stream finished.");
+                // Emit anything the path filters were still holding back.
+                if let Some(filter) = &mut stdout_filter {
+                    let _ = host_stdout.write_all(&filter.finish()).await;
+                }
+                if let Some(filter) = &mut stderr_filter {
+                    let _ = host_stderr.write_all(&filter.finish()).await;
+                }
                 // Final flush of host streams.
                 let _ = host_stdout.flush().await;
                 let _ = host_stderr.flush().await;
@@ -369,6 +548,136 @@ pub async fn exec_in_container(
     }
 }
 
+/// Writes what a stream's path filter is holding back after the stream went idle.
+async fn flush_filter<W: AsyncWrite + Unpin>(filter: &mut Option<OutputRewriter>, out: &mut W) {
+    if let Some(filter) = filter {
+        let held = filter.flush();
+        if !held.is_empty() {
+            let _ = out.write_all(&held).await;
+            let _ = out.flush().await;
+        }
+    }
+}
+
+/// Passes an output chunk through the stream's path filter, if there is one.
+fn filter_chunk(filter: &mut Option<OutputRewriter>, chunk: &[u8]) -> Vec<u8> {
+    match filter {
+        Some(filter) => filter.push(chunk),
+        None => chunk.to_vec(),
+    }
+}
+
+/// # Attached Exec (`AttachedExec`)
+///
+/// A running, non-TTY exec instance whose stdio the caller drives directly
+/// (instead of it being wired to the host terminal as in `exec_in_container`).
+pub struct AttachedExec {
+    /// The exec instance ID, for `exec_exit_code` once the output ends.
+    pub id: String,
+    /// Demultiplexed stdout/stderr chunks from the command.
+    pub output: Pin<Box<dyn Stream<Item = std::result::Result<LogOutput, BollardError>> + Send>>,
+    /// The command's stdin. Shut it down to signal EOF.
+    pub input: Pin<Box<dyn AsyncWrite + Send>>,
+}
+
+/// Starts a non-TTY command in a running container and hands its stdio streams to the caller.
+///
+/// Used by protocol bridges (e.g. `devrs env lsp`) that must inspect or rewrite
+/// the bytes flowing in each direction. The container must already be running.
+///
+/// # Arguments
+///
+/// * `name_or_id` - The name or ID of the target container.
+/// * `cmd` - The command and its arguments.
+/// * `workdir` - Optional working directory inside the container.
+/// * `user` - Optional user to run the command as.
+/// * `env` - Extra `KEY=VALUE` variables for this command only.
+///
+/// # Returns
+///
+/// * `Result<AttachedExec>` - The exec ID and its attached streams.
+///
+/// # Errors
+///
+/// * `DevrsError::ContainerNotFound` - If the container does not exist.
+/// * `DevrsError::DockerApi` - If the exec instance cannot be created or started.
+/// * `DevrsError::DockerOperation` - If Docker starts the exec detached.
+#[instrument(skip(name_or_id, cmd, workdir, user, env), fields(container = %name_or_id))] // Tracing span
+pub async fn exec_attached(
+    name_or_id: &str,
+    cmd: &[String],
+    workdir: Option<&str>,
+    user: Option<&str>,
+    env: &[String],
+) -> Result<AttachedExec> {
+    let docker = connect_docker().await?;
+    let exec_options = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        attach_stdin: Some(true),
+        tty: Some(false), // Keep stdout and stderr separate and the byte stream untouched.
+        cmd: Some(cmd.to_vec()),
+        working_dir: workdir.map(String::from),
+        user: user.map(String::from),
+        env: (!env.is_empty()).then(|| env.to_vec()),
+        ..Default::default()
+    };
+    let exec_id = docker
+        .create_exec(name_or_id, exec_options)
+        .await
+        .map_err(|e| match e {
+            BollardError::DockerResponseServerError {
+                status_code: 404, ..
+            } => anyhow!(DevrsError::ContainerNotFound {
+                name: name_or_id.to_string()
+            }),
+            _ => anyhow!(DevrsError::DockerApi { source: e }).context(format!(
+                "Failed to create exec instance in container '{}'",
+                name_or_id
+            )),
+        })?
+        .id;
+    debug!("Created attached exec instance ID: {}", exec_id);
+
+    match docker.start_exec(&exec_id, None).await.map_err(|e| {
+        anyhow!(DevrsError::DockerApi { source: e }).context("Failed to start exec instance")
+    })? {
+        StartExecResults::Attached { output, input } => Ok(AttachedExec {
+            id: exec_id,
+            output,
+            input,
+        }),
+        StartExecResults::Detached => Err(anyhow!(DevrsError::DockerOperation(format!(
+            "Exec instance '{}' started detached; expected attached streams.",
+            exec_id
+        )))),
+    }
+}
+
+/// Retrieves the exit code of a finished exec instance.
+///
+/// # Arguments
+///
+/// * `exec_id` - The exec instance ID (e.g. `AttachedExec::id`).
+///
+/// # Returns
+///
+/// * `Result<i64>` - The exit code, or `-1` if Docker does not report one.
+///
+/// # Errors
+///
+/// * `DevrsError::DockerApi` - If the exec instance cannot be inspected.
+pub async fn exec_exit_code(exec_id: &str) -> Result<i64> {
+    let docker = connect_docker().await?;
+    let inspect = docker.inspect_exec(exec_id).await.map_err(|e| {
+        anyhow!(DevrsError::DockerApi { source: e }).context(format!(
+            "Failed to inspect exec instance '{}' after execution",
+            exec_id
+        ))
+    })?;
+    Ok(inspect.exit_code.unwrap_or(-1))
+}
+
 /// Retrieves logs from a specified container and streams them to the host's standard output.
 ///
 /// This function connects to the Docker daemon, requests the logs for the given container,
@@ -21,7 +21,7 @@
 //! - **`start_container`**: Takes a container name/ID and attempts to start it if it's stopped. Handles the "already running" case gracefully (Docker 304 response).
 //! - **`stop_container`**: Takes a container name/ID and attempts to stop it gracefully within an optional timeout, falling back to a force kill if necessary. Handles the "already stopped" case gracefully (Docker 304 response).
 //! - **`remove_container`**: Takes a container name/ID and attempts to remove it. Includes a `force` flag. If `force` is false, it first checks if the container is running and returns an error if it is. Handles the "not found" case gracefully.
-//! - **`ensure_core_env_running`**: A higher-level function specifically for the core DevRS environment. It checks if the designated container exists and is running. If not, it automatically creates and/or starts it based on the application configuration (`config::Config`). This involves calling `state::container_exists`, `state::container_running`, `operations::run_container`, and `start_container` as needed.
+//! - **`ensure_core_env_running`**: A higher-level function specifically for the core DevRS environment. It checks if the designated container exists and is running. If not, it automatically creates and/or starts it based on the application configuration (`config::Config`). This involves calling `state::container_exists`, `state::container_running`, `operations::run_container`, and `start_container` as needed. It then writes the `inject = "file"` secrets of `[core_env.secrets]` to the container's `/run/secrets` tmpfs.
 //!
 //! These functions rely on helpers from sibling modules (`connect`, `state`, `operations`)
 //! and map Docker API errors to consistent `DevrsError` types.
@@ -60,7 +60,11 @@
 //!
 use crate::core::{
     config,                      // Application configuration structure
+    dotenv,                      // Variables from core_env.env_files
     error::{DevrsError, Result}, // Standard Result and custom Error types
+    lint,                        // Security checks before creating the container
+    ports,                       // Default binding for port mappings
+    secrets,                     // Secret files for /run/secrets
 };
 use anyhow::{anyhow, Context}; // For error context wrapping
 use bollard::container::{
@@ -73,9 +77,12 @@ use bollard::container::{
 use tracing::{debug, error, info, instrument, warn}; // Logging utilities
 
 // Import functions from sibling modules needed for lifecycle operations.
+use super::agents; // Forwarded ssh-agent/gpg-agent sockets
 use super::connect::connect_docker; // Get Docker client connection
+use super::interaction::exec_in_container_captured; // Fix ownership of fresh volumes, write secret files
 use super::operations; // Access operations like run_container (needed for ensure_core_env)
-use super::state::{container_exists, container_running}; // Check container status before actions
+use super::state::{container_exists, container_running, inspect_container}; // Check container status before actions
+use super::volumes::{self, PersistVolume}; // Named volumes for persisted directories
 
 /// Starts a stopped Docker container identified by its name or ID.
 ///
@@ -351,9 +358,11 @@ pub async fn remove_container(name_or_id: &str, force: bool) -> Result<()> {
 ///
 /// Returns an error if:
 /// - The container needs to be created but the configured image (`cfg.core_env.image_name`) is not found locally (`DevrsError::ImageNotFound`).
+/// - The container needs to be created but `core::lint` finds an unsuppressed error in the configuration (`DevrsError::Config`).
 /// - Container creation fails due to Docker API errors or conflicts (`DevrsError::DockerOperation`, `DevrsError::DockerApi`).
 /// - An existing, stopped container fails to start (`DevrsError::DockerApi`).
 /// - The container fails to reach a running state after creation/start attempts (`DevrsError::DockerOperation`).
+/// - A secret file cannot be resolved on the host or written to the container (see `write_secret_files`).
 #[instrument(skip(name, cfg), fields(container = %name))] // Tracing span
 pub async fn ensure_core_env_running(name: &str, cfg: &config::Config) -> Result<bool> {
     // Construct the full image name:tag string from configuration.
@@ -371,14 +380,44 @@ pub async fn ensure_core_env_running(name: &str, cfg: &config::Config) -> Result
         );
         created = true; // Mark that we are creating it.
 
+        // Security checks of the configuration; errors stop the creation.
+        lint::check_before_create(cfg, name)?;
+
+        // Persisted directories are backed by named volumes that outlive the container.
+        let persisted = volumes::persist_volumes(cfg);
+        for volume in &persisted {
+            volumes::create_volume(volume).await?;
+        }
+        // Variables from `env_files`, overridden by the literal `env_vars` (secret ones are
+        // resolved on each exec instead, see `core::secrets`), plus HISTFILE for history.
+        let mut env_vars =
+            dotenv::load_config_files(&cfg.core_env.env_files, "core_env.env_files")?;
+        env_vars.extend(
+            cfg.core_env
+                .env_vars
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.plain()?.to_string()))),
+        );
+        env_vars.extend(volumes::persist_env(cfg));
+        // Forwarded agents: the devrs agents directory with their proxy sockets, plus SSH_AUTH_SOCK.
+        let (agent_mounts, agent_env) = agents::agent_mounts(cfg);
+        let mut mounts = cfg.core_env.mounts.clone();
+        mounts.extend(agent_mounts);
+        env_vars.extend(agent_env);
+        debug!("Core env variables: {}", dotenv::Redacted(&env_vars));
+
         // Call the run_container operation (from the sibling 'operations' module).
         // Configure it for the core env: detached, persistent (no auto-remove).
+        // Ports without an explicit IP bind to `[network] bind_address` (localhost by default).
+        let port_mappings = ports::bind_default(&cfg.core_env.ports, &cfg.network.bind_address);
         match operations::run_container(
             &image_name_with_tag,                 // Image name from config.
             name,                                 // Target container name.
-            &cfg.core_env.ports,                  // Ports from config.
-            &cfg.core_env.mounts,                 // Mounts from config.
-            &cfg.core_env.env_vars,               // Env vars from config.
+            &port_mappings,                       // Ports from config.
+            &mounts,                              // Mounts from config (plus agents).
+            &persisted,                           // Volumes for [core_env.persist].
+            &[secrets::SECRETS_DIR.to_string()],  // tmpfs for secret files.
+            &env_vars,                            // Env vars from config.
             Some(&cfg.core_env.default_workdir), // Workdir from config.
             true,                                 // detached = true (run in background).
             false,                                // auto_remove = false (persist).
@@ -389,6 +428,7 @@ pub async fn ensure_core_env_running(name: &str, cfg: &config::Config) -> Result
             // Container created and started successfully.
             Ok(()) => {
                 info!("Successfully created and started container '{}'.", name);
+                prepare_persisted_dirs(name, cfg, &persisted).await;
                 // Optional brief pause if needed for services inside container to fully start.
                 // tokio::time::sleep(std::time::Duration::from_millis(500)).await;
             }
@@ -458,10 +498,111 @@ pub async fn ensure_core_env_running(name: &str, cfg: &config::Config) -> Result
         ))));
     }
 
+    // Secret files live in memory and are refreshed for every command.
+    write_secret_files(name, cfg).await?;
+
     // Return Ok, indicating if the container was newly created or just ensured running.
     Ok(created)
 }
 
+/// Writes the `inject = "file"` secrets of `[core_env.secrets]` to `/run/secrets`.
+///
+/// Each secret is resolved on the host and passed to a short exec as a variable, which
+/// writes it to `/run/secrets/<name>` with mode 0600 as the user commands run as
+/// (`core_env.user`, or the image's user). The values are never logged. Files are only
+/// written to a tmpfs, so containers created before DevRS mounted one must be recreated.
+///
+/// # Errors
+///
+/// Returns an error if a secret cannot be resolved (see `secrets::resolve`), if
+/// `/run/secrets` is not a tmpfs (`DevrsError::Config`), or if writing a file fails
+/// (`DevrsError::ExternalCommand`).
+async fn write_secret_files(name: &str, cfg: &config::Config) -> Result<()> {
+    let files = secrets::resolve_all(&cfg.core_env.secrets, config::SecretInjection::File)?;
+    let script = r#"grep -qs " $0 tmpfs " /proc/mounts || exit 3
+umask 077 && printf '%s' "$DEVRS_SECRET" > "$0/$1""#;
+    for (file, value) in files {
+        let cmd: Vec<String> = vec![
+            "sh".into(),
+            "-c".into(),
+            script.into(),
+            secrets::SECRETS_DIR.into(),
+            file.clone(),
+        ];
+        let env = [format!("DEVRS_SECRET={}", value)];
+        let user = cfg.core_env.user.as_deref();
+        match exec_in_container_captured(name, &cmd, None, user, &env).await? {
+            (0, _) => debug!("Wrote secret file '{}/{}'.", secrets::SECRETS_DIR, file),
+            (3, _) => {
+                return Err(anyhow!(DevrsError::Config(format!(
+                    "Container '{}' has no tmpfs at {} for core_env.secrets.{}. It was created by an older DevRS; recreate it with `devrs env rebuild`.",
+                    name,
+                    secrets::SECRETS_DIR,
+                    file
+                ))));
+            }
+            (code, output) => {
+                return Err(anyhow!(DevrsError::ExternalCommand {
+                    cmd: format!("write {}/{}", secrets::SECRETS_DIR, file),
+                    status: format!("exit code {}", code),
+                    output: String::from_utf8_lossy(&output).into_owned(),
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hands fresh persisted-directory volumes to the container's user.
+///
+/// A volume mounted at a path that does not exist in the image starts out empty and
+/// owned by root, so a non-root user could not write shell history or caches to it.
+/// Empty, root-owned volume roots are therefore chowned (as root) to the user that
+/// `env shell`/`env exec` run as: `core_env.user`, or the image's configured user.
+/// Failures are logged as warnings; the container is usable either way.
+async fn prepare_persisted_dirs(name: &str, cfg: &config::Config, persisted: &[PersistVolume]) {
+    if persisted.is_empty() {
+        return;
+    }
+    let owner = match &cfg.core_env.user {
+        Some(user) => user.clone(),
+        None => match inspect_container(name).await {
+            Ok(details) => details.config.and_then(|c| c.user).unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    "Could not inspect '{}' to prepare persisted directories: {}",
+                    name, e
+                );
+                return;
+            }
+        },
+    };
+    if owner.is_empty() || owner == "root" || owner == "0" {
+        return; // Root can write to the volumes already.
+    }
+    let script = r#"owner=$1; shift
+case "$owner" in *:*) spec=$owner ;; *) spec="$(id -u "$owner"):$(id -g "$owner")" || exit 1 ;; esac
+for dir in "$@"; do
+  if [ "$(stat -c %u "$dir")" = 0 ] && [ -z "$(ls -A "$dir")" ]; then chown "$spec" "$dir" || exit 1; fi
+done"#;
+    let mut cmd: Vec<String> = vec![
+        "sh".into(),
+        "-c".into(),
+        script.into(),
+        "sh".into(),
+        owner.clone(),
+    ];
+    cmd.extend(persisted.iter().map(|v| v.path.clone()));
+    match exec_in_container_captured(name, &cmd, None, Some("root"), &[]).await {
+        Ok((0, _)) => debug!("Persisted directories prepared for user '{}'.", owner),
+        Ok((code, _)) => warn!(
+            "Could not hand persisted directories to user '{}' (exit code {}).",
+            owner, code
+        ),
+        Err(e) => warn!("Could not prepare persisted directories: {}", e),
+    }
+}
+
 // --- Unit Tests ---
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,308 @@
+//! # DevRS Docker Log Streams
+//!
+//! File: cli/src/common/docker/logs.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! `interaction::get_container_logs` copies one container's raw log bytes to stdout.
+//! This module instead turns container logs into structured `LogLine` values
+//! (container, stream, timestamp, message) so that callers can merge several
+//! containers, filter lines and render them however they like. It also exposes a
+//! stream of container start events, used to attach to containers that start
+//! while logs are being followed.
+//!
+//! ## Architecture
+//!
+//! - `LogQuery` carries the Docker `logs` options (streams, follow, tail, since/until).
+//! - `log_lines` requests logs **with timestamps always on** (they are needed for
+//!   ordering), splits each frame into lines and parses the RFC 3339 prefix with
+//!   `split_timestamp`.
+//! - `container_starts` wraps the Docker events endpoint, filtered to container
+//!   `start` events.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::logs::{self, LogQuery};
+//! use futures_util::StreamExt;
+//! # use crate::core::error::Result;
+//! # async fn example() -> Result<()> {
+//! let query = LogQuery { tail: "10".to_string(), ..Default::default() };
+//! let mut lines = Box::pin(logs::log_lines("my-app", &query).await?);
+//! while let Some(line) = lines.next().await {
+//!     println!("{}", line?.message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Use standard Result and custom Error
+use anyhow::anyhow; // For creating errors
+use bollard::{
+    container::{LogOutput, LogsOptions}, // Log request options and frames
+    system::EventsOptions,               // Event stream options
+};
+use chrono::{DateTime, Utc}; // Parsed log timestamps
+use futures_util::{stream, Stream, StreamExt}; // Stream adapters
+use serde::Serialize; // For JSON line output
+use std::collections::HashMap; // For event filters and attributes
+use tracing::{debug, instrument}; // Logging utilities
+
+// Use the connect function from the sibling module.
+use super::connect::connect_docker;
+
+/// # Log Stream Kind (`LogStreamKind`)
+///
+/// Which output stream of the container a line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStreamKind {
+    /// Standard output (also used for TTY containers, whose streams are merged).
+    Stdout,
+    /// Standard error.
+    Stderr,
+}
+
+/// # Log Line (`LogLine`)
+///
+/// One line of container output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLine {
+    /// The container the line came from.
+    pub container: String,
+    /// The stream the line was written to.
+    pub stream: LogStreamKind,
+    /// When Docker recorded the line, if a timestamp was present.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The line itself, without timestamp or trailing newline.
+    pub message: String,
+}
+
+/// # Log Query (`LogQuery`)
+///
+/// Options for `log_lines`, mirroring the Docker `logs` endpoint.
+#[derive(Debug, Clone)]
+pub struct LogQuery {
+    /// Include standard output.
+    pub stdout: bool,
+    /// Include standard error.
+    pub stderr: bool,
+    /// Keep the stream open and deliver new lines as they are written.
+    pub follow: bool,
+    /// Number of lines from the end to start with, or `"all"`.
+    pub tail: String,
+    /// Only lines after this Unix timestamp (0 = no lower bound).
+    pub since: i64,
+    /// Only lines before this Unix timestamp (0 = no upper bound).
+    pub until: i64,
+}
+
+impl Default for LogQuery {
+    fn default() -> Self {
+        Self {
+            stdout: true,
+            stderr: true,
+            follow: false,
+            tail: "all".to_string(),
+            since: 0,
+            until: 0,
+        }
+    }
+}
+
+/// Splits the RFC 3339 timestamp Docker prefixes to each line when `timestamps` is enabled.
+///
+/// # Arguments
+///
+/// * `line` - A log line, e.g. `2024-03-01T10:00:00.123456789Z server started`.
+///
+/// # Returns
+///
+/// * `(Option<DateTime<Utc>>, &str)` - The parsed timestamp (if present) and the remaining message.
+pub fn split_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    if let Some((prefix, rest)) = line.split_once(' ') {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(prefix) {
+            return (Some(ts.with_timezone(&Utc)), rest);
+        }
+    }
+    (None, line)
+}
+
+/// Converts one log frame into lines. Lines without their own timestamp inherit the
+/// last one seen (TTY output can split a line across frames).
+fn frame_to_lines(
+    container: &str,
+    frame: LogOutput,
+    last_timestamp: &mut Option<DateTime<Utc>>,
+) -> Vec<LogLine> {
+    let (stream, bytes) = match frame {
+        LogOutput::StdErr { message } => (LogStreamKind::Stderr, message),
+        LogOutput::StdOut { message } | LogOutput::Console { message } => {
+            (LogStreamKind::Stdout, message)
+        }
+        LogOutput::StdIn { .. } => return Vec::new(), // Never requested.
+    };
+    String::from_utf8_lossy(&bytes)
+        .split('\n')
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.is_empty())
+        .map(|raw| {
+            let (timestamp, message) = split_timestamp(raw);
+            if timestamp.is_some() {
+                *last_timestamp = timestamp;
+            }
+            LogLine {
+                container: container.to_string(),
+                stream,
+                timestamp: timestamp.or(*last_timestamp),
+                message: message.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Streams a container's logs as structured lines.
+///
+/// # Arguments
+///
+/// * `container` - The name or ID of the container. This name is also used as `LogLine::container`.
+/// * `query` - Which lines to request.
+///
+/// # Returns
+///
+/// * `Result<impl Stream<Item = Result<LogLine>>>` - The lines, in the order Docker returns them.
+///
+/// # Errors
+///
+/// Returns an error if connecting to Docker fails. Errors while reading (e.g. the
+/// container does not exist) are yielded by the stream as `DevrsError::DockerApi`.
+#[instrument(skip(query))]
+pub async fn log_lines(
+    container: &str,
+    query: &LogQuery,
+) -> Result<impl Stream<Item = Result<LogLine>>> {
+    let docker = connect_docker().await?;
+    debug!("Requesting logs for '{}': {:?}", container, query);
+    let options = LogsOptions {
+        stdout: query.stdout,
+        stderr: query.stderr,
+        follow: query.follow,
+        since: query.since,
+        until: query.until,
+        timestamps: true, // Always on: needed to merge containers in time order.
+        tail: query.tail.clone(),
+    };
+    let name = container.to_string();
+    let mut last_timestamp = None;
+    Ok(docker
+        .logs(container, Some(options))
+        .flat_map(move |frame| {
+            let items: Vec<Result<LogLine>> = match frame {
+                Ok(frame) => frame_to_lines(&name, frame, &mut last_timestamp)
+                    .into_iter()
+                    .map(Ok)
+                    .collect(),
+                Err(e) => vec![Err(anyhow!(DevrsError::DockerApi { source: e })
+                    .context(format!("Error reading logs for container '{}'", name)))],
+            };
+            stream::iter(items)
+        }))
+}
+
+/// # Container Start (`ContainerStart`)
+///
+/// A container that has just started, as reported by the Docker events endpoint.
+#[derive(Debug, Clone)]
+pub struct ContainerStart {
+    /// The container ID.
+    pub id: String,
+    /// The container name.
+    pub name: String,
+    /// The image reference the container was created from.
+    pub image: String,
+    /// The container's labels (including those inherited from its image).
+    pub labels: HashMap<String, String>,
+}
+
+/// Streams container `start` events from the Docker daemon.
+///
+/// # Returns
+///
+/// * `Result<impl Stream<Item = Result<ContainerStart>>>` - One item per started container.
+///
+/// # Errors
+///
+/// Returns an error if connecting to Docker fails. Errors while reading events are
+/// yielded by the stream.
+pub async fn container_starts() -> Result<impl Stream<Item = Result<ContainerStart>>> {
+    let docker = connect_docker().await?;
+    let filters = HashMap::from([
+        ("type".to_string(), vec!["container".to_string()]),
+        ("event".to_string(), vec!["start".to_string()]),
+    ]);
+    let options = EventsOptions::<String> {
+        filters,
+        ..Default::default()
+    };
+    Ok(docker.events(Some(options)).filter_map(|event| async move {
+        match event {
+            Ok(event) => {
+                let actor = event.actor?;
+                let id = actor.id?;
+                let mut attributes = actor.attributes.unwrap_or_default();
+                // Event attributes hold the container's labels plus `name` and `image`.
+                let name = attributes.remove("name")?;
+                let image = attributes.remove("image").unwrap_or_default();
+                Some(Ok(ContainerStart {
+                    id,
+                    name,
+                    image,
+                    labels: attributes,
+                }))
+            }
+            Err(e) => {
+                Some(Err(anyhow!(DevrsError::DockerApi { source: e })
+                    .context("Error reading Docker events")))
+            }
+        }
+    }))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test timestamp splitting for prefixed and plain lines.
+    #[test]
+    fn test_split_timestamp() {
+        let (ts, msg) = split_timestamp("2024-03-01T10:00:00.5Z server started");
+        assert_eq!(ts.unwrap().timestamp_millis(), 1_709_287_200_500);
+        assert_eq!(msg, "server started");
+
+        let (ts, msg) = split_timestamp("no timestamp here");
+        assert!(ts.is_none());
+        assert_eq!(msg, "no timestamp here");
+    }
+
+    /// Test splitting a multi-line frame and inheriting timestamps.
+    #[test]
+    fn test_frame_to_lines() {
+        let mut last = None;
+        let frame = LogOutput::StdErr {
+            message: "2024-03-01T10:00:00Z first\r\ncontinued\n\n".into(),
+        };
+        let lines = frame_to_lines("app", frame, &mut last);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].stream, LogStreamKind::Stderr);
+        assert_eq!(lines[0].message, "first");
+        assert_eq!(lines[1].message, "continued");
+        assert_eq!(lines[1].timestamp, lines[0].timestamp);
+        assert_eq!(lines[1].container, "app");
+    }
+}
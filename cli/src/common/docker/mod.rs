@@ -19,12 +19,20 @@
 //!
 //! The `common::docker` module delegates tasks to the following specialized submodules:
 //!
+//! - **`agents`**: Forwards the host's ssh-agent and gpg-agent sockets into the core environment.
 //! - **`connect`**: Handles establishing the connection to the Docker daemon.
-//! - **`images`**: Manages Docker images (checking existence, listing, inspection, removal).
+//! - **`images`**: Manages Docker images (checking existence, listing, inspection, removal, save/load, tag/push).
 //! - **`state`**: Queries the status and details of Docker containers (existence, running state, inspection, listing).
 //! - **`lifecycle`**: Controls the lifecycle of containers (starting, stopping, removing, ensuring the core environment is running).
 //! - **`operations`**: Implements core actions like building images (`build_image`) and creating/starting containers (`run_container`).
 //! - **`interaction`**: Facilitates interaction with running containers, such as executing commands (`exec_in_container`) and streaming logs (`get_container_logs`).
+//! - **`logs`**: Structured, multi-container friendly log streams (`log_lines`) and container start events.
+//! - **`paths`**: Translates paths between the host and containers using the configured bind mounts.
+//! - **`provenance`**: Build-time labels (content hash of the Dockerfile and its COPY sources, git commit, build time) used to detect stale images.
+//! - **`roles`**: Classifies images by their DevRS role using build-time labels and naming conventions.
+//! - **`stats`**: Reads live resource usage (CPU, memory, network/block I/O, PIDs) from the stats endpoint.
+//! - **`system`**: Daemon-wide queries such as disk usage (`disk_usage`).
+//! - **`volumes`**: Named volumes backing the core environment's persisted directories.
 //!
 //! By re-exporting key functions, this module provides a simplified API surface for
 //! other parts of the application that need to perform Docker operations.
@@ -59,6 +67,8 @@
 //! ```
 //!
 
+/// Forwards the host's ssh-agent and gpg-agent into the core environment container.
+pub mod agents;
 /// Handles establishing a connection to the local Docker daemon.
 pub mod connect;
 /// Provides operations specific to Docker images (existence checks, listing, removal).
@@ -67,10 +77,24 @@ pub mod images;
 pub mod interaction;
 /// Contains functions for managing the lifecycle of containers (start, stop, remove).
 pub mod lifecycle;
+/// Structured log lines and container start events for multi-container log views.
+pub mod logs;
 /// Implements core Docker actions like building images and running containers.
 pub mod operations;
+/// Host ↔ container path translation based on bind mounts.
+pub mod paths;
+/// Provenance labels (content hash, git commit, build time) for staleness detection.
+pub mod provenance;
+/// Classifies images by their DevRS role (core environment, application, other).
+pub mod roles;
 /// Offers functions to query the state of containers (existence, running status, inspection).
 pub mod state;
+/// Live container resource usage from the Docker stats endpoint.
+pub mod stats;
+/// Daemon-wide queries such as disk usage (`system df`).
+pub mod system;
+/// Named volumes for `[core_env.persist]` (create, list, remove).
+pub mod volumes;
 
 // --- Re-exports for easier access from other parts of the application ---
 // Makes functions available like `docker::build_image(...)` instead of `docker::operations::build_image(...)`
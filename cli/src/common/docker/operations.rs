@@ -50,12 +50,12 @@
 //! let tag = "my-app:latest";
 //! let dockerfile = "Dockerfile";
 //! let context_dir = ".";
-//! operations::build_image(tag, dockerfile, context_dir, false).await?;
+//! operations::build_image(tag, dockerfile, context_dir, false, &HashMap::new()).await?;
 //!
 //! // Example: Running a container
 //! let image = "my-app:latest";
 //! let container_name = "my-app-1";
-//! let ports = vec!["8080:80".to_string()];
+//! let ports = vec!["127.0.0.1:8080:80".parse()?];
 //! let mounts = vec![config::MountConfig { host: "/path/on/host".into(), container: "/data".into(), readonly: false }]; // Example mount
 //! let env_vars = HashMap::from([("MODE".to_string(), "production".to_string())]);
 //! operations::run_container(
@@ -63,6 +63,8 @@
 //!     container_name,
 //!     &ports,
 //!     &mounts,
+//!     &[],          // named volumes
+//!     &[],          // tmpfs mounts
 //!     &env_vars,
 //!     Some("/app"), // workdir
 //!     true,         // detach
@@ -76,6 +78,7 @@
 use crate::common::archive::tar::create_context_tar; // Utility for creating build context tarball
 use crate::core::config; // Use config structs (e.g., MountConfig)
 use crate::core::error::{DevrsError, Result}; // Use standard Result and custom Error
+use crate::core::ports::PortMapping; // Typed port mappings
 use anyhow::{anyhow, Context}; // For error context wrapping
 use bollard::{
     container::{
@@ -85,8 +88,8 @@ use bollard::{
         StartContainerOptions,
     },
     image::BuildImageOptions, // Options struct for image building
-    models::{BuildInfo, HostConfig, Mount, MountTypeEnum, PortBinding}, // Data models from Docker API
-                                                                        // Docker client is obtained via connect_docker
+    models::{BuildInfo, HostConfig, Mount, MountTmpfsOptions, MountTypeEnum, PortBinding}, // Data models from Docker API
+                                                                                           // Docker client is obtained via connect_docker
 };
 use futures_util::stream::StreamExt; // Required for processing streams (like build output)
 use std::collections::HashMap; // For port bindings and env vars maps
@@ -98,6 +101,7 @@ use tracing::{debug, error, info, warn}; // Logging utilities
 // Import necessary functions from sibling modules.
 use super::connect::connect_docker; // Get Docker client connection
 use super::state::container_exists; // Check for existing container before creating
+use super::volumes::PersistVolume; // Named volumes mounted into the container
 
 // --- Image Building ---
 
@@ -115,6 +119,8 @@ use super::state::container_exists; // Check for existing container before creat
 /// * `dockerfile` - The path to the Dockerfile, *relative to the root of the `context_dir`*.
 /// * `context_dir` - The path to the directory containing the build context (files to be sent to Docker).
 /// * `no_cache` - If `true`, instructs Docker to build without using its layer cache.
+/// * `labels` - Labels to apply to the resulting image (e.g., `devrs.role`, see `roles::build_labels`).
+/// * `build_args` - Build arguments (`--build-arg NAME=VALUE`) passed to the Dockerfile.
 ///
 /// # Returns
 ///
@@ -133,6 +139,8 @@ pub async fn build_image(
     dockerfile: &str,
     context_dir: &str,
     no_cache: bool,
+    labels: &HashMap<String, String>,
+    build_args: &HashMap<String, String>,
 ) -> Result<()> {
     // Establish connection to Docker daemon.
     let docker = connect_docker().await?;
@@ -153,7 +161,9 @@ pub async fn build_image(
         t: tag.to_string(),                 // Image name and tag.
         rm: true,                           // Remove intermediate containers after build.
         nocache: no_cache,                  // Use Docker build cache?
-        // Add other options like buildargs, labels, target stage here if needed later.
+        labels: labels.clone(),             // Image labels (devrs role, project, ...).
+        buildargs: build_args.clone(),      // Dockerfile ARG values.
+        // Add other options like target stage here if needed later.
         ..Default::default()
     };
 
@@ -226,6 +236,38 @@ pub async fn build_image(
 
 // --- Container Running ---
 
+/// Bollard's port bindings (`"80/tcp"` -> host bindings) and exposed ports.
+type PortSpecs = (
+    HashMap<String, Option<Vec<PortBinding>>>,
+    HashMap<String, HashMap<(), ()>>,
+);
+
+/// Converts port mappings into Docker's bindings, one entry per container port of a range.
+/// A host port of `None` lets Docker choose a free port.
+fn port_bindings(ports: &[PortMapping]) -> PortSpecs {
+    let mut port_bindings: HashMap<String, Option<Vec<PortBinding>>> = HashMap::new();
+    // Keep track of ports that need to be formally exposed by the container config.
+    let mut exposed_ports: HashMap<String, HashMap<(), ()>> = HashMap::new(); // Value is just {}
+    for mapping in ports {
+        for (index, container_port) in mapping.container.ports().enumerate() {
+            // Key format expected by Docker API: "port/proto" (e.g., "80/tcp").
+            let key = format!("{}/{}", container_port, mapping.protocol);
+            exposed_ports.insert(key.clone(), HashMap::new());
+            let binding = PortBinding {
+                host_ip: mapping.ip.map(|ip| ip.to_string()),
+                host_port: mapping.host_port(index),
+            };
+            // Several bindings for one container port are allowed (e.g., IPv4 and IPv6).
+            port_bindings
+                .entry(key)
+                .or_default()
+                .get_or_insert_with(Vec::new)
+                .push(binding);
+        }
+    }
+    (port_bindings, exposed_ports)
+}
+
 /// Creates and starts a new Docker container based on the provided configuration.
 ///
 /// This function handles the two main steps: creating the container configuration
@@ -237,8 +279,11 @@ pub async fn build_image(
 ///
 /// * `image` - Name and tag of the Docker image to use (e.g., "my-app:latest").
 /// * `name` - The desired name for the new container. Must be unique.
-/// * `ports` - A slice of strings defining port mappings in "HOST:CONTAINER" format (e.g., `&["8080:80"]`).
+/// * `ports` - Port mappings to publish (see `core::ports`). Mappings without a host IP bind to
+///   all interfaces here; callers apply `[network] bind_address` first (`ports::bind_default`).
 /// * `mounts` - A slice of `config::MountConfig` structs defining volume mounts. Host paths must be absolute.
+/// * `volumes` - Named volumes to mount (e.g., the core environment's persisted directories). They should already exist.
+/// * `tmpfs` - Container directories to mount as in-memory tmpfs, writable by any user (e.g., `/run/secrets`).
 /// * `env_vars` - A `HashMap` containing environment variables (KEY=VALUE) to set inside the container.
 /// * `workdir` - An optional path string for the working directory inside the container. If `None`, uses the image's default.
 /// * `detach` - If `true`, the container runs in the background. If `false`, the container runs in the foreground (but this function doesn't wait or stream I/O). Also affects whether standard streams are attached by default.
@@ -260,8 +305,10 @@ pub async fn build_image(
 pub async fn run_container(
     image: &str,
     name: &str,
-    ports: &[String],
+    ports: &[PortMapping],
     mounts: &[config::MountConfig],
+    volumes: &[PersistVolume],
+    tmpfs: &[String],
     env_vars: &HashMap<String, String>,
     workdir: Option<&str>,
     detach: bool,
@@ -272,48 +319,30 @@ pub async fn run_container(
     let docker = connect_docker().await?;
 
     // --- Prepare HostConfig (Networking, Mounts, Resources) ---
-    // Parse port mapping strings into the structure required by bollard.
-    let mut port_bindings: HashMap<String, Option<Vec<PortBinding>>> = HashMap::new();
-    // Keep track of ports that need to be formally exposed by the container config.
-    let mut exposed_ports: HashMap<String, HashMap<(), ()>> = HashMap::new(); // Value is just {}
-
-    for mapping in ports {
-        // Expect "HOST:CONTAINER" or "HOST:CONTAINER/proto" format.
-        if let Some((host_part, container_part)) = mapping.split_once(':') {
-            // Check for protocol specification (e.g., "80/tcp"). Default to tcp.
-            let (container_port, proto) =
-                if let Some((port, protocol)) = container_part.split_once('/') {
-                    (port, format!("/{}", protocol.to_lowercase())) // Ensure proto includes '/' and is lowercase
-                } else {
-                    (container_part, "/tcp".to_string()) // Default to TCP
-                };
-            // Key format expected by Docker API: "port/proto" (e.g., "80/tcp").
-            let container_port_proto = format!("{}{}", container_port, proto);
-
-            // Mark the port as exposed in the container config.
-            exposed_ports.insert(container_port_proto.clone(), HashMap::new()); // Value is empty map.
-
-            // Create the host port binding specification.
-            let binding = PortBinding {
-                host_ip: None, // Bind to all host interfaces by default. Could be specified if needed.
-                host_port: Some(host_part.to_string()), // The host port to map to.
-            };
-            // Add the binding to the map, handling potential multiple bindings for one container port (rare).
-            port_bindings
-                .entry(container_port_proto)
-                .or_default() // Get Option<Vec>, default to None then insert Some(Vec::new())
-                .get_or_insert_with(Vec::new) // Ensure Vec exists
-                .push(binding);
-        } else {
-            // Log invalid port mapping formats.
-            warn!("Ignoring invalid port mapping format: {}", mapping);
-        }
-    }
+    // Expand port mappings (and ranges) into the structures required by bollard.
+    let (port_bindings, exposed_ports) = port_bindings(ports);
 
     // Convert the application's MountConfig structs into bollard's Mount structs.
     // This helper also validates paths.
-    let bollard_mounts =
+    let mut bollard_mounts =
         convert_mounts_to_bollard(mounts).context("Failed to prepare container mounts")?;
+    // Named volumes are copied-up from the image on first use, like `docker run -v name:/path`.
+    bollard_mounts.extend(volumes.iter().map(|volume| Mount {
+        target: Some(volume.path.clone()),
+        source: Some(volume.name.clone()),
+        typ: Some(MountTypeEnum::VOLUME),
+        ..Default::default()
+    }));
+    // In-memory directories; their content never reaches the host's disk.
+    bollard_mounts.extend(tmpfs.iter().map(|path| Mount {
+        target: Some(path.clone()),
+        typ: Some(MountTypeEnum::TMPFS),
+        tmpfs_options: Some(MountTmpfsOptions {
+            mode: Some(0o1777), // Like /tmp, so the container's user can write to it.
+            ..Default::default()
+        }),
+        ..Default::default()
+    }));
 
     // Construct the HostConfig part of the container creation request.
     let host_config = HostConfig {
@@ -0,0 +1,485 @@
+//! # DevRS Host/Container Path Mapping
+//!
+//! File: cli/src/common/docker/paths.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Translates paths between the host and a container using the bind mounts in
+//! `CoreEnvConfig.mounts`. Used wherever a host-side tool and the core environment
+//! must agree on file locations, e.g. `devrs env exec --map-cwd` (and the host
+//! command shims built on it) and the `devrs env lsp` URI rewriting.
+//!
+//! ## Architecture
+//!
+//! - `PathMapper::from_mounts` collects `(host, container)` prefix pairs. Host paths are
+//!   canonicalized when they exist, so symlinked checkouts still match.
+//! - `to_container` / `to_host` pick the **longest** matching prefix, so nested mounts
+//!   (e.g. `~/code` and `~/code/big-repo`) resolve to the most specific one.
+//! - Matching is per path component: `/code` matches `/code/x` but not `/codex`.
+//! - `OutputRewriter` applies `to_host` to paths found in streamed command output
+//!   (`devrs env exec --host-paths`). A held-back partial word is flushed once the
+//!   stream has been idle for `IDLE_FLUSH`, so prompts without a newline still show.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::paths::PathMapper;
+//! # fn example(cfg: &crate::core::config::Config) {
+//! let mapper = PathMapper::from_mounts(&cfg.core_env.mounts);
+//! if let Some(workdir) = mapper.to_container(&std::env::current_dir().unwrap()) {
+//!     println!("Running in {}", workdir);
+//! }
+//! # }
+//! ```
+//!
+use crate::core::config::MountConfig; // Mount definitions from the configuration.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+}; // Host path handling, idle flush delay.
+
+/// # Path Mapper (`PathMapper`)
+///
+/// Bidirectional host ↔ container path translation over a set of bind mounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathMapper {
+    /// `(host, container)` prefix pairs. Container paths have no trailing slash.
+    pairs: Vec<(PathBuf, String)>,
+}
+
+impl PathMapper {
+    /// Builds a mapper from the configured mounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `mounts` - The mounts (host paths already tilde-expanded).
+    ///
+    /// # Returns
+    ///
+    /// * `PathMapper` - The mapper (empty if there are no mounts).
+    pub fn from_mounts(mounts: &[MountConfig]) -> Self {
+        let pairs = mounts
+            .iter()
+            .filter(|m| !m.host.is_empty() && m.container.starts_with('/'))
+            .flat_map(|m| {
+                let host = PathBuf::from(&m.host);
+                let container = match m.container.trim_end_matches('/') {
+                    "" => "/".to_string(),
+                    trimmed => trimmed.to_string(),
+                };
+                // Match both the canonical and the configured spelling of the host path;
+                // the configured one comes last so `to_host` prefers it.
+                let canonical = fs::canonicalize(&host).ok().filter(|c| *c != host);
+                canonical
+                    .map(|c| (c, container.clone()))
+                    .into_iter()
+                    .chain([(host, container)])
+            })
+            .collect();
+        PathMapper { pairs }
+    }
+
+    /// Translates a host path to the corresponding container path.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_path` - An absolute host path (canonicalized here if it exists).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The container path, or `None` if the path is not under any mount.
+    pub fn to_container(&self, host_path: &Path) -> Option<String> {
+        let canonical = fs::canonicalize(host_path).unwrap_or_else(|_| host_path.to_path_buf());
+        self.map_to_container(&canonical)
+            .or_else(|| self.map_to_container(host_path))
+    }
+
+    /// Translates a host path to the container without touching the filesystem
+    /// (for paths inside protocol messages, which may not exist yet).
+    ///
+    /// # Arguments
+    ///
+    /// * `host_path` - An absolute host path.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The container path, or `None` if the path is not under any mount.
+    pub fn map_to_container(&self, host_path: &Path) -> Option<String> {
+        self.pairs
+            .iter()
+            .filter_map(|(host, container)| {
+                let rest = host_path.strip_prefix(host).ok()?;
+                Some((host.components().count(), join_container(container, rest)))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, path)| path)
+    }
+
+    /// Translates a container path to the corresponding host path.
+    ///
+    /// # Arguments
+    ///
+    /// * `container_path` - An absolute container path.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<PathBuf>` - The host path, or `None` if the path is not under any mount.
+    pub fn to_host(&self, container_path: &str) -> Option<PathBuf> {
+        self.pairs
+            .iter()
+            .filter_map(|(host, container)| {
+                let rest = strip_container_prefix(container_path, container)?;
+                let host = if rest.is_empty() {
+                    host.clone()
+                } else {
+                    host.join(rest)
+                };
+                Some((container.len(), host))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, path)| path)
+    }
+}
+
+/// Held-back partial lines longer than this are flushed unrewritten rather than buffered.
+const MAX_PENDING: usize = 4096;
+
+/// How long a stream may be quiet before `OutputRewriter::flush` should emit the partial
+/// word it holds back (e.g. a `Password:` prompt).
+pub const IDLE_FLUSH: Duration = Duration::from_millis(50);
+
+/// # Output Rewriter (`OutputRewriter`)
+///
+/// Streaming filter that rewrites container-absolute paths in command output to their
+/// host equivalents (e.g. `/home/me/code/app/src/lib.rs:3:5` in a compiler error).
+/// Use one instance per output stream.
+///
+/// - Chunks may split lines anywhere: only the trailing word of an unfinished line is
+///   held back until more output arrives. The caller emits it with `flush` once the
+///   stream has been idle for `IDLE_FLUSH`, or with `finish` at its end, so prompts still
+///   appear.
+/// - Output is only ever split on UTF-8 character boundaries; a character cut between
+///   two chunks waits for its remaining bytes.
+/// - ANSI escape sequences are copied verbatim and delimit paths, so colored paths match.
+/// - As soon as a stream looks binary (NUL bytes or invalid UTF-8), it is passed through
+///   untouched for the rest of its life.
+#[derive(Debug)]
+pub struct OutputRewriter {
+    mapper: PathMapper,
+    pending: Vec<u8>,
+    binary: bool,
+}
+
+impl OutputRewriter {
+    /// Creates a rewriter for one output stream.
+    pub fn new(mapper: PathMapper) -> Self {
+        OutputRewriter {
+            mapper,
+            pending: Vec::new(),
+            binary: false,
+        }
+    }
+
+    /// Feeds a chunk of output and returns the bytes that can be written now.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        if self.binary {
+            return chunk.to_vec();
+        }
+        self.pending.extend_from_slice(chunk);
+        if self.pending.contains(&0) {
+            return self.pass_through();
+        }
+        // Emit complete lines, plus an unfinished line up to its last whitespace.
+        let split = match self.pending.iter().rposition(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => 0,
+        };
+        let split = self.pending[split..]
+            .iter()
+            .rposition(|b| b.is_ascii_whitespace())
+            .map_or(split, |i| split + i + 1);
+        let split = if self.pending.len() - split > MAX_PENDING {
+            complete_utf8_len(&self.pending)
+        } else {
+            split
+        };
+        self.emit(split)
+    }
+
+    /// Whether a partial word is held back, i.e. whether the caller should `flush` when
+    /// the stream goes idle.
+    pub fn has_pending(&self) -> bool {
+        !self.binary && !self.pending.is_empty()
+    }
+
+    /// Emits the held-back partial word without waiting for more output (call when the
+    /// stream has been idle for `IDLE_FLUSH`). An incomplete UTF-8 character stays held.
+    pub fn flush(&mut self) -> Vec<u8> {
+        if self.binary {
+            return std::mem::take(&mut self.pending);
+        }
+        self.emit(complete_utf8_len(&self.pending))
+    }
+
+    /// Flushes whatever is still held back (call at end of stream).
+    pub fn finish(&mut self) -> Vec<u8> {
+        if self.binary {
+            return std::mem::take(&mut self.pending);
+        }
+        match String::from_utf8(std::mem::take(&mut self.pending)) {
+            Ok(text) => rewrite_output(&text, &self.mapper).into_bytes(),
+            Err(e) => e.into_bytes(),
+        }
+    }
+
+    /// Rewrites and returns the first `len` held bytes, switching to pass-through mode
+    /// (and returning everything) if they are not valid UTF-8.
+    fn emit(&mut self, len: usize) -> Vec<u8> {
+        let ready: Vec<u8> = self.pending.drain(..len).collect();
+        match String::from_utf8(ready) {
+            Ok(text) => rewrite_output(&text, &self.mapper).into_bytes(),
+            Err(e) => {
+                let mut raw = e.into_bytes();
+                raw.append(&mut self.pending);
+                self.binary = true;
+                raw
+            }
+        }
+    }
+
+    /// Switches to pass-through mode and returns the held bytes.
+    fn pass_through(&mut self) -> Vec<u8> {
+        self.binary = true;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Length of `bytes` without a trailing incomplete UTF-8 character (one cut off at the
+/// end of a chunk). Invalid UTF-8 elsewhere is left for the caller to detect.
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    match std::str::from_utf8(bytes) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    }
+}
+
+/// Rewrites container paths in a piece of text, leaving everything else untouched.
+///
+/// A path starts with `/` at the beginning of the text or after a delimiter (whitespace,
+/// punctuation or an ANSI escape sequence) and runs up to the next whitespace, quote,
+/// bracket, `:` or escape sequence. Only its mounted prefix is replaced.
+fn rewrite_output(text: &str, mapper: &PathMapper) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut at_boundary = true;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            let len = escape_len(rest);
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+            at_boundary = true;
+        } else if c == '/' && at_boundary {
+            let len = rest
+                .find(|c: char| c.is_whitespace() || "\x1b\"'`()[]{}<>,;:".contains(c))
+                .unwrap_or(rest.len());
+            let token = &rest[..len];
+            // Keep extra leading slashes (e.g. the third one in `file:///...`).
+            let slashes = token.len() - token.trim_start_matches('/').len() - 1;
+            match mapper.to_host(&token[slashes..]) {
+                Some(host) => {
+                    out.push_str(&token[..slashes]);
+                    out.push_str(&host.to_string_lossy());
+                }
+                None => out.push_str(token),
+            }
+            rest = &rest[len..];
+            at_boundary = false;
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            at_boundary = !(c.is_alphanumeric() || "_-.~".contains(c));
+        }
+    }
+    out
+}
+
+/// Length in bytes of the ANSI escape sequence at the start of `s` (which starts with ESC).
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.get(1) {
+        // CSI: ESC [ parameters/intermediates, then a final byte in 0x40..=0x7E.
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |i| i + 3),
+        // OSC: ESC ] ... terminated by BEL or ESC \.
+        Some(b']') => {
+            let body = &bytes[2..];
+            let bel = body.iter().position(|&b| b == 0x07).map(|i| i + 3);
+            let st = body.windows(2).position(|w| w == b"\x1b\\").map(|i| i + 4);
+            match (bel, st) {
+                (Some(a), Some(b)) => a.min(b),
+                (a, b) => a.or(b).unwrap_or(bytes.len()),
+            }
+        }
+        // Two-byte sequences (ESC followed by one ASCII character).
+        Some(b) if b.is_ascii() => 2,
+        _ => 1,
+    }
+}
+
+/// Joins a relative host remainder onto a container prefix with `/` separators.
+fn join_container(container: &str, rest: &Path) -> String {
+    let mut path = container.to_string();
+    for component in rest.components() {
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        path.push_str(&component.as_os_str().to_string_lossy());
+    }
+    path
+}
+
+/// Strips a container prefix on a component boundary, returning the remainder
+/// without a leading slash.
+fn strip_container_prefix<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    if prefix == "/" {
+        return path.strip_prefix('/');
+    }
+    match path.strip_prefix(prefix)? {
+        "" => Some(""),
+        rest => rest.strip_prefix('/'),
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(host: &str, container: &str) -> MountConfig {
+        MountConfig {
+            host: host.to_string(),
+            container: container.to_string(),
+            readonly: false,
+        }
+    }
+
+    /// Test translation in both directions, including nested mounts and component boundaries.
+    #[test]
+    fn test_path_mapping() {
+        let mapper = PathMapper::from_mounts(&[
+            mount("/nonexistent/home/me/code", "/home/me/code/"),
+            mount("/nonexistent/home/me/code/big", "/big"),
+        ]);
+        assert_eq!(
+            mapper.to_container(Path::new("/nonexistent/home/me/code/app/src")),
+            Some("/home/me/code/app/src".to_string())
+        );
+        assert_eq!(
+            mapper.to_container(Path::new("/nonexistent/home/me/code")),
+            Some("/home/me/code".to_string())
+        );
+        // The more specific mount wins.
+        assert_eq!(
+            mapper.to_container(Path::new("/nonexistent/home/me/code/big/lib")),
+            Some("/big/lib".to_string())
+        );
+        assert_eq!(mapper.to_container(Path::new("/tmp")), None);
+        assert_eq!(
+            mapper.to_container(Path::new("/nonexistent/home/me/codex")),
+            None
+        );
+
+        assert_eq!(
+            mapper.to_host("/home/me/code/app/src/lib.rs"),
+            Some(PathBuf::from("/nonexistent/home/me/code/app/src/lib.rs"))
+        );
+        assert_eq!(
+            mapper.to_host("/big"),
+            Some(PathBuf::from("/nonexistent/home/me/code/big"))
+        );
+        assert_eq!(mapper.to_host("/home/me/codex"), None);
+        assert_eq!(mapper.to_host("/usr/lib"), None);
+    }
+
+    /// Test path rewriting in output: delimiters, ANSI colors, URIs and non-matches.
+    #[test]
+    fn test_rewrite_output() {
+        let mapper = PathMapper::from_mounts(&[mount("/host/code", "/home/me/code")]);
+        assert_eq!(
+            rewrite_output(" --> /home/me/code/app/src/lib.rs:3:5\n", &mapper),
+            " --> /host/code/app/src/lib.rs:3:5\n"
+        );
+        assert_eq!(
+            rewrite_output("\x1b[1m/home/me/code/a.rs\x1b[0m (/home/me/code)", &mapper),
+            "\x1b[1m/host/code/a.rs\x1b[0m (/host/code)"
+        );
+        assert_eq!(
+            rewrite_output(
+                "file:///home/me/code/x 'a/home/me/code' /home/me/codex",
+                &mapper
+            ),
+            "file:///host/code/x 'a/home/me/code' /home/me/codex"
+        );
+    }
+
+    /// Test streaming behavior: split chunks, held-back words, and binary pass-through.
+    #[test]
+    fn test_output_rewriter_streaming() {
+        let mapper = PathMapper::from_mounts(&[mount("/host/code", "/home/me/code")]);
+        let mut rewriter = OutputRewriter::new(mapper.clone());
+        let mut out = rewriter.push(b"error at /home/me/co");
+        assert_eq!(out, b"error at ");
+        out.extend(rewriter.push(b"de/a.rs\nPassword: "));
+        out.extend(rewriter.push("/home/me/code/\u{e9}".as_bytes()));
+        out.extend(rewriter.finish());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "error at /host/code/a.rs\nPassword: /host/code/\u{e9}"
+        );
+
+        let mut rewriter = OutputRewriter::new(mapper);
+        let binary = b"\x7fELF\0/home/me/code\n";
+        assert_eq!(rewriter.push(binary), binary);
+        assert_eq!(rewriter.push(b"/home/me/code\n"), b"/home/me/code\n");
+        assert!(rewriter.finish().is_empty());
+    }
+
+    /// Test that an idle flush emits a held-back prompt, and that the rewriter keeps
+    /// working afterwards.
+    #[test]
+    fn test_output_rewriter_flush() {
+        let mapper = PathMapper::from_mounts(&[mount("/host/code", "/home/me/code")]);
+        let mut rewriter = OutputRewriter::new(mapper);
+        assert!(rewriter.push(b"Password:").is_empty());
+        assert!(rewriter.has_pending());
+        assert_eq!(rewriter.flush(), b"Password:");
+        assert!(!rewriter.has_pending());
+        assert_eq!(rewriter.push(b"\n/home/me/code/x "), b"\n/host/code/x ");
+    }
+
+    /// Test that a long partial line is split on a character boundary, not inside one.
+    #[test]
+    fn test_output_rewriter_utf8_boundary() {
+        let mapper = PathMapper::from_mounts(&[mount("/host/code", "/home/me/code")]);
+        let mut rewriter = OutputRewriter::new(mapper);
+        let mut chunk = "x".repeat(MAX_PENDING).into_bytes();
+        chunk.extend_from_slice(&"\u{e9}".as_bytes()[..1]);
+        let out = rewriter.push(&chunk);
+        assert_eq!(out, "x".repeat(MAX_PENDING).as_bytes());
+        assert!(rewriter.has_pending());
+        // The same applies to an idle flush.
+        assert!(rewriter.flush().is_empty());
+
+        let mut out = rewriter.push(&"\u{e9}".as_bytes()[1..]);
+        out.extend(rewriter.push(b" /home/me/code\n"));
+        assert_eq!(String::from_utf8(out).unwrap(), "\u{e9} /host/code\n");
+    }
+}
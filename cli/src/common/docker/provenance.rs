@@ -0,0 +1,346 @@
+//! # DevRS Image Provenance
+//!
+//! File: cli/src/common/docker/provenance.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Records where an image came from, as labels written at build time, so that
+//! DevRS can later tell whether the image still matches its sources:
+//!
+//! - `devrs.content-hash`: SHA-256 over the Dockerfile and every file it `COPY`s or `ADD`s.
+//! - `devrs.git-commit`: the commit of the build context (`-dirty` with local changes).
+//! - `devrs.build-time`: when the image was built (RFC 3339, UTC).
+//! - `devrs.build-context` / `devrs.dockerfile`: where the sources live, so the hash
+//!   can be recomputed from any working directory.
+//!
+//! ## Architecture
+//!
+//! - `copy_sources` extracts the local source paths from `COPY`/`ADD` instructions
+//!   (ignoring `--from=` copies, URLs and heredocs).
+//! - `content_hash` hashes the Dockerfile and the sources (wildcards expanded,
+//!   directories walked in a stable order). `.dockerignore` is not applied, so an
+//!   ignored file inside a copied directory still counts as a change.
+//! - `provenance_labels` assembles the label set passed to `build_image`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::provenance;
+//! use std::path::Path;
+//! # fn example() -> crate::core::error::Result<()> {
+//! let labels = provenance::provenance_labels(Path::new("."), "presets/Dockerfile.devrs")?;
+//! println!("{}", labels[provenance::CONTENT_HASH_LABEL]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For error creation.
+use chrono::{SecondsFormat, Utc}; // Build time label.
+use sha2::{Digest, Sha256}; // Content hashing.
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+}; // Labels, source files and `git`.
+use tracing::{debug, warn}; // Logging utilities.
+use walkdir::WalkDir; // For hashing copied directories.
+
+/// Label key holding the content hash of the Dockerfile and its COPY/ADD sources.
+pub const CONTENT_HASH_LABEL: &str = "devrs.content-hash";
+/// Label key holding the git commit of the build context.
+pub const GIT_COMMIT_LABEL: &str = "devrs.git-commit";
+/// Label key holding the build time (RFC 3339, UTC).
+pub const BUILD_TIME_LABEL: &str = "devrs.build-time";
+/// Label key holding the absolute path of the build context on the host.
+pub const BUILD_CONTEXT_LABEL: &str = "devrs.build-context";
+/// Label key holding the Dockerfile path, relative to the build context.
+pub const DOCKERFILE_LABEL: &str = "devrs.dockerfile";
+
+/// Extracts the local source paths of all `COPY` and `ADD` instructions.
+///
+/// # Arguments
+///
+/// * `dockerfile` - The Dockerfile contents.
+///
+/// # Returns
+///
+/// * `Vec<String>` - Source paths/patterns relative to the build context, in order of appearance.
+pub fn copy_sources(dockerfile: &str) -> Vec<String> {
+    // Join continuation lines so each instruction is one logical line.
+    let mut logical = Vec::new();
+    let mut current = String::new();
+    for line in dockerfile.lines() {
+        let trimmed = line.trim();
+        if current.is_empty() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+        match trimmed.strip_suffix('\\') {
+            Some(part) => {
+                current.push_str(part);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                logical.push(std::mem::take(&mut current));
+            }
+        }
+    }
+
+    let mut sources = Vec::new();
+    for line in logical {
+        let Some((instruction, rest)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        if !instruction.eq_ignore_ascii_case("COPY") && !instruction.eq_ignore_ascii_case("ADD") {
+            continue;
+        }
+        let mut rest = rest.trim();
+        let mut from_stage = false;
+        while let Some(flag) = rest.strip_prefix("--") {
+            let (flag, remainder) = flag.split_once(char::is_whitespace).unwrap_or((flag, ""));
+            from_stage |= flag.starts_with("from=");
+            rest = remainder.trim_start();
+        }
+        if from_stage {
+            continue; // Copies from another stage or image, not from the context.
+        }
+        let args: Vec<String> = if rest.starts_with('[') {
+            serde_json::from_str(rest).unwrap_or_default()
+        } else {
+            rest.split_whitespace().map(String::from).collect()
+        };
+        if args.len() < 2 {
+            continue;
+        }
+        sources.extend(
+            args[..args.len() - 1]
+                .iter()
+                .filter(|s| !s.starts_with("<<") && !s.contains("://") && !s.starts_with("git@"))
+                .cloned(),
+        );
+    }
+    sources
+}
+
+/// Feeds one named entry into the hash (name, length and content, so boundaries are unambiguous).
+fn hash_entry(hasher: &mut Sha256, name: &str, content: &[u8]) {
+    hasher.update(name.as_bytes());
+    hasher.update([0]);
+    hasher.update((content.len() as u64).to_le_bytes());
+    hasher.update(content);
+}
+
+/// Computes the content hash of a Dockerfile and the files it copies.
+///
+/// # Arguments
+///
+/// * `context` - The build context directory.
+/// * `dockerfile` - The Dockerfile path, relative to `context`.
+///
+/// # Returns
+///
+/// * `Result<String>` - The hash as `sha256:<hex>`.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the Dockerfile cannot be read. Missing
+/// sources do not fail; they are hashed as missing (the build would report them).
+pub fn content_hash(context: &Path, dockerfile: &str) -> Result<String> {
+    let dockerfile_path = context.join(dockerfile);
+    let contents = fs::read(&dockerfile_path).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to read '{}': {}",
+            dockerfile_path.display(),
+            e
+        )))
+    })?;
+    let mut hasher = Sha256::new();
+    hash_entry(&mut hasher, dockerfile, &contents);
+
+    let mut sources = copy_sources(&String::from_utf8_lossy(&contents));
+    sources.sort();
+    sources.dedup();
+    for source in sources {
+        let pattern = context.join(source.trim_start_matches('/'));
+        let mut matches: Vec<PathBuf> = glob::glob(&pattern.to_string_lossy())
+            .map(|paths| paths.filter_map(|p| p.ok()).collect())
+            .unwrap_or_default();
+        if matches.is_empty() {
+            debug!("COPY source '{}' not found in context", source);
+            hash_entry(&mut hasher, &format!("missing:{}", source), &[]);
+            continue;
+        }
+        matches.sort();
+        for path in matches {
+            for entry in WalkDir::new(&path).sort_by_file_name() {
+                let entry = match entry {
+                    Ok(entry) if entry.file_type().is_file() => entry,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("Skipping unreadable path while hashing: {}", e);
+                        continue;
+                    }
+                };
+                let relative = entry.path().strip_prefix(context).unwrap_or(entry.path());
+                match fs::read(entry.path()) {
+                    Ok(data) => hash_entry(&mut hasher, &relative.to_string_lossy(), &data),
+                    Err(e) => warn!("Skipping '{}' while hashing: {}", entry.path().display(), e),
+                }
+            }
+        }
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+/// Returns the current git commit of a directory, with `-dirty` if it has local changes.
+///
+/// # Returns
+///
+/// * `Option<String>` - The commit, or `None` if the directory is not a git checkout
+///   (or `git` is unavailable).
+pub fn git_commit(dir: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+    let commit = git(&["rev-parse", "HEAD"]).filter(|c| !c.is_empty())?;
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty());
+    Some(if dirty {
+        format!("{}-dirty", commit)
+    } else {
+        commit
+    })
+}
+
+/// Builds the provenance labels for an image about to be built.
+///
+/// # Arguments
+///
+/// * `context` - The build context directory.
+/// * `dockerfile` - The Dockerfile path, relative to `context`.
+///
+/// # Returns
+///
+/// * `Result<HashMap<String, String>>` - The labels (the git commit is omitted outside a checkout).
+///
+/// # Errors
+///
+/// Returns an error if the Dockerfile cannot be read.
+pub fn provenance_labels(context: &Path, dockerfile: &str) -> Result<HashMap<String, String>> {
+    let context_abs = context
+        .canonicalize()
+        .unwrap_or_else(|_| context.to_path_buf());
+    let mut labels = HashMap::from([
+        (
+            CONTENT_HASH_LABEL.to_string(),
+            content_hash(context, dockerfile)?,
+        ),
+        (
+            BUILD_TIME_LABEL.to_string(),
+            Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        ),
+        (
+            BUILD_CONTEXT_LABEL.to_string(),
+            context_abs.to_string_lossy().into_owned(),
+        ),
+        (DOCKERFILE_LABEL.to_string(), dockerfile.to_string()),
+    ]);
+    if let Some(commit) = git_commit(context) {
+        labels.insert(GIT_COMMIT_LABEL.to_string(), commit);
+    }
+    Ok(labels)
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test COPY/ADD source extraction across the instruction forms.
+    #[test]
+    fn test_copy_sources() {
+        let dockerfile = r#"
+FROM rust:1 AS builder
+# COPY commented.txt /nowhere
+COPY --chown=me:me config/init.lua /home/me/.config/nvim/init.lua
+COPY --from=builder /app/target /usr/local/bin
+copy a.txt b.txt /dest/
+ADD https://example.com/tool.tar.gz /opt/
+ADD ["with space.txt", "/dest/"]
+COPY scripts/*.sh \
+     /usr/local/bin/
+RUN echo done
+"#;
+        assert_eq!(
+            copy_sources(dockerfile),
+            vec![
+                "config/init.lua",
+                "a.txt",
+                "b.txt",
+                "with space.txt",
+                "scripts/*.sh"
+            ]
+        );
+    }
+
+    /// Test that the hash changes with the Dockerfile and with copied files, but not with others.
+    #[test]
+    fn test_content_hash_tracks_sources() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("presets")).unwrap();
+        fs::create_dir_all(root.join("config/nested")).unwrap();
+        fs::write(
+            root.join("presets/Dockerfile"),
+            "FROM scratch\nCOPY config /cfg\n",
+        )
+        .unwrap();
+        fs::write(root.join("config/nested/init.lua"), "-- v1").unwrap();
+        fs::write(root.join("unrelated.txt"), "x").unwrap();
+
+        let first = content_hash(root, "presets/Dockerfile").unwrap();
+        assert!(first.starts_with("sha256:"));
+        assert_eq!(content_hash(root, "presets/Dockerfile").unwrap(), first);
+
+        fs::write(root.join("unrelated.txt"), "y").unwrap();
+        assert_eq!(content_hash(root, "presets/Dockerfile").unwrap(), first);
+
+        fs::write(root.join("config/nested/init.lua"), "-- v2").unwrap();
+        let second = content_hash(root, "presets/Dockerfile").unwrap();
+        assert_ne!(second, first);
+
+        fs::write(
+            root.join("presets/Dockerfile"),
+            "FROM scratch\nCOPY config /cfg\nRUN true\n",
+        )
+        .unwrap();
+        assert_ne!(content_hash(root, "presets/Dockerfile").unwrap(), second);
+        assert!(content_hash(root, "presets/missing").is_err());
+    }
+
+    /// Test the assembled label set.
+    #[test]
+    fn test_provenance_labels() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Dockerfile"), "FROM scratch\n").unwrap();
+        let labels = provenance_labels(dir.path(), "Dockerfile").unwrap();
+        assert_eq!(labels[DOCKERFILE_LABEL], "Dockerfile");
+        assert!(labels.contains_key(CONTENT_HASH_LABEL));
+        assert!(labels.contains_key(BUILD_TIME_LABEL));
+        assert!(Path::new(&labels[BUILD_CONTEXT_LABEL]).is_absolute());
+    }
+}
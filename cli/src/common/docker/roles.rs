@@ -0,0 +1,234 @@
+//! # DevRS Resource Roles
+//!
+//! File: cli/src/common/docker/roles.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module classifies Docker images by the part they play in a DevRS workflow
+//! (their "devrs role"), so inventory and cleanup commands such as `devrs images ls`,
+//! `devrs images prune` and `devrs du` can tell DevRS-managed images apart from
+//! everything else on the Docker host. Containers inherit the labels of their image,
+//! so the same classification applies to them.
+//!
+//! ## Architecture
+//!
+//! Classification uses two signals, in order:
+//! 1. **Labels** written at build time: `devrs container build` and `devrs env build`
+//!    stamp images with `devrs.role` (and `devrs.project` for application images).
+//!    See `build_labels`.
+//! 2. **Naming conventions** for images built before labels existed: the core environment
+//!    repository is `core_env.image_name`, and application images use
+//!    `<application_defaults.default_image_prefix>-<project>`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::roles::{self, DevrsRole};
+//! # use crate::core::config::Config;
+//! # let cfg = Config::default();
+//! // Labels stamped at build time identify the image...
+//! let labels = roles::build_labels(DevrsRole::App, Some("webapp"));
+//! // ...and are read back when classifying it later.
+//! let role = roles::image_role(&["webapp:latest".to_string()], &labels, &cfg);
+//! assert_eq!(role, DevrsRole::App);
+//! ```
+//!
+use crate::core::config::Config; // For the configured image names and prefixes.
+use std::collections::HashMap; // For image labels.
+use std::fmt; // For Display.
+
+use super::images::split_reference; // To extract the repository from a tag.
+
+/// Label key recording the devrs role of an image (`core-env`, `profile`, `app` or `snapshot`).
+pub const ROLE_LABEL: &str = "devrs.role";
+/// Label key recording the project (directory name) an application image was built from.
+pub const PROJECT_LABEL: &str = "devrs.project";
+
+/// # DevRS Role (`DevrsRole`)
+///
+/// The part an image plays in DevRS workflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DevrsRole {
+    /// The shared core development environment image (`devrs env`).
+    CoreEnv,
+    /// A variant of the core environment built for a specific toolchain profile.
+    /// Only detected via the `devrs.role` label.
+    Profile,
+    /// An application image built with `devrs container build`.
+    App,
+    /// An image committed from a container to capture its state.
+    /// Only detected via the `devrs.role` label.
+    Snapshot,
+    /// Any image not managed by DevRS.
+    Other,
+}
+
+impl DevrsRole {
+    /// The value stored in the `devrs.role` label (and shown in tables).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DevrsRole::CoreEnv => "core-env",
+            DevrsRole::Profile => "profile",
+            DevrsRole::App => "app",
+            DevrsRole::Snapshot => "snapshot",
+            DevrsRole::Other => "other",
+        }
+    }
+
+    /// Whether the image is managed by DevRS.
+    pub fn is_devrs(&self) -> bool {
+        !matches!(self, DevrsRole::Other)
+    }
+}
+
+impl fmt::Display for DevrsRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Builds the labels to stamp onto an image at build time.
+///
+/// # Arguments
+///
+/// * `role` - The role of the image being built.
+/// * `project` - The project name (for application images), if known.
+///
+/// # Returns
+///
+/// * `HashMap<String, String>` - Labels suitable for `operations::build_image`.
+pub fn build_labels(role: DevrsRole, project: Option<&str>) -> HashMap<String, String> {
+    let mut labels = HashMap::from([(ROLE_LABEL.to_string(), role.as_str().to_string())]);
+    if let Some(project) = project {
+        labels.insert(PROJECT_LABEL.to_string(), project.to_string());
+    }
+    labels
+}
+
+/// Classifies an image from its tags and labels.
+///
+/// # Arguments
+///
+/// * `repo_tags` - The image's `repository:tag` references (may be empty for dangling images).
+/// * `labels` - The image's labels.
+/// * `cfg` - The loaded DevRS configuration (for naming conventions).
+///
+/// # Returns
+///
+/// * `DevrsRole` - The detected role.
+pub fn image_role(
+    repo_tags: &[String],
+    labels: &HashMap<String, String>,
+    cfg: &Config,
+) -> DevrsRole {
+    // 1. Labels are authoritative when present.
+    match labels.get(ROLE_LABEL).map(String::as_str) {
+        Some("core-env") => return DevrsRole::CoreEnv,
+        Some("profile") => return DevrsRole::Profile,
+        Some("app") => return DevrsRole::App,
+        Some("snapshot") => return DevrsRole::Snapshot,
+        _ => {}
+    }
+
+    // 2. Fall back to naming conventions.
+    let app_prefix = cfg
+        .application_defaults
+        .default_image_prefix
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("{}-", p));
+    for reference in repo_tags {
+        let (repo, _) = split_reference(reference);
+        if repo == cfg.core_env.image_name {
+            return DevrsRole::CoreEnv;
+        }
+        if app_prefix.as_deref().is_some_and(|p| repo.starts_with(p)) {
+            return DevrsRole::App;
+        }
+    }
+    DevrsRole::Other
+}
+
+/// Classifies a container from its name, image and labels.
+///
+/// Uses `image_role` on the container's image, and additionally recognizes the core
+/// environment container by its conventional name (`<core_env.image_name>-instance`),
+/// which covers containers whose image reference is an ID.
+///
+/// # Arguments
+///
+/// * `name` - The container name (without leading `/`).
+/// * `image` - The image reference the container runs.
+/// * `labels` - The container's labels (including those inherited from its image).
+/// * `cfg` - The loaded DevRS configuration.
+///
+/// # Returns
+///
+/// * `DevrsRole` - The detected role.
+pub fn container_role(
+    name: &str,
+    image: &str,
+    labels: &HashMap<String, String>,
+    cfg: &Config,
+) -> DevrsRole {
+    match image_role(&[image.to_string()], labels, cfg) {
+        DevrsRole::Other if name == format!("{}-instance", cfg.core_env.image_name) => {
+            DevrsRole::CoreEnv
+        }
+        role => role,
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper: a config with an application image prefix.
+    fn config_with_prefix(prefix: Option<&str>) -> Config {
+        let mut cfg = Config::default();
+        cfg.core_env.image_name = "devrs-core-env".to_string();
+        cfg.application_defaults.default_image_prefix = prefix.map(String::from);
+        cfg
+    }
+
+    /// Test classification by naming convention.
+    #[test]
+    fn test_image_role_by_name() {
+        let cfg = config_with_prefix(Some("acme"));
+        let none = HashMap::new();
+        assert_eq!(
+            image_role(&["devrs-core-env:old".to_string()], &none, &cfg),
+            DevrsRole::CoreEnv
+        );
+        assert_eq!(
+            image_role(&["acme-webapp:latest".to_string()], &none, &cfg),
+            DevrsRole::App
+        );
+        assert_eq!(
+            image_role(&["postgres:16".to_string()], &none, &cfg),
+            DevrsRole::Other
+        );
+        assert_eq!(image_role(&[], &none, &cfg), DevrsRole::Other);
+    }
+
+    /// Test that labels take precedence over names.
+    #[test]
+    fn test_image_role_by_label() {
+        let cfg = config_with_prefix(None);
+        let labels = build_labels(DevrsRole::App, Some("webapp"));
+        assert_eq!(
+            labels.get(PROJECT_LABEL).map(String::as_str),
+            Some("webapp")
+        );
+        assert_eq!(
+            image_role(&["webapp:latest".to_string()], &labels, &cfg),
+            DevrsRole::App
+        );
+    }
+}
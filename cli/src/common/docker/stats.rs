@@ -0,0 +1,299 @@
+//! # DevRS Docker Container Stats
+//!
+//! File: cli/src/common/docker/stats.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module reads live resource usage from the Docker stats endpoint and
+//! reduces each raw `bollard::container::Stats` record to a `StatsSample`: CPU %,
+//! memory usage and limit, network I/O, block I/O and PID count. The calculations
+//! match the ones the Docker CLI uses for `docker stats`.
+//!
+//! ## Architecture
+//!
+//! - `sample_once`: a single sample. The daemon waits one collection cycle
+//!   (about a second) so that the CPU percentage can be computed from two readings.
+//! - `sample_stream`: a stream of samples, one per collection cycle, for live views.
+//! - `StatsSample::from_stats`: the pure reduction, unit tested against recorded
+//!   API payloads.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::stats;
+//! # use crate::core::error::Result;
+//! # async fn example() -> Result<()> {
+//! let sample = stats::sample_once("devrs-core-env-instance").await?;
+//! println!("CPU: {:.2}%", sample.cpu_percent);
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Use standard Result and custom Error
+use anyhow::anyhow; // For creating errors
+use bollard::container::{MemoryStatsStats, Stats, StatsOptions}; // Stats API types
+use futures_util::{Stream, StreamExt}; // For consuming the stats stream
+use tracing::{debug, instrument}; // Logging utilities
+
+// Use the connect function from the sibling module.
+use super::connect::connect_docker;
+
+/// # Stats Sample (`StatsSample`)
+///
+/// Resource usage of one container at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsSample {
+    /// CPU usage as a percentage of one core (can exceed 100% on multi-core hosts).
+    pub cpu_percent: f64,
+    /// Memory in use, excluding reclaimable page cache, in bytes.
+    pub memory_usage: u64,
+    /// Memory limit in bytes (host memory if the container has no limit).
+    pub memory_limit: u64,
+    /// Bytes received over all networks.
+    pub net_rx: u64,
+    /// Bytes sent over all networks.
+    pub net_tx: u64,
+    /// Bytes read from block devices.
+    pub block_read: u64,
+    /// Bytes written to block devices.
+    pub block_write: u64,
+    /// Number of processes/threads in the container.
+    pub pids: u64,
+}
+
+impl StatsSample {
+    /// Reduces a raw stats record to a sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - A record from the Docker stats endpoint.
+    ///
+    /// # Returns
+    ///
+    /// * `StatsSample` - The derived usage figures.
+    pub fn from_stats(stats: &Stats) -> Self {
+        // CPU %: container CPU time delta relative to system CPU time delta, scaled by CPU count.
+        let cpu_delta = stats
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+        let system_delta = stats
+            .cpu_stats
+            .system_cpu_usage
+            .unwrap_or(0)
+            .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or(0));
+        let online_cpus = stats.cpu_stats.online_cpus.unwrap_or_else(|| {
+            // Older daemons only report per-CPU usage.
+            stats
+                .cpu_stats
+                .cpu_usage
+                .percpu_usage
+                .as_ref()
+                .map_or(1, |p| p.len() as u64)
+        });
+        let cpu_percent = if system_delta > 0 && cpu_delta > 0 {
+            cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        // Memory: subtract page cache, which the kernel can reclaim at any time.
+        let usage = stats.memory_stats.usage.unwrap_or(0);
+        let cache = match &stats.memory_stats.stats {
+            Some(MemoryStatsStats::V1(v1)) => v1.total_inactive_file,
+            Some(MemoryStatsStats::V2(v2)) => v2.inactive_file,
+            None => 0,
+        };
+
+        // Network: sum over all attached networks.
+        let (net_rx, net_tx) = stats
+            .networks
+            .iter()
+            .flat_map(|nets| nets.values())
+            .fold((0, 0), |(rx, tx), n| (rx + n.rx_bytes, tx + n.tx_bytes));
+
+        // Block I/O: sum read/write operations over all devices.
+        let (mut block_read, mut block_write) = (0, 0);
+        for entry in stats
+            .blkio_stats
+            .io_service_bytes_recursive
+            .iter()
+            .flatten()
+        {
+            match entry.op.to_ascii_lowercase().as_str() {
+                "read" => block_read += entry.value,
+                "write" => block_write += entry.value,
+                _ => {}
+            }
+        }
+
+        Self {
+            cpu_percent,
+            memory_usage: usage.saturating_sub(cache),
+            memory_limit: stats.memory_stats.limit.unwrap_or(0),
+            net_rx,
+            net_tx,
+            block_read,
+            block_write,
+            pids: stats.pids_stats.current.unwrap_or(0),
+        }
+    }
+
+    /// Memory usage as a percentage of the limit.
+    pub fn memory_percent(&self) -> f64 {
+        if self.memory_limit == 0 {
+            0.0
+        } else {
+            self.memory_usage as f64 / self.memory_limit as f64 * 100.0
+        }
+    }
+}
+
+/// Takes a single stats sample for a running container.
+///
+/// # Arguments
+///
+/// * `container` - The name or ID of the container.
+///
+/// # Returns
+///
+/// * `Result<StatsSample>` - The sample.
+///
+/// # Errors
+///
+/// * `DevrsError::DockerApi` - If the container does not exist or the daemon call fails.
+/// * `DevrsError::DockerOperation` - If the daemon returned no data.
+#[instrument]
+pub async fn sample_once(container: &str) -> Result<StatsSample> {
+    let docker = connect_docker().await?;
+    debug!("Sampling stats for container '{}'", container);
+    // stream=false with one_shot=false: the daemon waits for a second reading so CPU % is meaningful.
+    let options = Some(StatsOptions {
+        stream: false,
+        one_shot: false,
+    });
+    let mut stream = docker.stats(container, options);
+    match stream.next().await {
+        Some(Ok(stats)) => Ok(StatsSample::from_stats(&stats)),
+        Some(Err(e)) => Err(
+            anyhow!(DevrsError::DockerApi { source: e }).context(format!(
+                "Failed to read stats for container '{}'",
+                container
+            )),
+        ),
+        None => Err(anyhow!(DevrsError::DockerOperation(format!(
+            "No stats returned for container '{}'",
+            container
+        )))),
+    }
+}
+
+/// Streams stats samples for a running container, one per daemon collection cycle.
+///
+/// The stream ends when the container stops.
+///
+/// # Arguments
+///
+/// * `container` - The name or ID of the container.
+///
+/// # Returns
+///
+/// * `Result<impl Stream<Item = Result<StatsSample>>>` - The sample stream.
+///
+/// # Errors
+///
+/// Returns an error if connecting to Docker fails. Errors for individual samples are
+/// yielded by the stream.
+pub async fn sample_stream(container: &str) -> Result<impl Stream<Item = Result<StatsSample>>> {
+    let docker = connect_docker().await?;
+    let name = container.to_string();
+    let options = Some(StatsOptions {
+        stream: true,
+        one_shot: false,
+    });
+    Ok(docker.stats(container, options).map(move |item| {
+        item.map(|stats| StatsSample::from_stats(&stats))
+            .map_err(|e| {
+                anyhow!(DevrsError::DockerApi { source: e })
+                    .context(format!("Failed to read stats for container '{}'", name))
+            })
+    }))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A trimmed stats payload as returned by a cgroup v2 daemon.
+    const STATS_JSON: &str = r#"{
+        "read": "2024-03-01T10:00:01Z",
+        "preread": "2024-03-01T10:00:00Z",
+        "num_procs": 0,
+        "pids_stats": { "current": 12 },
+        "networks": {
+            "eth0": { "rx_bytes": 1000, "tx_bytes": 200, "rx_packets": 0, "tx_packets": 0,
+                      "rx_errors": 0, "tx_errors": 0, "rx_dropped": 0, "tx_dropped": 0 },
+            "eth1": { "rx_bytes": 500, "tx_bytes": 100, "rx_packets": 0, "tx_packets": 0,
+                      "rx_errors": 0, "tx_errors": 0, "rx_dropped": 0, "tx_dropped": 0 }
+        },
+        "memory_stats": { "usage": 300000000, "limit": 1000000000,
+                          "stats": { "inactive_file": 100000000, "active_anon": 0, "active_file": 0,
+                                     "anon": 0, "anon_thp": 0, "file": 0, "file_dirty": 0,
+                                     "file_mapped": 0, "file_writeback": 0, "inactive_anon": 0,
+                                     "kernel_stack": 0, "pgactivate": 0, "pgdeactivate": 0,
+                                     "pgfault": 0, "pglazyfree": 0, "pglazyfreed": 0,
+                                     "pgmajfault": 0, "pgrefill": 0, "pgscan": 0, "pgsteal": 0,
+                                     "shmem": 0, "slab": 0, "slab_reclaimable": 0,
+                                     "slab_unreclaimable": 0, "sock": 0, "thp_collapse_alloc": 0,
+                                     "thp_fault_alloc": 0, "unevictable": 0,
+                                     "workingset_activate": 0, "workingset_nodereclaim": 0,
+                                     "workingset_refault": 0 } },
+        "blkio_stats": { "io_service_bytes_recursive": [
+            { "major": 8, "minor": 0, "op": "read", "value": 4096 },
+            { "major": 8, "minor": 0, "op": "write", "value": 8192 }
+        ] },
+        "cpu_stats": { "cpu_usage": { "total_usage": 2000000000, "usage_in_usermode": 0,
+                                      "usage_in_kernelmode": 0 },
+                       "system_cpu_usage": 20000000000, "online_cpus": 4,
+                       "throttling_data": { "periods": 0, "throttled_periods": 0, "throttled_time": 0 } },
+        "precpu_stats": { "cpu_usage": { "total_usage": 1000000000, "usage_in_usermode": 0,
+                                         "usage_in_kernelmode": 0 },
+                          "system_cpu_usage": 10000000000, "online_cpus": 4,
+                          "throttling_data": { "periods": 0, "throttled_periods": 0, "throttled_time": 0 } },
+        "storage_stats": {},
+        "name": "/devrs-core-env-instance",
+        "id": "abc"
+    }"#;
+
+    /// Test the reduction of a raw stats record.
+    #[test]
+    fn test_sample_from_stats() {
+        let stats: Stats = serde_json::from_str(STATS_JSON).expect("fixture should deserialize");
+        let sample = StatsSample::from_stats(&stats);
+        // 1e9 / 1e10 * 4 CPUs * 100 = 40%.
+        assert!((sample.cpu_percent - 40.0).abs() < 1e-9);
+        assert_eq!(sample.memory_usage, 200_000_000);
+        assert_eq!(sample.memory_limit, 1_000_000_000);
+        assert!((sample.memory_percent() - 20.0).abs() < 1e-9);
+        assert_eq!((sample.net_rx, sample.net_tx), (1500, 300));
+        assert_eq!((sample.block_read, sample.block_write), (4096, 8192));
+        assert_eq!(sample.pids, 12);
+    }
+
+    /// Test that a first sample without previous readings reports 0% CPU.
+    #[test]
+    fn test_sample_without_precpu() {
+        let mut stats: Stats = serde_json::from_str(STATS_JSON).unwrap();
+        stats.precpu_stats.system_cpu_usage = None;
+        stats.precpu_stats.cpu_usage.total_usage = stats.cpu_stats.cpu_usage.total_usage;
+        assert_eq!(StatsSample::from_stats(&stats).cpu_percent, 0.0);
+        assert_eq!(StatsSample::default().memory_percent(), 0.0);
+    }
+}
@@ -0,0 +1,58 @@
+//! # DevRS Docker System Queries
+//!
+//! File: cli/src/common/docker/system.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module wraps daemon-wide Docker endpoints that are not specific to a
+//! single image or container. Currently this is the `system df` endpoint, which
+//! reports disk usage for images, containers, volumes and the build cache in a
+//! single call.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::system;
+//! # use crate::core::error::Result;
+//! # async fn example() -> Result<()> {
+//! let usage = system::disk_usage().await?;
+//! println!("Layers: {:?} bytes", usage.layers_size);
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Use standard Result and custom Error
+use anyhow::anyhow; // For creating errors
+use bollard::models::SystemDataUsageResponse; // Response struct for system df
+use tracing::{info, instrument}; // Logging utilities
+
+// Use the connect function from the sibling module.
+use super::connect::connect_docker;
+
+/// Retrieves Docker disk usage (the equivalent of `docker system df -v`).
+///
+/// The daemon computes image shared sizes, container writable-layer sizes and
+/// volume usage for this call, which can take a few seconds on hosts with many
+/// resources.
+///
+/// # Returns
+///
+/// * `Result<SystemDataUsageResponse>` - Images, containers, volumes and build cache records with sizes.
+///
+/// # Errors
+///
+/// * `DevrsError::DockerApi` - For errors during communication with the Docker daemon.
+#[instrument]
+pub async fn disk_usage() -> Result<SystemDataUsageResponse> {
+    // Establish connection to Docker daemon.
+    let docker = connect_docker().await?;
+    info!("Querying Docker disk usage (system df)...");
+    docker.df().await.map_err(|e| {
+        anyhow!(DevrsError::DockerApi { source: e }).context("Failed to query Docker disk usage")
+    })
+}
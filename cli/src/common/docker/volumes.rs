@@ -0,0 +1,324 @@
+//! # DevRS Docker Volume Management
+//!
+//! File: cli/src/common/docker/volumes.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module manages the named volumes behind `[core_env.persist]`. Each persisted
+//! container directory of a core environment profile (identified by
+//! `core_env.image_name`) is backed by its own volume, so shell history, dotfiles and
+//! caches outlive the container across `devrs env rebuild`.
+//!
+//! ## Architecture
+//!
+//! - `persist_volumes` derives the volumes for a configuration. Names are
+//!   deterministic (`devrs-persist-<profile>-<path slug>-<hash>`), so a recreated
+//!   container picks up the same volumes.
+//! - Volumes carry `devrs.persist.profile` and `devrs.persist.path` labels, which
+//!   `list_persist_volumes` uses to find them (including ones for paths no longer
+//!   configured) and `devrs du` uses to count them as DevRS volumes.
+//! - `create_volume`, `remove_volume` wrap the Docker volume API with DevRS errors;
+//!   removing a volume still mounted by a container returns `DevrsError::VolumeInUse`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::docker::volumes;
+//! # use crate::core::{config::Config, error::Result};
+//! # async fn example(cfg: &Config) -> Result<()> {
+//! for volume in volumes::persist_volumes(cfg) {
+//!     volumes::create_volume(&volume).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::{
+    config::Config,              // For the persisted paths and profile name.
+    error::{DevrsError, Result}, // Use standard Result and custom Error.
+};
+use anyhow::anyhow; // For creating errors.
+use bollard::{
+    models::Volume,
+    volume::{CreateVolumeOptions, ListVolumesOptions, RemoveVolumeOptions},
+}; // Volume API types.
+use sha2::{Digest, Sha256}; // Disambiguates volume names.
+use std::collections::HashMap; // For labels and filters.
+use tracing::{error, info, instrument, warn}; // Logging utilities.
+
+// Use the connect function from the sibling module.
+use super::connect::connect_docker;
+
+/// Label recording the core environment profile (`core_env.image_name`) a volume belongs to.
+pub const PROFILE_LABEL: &str = "devrs.persist.profile";
+/// Label recording the container path a volume is mounted at.
+pub const PATH_LABEL: &str = "devrs.persist.path";
+/// Persisted directory holding shell history when `[core_env.persist] history = true`.
+pub const HISTORY_DIR: &str = "/var/lib/devrs/history";
+
+/// # Persisted Volume (`PersistVolume`)
+///
+/// A named volume backing one persisted container directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistVolume {
+    /// The Docker volume name.
+    pub name: String,
+    /// The container directory it is mounted at.
+    pub path: String,
+    /// The core environment profile it belongs to.
+    pub profile: String,
+}
+
+impl PersistVolume {
+    /// Describes the volume for `path` in `profile`.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The core environment profile (`core_env.image_name`).
+    /// * `path` - The absolute container directory.
+    pub fn new(profile: &str, path: &str) -> Self {
+        let path = normalize(path);
+        Self {
+            name: volume_name(profile, &path),
+            path,
+            profile: profile.to_string(),
+        }
+    }
+
+    /// The labels stamped onto the volume.
+    fn labels(&self) -> HashMap<String, String> {
+        HashMap::from([
+            (PROFILE_LABEL.to_string(), self.profile.clone()),
+            (PATH_LABEL.to_string(), self.path.clone()),
+        ])
+    }
+}
+
+/// The volumes for the persisted directories of the configured core environment,
+/// including the history directory if `history` is enabled.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration.
+///
+/// # Returns
+///
+/// * `Vec<PersistVolume>` - One volume per distinct directory, in configuration order.
+pub fn persist_volumes(cfg: &Config) -> Vec<PersistVolume> {
+    let persist = &cfg.core_env.persist;
+    let history = persist.history.unwrap_or(false).then_some(HISTORY_DIR);
+    let mut volumes: Vec<PersistVolume> = Vec::new();
+    for path in persist.paths.iter().map(String::as_str).chain(history) {
+        let volume = PersistVolume::new(&cfg.core_env.image_name, path);
+        if !volumes.iter().any(|v| v.path == volume.path) {
+            volumes.push(volume);
+        }
+    }
+    volumes
+}
+
+/// The environment variables persistence adds to the core container: `HISTFILE`
+/// pointing into `HISTORY_DIR` when history is persisted and not set explicitly.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration.
+///
+/// # Returns
+///
+/// * `HashMap<String, String>` - The added variables (none without persisted history).
+pub fn persist_env(cfg: &Config) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    if cfg.core_env.persist.history.unwrap_or(false)
+        && !cfg.core_env.env_vars.contains_key("HISTFILE")
+    {
+        env.insert(
+            "HISTFILE".to_string(),
+            format!("{}/.bash_history", HISTORY_DIR),
+        );
+    }
+    env
+}
+
+/// Strips trailing slashes from a container path (keeping `/` itself).
+fn normalize(path: &str) -> String {
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Builds a readable, collision-free volume name for a profile and path.
+/// Docker allows `[a-zA-Z0-9][a-zA-Z0-9_.-]*`; the hash keeps e.g. `/a/b` and `/a-b` apart.
+fn volume_name(profile: &str, path: &str) -> String {
+    let slug = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>()
+            .trim_matches('-')
+            .to_string()
+    };
+    let digest = Sha256::digest(format!("{}\0{}", profile, path).as_bytes());
+    let hash: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("devrs-persist-{}-{}-{}", slug(profile), slug(path), hash)
+}
+
+/// Creates the volume (with its labels) if it does not exist yet.
+///
+/// # Arguments
+///
+/// * `volume` - The volume to create.
+///
+/// # Returns
+///
+/// * `Result<()>` - `Ok(())` once the volume exists.
+///
+/// # Errors
+///
+/// * `DevrsError::DockerApi` - For errors during communication with the Docker daemon.
+#[instrument(skip(volume), fields(volume = %volume.name))]
+pub async fn create_volume(volume: &PersistVolume) -> Result<()> {
+    let docker = connect_docker().await?;
+    info!("Ensuring volume '{}' for '{}'...", volume.name, volume.path);
+    // Creating an existing local volume returns it unchanged.
+    docker
+        .create_volume(CreateVolumeOptions {
+            name: volume.name.clone(),
+            labels: volume.labels(),
+            ..Default::default()
+        })
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            anyhow!(DevrsError::DockerApi { source: e })
+                .context(format!("Failed to create volume '{}'", volume.name))
+        })
+}
+
+/// Lists the persisted-directory volumes, optionally only those of one profile.
+///
+/// # Arguments
+///
+/// * `profile` - Only return volumes of this profile (`None` for all profiles).
+///
+/// # Returns
+///
+/// * `Result<Vec<Volume>>` - The matching volumes.
+///
+/// # Errors
+///
+/// * `DevrsError::DockerApi` - For errors during communication with the Docker daemon.
+#[instrument]
+pub async fn list_persist_volumes(profile: Option<&str>) -> Result<Vec<Volume>> {
+    let docker = connect_docker().await?;
+    let label = match profile {
+        Some(profile) => format!("{}={}", PROFILE_LABEL, profile),
+        None => PROFILE_LABEL.to_string(),
+    };
+    let options = ListVolumesOptions {
+        filters: HashMap::from([("label".to_string(), vec![label])]),
+    };
+    let response = docker.list_volumes(Some(options)).await.map_err(|e| {
+        anyhow!(DevrsError::DockerApi { source: e }).context("Failed to list volumes")
+    })?;
+    Ok(response.volumes.unwrap_or_default())
+}
+
+/// Removes a volume and its data.
+///
+/// # Arguments
+///
+/// * `name` - The volume name.
+///
+/// # Returns
+///
+/// * `Result<()>` - `Ok(())` once the volume is gone (including if it did not exist).
+///
+/// # Errors
+///
+/// * `DevrsError::VolumeInUse` - If a container (running or stopped) still mounts it (Docker 409).
+/// * `DevrsError::DockerApi` - For other errors during communication with the Docker daemon.
+#[instrument]
+pub async fn remove_volume(name: &str) -> Result<()> {
+    let docker = connect_docker().await?;
+    info!("Removing volume '{}'...", name);
+    match docker
+        .remove_volume(name, Some(RemoveVolumeOptions { force: false }))
+        .await
+    {
+        Ok(()) => Ok(()),
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => {
+            warn!("Volume '{}' not found, nothing to remove.", name);
+            Ok(())
+        }
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 409,
+            message,
+        }) => {
+            error!("Conflict removing volume '{}': {}", name, message);
+            Err(anyhow!(DevrsError::VolumeInUse {
+                name: name.to_string()
+            }))
+        }
+        Err(e) => Err(anyhow!(DevrsError::DockerApi { source: e })
+            .context(format!("Failed to remove volume '{}'", name))),
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test volume naming and the volumes derived from the configuration.
+    #[test]
+    fn test_persist_volumes() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [core_env]
+            image_name = "devrs-core-env"
+            env_vars = { RUST_LOG = "info" }
+            [core_env.persist]
+            paths = ["/home/me/.cargo/registry/", "/home/me/.cargo/registry", "/a-b", "/a/b"]
+            history = true
+            "#,
+        )
+        .unwrap();
+
+        let volumes = persist_volumes(&cfg);
+        let paths: Vec<&str> = volumes.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["/home/me/.cargo/registry", "/a-b", "/a/b", HISTORY_DIR]
+        );
+        assert!(volumes[0]
+            .name
+            .starts_with("devrs-persist-devrs-core-env-home-me-.cargo-registry-"));
+        assert_ne!(volumes[1].name, volumes[2].name);
+        // Names are stable and differ per profile.
+        assert_eq!(volumes[1], PersistVolume::new("devrs-core-env", "/a-b"));
+        assert_ne!(
+            volumes[1].name,
+            PersistVolume::new("rust-profile", "/a-b").name
+        );
+        assert_eq!(volumes[0].labels()[PATH_LABEL], "/home/me/.cargo/registry");
+
+        let env = persist_env(&cfg);
+        assert_eq!(env["HISTFILE"], "/var/lib/devrs/history/.bash_history");
+        // Only persistence adds variables; `env_vars` are set by the container creation.
+        assert_eq!(env.len(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+//! # DevRS Persistent Log Store
+//!
+//! File: cli/src/common/logstore/mod.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Docker only keeps a container's logs as long as the container exists, so
+//! `devrs env rebuild` (or any `rm`) throws the history away. The log store keeps a
+//! copy on the host: the background collector (`devrs collector`) appends every
+//! line it reads to a per-container directory, and `devrs env logs --archived` /
+//! `devrs logs --archived` read it back.
+//!
+//! ## Architecture
+//!
+//! Layout under the store root (default `~/.local/state/devrs/logs`, or `logs.directory`):
+//!
+//! ```text
+//! <root>/collector.pid                  PID of the running collector
+//! <root>/<container>/container.json     image and labels, for selector matching
+//! <root>/<container>/current.log        active segment (plain text)
+//! <root>/<container>/segment-<start>.log.gz   rotated segments (gzip)
+//! <root>/<container>/.cursor            timestamp of the last stored line
+//! ```
+//!
+//! Each record is one line: `<RFC 3339 timestamp> <stdout|stderr> <message>`.
+//!
+//! - **`writer`**: `RotatingLog` appends records and rotates by size and age,
+//!   compressing rotated segments with `common::archive::compression` and pruning
+//!   them by age and count (`RotationPolicy`).
+//! - **`reader`**: lists stored containers and reads segments back as `LogLine`s.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::logstore::{self, reader};
+//! # use crate::core::config::Config;
+//! # fn example(cfg: &Config) -> crate::core::error::Result<()> {
+//! let root = logstore::root_dir(cfg);
+//! for line in reader::read_history(&root, "devrs-core-env-instance")? {
+//!     println!("{}", line.message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::{
+    common::docker::logs::{LogLine, LogStreamKind}, // Structured log lines.
+    core::config::Config,                           // For the configured store directory.
+};
+use chrono::{DateTime, SecondsFormat, Utc}; // Record timestamps.
+use serde::{Deserialize, Serialize}; // For container metadata files.
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+}; // Labels and store paths.
+
+pub mod reader;
+pub mod writer;
+
+/// File name of the active (uncompressed) segment.
+pub const ACTIVE_FILE: &str = "current.log";
+/// File name holding the timestamp of the last stored line.
+pub const CURSOR_FILE: &str = ".cursor";
+/// File name of the per-container metadata.
+pub const META_FILE: &str = "container.json";
+/// File name of the collector PID file (in the store root).
+pub const PID_FILE: &str = "collector.pid";
+/// Prefix of rotated segment files.
+pub const SEGMENT_PREFIX: &str = "segment-";
+
+/// # Stored Container (`ContainerMeta`)
+///
+/// What the store knows about a container, kept next to its logs so that archived
+/// history can still be selected by role or project after the container is gone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerMeta {
+    /// The container name.
+    pub name: String,
+    /// The image reference the container ran.
+    pub image: String,
+    /// The container's labels.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+/// Resolves the root directory of the log store.
+///
+/// # Arguments
+///
+/// * `cfg` - The loaded configuration (`logs.directory`, already tilde-expanded).
+///
+/// # Returns
+///
+/// * `PathBuf` - `logs.directory` if set, otherwise `<state dir>/devrs/logs`
+///   (`~/.local/state/devrs/logs` on Linux).
+pub fn root_dir(cfg: &Config) -> PathBuf {
+    if let Some(dir) = cfg.logs.directory.as_deref().filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    dirs::state_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("devrs")
+        .join("logs")
+}
+
+/// The directory holding one container's logs.
+pub fn container_dir(root: &Path, container: &str) -> PathBuf {
+    root.join(container)
+}
+
+/// Formats a line as a store record (without trailing newline).
+pub fn format_record(line: &LogLine) -> String {
+    let timestamp = line.timestamp.map_or("-".to_string(), |ts| {
+        ts.to_rfc3339_opts(SecondsFormat::Nanos, true)
+    });
+    let stream = match line.stream {
+        LogStreamKind::Stdout => "stdout",
+        LogStreamKind::Stderr => "stderr",
+    };
+    format!("{} {} {}", timestamp, stream, line.message)
+}
+
+/// Parses a store record back into a line.
+///
+/// # Arguments
+///
+/// * `container` - The container the record belongs to.
+/// * `record` - One line of a segment file.
+///
+/// # Returns
+///
+/// * `Option<LogLine>` - The line, or `None` if the record is malformed.
+pub fn parse_record(container: &str, record: &str) -> Option<LogLine> {
+    let mut parts = record.splitn(3, ' ');
+    let timestamp = match parts.next()? {
+        "-" => None,
+        ts => Some(DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&Utc)),
+    };
+    let stream = match parts.next()? {
+        "stdout" => LogStreamKind::Stdout,
+        "stderr" => LogStreamKind::Stderr,
+        _ => return None,
+    };
+    Some(LogLine {
+        container: container.to_string(),
+        stream,
+        timestamp,
+        message: parts.next().unwrap_or_default().to_string(),
+    })
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that records survive a format/parse round trip.
+    #[test]
+    fn test_record_round_trip() {
+        let line = LogLine {
+            container: "app".to_string(),
+            stream: LogStreamKind::Stderr,
+            timestamp: DateTime::from_timestamp(1_709_287_200, 123_456_789),
+            message: "failed: a b  c".to_string(),
+        };
+        let record = format_record(&line);
+        assert_eq!(
+            record,
+            "2024-03-01T10:00:00.123456789Z stderr failed: a b  c"
+        );
+        assert_eq!(parse_record("app", &record), Some(line));
+        assert_eq!(
+            parse_record("app", "- stdout ").map(|l| l.timestamp),
+            Some(None)
+        );
+        assert!(parse_record("app", "garbage").is_none());
+    }
+
+    /// Test the configured store directory override.
+    #[test]
+    fn test_root_dir_override() {
+        let mut cfg = Config::default();
+        cfg.logs.directory = Some("/var/tmp/devrs-logs".to_string());
+        assert_eq!(root_dir(&cfg), PathBuf::from("/var/tmp/devrs-logs"));
+        cfg.logs.directory = None;
+        assert!(root_dir(&cfg).ends_with("devrs/logs"));
+    }
+}
@@ -0,0 +1,192 @@
+//! # DevRS Log Store Reader
+//!
+//! File: cli/src/common/logstore/reader.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Reads archived container logs back from the log store for
+//! `devrs env logs --archived` and `devrs logs --archived`. Compressed and plain
+//! segments are both handled, since decompression is detected from file content
+//! (`common::archive::compression::open_decompressed`).
+//!
+//! ## Architecture
+//!
+//! - `stored_containers` lists the container directories with their metadata.
+//! - `read_history` reads the rotated segments (oldest first) followed by the
+//!   active segment and parses every record into a `LogLine`.
+//! - `write_meta` stores a container's metadata; it is used by the collector.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::logstore::reader;
+//! # fn example(root: &std::path::Path) -> crate::core::error::Result<()> {
+//! for meta in reader::stored_containers(root)? {
+//!     let lines = reader::read_history(root, &meta.name)?;
+//!     println!("{}: {} lines", meta.name, lines.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::{
+    common::{archive::compression, docker::logs::LogLine}, // Segment decoding and line type.
+    core::error::{DevrsError, Result}, // Standard Result type and custom errors.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::Path,
+}; // Reading segments.
+use tracing::{debug, warn}; // Logging utilities.
+
+use super::{container_dir, parse_record, writer, ContainerMeta, ACTIVE_FILE, META_FILE};
+
+/// Lists the containers with stored logs, sorted by name.
+///
+/// Directories without a readable `container.json` are still listed, with only
+/// their name filled in.
+///
+/// # Arguments
+///
+/// * `root` - The store root.
+///
+/// # Returns
+///
+/// * `Result<Vec<ContainerMeta>>` - The stored containers (empty if the store does not exist).
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the store root exists but cannot be read.
+pub fn stored_containers(root: &Path) -> Result<Vec<ContainerMeta>> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(root).map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to read log store '{}': {}",
+            root.display(),
+            e
+        )))
+    })?;
+    let mut containers: Vec<ContainerMeta> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            fs::read_to_string(entry.path().join(META_FILE))
+                .ok()
+                .and_then(|json| serde_json::from_str::<ContainerMeta>(&json).ok())
+                .unwrap_or(ContainerMeta {
+                    name,
+                    ..Default::default()
+                })
+        })
+        .collect();
+    containers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(containers)
+}
+
+/// Writes a container's metadata file, creating its directory if needed.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if the directory or file cannot be written.
+pub fn write_meta(root: &Path, meta: &ContainerMeta) -> Result<()> {
+    let dir = container_dir(root, &meta.name);
+    let path = dir.join(META_FILE);
+    let json = serde_json::to_string_pretty(meta).context("Failed to serialize metadata")?;
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, json))
+        .map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to write '{}': {}",
+                path.display(),
+                e
+            )))
+        })
+}
+
+/// Reads all stored lines of a container, oldest first.
+///
+/// # Arguments
+///
+/// * `root` - The store root.
+/// * `container` - The container name.
+///
+/// # Returns
+///
+/// * `Result<Vec<LogLine>>` - The stored lines (empty if nothing was stored).
+///
+/// # Errors
+///
+/// Returns an error if the container directory cannot be listed. Unreadable
+/// segments are skipped with a warning.
+pub fn read_history(root: &Path, container: &str) -> Result<Vec<LogLine>> {
+    let dir = container_dir(root, container);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = writer::segment_files(&dir)?;
+    files.push(dir.join(ACTIVE_FILE));
+
+    let mut lines = Vec::new();
+    for path in files.iter().filter(|p| p.is_file()) {
+        debug!("Reading log segment '{}'", path.display());
+        let reader = match compression::open_decompressed(path) {
+            Ok((_, reader)) => reader,
+            Err(e) => {
+                warn!("Skipping unreadable segment '{}': {:#}", path.display(), e);
+                continue;
+            }
+        };
+        for record in BufReader::new(reader).lines() {
+            match record {
+                Ok(record) => lines.extend(parse_record(container, &record)),
+                Err(e) => {
+                    warn!("Stopped reading '{}': {}", path.display(), e);
+                    break;
+                }
+            }
+        }
+    }
+    Ok(lines)
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    /// Test listing containers with and without metadata.
+    #[test]
+    fn test_stored_containers() {
+        let root = tempdir().unwrap();
+        assert!(stored_containers(&root.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        let meta = ContainerMeta {
+            name: "web-1".to_string(),
+            image: "webapp:latest".to_string(),
+            labels: HashMap::from([("devrs.project".to_string(), "webapp".to_string())]),
+        };
+        write_meta(root.path(), &meta).unwrap();
+        fs::create_dir(root.path().join("bare")).unwrap();
+
+        let stored = stored_containers(root.path()).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].name, "bare");
+        assert!(stored[0].image.is_empty());
+        assert_eq!(stored[1], meta);
+        assert!(read_history(root.path(), "bare").unwrap().is_empty());
+    }
+}
@@ -28,10 +28,11 @@
 //! - **`archive`**: Utilities for creating and potentially extracting archive files (e.g., `.tar.gz`). Includes the `tar` submodule.
 //! - **`docker`**: The main interface for interacting with the Docker daemon via the `bollard` crate. Handles images, containers, lifecycle, state, interaction, etc.
 //! - **`fs`**: Foundational filesystem operations like reading/writing files, copying directories, ensuring directory existence, and managing symbolic links. Includes `io`, `copy`, `links`.
+//! - **`logstore`**: Persistent, rotating on-disk copies of container logs (written by `devrs collector`, read by `--archived`).
 //! - **`network`**: *(Placeholder)* Intended for network-related utilities like IP detection and port checking.
 //! - **`process`**: *(Placeholder)* Intended for executing external commands/processes and managing their output.
 //! - **`system`**: *(Placeholder)* Intended for system-level inspection like shell detection or checking for required tools.
-//! - **`ui`**: *(Placeholder)* Intended for terminal UI enhancements like progress bars, tables, and prompts.
+//! - **`ui`**: Terminal UI helpers: human-readable formatting and aligned tables (progress bars and prompts are planned).
 //!
 //! ## Usage
 //!
@@ -70,11 +71,13 @@ pub mod archive;
 pub mod docker;
 /// Utilities for filesystem operations (copying, I/O, links).
 pub mod fs;
+/// Persistent container log storage with rotation and compression.
+pub mod logstore;
 /// (Placeholder) Utilities related to network operations (IP detection, port scanning).
 pub mod network;
 /// (Placeholder) Utilities for executing and managing external processes.
 pub mod process;
 /// (Placeholder) Utilities for system-level information and checks (shell, tool detection).
 pub mod system;
-/// (Placeholder) Utilities for terminal user interface elements (progress, tables, prompts).
+/// Utilities for terminal user interface elements (formatting, tables; progress and prompts planned).
 pub mod ui;
@@ -0,0 +1,196 @@
+//! # DevRS Display Formatting (`common::ui::format`)
+//!
+//! File: cli/src/common/ui/format.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Small helpers that turn raw numbers from the Docker API (byte counts, Unix
+//! timestamps) into the short human-readable strings shown in DevRS tables and
+//! summaries, e.g. `1.2 GB` or `3 days ago`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::ui::format;
+//! use std::time::Duration;
+//!
+//! assert_eq!(format::format_bytes(1_500_000), "1.5 MB");
+//! assert_eq!(format::format_duration(Duration::from_secs(7200)), "2 hours");
+//! assert_eq!(format::format_percent(12.345), "12.35%");
+//! ```
+//!
+use chrono::{DateTime, Local}; // For parsing Docker RFC 3339 timestamps and local display.
+use std::time::{Duration, SystemTime, UNIX_EPOCH}; // For age calculations.
+
+/// Formats a byte count using decimal units (B, kB, MB, GB, TB), like the Docker CLI.
+///
+/// # Arguments
+///
+/// * `bytes` - The number of bytes.
+///
+/// # Returns
+///
+/// * `String` - e.g., `"512 B"`, `"1.5 MB"`, `"12.3 GB"`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Formats a duration as a single coarse unit (e.g., `"45 seconds"`, `"3 days"`).
+///
+/// # Arguments
+///
+/// * `duration` - The duration to describe.
+///
+/// # Returns
+///
+/// * `String` - The largest whole unit that fits, pluralized.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (value, unit) = match secs {
+        0..=59 => (secs, "second"),
+        60..=3_599 => (secs / 60, "minute"),
+        3_600..=86_399 => (secs / 3_600, "hour"),
+        86_400..=604_799 => (secs / 86_400, "day"),
+        604_800..=2_629_799 => (secs / 604_800, "week"),
+        2_629_800..=31_557_599 => (secs / 2_629_800, "month"),
+        _ => (secs / 31_557_600, "year"),
+    };
+    format!("{} {}{}", value, unit, if value == 1 { "" } else { "s" })
+}
+
+/// Returns how long ago a Unix timestamp was, relative to now.
+///
+/// Timestamps in the future (clock skew) are treated as "now".
+///
+/// # Arguments
+///
+/// * `unix_secs` - Seconds since the Unix epoch (as reported by the Docker API).
+///
+/// # Returns
+///
+/// * `Duration` - The elapsed time.
+pub fn age_since(unix_secs: i64) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    Duration::from_secs(now.saturating_sub(unix_secs).max(0) as u64)
+}
+
+/// Formats a Unix timestamp relative to now, e.g., `"3 days ago"`.
+///
+/// # Arguments
+///
+/// * `unix_secs` - Seconds since the Unix epoch.
+///
+/// # Returns
+///
+/// * `String` - The relative description.
+pub fn format_ago(unix_secs: i64) -> String {
+    format!("{} ago", format_duration(age_since(unix_secs)))
+}
+
+/// Formats a Unix timestamp as a local date and time plus its age,
+/// e.g. `"2024-03-01 09:15:00 +01:00 (3 days ago)"`.
+///
+/// # Arguments
+///
+/// * `unix_secs` - Seconds since the Unix epoch.
+///
+/// # Returns
+///
+/// * `String` - The formatted timestamp, or the raw number if it is out of range.
+pub fn format_timestamp(unix_secs: i64) -> String {
+    match DateTime::from_timestamp(unix_secs, 0) {
+        Some(dt) => format!(
+            "{} ({})",
+            dt.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z"),
+            format_ago(unix_secs)
+        ),
+        None => unix_secs.to_string(),
+    }
+}
+
+/// Parses an RFC 3339 timestamp from the Docker API (e.g. `State.StartedAt`) into Unix seconds.
+///
+/// Docker reports the zero time (`0001-01-01T00:00:00Z`) for events that have not
+/// happened yet (e.g. `FinishedAt` of a running container); those yield `None`.
+///
+/// # Arguments
+///
+/// * `value` - The timestamp string.
+///
+/// # Returns
+///
+/// * `Option<i64>` - Seconds since the Unix epoch, or `None` if unset or unparsable.
+pub fn parse_docker_time(value: &str) -> Option<i64> {
+    if value.starts_with("0001-01-01") {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+/// Formats a percentage with two decimals, like `docker stats` (e.g. `"3.27%"`).
+pub fn format_percent(value: f64) -> String {
+    format!("{:.2}%", value)
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test byte formatting across units.
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1_500), "1.5 kB");
+        assert_eq!(format_bytes(2_300_000_000), "2.3 GB");
+    }
+
+    /// Test duration formatting and pluralization.
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1 minute");
+        assert_eq!(format_duration(Duration::from_secs(3 * 86_400)), "3 days");
+        assert_eq!(format_duration(Duration::from_secs(14 * 86_400)), "2 weeks");
+    }
+
+    /// Test parsing of Docker timestamps, including the zero value.
+    #[test]
+    fn test_parse_docker_time() {
+        assert_eq!(
+            parse_docker_time("2023-10-27T10:00:00.123456789Z"),
+            Some(1_698_400_800)
+        );
+        assert_eq!(parse_docker_time("0001-01-01T00:00:00Z"), None);
+        assert_eq!(parse_docker_time("garbage"), None);
+        assert!(format_timestamp(1_698_400_800).ends_with("ago)"));
+    }
+
+    /// Test that future timestamps do not underflow.
+    #[test]
+    fn test_age_since_future_is_zero() {
+        assert_eq!(age_since(i64::MAX), Duration::ZERO);
+    }
+}
@@ -10,23 +10,23 @@
 
 //! ## Overview
 //!
-//! This module serves as a **placeholder** for future user interface (UI) utilities
-//! designed to enhance the command-line experience of DevRS. The intent is to
-//! centralize components for displaying information clearly and potentially interacting
-//! with the user in a more sophisticated way than simple `println!`.
+//! This module centralizes user interface (UI) utilities designed to enhance the
+//! command-line experience of DevRS: components for displaying information clearly
+//! and potentially interacting with the user in a more sophisticated way than simple `println!`.
 //!
-//! **Note:** Currently, the submodules and functionality described below are **not implemented**.
+//! **Note:** `format` and `tables` are implemented; `progress` and `prompts` are still planned.
 //!
-//! ## Planned Architecture
+//! ## Architecture
 //!
-//! The module is intended to be organized into submodules based on UI element type:
+//! The module is organized into submodules based on UI element type:
 //!
+//! - **`format`**: (Implemented) Human-readable byte sizes and relative ages
+//!   (e.g., `1.2 GB`, `3 days ago`) for values reported by the Docker API.
 //! - **`progress`**: Would contain utilities for displaying progress bars or spinners
 //!   during long-running operations (like Docker builds or large file copies). Might use
 //!   crates like `indicatif`.
-//! - **`tables`**: Would provide functions to format and display data in neat, aligned
-//!   tabular layouts in the terminal. Could use crates like `comfy-table` or `cli-table`.
-//!   Useful for commands like `devrs container status` or `devrs blueprint list`.
+//! - **`tables`**: (Implemented) Renders neat, aligned tabular layouts in the terminal,
+//!   in the style of `docker images`. Used by commands like `devrs images ls`.
 //! - **`prompts`**: Would include tools for interactive user input beyond simple line reading,
 //!   such as confirmation prompts (`[y/N]`), multiple-choice selections, or password input.
 //!   Could leverage crates like `dialoguer`.
@@ -73,6 +73,7 @@
 //!
 //! Currently, this module only defines the basic structure.
 
+pub mod format;
 // pub mod progress; // Future submodule placeholder
-// pub mod tables;   // Future submodule placeholder
+pub mod tables;
 // pub mod prompts;  // Future submodule placeholder
@@ -0,0 +1,108 @@
+//! # DevRS Table Rendering (`common::ui::tables`)
+//!
+//! File: cli/src/common/ui/tables.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Renders simple left-aligned, space-separated tables in the style of the Docker
+//! CLI (`docker images`, `docker ps`). Column widths are computed from the widest
+//! cell, so output lines up for any content. No external crate is needed.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::common::ui::tables;
+//!
+//! let headers = ["NAME", "STATUS"];
+//! let rows = vec![vec!["app1".to_string(), "running".to_string()]];
+//! tables::print_table(&headers, &rows);
+//! // NAME   STATUS
+//! // app1   running
+//! ```
+//!
+
+/// Number of spaces between columns.
+const COLUMN_GAP: usize = 3;
+
+/// Renders a table to a string (one line per row, trailing newline included).
+///
+/// Rows shorter than the header are padded with empty cells.
+///
+/// # Arguments
+///
+/// * `headers` - Column titles.
+/// * `rows` - Table cells, one `Vec` per row.
+///
+/// # Returns
+///
+/// * `String` - The rendered table.
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    // Compute each column's width from the header and all cells (by character count).
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate().take(widths.len()) {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let render_line = |cells: Vec<&str>| -> String {
+        let mut line = String::new();
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).copied().unwrap_or("");
+            if i + 1 == widths.len() {
+                line.push_str(cell); // No padding after the last column.
+            } else {
+                line.push_str(cell);
+                let padding = width - cell.chars().count() + COLUMN_GAP;
+                line.push_str(&" ".repeat(padding));
+            }
+        }
+        line.trim_end().to_string()
+    };
+
+    let mut out = render_line(headers.to_vec());
+    out.push('\n');
+    for row in rows {
+        out.push_str(&render_line(row.iter().map(String::as_str).collect()));
+        out.push('\n');
+    }
+    out
+}
+
+/// Prints a table to stdout. See `render_table`.
+pub fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    print!("{}", render_table(headers, rows));
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that columns align to the widest cell.
+    #[test]
+    fn test_render_table_alignment() {
+        let rows = vec![
+            vec!["a".to_string(), "x".to_string()],
+            vec!["longer".to_string(), "y".to_string()],
+        ];
+        let out = render_table(&["NAME", "V"], &rows);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "NAME     V");
+        assert_eq!(lines[1], "a        x");
+        assert_eq!(lines[2], "longer   y");
+    }
+
+    /// Test that short rows are padded rather than panicking.
+    #[test]
+    fn test_render_table_short_row() {
+        let rows = vec![vec!["only".to_string()]];
+        let out = render_table(&["A", "B"], &rows);
+        assert_eq!(out.lines().nth(1), Some("only"));
+    }
+}
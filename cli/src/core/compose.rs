@@ -0,0 +1,961 @@
+//! # DevRS docker-compose Compatibility
+//!
+//! File: cli/src/core/compose.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Colleagues who do not use DevRS can still run the same setup with Docker Compose.
+//! This module translates between the DevRS configuration and a Compose v3 file:
+//!
+//! - **Export** (`export`): used by `devrs config export --compose`. The core
+//!   environment becomes the `core` service and every `[apps.<name>]` entry becomes a
+//!   service of the same name, all attached to one shared `devrs` network.
+//! - **Import** (`load`): used by `devrs config import --compose` to seed a
+//!   `.devrs.toml`. One service (default `core`) becomes `[core_env]`, the others
+//!   become `[apps.<name>]`.
+//!
+//! | Compose service key            | `[core_env]`                  | `[apps.<name>]`        |
+//! |--------------------------------|-------------------------------|------------------------|
+//! | `image`                        | `base_image`                  | `image`                |
+//! | `build` (`context`, `dockerfile`, `args`) | `build`            | `build`                |
+//! | `command`                      | -                             | `command`              |
+//! | `ports`                        | `ports`                       | `ports`                |
+//! | `environment`                  | `env_vars`                    | `env_vars`             |
+//! | `env_file`                     | `env_files`                   | `env_files`            |
+//! | `volumes` (bind mounts)        | `mounts`                      | `mounts`               |
+//! | `working_dir`                  | `default_workdir`             | `workdir`              |
+//! | `user`                         | `user`                        | -                      |
+//! | `depends_on`                   | -                             | `depends_on`           |
+//!
+//! ## Architecture
+//!
+//! - Documents are handled as `serde_yaml::Value` so key order is preserved on export
+//!   and unknown keys can be reported on import.
+//! - Host paths below the directory the file is written to are exported relative to
+//!   it, and relative paths are made absolute again on import, so the file can be
+//!   shared with the project.
+//! - Compose interpolates `$VAR` in values, so `$` in exported environment values is
+//!   written as `$$` (and unescaped again on import). A `{ secret = "..." }` value is
+//!   exported as `${NAME}`, so `docker compose` takes it from the host environment.
+//! - Anything that cannot be represented (named volumes, `restart`,
+//!   healthchecks, lifecycle hooks, secrets, ...) is collected in `unsupported` rather
+//!   than silently dropped. Secrets are listed by reference; their values are never
+//!   resolved for an export.
+//! - Import produces a TOML table rather than a `Config`, so the written `.devrs.toml`
+//!   only contains the settings that came from the Compose file.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! # fn example(cfg: &crate::core::config::Config) {
+//! let (document, unsupported) = compose::export(cfg, std::path::Path::new("/src/app"));
+//! println!("{}", serde_yaml::to_string(&document).unwrap());
+//! for note in &unsupported {
+//!     eprintln!("not exported: {}", note);
+//! }
+//! # }
+//! ```
+//!
+use crate::core::{
+    config::{default_core_image, Config, CoreEnvBuildConfig, EnvValue, MountConfig}, // The configuration model.
+    devcontainer::{docker_name, shell_quote}, // Shared image/command helpers.
+    error::{DevrsError, Result},              // Standard Result type and custom errors.
+    ports::PortMapping,                       // Typed port mappings.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use serde_yaml::{Mapping, Value}; // Compose documents.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+}; // File access and stable key order.
+use tracing::debug; // Logging framework utilities.
+
+/// Name of the Compose service holding the core development environment.
+pub const CORE_SERVICE: &str = "core";
+
+/// Name of the network shared by all exported services.
+const NETWORK: &str = "devrs";
+
+/// Compose file names looked up (in this order) when no file is given.
+pub const DEFAULT_FILES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+];
+
+/// # Compose Import (`ComposeImport`)
+///
+/// The result of `load`: the translated settings and what could not be translated.
+#[derive(Debug, Clone)]
+pub struct ComposeImport {
+    /// The `.devrs.toml` content (only the settings found in the Compose file).
+    pub config: toml::Table,
+    /// Human-readable notes on keys and values that were not imported.
+    pub unsupported: Vec<String>,
+}
+
+/// Renders the effective configuration as a Compose v3 document.
+///
+/// # Arguments
+///
+/// * `cfg` - The effective configuration.
+/// * `base_dir` - Absolute directory the file will be written to. Host paths below it
+///   are written relative to it (`./...`), as Compose resolves them against the file.
+///
+/// # Returns
+///
+/// * `(Value, Vec<String>)` - The document and notes on settings that have no
+///   Compose equivalent (and were therefore left out).
+pub fn export(cfg: &Config, base_dir: &Path) -> (Value, Vec<String>) {
+    let env = &cfg.core_env;
+    let mut unsupported = Vec::new();
+    let mut services = Mapping::new();
+
+    let builds_core = env.build.dockerfile.is_some() || env.build.context.is_some();
+    // Without a Dockerfile, an upstream base image runs as it is published.
+    let core_image = match (&env.base_image, builds_core) {
+        (Some(base_image), false) => base_image.clone(),
+        _ => format!("{}:{}", env.image_name, env.image_tag),
+    };
+    if !builds_core && env.base_image.is_none() && env.image_name == default_core_image() {
+        // The default core image only exists where `devrs env build` has been run.
+        unsupported.push(format!(
+            "core_env image '{}' is built by `devrs env build` (push it to a registry or set [core_env.build])",
+            core_image
+        ));
+    }
+    let mut core = service(Some(&core_image), &env.build, base_dir);
+    add_runtime(
+        &mut core,
+        base_dir,
+        &env.ports,
+        &env.env_files,
+        &env.env_vars,
+        &env.mounts,
+        Some(&env.default_workdir),
+    );
+    if let Some(user) = &env.user {
+        core.insert("user".into(), user.as_str().into());
+    }
+    core.insert("networks".into(), vec![Value::from(NETWORK)].into());
+    services.insert(CORE_SERVICE.into(), core.into());
+    if env.forward_ssh_agent.unwrap_or(false) {
+        unsupported.push("core_env.forward_ssh_agent (mount the agent socket instead)".into());
+    }
+    if env.forward_gpg_agent.unwrap_or(false) {
+        unsupported.push("core_env.forward_gpg_agent".into());
+    }
+    for (stage, hooks) in [
+        ("on_create", &env.hooks.on_create),
+        ("on_start", &env.hooks.on_start),
+        ("on_shell", &env.hooks.on_shell),
+    ] {
+        for hook in hooks {
+            unsupported.push(format!("core_env.hooks.{} '{}'", stage, hook.command));
+        }
+    }
+    // Exporting a secret would mean writing its value; only DevRS resolves the references.
+    let secrets: BTreeMap<_, _> = env.secrets.iter().collect();
+    for (name, secret) in secrets {
+        unsupported.push(format!("core_env.secrets.{} ({})", name, secret.secret));
+    }
+
+    secret_vars(&mut unsupported, "core_env", &env.env_vars);
+
+    let apps: BTreeMap<_, _> = cfg.apps.iter().collect();
+    for (name, app) in apps {
+        if name == CORE_SERVICE {
+            unsupported.push(format!(
+                "apps.{} (the name is used by the core environment)",
+                name
+            ));
+            continue;
+        }
+        let mut svc = service(app.image.as_deref(), &app.build, base_dir);
+        if let Some(command) = &app.command {
+            svc.insert("command".into(), command.as_str().into());
+        }
+        add_runtime(
+            &mut svc,
+            base_dir,
+            &app.ports,
+            &app.env_files,
+            &app.env_vars,
+            &app.mounts,
+            app.workdir.as_deref(),
+        );
+        secret_vars(&mut unsupported, &format!("apps.{}", name), &app.env_vars);
+        if !app.depends_on.is_empty() {
+            let deps: Vec<Value> = app.depends_on.iter().map(|d| d.as_str().into()).collect();
+            svc.insert("depends_on".into(), deps.into());
+        }
+        svc.insert("networks".into(), vec![Value::from(NETWORK)].into());
+        services.insert(name.as_str().into(), svc.into());
+    }
+
+    let mut networks = Mapping::new();
+    networks.insert(NETWORK.into(), Mapping::new().into());
+    let mut doc = Mapping::new();
+    doc.insert("version".into(), "3.8".into());
+    doc.insert("services".into(), services.into());
+    doc.insert("networks".into(), networks.into());
+    (doc.into(), unsupported)
+}
+
+/// Starts a service mapping with its `image` and/or `build` keys.
+fn service(image: Option<&str>, build: &CoreEnvBuildConfig, base_dir: &Path) -> Mapping {
+    let mut svc = Mapping::new();
+    if let Some(image) = image {
+        svc.insert("image".into(), image.into());
+    }
+    if build.dockerfile.is_some() || build.context.is_some() {
+        let mut spec = Mapping::new();
+        // DevRS defaults the context to the Dockerfile's directory; Compose needs it spelled out.
+        let context = build.context.clone().or_else(|| {
+            build.dockerfile.as_ref().map(|d| {
+                Path::new(d)
+                    .parent()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or_else(|| ".".to_string())
+            })
+        });
+        let context = context.unwrap_or_default();
+        spec.insert("context".into(), relative_to(&context, base_dir).into());
+        if let Some(dockerfile) = &build.dockerfile {
+            spec.insert("dockerfile".into(), dockerfile.as_str().into());
+        }
+        if !build.args.is_empty() {
+            spec.insert("args".into(), sorted_map(&build.args, false).into());
+        }
+        svc.insert("build".into(), spec.into());
+    }
+    svc
+}
+
+/// Notes the secret `env_vars` of a service: their values are not exported, the file
+/// only references them as host variables.
+fn secret_vars(unsupported: &mut Vec<String>, section: &str, env_vars: &HashMap<String, EnvValue>) {
+    let secrets: BTreeMap<_, _> = env_vars
+        .iter()
+        .filter_map(|(name, value)| match value {
+            EnvValue::Secret(secret) => Some((name, &secret.secret)),
+            EnvValue::Plain(_) => None,
+        })
+        .collect();
+    for (name, reference) in secrets {
+        unsupported.push(format!(
+            "the value of {}.env_vars.{} (secret {}; written as ${{{}}}, set it where `docker compose` runs)",
+            section, name, reference, name
+        ));
+    }
+}
+
+/// Adds `ports`, `env_file`, `environment`, `volumes` and `working_dir` to a service
+/// mapping. As in DevRS, Compose lets `environment` override `env_file`.
+fn add_runtime(
+    svc: &mut Mapping,
+    base_dir: &Path,
+    ports: &[PortMapping],
+    env_files: &[String],
+    env_vars: &HashMap<String, EnvValue>,
+    mounts: &[MountConfig],
+    workdir: Option<&str>,
+) {
+    if !ports.is_empty() {
+        let ports: Vec<Value> = ports.iter().map(|p| p.to_string().into()).collect();
+        svc.insert("ports".into(), ports.into());
+    }
+    if !env_files.is_empty() {
+        // DevRS skips missing files, so they are optional for Compose too.
+        let files: Vec<Value> = env_files
+            .iter()
+            .map(|file| {
+                let mut entry = Mapping::new();
+                entry.insert("path".into(), relative_to(file, base_dir).into());
+                entry.insert("required".into(), false.into());
+                entry.into()
+            })
+            .collect();
+        svc.insert("env_file".into(), files.into());
+    }
+    if !env_vars.is_empty() {
+        // Secrets become host variable references, interpolated by `docker compose`.
+        let sorted: BTreeMap<_, _> = env_vars.iter().collect();
+        let environment: Mapping = sorted
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    EnvValue::Plain(value) => value.replace('$', "$$"),
+                    EnvValue::Secret(_) => format!("${{{}}}", name),
+                };
+                (Value::from(name.as_str()), Value::from(value))
+            })
+            .collect();
+        svc.insert("environment".into(), environment.into());
+    }
+    if !mounts.is_empty() {
+        let volumes: Vec<Value> = mounts
+            .iter()
+            .map(|m| {
+                let suffix = if m.readonly { ":ro" } else { "" };
+                let host = relative_to(&m.host, base_dir);
+                format!("{}:{}{}", host, m.container, suffix).into()
+            })
+            .collect();
+        svc.insert("volumes".into(), volumes.into());
+    }
+    if let Some(workdir) = workdir {
+        svc.insert("working_dir".into(), workdir.into());
+    }
+}
+
+/// Writes `path` relative to `base_dir` (`.` or `./sub/dir`) if it lies below it.
+fn relative_to(path: &str, base_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(base_dir) {
+        Ok(rest) if rest.as_os_str().is_empty() => ".".to_string(),
+        Ok(rest) => format!("./{}", rest.display()),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Converts a string map to a mapping with sorted keys, optionally escaping `$` for Compose.
+fn sorted_map(map: &HashMap<String, String>, escape: bool) -> Mapping {
+    let sorted: BTreeMap<_, _> = map.iter().collect();
+    sorted
+        .into_iter()
+        .map(|(k, v)| {
+            let v = if escape {
+                v.replace('$', "$$")
+            } else {
+                v.clone()
+            };
+            (Value::from(k.as_str()), Value::from(v))
+        })
+        .collect()
+}
+
+/// Reads and translates a Compose file.
+///
+/// # Arguments
+///
+/// * `path` - The Compose file.
+/// * `core_service` - The service to import as `[core_env]` (if present).
+///
+/// # Returns
+///
+/// * `Result<ComposeImport>` - The translated `.devrs.toml` content.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if the file cannot be read, is not valid YAML, or has
+/// no `services` mapping.
+pub fn load(path: &Path, core_service: &str) -> Result<ComposeImport> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read compose file: {}", path.display()))?;
+    let document: Value = serde_yaml::from_str(&content).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Failed to parse '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+    // Relative paths in a Compose file are relative to the file's directory.
+    let base_dir = std::path::absolute(path.parent().unwrap_or(Path::new(".")))
+        .context("Failed to resolve the compose file's directory")?;
+    let import = import(&document, &base_dir, core_service)?;
+    debug!(
+        "Imported '{}' ({} unsupported item(s))",
+        path.display(),
+        import.unsupported.len()
+    );
+    Ok(import)
+}
+
+/// Translates a parsed Compose document into `.devrs.toml` content.
+///
+/// # Arguments
+///
+/// * `document` - The parsed document.
+/// * `base_dir` - Absolute directory that relative paths resolve against.
+/// * `core_service` - The service to import as `[core_env]`.
+///
+/// # Returns
+///
+/// * `Result<ComposeImport>` - The table and the unsupported notes.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if the document has no `services` mapping.
+fn import(document: &Value, base_dir: &Path, core_service: &str) -> Result<ComposeImport> {
+    let services = document
+        .get("services")
+        .and_then(Value::as_mapping)
+        .ok_or_else(|| {
+            anyhow!(DevrsError::Config(
+                "The compose file has no 'services' mapping.".to_string()
+            ))
+        })?;
+    let mut unsupported = Vec::new();
+    for (key, value) in document.as_mapping().into_iter().flatten() {
+        match key.as_str().unwrap_or_default() {
+            "version" | "name" | "services" => {}
+            // Every DevRS app shares one network, so plain network declarations are fine.
+            "networks" => {
+                for (name, spec) in value.as_mapping().into_iter().flatten() {
+                    if !spec.is_null() && spec.as_mapping().is_none_or(|m| !m.is_empty()) {
+                        unsupported.push(format!("networks.{}", display(name)));
+                    }
+                }
+            }
+            "volumes" => {
+                for name in value.as_mapping().into_iter().flatten().map(|(k, _)| k) {
+                    unsupported.push(format!("volumes.{} (named volumes)", display(name)));
+                }
+            }
+            other => unsupported.push(other.to_string()),
+        }
+    }
+
+    let names: Vec<String> = services.keys().map(display).collect();
+    let mut config = toml::Table::new();
+    let mut apps = toml::Table::new();
+    for (name, spec) in services {
+        let name = display(name);
+        let Some(spec) = spec.as_mapping() else {
+            unsupported.push(format!("services.{}", name));
+            continue;
+        };
+        let is_core = name == core_service;
+        let mut importer = ServiceImporter {
+            prefix: format!("services.{}", name),
+            base_dir,
+            table: toml::Table::new(),
+            unsupported: &mut unsupported,
+        };
+        for (key, value) in spec {
+            importer.key(&display(key), value, is_core, &names, core_service);
+        }
+        let mut table = importer.table;
+        if is_core {
+            // The image DevRS builds for the service gets a project-specific, Docker-safe
+            // name, so it replaces neither the default core image nor the upstream tag.
+            if table.contains_key("base_image") || table.contains_key("build") {
+                let project = base_dir.file_name().unwrap_or_default().to_string_lossy();
+                let image_name = format!("{}-core", docker_name(&project));
+                table.insert("image_name".into(), image_name.into());
+            }
+            config.insert("core_env".into(), table.into());
+        } else if table.contains_key("image") || table.contains_key("build") {
+            apps.insert(name, table.into());
+        } else {
+            unsupported.push(format!("services.{} (no image or build)", name));
+        }
+    }
+    if !apps.is_empty() {
+        config.insert("apps".into(), apps.into());
+    }
+    Ok(ComposeImport {
+        config,
+        unsupported,
+    })
+}
+
+/// Translates the keys of one Compose service into a `[core_env]` or `[apps.<name>]` table.
+struct ServiceImporter<'a> {
+    /// `services.<name>`, used in notes.
+    prefix: String,
+    /// Directory that relative paths resolve against.
+    base_dir: &'a Path,
+    /// The table being built.
+    table: toml::Table,
+    /// Notes on what was not imported.
+    unsupported: &'a mut Vec<String>,
+}
+
+impl ServiceImporter<'_> {
+    /// Imports one service key.
+    fn key(&mut self, key: &str, value: &Value, is_core: bool, names: &[String], core: &str) {
+        match (key, is_core) {
+            ("image", _) => match value.as_str() {
+                Some(image) if is_core => {
+                    self.table.insert("base_image".into(), image.into());
+                }
+                Some(image) if !is_core => {
+                    self.table.insert("image".into(), image.into());
+                }
+                _ => self.note(key, value),
+            },
+            ("build", _) => self.build(value),
+            ("command", false) => match command_line(value) {
+                Some(command) => {
+                    self.table.insert("command".into(), command.into());
+                }
+                None => self.note(key, value),
+            },
+            ("ports", _) => {
+                let mut ports = Vec::new();
+                for port in value.as_sequence().into_iter().flatten() {
+                    match port_mapping(port) {
+                        Some(mapping) => ports.push(toml::Value::from(mapping.to_string())),
+                        None => self.note(key, port),
+                    }
+                }
+                self.table.insert("ports".into(), ports.into());
+            }
+            ("environment", _) => self.environment(value),
+            ("env_file", _) => self.env_files(value),
+            ("volumes", _) => {
+                let mut mounts = Vec::new();
+                for volume in value.as_sequence().into_iter().flatten() {
+                    match self.mount(volume) {
+                        Some(mount) => mounts.push(toml::Value::from(mount)),
+                        None => self.note("volumes (only bind mounts are supported)", volume),
+                    }
+                }
+                self.table.insert("mounts".into(), mounts.into());
+            }
+            ("working_dir", _) => match value.as_str() {
+                Some(dir) => {
+                    let field = if is_core {
+                        "default_workdir"
+                    } else {
+                        "workdir"
+                    };
+                    self.table.insert(field.into(), dir.into());
+                }
+                None => self.note(key, value),
+            },
+            ("user", true) => match value.as_str() {
+                Some(user) => {
+                    self.table.insert("user".into(), user.into());
+                }
+                None => self.note(key, value),
+            },
+            ("depends_on", false) => {
+                // Short (list) and long (mapping) syntax; conditions are not representable.
+                let deps: Vec<String> = match value {
+                    Value::Sequence(items) => items.iter().map(display).collect(),
+                    Value::Mapping(map) => map.keys().map(display).collect(),
+                    _ => Vec::new(),
+                };
+                let mut kept = Vec::new();
+                for dep in deps {
+                    if dep != core && names.contains(&dep) {
+                        kept.push(toml::Value::from(dep));
+                    } else {
+                        self.unsupported
+                            .push(format!("{}.depends_on: {}", self.prefix, dep));
+                    }
+                }
+                self.table.insert("depends_on".into(), kept.into());
+            }
+            // All DevRS apps share one network.
+            ("networks", _) => {}
+            _ => self.unsupported.push(format!("{}.{}", self.prefix, key)),
+        }
+    }
+
+    /// Imports `build` (a context path or a mapping).
+    fn build(&mut self, value: &Value) {
+        let mut build = toml::Table::new();
+        match value {
+            Value::String(context) => {
+                build.insert("context".into(), self.resolve(context).into());
+            }
+            Value::Mapping(spec) => {
+                for (key, value) in spec {
+                    match (display(key).as_str(), value) {
+                        ("context", Value::String(context)) => {
+                            build.insert("context".into(), self.resolve(context).into());
+                        }
+                        // Relative Dockerfiles stay relative to the context, as in Compose.
+                        ("dockerfile", Value::String(dockerfile)) => {
+                            build.insert("dockerfile".into(), dockerfile.as_str().into());
+                        }
+                        ("args", args) => {
+                            let args = self.string_map("build.args", args, false);
+                            build.insert("args".into(), args.into());
+                        }
+                        (other, _) => self
+                            .unsupported
+                            .push(format!("{}.build.{}", self.prefix, other)),
+                    }
+                }
+                if !build.contains_key("context") {
+                    build.insert("context".into(), self.resolve(".").into());
+                }
+            }
+            _ => self.note("build", value),
+        }
+        if !build.is_empty() {
+            self.table.insert("build".into(), build.into());
+        }
+    }
+
+    /// Imports `environment` (a mapping or a list of `KEY=VALUE`).
+    fn environment(&mut self, value: &Value) {
+        let vars = self.string_map("environment", value, true);
+        self.table.insert("env_vars".into(), vars.into());
+    }
+
+    /// Imports `env_file` (a path, or a list of paths or `{ path, required }` entries).
+    fn env_files(&mut self, value: &Value) {
+        let entries = match value {
+            Value::Sequence(entries) => entries.clone(),
+            single => vec![single.clone()],
+        };
+        let mut files = Vec::new();
+        for entry in &entries {
+            let path = match entry {
+                Value::String(path) => Some(path.as_str()),
+                Value::Mapping(spec) => spec.get("path").and_then(Value::as_str),
+                _ => None,
+            };
+            match path {
+                Some(path) => files.push(toml::Value::from(self.resolve(path))),
+                None => self.note("env_file", entry),
+            }
+        }
+        self.table.insert("env_files".into(), files.into());
+    }
+
+    /// Converts a Compose string map (mapping or `KEY=VALUE` list) into a TOML table.
+    fn string_map(&mut self, key: &str, value: &Value, unescape: bool) -> toml::Table {
+        let mut table = toml::Table::new();
+        let mut add = |name: String, value: Option<String>| match value {
+            Some(value) if unescape => {
+                // After removing `$$` escapes, a remaining `$` is Compose interpolation.
+                if value.replace("$$", "").contains('$') {
+                    self.unsupported.push(format!(
+                        "{}.{}.{}: interpolation (kept verbatim)",
+                        self.prefix, key, name
+                    ));
+                }
+                table.insert(name, value.replace("$$", "$").into());
+            }
+            Some(value) => {
+                table.insert(name, value.into());
+            }
+            // A bare name passes the variable through from the host environment.
+            None => self
+                .unsupported
+                .push(format!("{}.{}.{} (no value)", self.prefix, key, name)),
+        };
+        match value {
+            Value::Mapping(map) => {
+                for (name, value) in map {
+                    add(display(name), scalar(value));
+                }
+            }
+            Value::Sequence(items) => {
+                for item in items {
+                    let item = display(item);
+                    match item.split_once('=') {
+                        Some((name, value)) => add(name.to_string(), Some(value.to_string())),
+                        None => add(item, None),
+                    }
+                }
+            }
+            _ => self.note(key, value),
+        }
+        table
+    }
+
+    /// Converts a volume (short or long syntax) into a mount, if it is a bind mount.
+    fn mount(&self, volume: &Value) -> Option<toml::Table> {
+        let (source, target, readonly) = match volume {
+            Value::String(spec) => {
+                let mut parts = spec.splitn(3, ':');
+                let (source, target) = (parts.next()?, parts.next()?);
+                let readonly = match parts.next() {
+                    None | Some("rw") => false,
+                    Some("ro") => true,
+                    Some(_) => return None,
+                };
+                (source.to_string(), target.to_string(), readonly)
+            }
+            Value::Mapping(spec) => {
+                if spec.get("type").and_then(Value::as_str) != Some("bind") {
+                    return None;
+                }
+                let source = spec.get("source")?.as_str()?.to_string();
+                let target = spec.get("target")?.as_str()?.to_string();
+                let readonly = spec
+                    .get("read_only")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                (source, target, readonly)
+            }
+            _ => return None,
+        };
+        // Anything that is not a path is a named volume.
+        if !(source.starts_with('.') || source.starts_with('/') || source.starts_with('~')) {
+            return None;
+        }
+        let mut mount = toml::Table::new();
+        mount.insert("host".into(), self.resolve(&source).into());
+        mount.insert("container".into(), target.into());
+        if readonly {
+            mount.insert("readonly".into(), true.into());
+        }
+        Some(mount)
+    }
+
+    /// Makes a relative path absolute against the Compose file's directory.
+    fn resolve(&self, path: &str) -> String {
+        if path.starts_with('/') || path.starts_with('~') {
+            return path.to_string();
+        }
+        let relative = path.trim_start_matches("./");
+        if relative.is_empty() || relative == "." {
+            self.base_dir.to_string_lossy().into_owned()
+        } else {
+            self.base_dir.join(relative).to_string_lossy().into_owned()
+        }
+    }
+
+    /// Records a key/value that could not be imported.
+    fn note(&mut self, key: &str, value: &Value) {
+        self.unsupported
+            .push(format!("{}.{}: {}", self.prefix, key, display(value)));
+    }
+}
+
+/// Converts a scalar YAML value to a string (`None` for null and collections).
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Renders a YAML value for notes and keys (strings without quotes).
+fn display(value: &Value) -> String {
+    scalar(value).unwrap_or_else(|| {
+        serde_yaml::to_string(value)
+            .map(|s| s.trim().replace('\n', " "))
+            .unwrap_or_default()
+    })
+}
+
+/// Converts a `command` (string or exec-form list) into a single command line.
+fn command_line(value: &Value) -> Option<String> {
+    match value {
+        Value::String(command) => Some(command.clone()),
+        Value::Sequence(words) => {
+            let words: Option<Vec<String>> = words.iter().map(scalar).collect();
+            Some(
+                words?
+                    .iter()
+                    .map(|w| shell_quote(w))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Converts a Compose port (number, `"HOST:CONTAINER"`, `"PORT"` or long syntax) into a
+/// `HOST:CONTAINER` mapping. Host IPs, protocols and ranges are not imported.
+fn port_mapping(port: &Value) -> Option<PortMapping> {
+    let is_port = |s: &str| s.parse::<u16>().is_ok();
+    let same = |port: &str| port.parse().ok().map(PortMapping::same);
+    match port {
+        Value::Number(n) => n
+            .as_u64()
+            .and_then(|n| u16::try_from(n).ok())
+            .map(PortMapping::same),
+        Value::String(s) => match s.split_once(':') {
+            None if is_port(s) => same(s),
+            Some((host, container)) if is_port(host) && is_port(container) => s.parse().ok(),
+            _ => None,
+        },
+        Value::Mapping(spec) => {
+            if spec
+                .get("protocol")
+                .and_then(Value::as_str)
+                .unwrap_or("tcp")
+                != "tcp"
+                || spec.contains_key("host_ip")
+            {
+                return None;
+            }
+            let target = scalar(spec.get("target")?)?;
+            let published = spec
+                .get("published")
+                .and_then(scalar)
+                .unwrap_or(target.clone());
+            format!("{}:{}", published, target)
+                .parse()
+                .ok()
+                .filter(|_| is_port(&target) && is_port(&published))
+        }
+        _ => None,
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test exporting the core env and an app onto the shared network.
+    #[test]
+    fn test_export() {
+        let cfg: Config = toml::from_str(
+            r#"
+            [core_env]
+            image_name = "devrs-core-env"
+            default_workdir = "/home/me/code"
+            ports = ["8080:80"]
+            env_vars = { PRICE = "$5" }
+            env_files = ["/src/app/.env"]
+            [[core_env.mounts]]
+            host = "/src/app"
+            container = "/home/me/code"
+            [[core_env.mounts]]
+            host = "/home/me/.ssh"
+            container = "/home/me/.ssh"
+            readonly = true
+            [[core_env.hooks.on_create]]
+            command = "make setup"
+
+            [apps.db]
+            image = "postgres:16"
+            ports = ["5432:5432"]
+            env_vars = { POSTGRES_PASSWORD = { secret = "file:~/.secrets/pg" } }
+            [apps.api]
+            build = { context = "/src/app/api", args = { PROFILE = "dev" } }
+            command = "cargo run"
+            depends_on = ["db"]
+            "#,
+        )
+        .unwrap();
+
+        let (doc, notes) = export(&cfg, Path::new("/src/app"));
+        let core = &doc["services"]["core"];
+        assert_eq!(core["image"].as_str(), Some("devrs-core-env:latest"));
+        assert_eq!(core["working_dir"].as_str(), Some("/home/me/code"));
+        assert_eq!(core["environment"]["PRICE"].as_str(), Some("$$5"));
+        assert_eq!(core["env_file"][0]["path"].as_str(), Some("./.env"));
+        assert_eq!(core["env_file"][0]["required"].as_bool(), Some(false));
+        assert_eq!(core["volumes"][0].as_str(), Some(".:/home/me/code"));
+        assert_eq!(
+            core["volumes"][1].as_str(),
+            Some("/home/me/.ssh:/home/me/.ssh:ro")
+        );
+        assert_eq!(core["networks"][0].as_str(), Some(NETWORK));
+
+        let api = &doc["services"]["api"];
+        assert!(api.get("image").is_none());
+        assert_eq!(api["build"]["context"].as_str(), Some("./api"));
+        assert_eq!(api["build"]["args"]["PROFILE"].as_str(), Some("dev"));
+        assert_eq!(api["depends_on"][0].as_str(), Some("db"));
+        let db = &doc["services"]["db"];
+        assert_eq!(db["ports"][0].as_str(), Some("5432:5432"));
+        // A secret is referenced as a host variable, never resolved into the file.
+        assert_eq!(
+            db["environment"]["POSTGRES_PASSWORD"].as_str(),
+            Some("${POSTGRES_PASSWORD}")
+        );
+        assert!(doc["networks"].get(NETWORK).is_some());
+
+        // The locally built default image, the hook and the secret's value have no
+        // Compose equivalent.
+        assert_eq!(notes.len(), 3, "{:?}", notes);
+        assert!(notes[1].contains("make setup"));
+        assert!(notes[2].contains("apps.db.env_vars.POSTGRES_PASSWORD"));
+    }
+
+    /// Test importing services into `[core_env]` and `[apps]`, reporting the rest.
+    #[test]
+    fn test_import() {
+        let document: Value = serde_yaml::from_str(
+            r#"
+            version: "3.9"
+            services:
+              core:
+                image: ghcr.io/acme/dev:1.2
+                working_dir: /work
+                user: dev
+                volumes: [".:/work", "~/.ssh:/home/dev/.ssh:ro", "cache:/cache"]
+                environment: ["PRICE=$$5", "HOME_DIR=${HOME}", "PASSTHROUGH"]
+                ports: ["8080:80", 3000, "127.0.0.1:9000:9000"]
+              db:
+                image: postgres:16
+                env_file: [db.env, { path: /etc/shared.env, required: false }]
+                restart: always
+                environment: { POSTGRES_PASSWORD: secret }
+                ports: [{ target: 5432, published: 15432 }]
+              web:
+                build: { context: ./web, args: ["NODE=20"], target: dev }
+                command: ["npm", "run", "dev", "--", "--host", "0.0.0.0"]
+                depends_on: { db: { condition: service_healthy }, core: {} }
+            volumes:
+              cache:
+            "#,
+        )
+        .unwrap();
+
+        let import = import(&document, Path::new("/src/app"), CORE_SERVICE).unwrap();
+        let rendered = toml::to_string(&import.config).unwrap();
+        let cfg: Config = toml::from_str(&rendered).unwrap();
+
+        let env = &cfg.core_env;
+        assert_eq!(env.base_image.as_deref(), Some("ghcr.io/acme/dev:1.2"));
+        assert_eq!(env.image_name, "app-core");
+        assert_eq!(env.image_tag, "latest");
+        assert_eq!(env.default_workdir, "/work");
+        assert_eq!(env.user.as_deref(), Some("dev"));
+        assert_eq!(env.ports, vec!["8080:80", "3000:3000"]);
+        assert_eq!(env.mounts.len(), 2);
+        assert_eq!(env.mounts[0].host, "/src/app");
+        assert_eq!(env.mounts[1].host, "~/.ssh");
+        assert!(env.mounts[1].readonly);
+        assert_eq!(env.env_vars["PRICE"].plain(), Some("$5"));
+
+        let db = &cfg.apps["db"];
+        assert_eq!(db.image.as_deref(), Some("postgres:16"));
+        assert_eq!(db.ports, vec!["15432:5432"]);
+        assert_eq!(db.env_vars["POSTGRES_PASSWORD"].plain(), Some("secret"));
+        assert_eq!(db.env_files, vec!["/src/app/db.env", "/etc/shared.env"]);
+        let web = &cfg.apps["web"];
+        assert_eq!(web.build.context.as_deref(), Some("/src/app/web"));
+        assert_eq!(web.build.args["NODE"], "20");
+        assert_eq!(
+            web.command.as_deref(),
+            Some("npm run dev -- --host 0.0.0.0")
+        );
+        assert_eq!(web.depends_on, vec!["db"]);
+
+        for expected in [
+            "volumes.cache",
+            "services.core.volumes",
+            "services.core.environment.HOME_DIR",
+            "services.core.environment.PASSTHROUGH",
+            "services.core.ports: 127.0.0.1:9000:9000",
+            "services.db.restart",
+            "services.web.build.target",
+            "services.web.depends_on: core",
+        ] {
+            assert!(
+                import.unsupported.iter().any(|n| n.starts_with(expected)),
+                "missing note {}: {:?}",
+                expected,
+                import.unsupported
+            );
+        }
+        assert_eq!(import.unsupported.len(), 8, "{:?}", import.unsupported);
+    }
+}
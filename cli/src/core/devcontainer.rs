@@ -0,0 +1,919 @@
+//! # DevRS devcontainer.json Compatibility
+//!
+//! File: cli/src/core/devcontainer.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! Many repositories already describe their development container in
+//! `.devcontainer/devcontainer.json`. This module translates the subset of that
+//! format that maps onto `CoreEnvConfig`, in both directions:
+//!
+//! - **Import** (`load`): used by `config::load_config` as a layer between the user
+//!   config and `.devrs.toml`, so `.devrs.toml` can still override anything.
+//! - **Export** (`export`): used by `devrs config export --devcontainer`.
+//!
+//! | devcontainer.json                     | DevRS                                      |
+//! |---------------------------------------|--------------------------------------------|
+//! | `image`                               | `core_env.base_image`                      |
+//! | `build.dockerfile`/`context`/`args`   | `core_env.build`                           |
+//! | `workspaceFolder`, `workspaceMount`   | `core_env.default_workdir`, a mount        |
+//! | `mounts` (bind mounts)                | `core_env.mounts`                          |
+//! | `forwardPorts`, `appPort`             | `core_env.ports`                           |
+//! | `containerEnv`, `remoteEnv`           | `core_env.env_vars`                        |
+//! | `runArgs` `--env-file`                | `core_env.env_files`                       |
+//! | `remoteUser`                          | `core_env.user`                            |
+//! | `postCreateCommand`/`postStartCommand`| `core_env.hooks.on_create` / `on_start`    |
+//!
+//! ## Architecture
+//!
+//! - The file is JSON with comments (JSONC); `strip_jsonc` removes comments and
+//!   trailing commas before parsing.
+//! - `${localWorkspaceFolder}`, `${localWorkspaceFolderBasename}`,
+//!   `${containerWorkspaceFolder}`, `${containerWorkspaceFolderBasename}` and
+//!   `${localEnv:VAR[:default]}` are substituted; values using other variables are skipped.
+//! - Anything that cannot be represented (other keys, volume mounts, build targets,
+//!   ...) is collected in `unsupported` rather than silently dropped. `load_config`
+//!   logs these as a warning; `export` returns its own list for settings that have
+//!   no devcontainer.json equivalent (including `[core_env.secrets]`, which are never
+//!   resolved for an export).
+//!
+//! ## Usage
+//!
+//! ```rust
+//! # fn example() -> crate::core::error::Result<()> {
+//! # let project_dir = std::path::Path::new(".");
+//! if let Some(path) = devcontainer::find(project_dir) {
+//!     let import = devcontainer::load(&path)?;
+//!     for note in &import.unsupported {
+//!         eprintln!("not imported: {}", note);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::{
+    config::{self, Config, EnvValue, HookConfig, MountConfig}, // The configuration model imported into.
+    error::{DevrsError, Result}, // Standard Result type and custom errors.
+    ports::{PortMapping, Protocol}, // Typed port mappings.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use serde_json::{json, Map, Value}; // devcontainer.json documents.
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+}; // File access and stable key order.
+use tracing::debug; // Logging framework utilities.
+
+/// # devcontainer Import (`DevcontainerImport`)
+///
+/// The result of `load`: the translated settings and what could not be translated.
+#[derive(Debug, Clone)]
+pub struct DevcontainerImport {
+    /// A configuration layer holding the translated settings (other fields at their defaults).
+    pub config: Config,
+    /// Human-readable notes on keys and values that were not imported.
+    pub unsupported: Vec<String>,
+}
+
+/// Finds the devcontainer.json for a project: `.devcontainer/devcontainer.json` or
+/// `.devcontainer.json` in `start` or an ancestor, stopping at the git repository root.
+///
+/// # Arguments
+///
+/// * `start` - The directory to search from (usually the current directory).
+///
+/// # Returns
+///
+/// * `Option<PathBuf>` - The file, if one was found.
+pub fn find(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        for candidate in [
+            dir.join(".devcontainer").join("devcontainer.json"),
+            dir.join(".devcontainer.json"),
+        ] {
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        if dir.join(".git").is_dir() {
+            break;
+        }
+    }
+    None
+}
+
+/// Reads and translates a devcontainer.json file.
+///
+/// # Arguments
+///
+/// * `path` - The devcontainer.json file.
+///
+/// # Returns
+///
+/// * `Result<DevcontainerImport>` - The translated configuration layer.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if the file cannot be read or is not a JSON object.
+pub fn load(path: &Path) -> Result<DevcontainerImport> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read devcontainer file: {}", path.display()))?;
+    let document: Value = serde_json::from_str(&strip_jsonc(&content)).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Failed to parse '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    // `.devcontainer/devcontainer.json` belongs to the directory above `.devcontainer`.
+    let workspace = if base_dir.file_name().is_some_and(|n| n == ".devcontainer") {
+        base_dir.parent().unwrap_or(base_dir)
+    } else {
+        base_dir
+    };
+    let (config, unsupported) = import(&document, base_dir, workspace)?;
+    debug!(
+        "Imported '{}' ({} unsupported item(s))",
+        path.display(),
+        unsupported.len()
+    );
+    Ok(DevcontainerImport {
+        config,
+        unsupported,
+    })
+}
+
+/// Translates a parsed devcontainer.json document into a configuration layer.
+///
+/// # Arguments
+///
+/// * `document` - The parsed document.
+/// * `base_dir` - The directory containing the file (relative build paths resolve against it).
+/// * `workspace` - The project directory mounted into the container.
+///
+/// # Returns
+///
+/// * `Result<(Config, Vec<String>)>` - The layer and the unsupported notes.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if the document is not a JSON object.
+fn import(document: &Value, base_dir: &Path, workspace: &Path) -> Result<(Config, Vec<String>)> {
+    let object = document.as_object().ok_or_else(|| {
+        anyhow!(DevrsError::Config(
+            "devcontainer.json must contain a JSON object.".to_string()
+        ))
+    })?;
+    // Start from the serde defaults so the layer only overrides what the file sets.
+    let mut cfg = config::default_config();
+    let mut unsupported = Vec::new();
+
+    let basename = workspace
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "workspace".to_string());
+    let local_workspace = workspace.to_string_lossy().into_owned();
+    let default_folder = format!("/workspaces/{}", basename);
+    let workspace_folder = match object.get("workspaceFolder") {
+        Some(value) => {
+            let raw = value.as_str().unwrap_or_default();
+            let vars = Substitutions {
+                local_workspace: local_workspace.clone(),
+                container_workspace: raw.to_string(),
+            };
+            vars.apply(raw)
+                .filter(|f| f.starts_with('/'))
+                .unwrap_or_else(|| {
+                    unsupported.push(format!("workspaceFolder: {}", value));
+                    default_folder
+                })
+        }
+        None => default_folder,
+    };
+    let vars = Substitutions {
+        local_workspace,
+        container_workspace: workspace_folder.clone(),
+    };
+    cfg.core_env.default_workdir = workspace_folder.clone();
+
+    // devcontainer.json always mounts the workspace (`workspaceMount` or the default);
+    // make that explicit for DevRS as the first mount.
+    match object.get("workspaceMount") {
+        Some(value) => match value.as_str().and_then(|m| parse_mount(m, &vars)) {
+            Some(mount) => cfg.core_env.mounts.push(mount),
+            None => unsupported.push(format!("workspaceMount: {}", value)),
+        },
+        None => cfg.core_env.mounts.push(MountConfig {
+            host: vars.local_workspace.clone(),
+            container: workspace_folder,
+            readonly: false,
+        }),
+    }
+
+    for (key, value) in object {
+        match key.as_str() {
+            "$schema" | "name" => {}
+            "image" => match value.as_str() {
+                Some(image) => cfg.core_env.base_image = Some(image.to_string()),
+                None => unsupported.push(format!("image: {}", value)),
+            },
+            "build" => import_build(value, base_dir, &mut cfg, &mut unsupported),
+            // Handled above, as are the environment maps below.
+            "workspaceFolder" | "workspaceMount" => {}
+            "mounts" => {
+                for mount in value.as_array().into_iter().flatten() {
+                    let parsed = match mount {
+                        Value::String(spec) => parse_mount(spec, &vars),
+                        Value::Object(fields) => parse_mount_object(fields, &vars),
+                        _ => None,
+                    };
+                    match parsed {
+                        Some(mount) => cfg.core_env.mounts.push(mount),
+                        None => unsupported.push(format!(
+                            "mounts: {} (only bind mounts are supported)",
+                            mount
+                        )),
+                    }
+                }
+            }
+            "forwardPorts" | "appPort" => {
+                let ports = match value {
+                    Value::Array(items) => items.clone(),
+                    other => vec![other.clone()],
+                };
+                for port in ports {
+                    match port_mapping(&port) {
+                        Some(mapping) => cfg.core_env.ports.push(mapping),
+                        None => unsupported.push(format!("{}: {}", key, port)),
+                    }
+                }
+            }
+            // remoteEnv is applied after containerEnv so it wins on conflicts.
+            "containerEnv" | "remoteEnv" => {}
+            "remoteUser" => match value.as_str() {
+                Some(user) => cfg.core_env.user = Some(user.to_string()),
+                None => unsupported.push(format!("remoteUser: {}", value)),
+            },
+            "postCreateCommand" => {
+                cfg.core_env.hooks.on_create = lifecycle_hooks(key, value, &mut unsupported)
+            }
+            "postStartCommand" => {
+                cfg.core_env.hooks.on_start = lifecycle_hooks(key, value, &mut unsupported)
+            }
+            "runArgs" => {
+                let files = env_file_args(value, &vars, workspace, &mut unsupported);
+                cfg.core_env.env_files.extend(files);
+            }
+            _ => unsupported.push(key.clone()),
+        }
+    }
+    for key in ["containerEnv", "remoteEnv"] {
+        for (name, value) in object
+            .get(key)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            match value.as_str().and_then(|v| vars.apply(v)) {
+                Some(value) => {
+                    cfg.core_env.env_vars.insert(name.clone(), value.into());
+                }
+                None => unsupported.push(format!("{}.{}: {}", key, name, value)),
+            }
+        }
+    }
+    // The image DevRS builds (from the Dockerfile, or `FROM` the upstream image) gets a
+    // project-specific, Docker-safe name, so it replaces neither the shared default
+    // core image nor the upstream tag, and `<image_name>-instance` is a valid container name.
+    if cfg.core_env.build.dockerfile.is_some() || cfg.core_env.base_image.is_some() {
+        cfg.core_env.image_name = image_name_for(
+            object
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or(&basename),
+        );
+    }
+    Ok((cfg, unsupported))
+}
+
+/// Imports the `--env-file` arguments of `runArgs` (relative to the workspace, like the
+/// `docker run` they are passed to); other arguments are not supported.
+fn env_file_args(
+    value: &Value,
+    vars: &Substitutions,
+    workspace: &Path,
+    unsupported: &mut Vec<String>,
+) -> Vec<String> {
+    let args: Vec<&str> = value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let mut files = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let file = match arg.strip_prefix("--env-file") {
+            Some("") => args.next(),
+            Some(inline) => inline.strip_prefix('='),
+            None => None,
+        };
+        match file.and_then(|f| vars.apply(f)) {
+            Some(file) => files.push(workspace.join(file).to_string_lossy().into_owned()),
+            None => unsupported.push(format!("runArgs: {}", arg)),
+        }
+    }
+    files
+}
+
+/// Imports the `build` object into `core_env.build`.
+fn import_build(value: &Value, base_dir: &Path, cfg: &mut Config, unsupported: &mut Vec<String>) {
+    let Some(build) = value.as_object() else {
+        unsupported.push(format!("build: {}", value));
+        return;
+    };
+    let resolve = |p: &str| base_dir.join(p).to_string_lossy().into_owned();
+    for (key, value) in build {
+        match (key.as_str(), value) {
+            ("dockerfile", Value::String(path)) => {
+                cfg.core_env.build.dockerfile = Some(resolve(path))
+            }
+            ("context", Value::String(path)) => cfg.core_env.build.context = Some(resolve(path)),
+            ("args", Value::Object(args)) => {
+                for (name, value) in args {
+                    match value.as_str() {
+                        Some(v) => {
+                            cfg.core_env.build.args.insert(name.clone(), v.to_string());
+                        }
+                        None => unsupported.push(format!("build.args.{}: {}", name, value)),
+                    }
+                }
+            }
+            _ => unsupported.push(format!("build.{}", key)),
+        }
+    }
+    // devcontainer.json resolves the default context against the file's directory.
+    if cfg.core_env.build.dockerfile.is_some() && cfg.core_env.build.context.is_none() {
+        cfg.core_env.build.context = Some(resolve("."));
+    }
+}
+
+/// Converts a lifecycle command (string, array or object of named commands) into hooks.
+/// Named commands, which run in parallel in devcontainer.json, run in order here.
+fn lifecycle_hooks(key: &str, value: &Value, unsupported: &mut Vec<String>) -> Vec<HookConfig> {
+    let hook = |command: String| HookConfig {
+        command,
+        ..Default::default()
+    };
+    match value {
+        Value::String(command) => vec![hook(command.clone())],
+        Value::Array(_) => command_line(value).map(hook).into_iter().collect(),
+        Value::Object(commands) => commands
+            .iter()
+            .filter_map(|(name, command)| {
+                let line = match command {
+                    Value::String(s) => Some(s.clone()),
+                    other => command_line(other),
+                };
+                if line.is_none() {
+                    unsupported.push(format!("{}.{}: {}", key, name, command));
+                }
+                line.map(hook)
+            })
+            .collect(),
+        _ => {
+            unsupported.push(format!("{}: {}", key, value));
+            Vec::new()
+        }
+    }
+}
+
+/// Joins an array-form command into a single shell command line.
+fn command_line(value: &Value) -> Option<String> {
+    let words = value
+        .as_array()?
+        .iter()
+        .map(|w| w.as_str().map(shell_quote))
+        .collect::<Option<Vec<_>>>()?;
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Quotes a word for `sh` if it contains anything but safe characters.
+pub(crate) fn shell_quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+    {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r#"'\''"#))
+    }
+}
+
+/// Variable values for `${...}` substitution.
+struct Substitutions {
+    local_workspace: String,
+    container_workspace: String,
+}
+
+impl Substitutions {
+    /// Substitutes the supported variables. Returns `None` if the string uses any other variable.
+    fn apply(&self, input: &str) -> Option<String> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let end = rest[start..].find('}')? + start;
+            let var = &rest[start + 2..end];
+            out.push_str(&self.resolve(var)?);
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        Some(out)
+    }
+
+    /// Resolves one variable name.
+    fn resolve(&self, var: &str) -> Option<String> {
+        let basename = |p: &str| {
+            Path::new(p)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        match var {
+            "localWorkspaceFolder" => Some(self.local_workspace.clone()),
+            "localWorkspaceFolderBasename" => Some(basename(&self.local_workspace)),
+            "containerWorkspaceFolder" => Some(self.container_workspace.clone()),
+            "containerWorkspaceFolderBasename" => Some(basename(&self.container_workspace)),
+            _ => {
+                let spec = var.strip_prefix("localEnv:").or(var.strip_prefix("env:"))?;
+                let (name, default) = match spec.split_once(':') {
+                    Some((name, default)) => (name, default),
+                    None => (spec, ""),
+                };
+                Some(std::env::var(name).unwrap_or_else(|_| default.to_string()))
+            }
+        }
+    }
+}
+
+/// Parses a `--mount` style string (`source=...,target=...,type=bind[,readonly]`).
+/// Returns `None` for anything but bind mounts.
+fn parse_mount(spec: &str, vars: &Substitutions) -> Option<MountConfig> {
+    let mut fields = Map::new();
+    for part in spec.split(',') {
+        let (key, value) = match part.split_once('=') {
+            Some((key, value)) => (key.trim(), Value::String(value.trim().to_string())),
+            None => (part.trim(), Value::Bool(true)),
+        };
+        fields.insert(key.to_string(), value);
+    }
+    parse_mount_object(&fields, vars)
+}
+
+/// Parses an object-form mount (`{"source", "target", "type"}`). Bind mounts only.
+fn parse_mount_object(fields: &Map<String, Value>, vars: &Substitutions) -> Option<MountConfig> {
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|n| fields.get(*n))
+            .and_then(Value::as_str)
+    };
+    let source = vars.apply(field(&["source", "src"])?)?;
+    let target = vars.apply(field(&["target", "destination", "dst"])?)?;
+    let kind = field(&["type"]).unwrap_or(if source.starts_with('/') {
+        "bind"
+    } else {
+        "volume"
+    });
+    if kind != "bind" {
+        return None;
+    }
+    let readonly = ["readonly", "ro"].iter().any(|k| match fields.get(*k) {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::String(s)) => s == "true" || s == "1",
+        _ => false,
+    });
+    Some(MountConfig {
+        host: source,
+        container: target,
+        readonly,
+    })
+}
+
+/// Converts a `forwardPorts`/`appPort` entry into a port mapping (see `core::ports`).
+/// Entries that forward another service's port (`"db:5432"`) are not representable.
+fn port_mapping(port: &Value) -> Option<PortMapping> {
+    match port {
+        Value::Number(n) => n
+            .as_u64()
+            .and_then(|n| u16::try_from(n).ok())
+            .map(PortMapping::same),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Derives a Docker image name from a devcontainer or directory name.
+fn image_name_for(name: &str) -> String {
+    format!("{}-devcontainer", docker_name(name))
+}
+
+/// Turns a project or service name into a Docker-safe name: lowercase ASCII letters and
+/// digits joined by `-` (`project` if none remain), also valid in container names.
+pub(crate) fn docker_name(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return "project".to_string();
+    }
+    words.join("-")
+}
+
+/// Removes `//` and `/* */` comments and trailing commas from JSONC, leaving strings intact.
+fn strip_jsonc(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (']' | '}', _) => {
+                // Drop a trailing comma before the closing bracket.
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders the effective configuration as a devcontainer.json document.
+///
+/// # Arguments
+///
+/// * `cfg` - The effective configuration.
+///
+/// # Returns
+///
+/// * `(Value, Vec<String>)` - The document and notes on settings that have no
+///   devcontainer.json equivalent (and were therefore left out).
+pub fn export(cfg: &Config) -> (Value, Vec<String>) {
+    let env = &cfg.core_env;
+    let mut doc = Map::new();
+    let mut unsupported = Vec::new();
+
+    match (&env.build.dockerfile, &env.base_image) {
+        (Some(dockerfile), _) => {
+            let mut build = Map::new();
+            build.insert("dockerfile".into(), json!(dockerfile));
+            if let Some(context) = &env.build.context {
+                build.insert("context".into(), json!(context));
+            }
+            if !env.build.args.is_empty() {
+                let args: BTreeMap<_, _> = env.build.args.iter().collect();
+                build.insert("args".into(), json!(args));
+            }
+            doc.insert("build".into(), Value::Object(build));
+        }
+        (None, Some(base_image)) => {
+            doc.insert("image".into(), json!(base_image));
+        }
+        (None, None) => {
+            doc.insert(
+                "image".into(),
+                json!(format!("{}:{}", env.image_name, env.image_tag)),
+            );
+        }
+    }
+    doc.insert("workspaceFolder".into(), json!(env.default_workdir));
+
+    // The mount for the working directory becomes the workspace mount; the rest are extra mounts.
+    let mut mounts = Vec::new();
+    for mount in &env.mounts {
+        let spec = format!(
+            "source={},target={},type=bind{}",
+            mount.host,
+            mount.container,
+            if mount.readonly { ",readonly" } else { "" }
+        );
+        if mount.container == env.default_workdir && !doc.contains_key("workspaceMount") {
+            doc.insert("workspaceMount".into(), json!(spec));
+        } else {
+            mounts.push(json!(spec));
+        }
+    }
+    if !mounts.is_empty() {
+        doc.insert("mounts".into(), Value::Array(mounts));
+    }
+
+    // Same-port mappings are forwarded ports; others need `appPort` to keep both sides.
+    let (mut forward, mut app) = (Vec::new(), Vec::new());
+    for mapping in &env.ports {
+        let same_port = mapping.host == Some(mapping.container) && mapping.container.is_single();
+        if same_port && mapping.ip.is_none() && mapping.protocol == Protocol::Tcp {
+            forward.push(json!(mapping.container.start));
+        } else {
+            app.push(json!(mapping.to_string()));
+        }
+    }
+    if !forward.is_empty() {
+        doc.insert("forwardPorts".into(), Value::Array(forward));
+    }
+    if !app.is_empty() {
+        doc.insert("appPort".into(), Value::Array(app));
+    }
+
+    if !env.env_vars.is_empty() {
+        // Secrets are taken from the host environment of the editor.
+        let sorted: BTreeMap<_, _> = env.env_vars.iter().collect();
+        let vars: BTreeMap<_, _> = sorted
+            .into_iter()
+            .map(|(name, value)| match value {
+                EnvValue::Plain(value) => (name, value.clone()),
+                EnvValue::Secret(secret) => {
+                    unsupported.push(format!(
+                        "the value of core_env.env_vars.{} (secret {}; written as ${{localEnv:{}}}, set it on the host)",
+                        name, secret.secret, name
+                    ));
+                    (name, format!("${{localEnv:{}}}", name))
+                }
+            })
+            .collect();
+        doc.insert("containerEnv".into(), json!(vars));
+    }
+    if !env.env_files.is_empty() {
+        let args: Vec<&str> = env
+            .env_files
+            .iter()
+            .flat_map(|file| ["--env-file", file.as_str()])
+            .collect();
+        doc.insert("runArgs".into(), json!(args));
+    }
+    if let Some(user) = &env.user {
+        doc.insert("remoteUser".into(), json!(user));
+    }
+
+    for (key, stage, hooks) in [
+        ("postCreateCommand", "on_create", &env.hooks.on_create),
+        ("postStartCommand", "on_start", &env.hooks.on_start),
+    ] {
+        let commands: Vec<&str> = hooks.iter().map(|h| h.command.as_str()).collect();
+        for hook in hooks {
+            if hook.user.is_some() || hook.workdir.is_some() || hook.timeout.is_some() {
+                unsupported.push(format!(
+                    "core_env.hooks.{} '{}': user/workdir/timeout",
+                    stage, hook.command
+                ));
+            }
+        }
+        match commands.as_slice() {
+            [] => {}
+            [single] => {
+                doc.insert(key.into(), json!(single));
+            }
+            // Run in order, stopping at the first failure, like the hooks do.
+            many => {
+                doc.insert(key.into(), json!(many.join(" && ")));
+            }
+        }
+    }
+    for hook in &env.hooks.on_shell {
+        unsupported.push(format!("core_env.hooks.on_shell '{}'", hook.command));
+    }
+    // Exporting a secret would mean writing its value; only DevRS resolves the references.
+    let secrets: BTreeMap<_, _> = env.secrets.iter().collect();
+    for (name, secret) in secrets {
+        unsupported.push(format!("core_env.secrets.{} ({})", name, secret.secret));
+    }
+    (Value::Object(doc), unsupported)
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test comment and trailing-comma removal, leaving string contents intact.
+    #[test]
+    fn test_strip_jsonc() {
+        let input = r#"{
+            // a comment
+            "url": "http://example.com/*not a comment*/", /* block */
+            "list": [1, 2,],
+        }"#;
+        let value: Value = serde_json::from_str(&strip_jsonc(input)).unwrap();
+        assert_eq!(value["url"], "http://example.com/*not a comment*/");
+        assert_eq!(value["list"], json!([1, 2]));
+    }
+
+    /// Test loading a representative devcontainer.json, including unsupported items.
+    #[test]
+    fn test_load_devcontainer() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("myproj");
+        fs::create_dir_all(project.join(".devcontainer")).unwrap();
+        fs::create_dir(project.join(".git")).unwrap();
+        let file = project.join(".devcontainer").join("devcontainer.json");
+        fs::write(
+            &file,
+            r#"{
+                "name": "My Project",
+                "build": { "dockerfile": "Dockerfile", "args": { "VARIANT": "1.80" }, "target": "dev" },
+                "workspaceFolder": "/work/${localWorkspaceFolderBasename}",
+                "mounts": [
+                    "source=${localWorkspaceFolder}/.cache,target=/cache,type=bind,readonly",
+                    "source=node_modules,target=${containerWorkspaceFolder}/node_modules,type=volume"
+                ],
+                "forwardPorts": [3000, "8080:80", "db:5432"],
+                "containerEnv": { "A": "1", "B": "x" },
+                "remoteEnv": { "B": "y", "P": "${containerEnv:PATH}" },
+                "remoteUser": "vscode",
+                "runArgs": ["--env-file", ".devcontainer/dev.env", "--privileged"],
+                "postCreateCommand": ["npm", "install", "--prefix", "my dir"],
+                "postStartCommand": { "one": "echo 1", "two": "echo 2" },
+                "features": {},
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(find(&project.join(".devcontainer")), Some(file.clone()));
+        let import = load(&file).unwrap();
+        let env = &import.config.core_env;
+        let root = project.to_string_lossy();
+        assert_eq!(env.image_name, "my-project-devcontainer");
+        assert_eq!(
+            env.build.dockerfile.as_deref(),
+            Some(&*format!("{}/.devcontainer/Dockerfile", root))
+        );
+        assert_eq!(
+            env.build.args.get("VARIANT").map(String::as_str),
+            Some("1.80")
+        );
+        assert_eq!(env.default_workdir, "/work/myproj");
+        assert_eq!(env.mounts.len(), 2);
+        assert_eq!(env.mounts[0].host, root);
+        assert_eq!(env.mounts[0].container, "/work/myproj");
+        assert_eq!(env.mounts[1].host, format!("{}/.cache", root));
+        assert!(env.mounts[1].readonly);
+        assert_eq!(env.ports, vec!["3000:3000", "8080:80"]);
+        assert_eq!(env.env_vars.get("B").and_then(EnvValue::plain), Some("y"));
+        assert_eq!(env.user.as_deref(), Some("vscode"));
+        assert_eq!(
+            env.env_files,
+            vec![format!("{}/.devcontainer/dev.env", root)]
+        );
+        assert_eq!(
+            env.hooks.on_create[0].command,
+            "npm install --prefix 'my dir'"
+        );
+        assert_eq!(env.hooks.on_start.len(), 2);
+
+        let notes = import.unsupported.join("\n");
+        for expected in [
+            "build.target",
+            "type=volume",
+            "db:5432",
+            "remoteEnv.P",
+            "runArgs: --privileged",
+            "features",
+        ] {
+            assert!(
+                notes.contains(expected),
+                "missing note for {}: {}",
+                expected,
+                notes
+            );
+        }
+    }
+
+    /// Test that an exported configuration imports back to the same settings.
+    #[test]
+    fn test_export_round_trip() {
+        let mut cfg: Config = toml::from_str(
+            r#"
+            [core_env]
+            image_name = "devrs-core-env"
+            image_tag = "v2"
+            default_workdir = "/home/me/code"
+            ports = ["8000:8000", "8080:80"]
+            user = "me"
+            env_vars = { RUST_LOG = "debug" }
+            env_files = ["/src/code/.env"]
+            [[core_env.mounts]]
+            host = "/src/code"
+            container = "/home/me/code"
+            [[core_env.mounts]]
+            host = "/src/.ssh"
+            container = "/home/me/.ssh"
+            readonly = true
+            [[core_env.hooks.on_create]]
+            command = "make setup"
+            [[core_env.hooks.on_create]]
+            command = "make seed"
+            [[core_env.hooks.on_shell]]
+            command = "motd"
+            "#,
+        )
+        .unwrap();
+        cfg.core_env.hooks.on_create[1].timeout = Some("1m".to_string());
+
+        let (doc, notes) = export(&cfg);
+        assert_eq!(doc["image"], "devrs-core-env:v2");
+        assert_eq!(doc["forwardPorts"], json!([8000]));
+        assert_eq!(doc["appPort"], json!(["8080:80"]));
+        assert_eq!(doc["postCreateCommand"], "make setup && make seed");
+        assert_eq!(notes.len(), 2);
+
+        let (back, unsupported) = import(&doc, Path::new("/src"), Path::new("/src/code")).unwrap();
+        assert!(unsupported.is_empty(), "{:?}", unsupported);
+        assert_eq!(
+            back.core_env.base_image.as_deref(),
+            Some("devrs-core-env:v2")
+        );
+        assert_eq!(back.core_env.image_name, "code-devcontainer");
+        assert_eq!(back.core_env.mounts, cfg.core_env.mounts);
+        assert_eq!(back.core_env.default_workdir, "/home/me/code");
+        assert_eq!(back.core_env.user.as_deref(), Some("me"));
+        assert_eq!(back.core_env.env_files, cfg.core_env.env_files);
+        let mut ports = back.core_env.ports.clone();
+        ports.sort();
+        assert_eq!(ports, vec!["8000:8000", "8080:80"]);
+    }
+
+    /// Test that a registry image becomes the base image of a Docker-safe local image.
+    #[test]
+    fn test_import_registry_image() {
+        let doc =
+            json!({ "name": "Rust (Dev)", "image": "mcr.microsoft.com/devcontainers/rust:1" });
+        let (cfg, unsupported) = import(&doc, Path::new("/src"), Path::new("/src/code")).unwrap();
+        assert!(unsupported.is_empty(), "{:?}", unsupported);
+        let env = &cfg.core_env;
+        assert_eq!(
+            env.base_image.as_deref(),
+            Some("mcr.microsoft.com/devcontainers/rust:1")
+        );
+        assert_eq!(env.image_name, "rust-dev-devcontainer");
+        assert_eq!(env.image_tag, "latest");
+        let container = format!("{}-instance", env.image_name);
+        assert!(container
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c)));
+
+        let (doc, _) = export(&cfg);
+        assert_eq!(doc["image"], "mcr.microsoft.com/devcontainers/rust:1");
+
+        // Digest references work as a base image too.
+        let doc = json!({ "image": "rust@sha256:0123" });
+        let (cfg, _) = import(&doc, Path::new("/src"), Path::new("/src/code")).unwrap();
+        assert_eq!(cfg.core_env.base_image.as_deref(), Some("rust@sha256:0123"));
+        assert_eq!(cfg.core_env.image_name, "code-devcontainer");
+    }
+}
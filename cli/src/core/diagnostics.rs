@@ -0,0 +1,336 @@
+//! # DevRS Configuration Diagnostics
+//!
+//! File: cli/src/core/diagnostics.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module renders problems in config files the way a compiler would: with the
+//! file, line and column, the offending line, a marker under the problem and, for
+//! misspelled keys, a "did you mean" suggestion:
+//!
+//! ```text
+//! unknown key `prots` in `core_env`
+//!  --> /work/app/.devrs.toml:3:1
+//!   |
+//! 3 | prots = ["3000:3000"]
+//!   | ^^^^^
+//!   = help: did you mean `ports`?
+//! ```
+//!
+//! ## Architecture
+//!
+//! - A `Diagnostic` is a message, an optional byte range into the file and an optional hint.
+//! - Locations come either from the TOML parser (`from_toml_error`) or from a key path
+//!   (`key_span`), which is looked up in a span-preserving `toml_edit` parse of the file.
+//!   Dotted keys, `[table]` headers, inline tables and `[[arrays]]` are all found.
+//! - `did_you_mean` picks the closest candidate by Jaro-Winkler similarity, the measure
+//!   `clap` uses for misspelled subcommands.
+//! - `core::schema` produces diagnostics for unknown keys; `core::config` renders them,
+//!   together with type errors, into a single `DevrsError::Config`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::diagnostics::{self, Diagnostic, Segment};
+//! # fn example(path: &std::path::Path, content: &str) {
+//! let key = vec![Segment::Key("core_env".into()), Segment::Key("prots".into())];
+//! let diagnostic = Diagnostic {
+//!     message: "unknown key `prots` in `core_env`".into(),
+//!     span: diagnostics::key_span(content, &key),
+//!     help: diagnostics::did_you_mean("prots", ["ports", "mounts"]),
+//! };
+//! eprintln!("{}", diagnostics::render(path, content, &[diagnostic]));
+//! # }
+//! ```
+//!
+use std::{borrow::Cow, fmt, ops::Range, path::Path}; // Locations in files.
+use toml_edit::{ImDocument, Item, TableLike}; // Span-preserving TOML parse.
+
+/// Minimum Jaro-Winkler similarity for a "did you mean" suggestion.
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// # Diagnostic (`Diagnostic`)
+///
+/// One problem in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What is wrong, e.g. "unknown key `prots` in `core_env`".
+    pub message: String,
+    /// The byte range the problem refers to, if known.
+    pub span: Option<Range<usize>>,
+    /// A hint, e.g. "did you mean `ports`?".
+    pub help: Option<String>,
+}
+
+/// One step of a key path: a table key or an array index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// A key of a table.
+    Key(String),
+    /// An element of an array (or array of tables).
+    Index(usize),
+}
+
+/// Formats a key path as written in messages, e.g. `core_env.mounts[1].host`.
+pub struct Dotted<'a>(pub &'a [Segment]);
+
+impl fmt::Display for Dotted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if index == 0 => write!(f, "{}", key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(i) => write!(f, "[{}]", i)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts an error of the `toml` crate (syntax or type error) into a diagnostic.
+pub fn from_toml_error(error: &toml::de::Error) -> Diagnostic {
+    Diagnostic {
+        message: error.message().trim().to_string(),
+        span: error.span(),
+        help: None,
+    }
+}
+
+/// Finds the byte range of the last key of `path` in a TOML document.
+///
+/// # Arguments
+///
+/// * `content` - The TOML text.
+/// * `path` - The key path; it must end with a key.
+///
+/// # Returns
+///
+/// * `Option<Range<usize>>` - The range of the key, or `None` if the text does not parse
+///   or the key is not there.
+pub fn key_span(content: &str, path: &[Segment]) -> Option<Range<usize>> {
+    let doc = ImDocument::parse(content).ok()?;
+    let (Segment::Key(key), parents) = path.split_last()? else {
+        return None;
+    };
+    let mut node = Node::Table(doc.as_table());
+    for segment in parents {
+        node = node.child(segment)?;
+    }
+    node.table()?.key(key)?.span()
+}
+
+/// A position in a parsed document while following a key path.
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Item(&'a Item),
+    Value(&'a toml_edit::Value),
+    Table(&'a toml_edit::Table),
+}
+
+impl<'a> Node<'a> {
+    /// The node as a table, if it is one (including inline tables).
+    fn table(self) -> Option<&'a dyn TableLike> {
+        match self {
+            Node::Item(item) => item.as_table_like(),
+            Node::Value(value) => value.as_inline_table().map(|t| t as &dyn TableLike),
+            Node::Table(table) => Some(table),
+        }
+    }
+
+    /// The node one step down the path.
+    fn child(self, segment: &Segment) -> Option<Node<'a>> {
+        match (segment, self) {
+            (Segment::Key(key), _) => self.table()?.get(key).map(Node::Item),
+            (Segment::Index(i), Node::Item(Item::ArrayOfTables(tables))) => {
+                tables.get(*i).map(Node::Table)
+            }
+            (Segment::Index(i), Node::Item(Item::Value(value)) | Node::Value(value)) => {
+                value.as_array()?.get(*i).map(Node::Value)
+            }
+            (Segment::Index(_), _) => None,
+        }
+    }
+}
+
+/// Blanks out top-level entries (such as `include` and `version`) of a TOML text. The
+/// result has the same length and line breaks, so positions of errors in it are valid
+/// in the original.
+///
+/// # Returns
+///
+/// * `Cow<str>` - The text without the entries (unchanged if there are none, or the
+///   text does not parse).
+pub fn mask_keys<'a>(content: &'a str, keys: &[&str]) -> Cow<'a, str> {
+    let Ok(doc) = ImDocument::parse(content) else {
+        return Cow::Borrowed(content);
+    };
+    let spans: Vec<Range<usize>> = keys
+        .iter()
+        .filter_map(|key| {
+            let (key, item) = doc.as_table().get_key_value(key)?;
+            Some(key.span()?.start..item.span()?.end)
+        })
+        .collect();
+    if spans.is_empty() {
+        return Cow::Borrowed(content);
+    }
+    let mut masked = content.to_string();
+    for span in spans {
+        let blank: String = content[span.clone()]
+            .chars()
+            .map(|c| match c {
+                '\n' | '\r' => c.to_string(),
+                _ => " ".repeat(c.len_utf8()),
+            })
+            .collect();
+        masked.replace_range(span, &blank);
+    }
+    Cow::Owned(masked)
+}
+
+/// Suggests the candidate closest to a misspelled name.
+///
+/// # Returns
+///
+/// * `Option<String>` - "did you mean `<candidate>`?", or `None` if nothing is similar enough.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(name, candidate), candidate))
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, candidate)| format!("did you mean `{}`?", candidate))
+}
+
+/// Renders diagnostics for a file, each with its location and the offending line.
+///
+/// # Arguments
+///
+/// * `path` - The file, shown in the location line.
+/// * `content` - The file's text, for line numbers and snippets.
+/// * `diagnostics` - The problems to show.
+///
+/// # Returns
+///
+/// * `String` - The rendered problems, separated by blank lines.
+pub fn render(path: &Path, content: &str, diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| render_one(path, content, diagnostic))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Renders one diagnostic.
+fn render_one(path: &Path, content: &str, diagnostic: &Diagnostic) -> String {
+    let mut out = diagnostic.message.clone();
+    let span = diagnostic
+        .span
+        .clone()
+        .filter(|span| span.start <= content.len());
+    match span {
+        Some(span) => {
+            let line_start = content[..span.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = content[span.start..]
+                .find('\n')
+                .map_or(content.len(), |i| span.start + i);
+            let line = &content[line_start..line_end];
+            let number = content[..span.start].matches('\n').count() + 1;
+            let column = content[line_start..span.start].chars().count() + 1;
+            let width = content[span.start..span.end.clamp(span.start, line_end)]
+                .chars()
+                .count()
+                .max(1);
+            let gutter = " ".repeat(number.to_string().len());
+            out.push_str(&format!(
+                "\n{g}--> {}:{}:{}\n{g} |\n{} | {}\n{g} | {}{}",
+                path.display(),
+                number,
+                column,
+                number,
+                line.trim_end_matches('\r'),
+                " ".repeat(column - 1),
+                "^".repeat(width),
+                g = gutter,
+            ));
+            if let Some(help) = &diagnostic.help {
+                out.push_str(&format!("\n{} = help: {}", gutter, help));
+            }
+        }
+        None => {
+            out.push_str(&format!("\n --> {}", path.display()));
+            if let Some(help) = &diagnostic.help {
+                out.push_str(&format!("\n  = help: {}", help));
+            }
+        }
+    }
+    out
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(path: &[&str]) -> Vec<Segment> {
+        path.iter().map(|k| Segment::Key(k.to_string())).collect()
+    }
+
+    /// Test that keys are found in headers, dotted keys, inline tables and arrays of tables.
+    #[test]
+    fn test_key_span() {
+        let content = "logs.collect = true\n\n[core_env]\nprots = []\nmounts = [{ host = \"/a\", contianer = \"/a\" }]\n\n[[core_env.hooks.on_start]]\ncommand = \"x\"\n";
+        let at = |path: &[Segment]| key_span(content, path).map(|span| &content[span]);
+        assert_eq!(at(&key(&["logs", "collect"])), Some("collect"));
+        assert_eq!(at(&key(&["core_env", "prots"])), Some("prots"));
+        let mut mount = key(&["core_env", "mounts"]);
+        mount.extend([Segment::Index(0), Segment::Key("contianer".into())]);
+        assert_eq!(at(&mount), Some("contianer"));
+        let mut hook = key(&["core_env", "hooks", "on_start"]);
+        hook.extend([Segment::Index(0), Segment::Key("command".into())]);
+        assert_eq!(at(&hook), Some("command"));
+        assert_eq!(at(&key(&["core_env", "missing"])), None);
+        assert_eq!(Dotted(&mount).to_string(), "core_env.mounts[0].contianer");
+    }
+
+    /// Test suggestions and the rendered layout.
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            did_you_mean("prots", ["mounts", "ports", "env_vars"]).as_deref(),
+            Some("did you mean `ports`?")
+        );
+        assert_eq!(did_you_mean("zzz", ["mounts", "ports"]), None);
+
+        let content = "[core_env]\nprots = []\n";
+        let diagnostic = Diagnostic {
+            message: "unknown key `prots` in `core_env`".into(),
+            span: key_span(content, &key(&["core_env", "prots"])),
+            help: Some("did you mean `ports`?".into()),
+        };
+        assert_eq!(
+            render(Path::new("/p/.devrs.toml"), content, &[diagnostic]),
+            "unknown key `prots` in `core_env`\n --> /p/.devrs.toml:2:1\n  |\n2 | prots = []\n  | ^^^^^\n  = help: did you mean `ports`?"
+        );
+    }
+
+    /// Test that masking keeps every other position.
+    #[test]
+    fn test_mask_keys() {
+        let content = "version = 2\ninclude = [\n  \"a.toml\",\n]\n[core_env]\nprots = 1\n";
+        let masked = mask_keys(content, &["include", "version"]);
+        assert_eq!(masked.len(), content.len());
+        assert_eq!(masked.find("prots"), content.find("prots"));
+        assert!(!masked.contains("include") && !masked.contains("version"));
+        assert_eq!(masked.lines().count(), content.lines().count());
+        assert_eq!(mask_keys("[core_env]\n", &["include"]), "[core_env]\n");
+    }
+}
@@ -0,0 +1,543 @@
+//! # DevRS Env Files
+//!
+//! File: cli/src/core/dotenv.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module reads `.env` files, so the variables a project already keeps in them
+//! can be passed into containers (`env_files` in `[core_env]` and `[apps.<name>]`,
+//! `devrs container run --env-file`):
+//!
+//! ```text
+//! # Database
+//! export DB_HOST=localhost
+//! DB_URL="postgres://${DB_USER:-dev}@${DB_HOST}/app"   # Expanded
+//! GREETING='Hello, $USER'                              # Literal
+//! CERT="-----BEGIN CERTIFICATE-----
+//! MIIB...
+//! -----END CERTIFICATE-----"
+//! ```
+//!
+//! ## Architecture
+//!
+//! - Syntax, as in Docker Compose and the dotenv libraries:
+//!   * One `KEY=VALUE` per line, optionally prefixed with `export`. Blank lines and
+//!     lines starting with `#` are ignored. A bare `KEY` passes the host's variable
+//!     through (and is skipped if the host does not set it).
+//!   * Unquoted values are trimmed; a `#` preceded by whitespace starts a comment.
+//!   * Single-quoted values are literal and may span lines.
+//!   * Double-quoted values may span lines and understand the escapes `\n`, `\r`,
+//!     `\t`, `\"`, `\\` and `\$`.
+//! - Unquoted and double-quoted values expand `$NAME`, `${NAME}`, `${NAME:-default}`
+//!   (unset or empty) and `${NAME-default}` (unset). Names resolve to variables defined
+//!   earlier in the file or in earlier files of the same list, then to the host
+//!   environment; anything else expands to an empty string.
+//! - Later files override earlier ones. Values set in the config (`env_vars`, `-e`)
+//!   override all files.
+//! - Values never appear in errors or logs: errors name the file, line and variable,
+//!   and env maps are logged through `Redacted`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::dotenv;
+//! # fn example(cfg: &crate::core::config::Config) -> anyhow::Result<()> {
+//! let mut env = dotenv::load_config_files(&cfg.core_env.env_files, "core_env.env_files")?;
+//! env.extend(cfg.core_env.env_vars.clone()); // The config overrides the files.
+//! tracing::debug!("Container environment: {}", dotenv::Redacted(&env));
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use std::{
+    collections::{BTreeSet, HashMap},
+    env, fmt, fs,
+    path::Path,
+}; // Reading files and the host environment.
+use tracing::{debug, warn}; // Logging framework utilities.
+
+/// A problem in an env file, without the value it concerns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The 1-based line the entry starts on.
+    pub line: usize,
+    /// What is wrong, naming the variable if known.
+    pub message: String,
+}
+
+/// Parses the content of an env file.
+///
+/// # Arguments
+///
+/// * `content` - The file's text.
+/// * `lookup` - Resolves names the file does not define itself (earlier files, the
+///   host environment); `None` means unset.
+///
+/// # Returns
+///
+/// * `Result<Vec<(String, String)>, Problem>` - The variables in file order (a variable
+///   set twice appears twice; the last wins), or the first problem.
+pub fn parse(
+    content: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> std::result::Result<Vec<(String, String)>, Problem> {
+    let mut parser = Parser {
+        rest: content,
+        line: 1,
+        defined: HashMap::new(),
+        lookup,
+    };
+    let mut vars = Vec::new();
+    while let Some(entry) = parser.entry()? {
+        parser.defined.insert(entry.0.clone(), entry.1.clone());
+        vars.push(entry);
+    }
+    Ok(vars)
+}
+
+/// The state of `parse`.
+struct Parser<'a> {
+    /// The unparsed text.
+    rest: &'a str,
+    /// The line `rest` starts on.
+    line: usize,
+    /// The variables parsed so far, for expansion.
+    defined: HashMap<String, String>,
+    /// Resolves names the file does not define.
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses the next entry, skipping blank lines and comments.
+    fn entry(&mut self) -> std::result::Result<Option<(String, String)>, Problem> {
+        loop {
+            self.skip_while(char::is_whitespace);
+            if self.rest.is_empty() {
+                return Ok(None);
+            }
+            if !self.rest.starts_with('#') {
+                break;
+            }
+            self.skip_while(|c| c != '\n');
+        }
+
+        let line = self.line;
+        let problem = |message: String| Problem { line, message };
+        if let Some(after) = self.rest.strip_prefix("export") {
+            if after.starts_with([' ', '\t']) {
+                self.rest = after;
+                self.skip_while(|c| c == ' ' || c == '\t');
+            }
+        }
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            let found: String = self.rest.chars().take_while(|c| *c != '\n').collect();
+            return Err(problem(format!(
+                "expected a variable name, found `{}`",
+                if name.is_empty() { found } else { name }
+            )));
+        }
+        self.skip_while(|c| c == ' ' || c == '\t');
+
+        // A bare name passes the host's variable through.
+        if self.rest.is_empty() || self.rest.starts_with(['\n', '\r', '#']) {
+            self.skip_while(|c| c != '\n');
+            return match (self.lookup)(&name) {
+                Some(value) => Ok(Some((name, value))),
+                None => {
+                    debug!("Env file line {}: {} is not set on the host.", line, name);
+                    self.entry()
+                }
+            };
+        }
+        let Some(after) = self.rest.strip_prefix('=') else {
+            return Err(problem(format!("expected `=` after `{}`", name)));
+        };
+        self.rest = after;
+        self.skip_while(|c| c == ' ' || c == '\t');
+
+        let value = match self.rest.chars().next() {
+            Some('\'') => {
+                self.rest = &self.rest[1..];
+                let Some(end) = self.rest.find('\'') else {
+                    return Err(problem(format!(
+                        "unterminated single-quoted value of `{}`",
+                        name
+                    )));
+                };
+                let value = self.advance(end).to_string();
+                self.rest = &self.rest[1..];
+                self.end_of_quoted(&name, line)?;
+                value
+            }
+            Some('"') => {
+                self.rest = &self.rest[1..];
+                let value = self.double_quoted(&name, line)?;
+                self.end_of_quoted(&name, line)?;
+                value
+            }
+            _ => {
+                let end = self.rest.find('\n').unwrap_or(self.rest.len());
+                let raw = self.advance(end);
+                // A `#` after whitespace starts a comment.
+                let raw = raw
+                    .char_indices()
+                    .find(|(i, c)| *c == '#' && raw[..*i].ends_with([' ', '\t']))
+                    .map_or(raw, |(i, _)| &raw[..i]);
+                self.expand_all(raw.trim(), &name, line)?
+            }
+        };
+        Ok(Some((name, value)))
+    }
+
+    /// Reads a double-quoted value after its opening quote, through the closing quote.
+    fn double_quoted(&mut self, name: &str, line: usize) -> std::result::Result<String, Problem> {
+        let mut value = String::new();
+        let mut rest = self.rest;
+        loop {
+            match next_char(&mut rest) {
+                Some('"') => break,
+                Some('\\') => match next_char(&mut rest) {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '$')) => value.push(c),
+                    Some(other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                    None => value.push('\\'),
+                },
+                Some('$') => value.push_str(&self.expand(&mut rest, name, line)?),
+                Some(c) => value.push(c),
+                None => {
+                    return Err(Problem {
+                        line,
+                        message: format!("unterminated double-quoted value of `{}`", name),
+                    })
+                }
+            }
+        }
+        self.advance(self.rest.len() - rest.len());
+        Ok(value)
+    }
+
+    /// Expands every variable reference in `text`.
+    fn expand_all(
+        &self,
+        mut text: &str,
+        name: &str,
+        line: usize,
+    ) -> std::result::Result<String, Problem> {
+        let mut value = String::new();
+        while let Some(c) = next_char(&mut text) {
+            if c == '$' {
+                value.push_str(&self.expand(&mut text, name, line)?);
+            } else {
+                value.push(c);
+            }
+        }
+        Ok(value)
+    }
+
+    /// Expands the variable reference at the start of `rest` (just after its `$`) and
+    /// consumes it. A `$` not followed by a name or `{` is kept.
+    fn expand(
+        &self,
+        rest: &mut &str,
+        name: &str,
+        line: usize,
+    ) -> std::result::Result<String, Problem> {
+        if let Some(braced) = rest.strip_prefix('{') {
+            let Some(end) = braced.find('}') else {
+                return Err(Problem {
+                    line,
+                    message: format!("unclosed `${{` in the value of `{}`", name),
+                });
+            };
+            let expression = &braced[..end];
+            *rest = &braced[end + 1..];
+            let (var, default, empty_is_unset) = match expression.split_once(":-") {
+                Some((var, default)) => (var, Some(default), true),
+                None => match expression.split_once('-') {
+                    Some((var, default)) => (var, Some(default), false),
+                    None => (expression, None, false),
+                },
+            };
+            let value = self
+                .resolve(var)
+                .filter(|value| !(empty_is_unset && value.is_empty()));
+            return match (value, default) {
+                (Some(value), _) => Ok(value),
+                (None, Some(default)) => self.expand_all(default, name, line),
+                (None, None) => Ok(String::new()),
+            };
+        }
+        let end = rest
+            .char_indices()
+            .find(|(i, c)| {
+                !(*c == '_' || c.is_ascii_alphabetic() || (*i > 0 && c.is_ascii_digit()))
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        if end == 0 {
+            return Ok("$".to_string());
+        }
+        let var = &rest[..end];
+        *rest = &rest[end..];
+        Ok(self.resolve(var).unwrap_or_default())
+    }
+
+    /// Resolves a name: earlier entries first, then `lookup`.
+    fn resolve(&self, var: &str) -> Option<String> {
+        self.defined
+            .get(var)
+            .cloned()
+            .or_else(|| (self.lookup)(var))
+    }
+
+    /// After a closing quote, only whitespace and a comment may follow on the line.
+    fn end_of_quoted(&mut self, name: &str, line: usize) -> std::result::Result<(), Problem> {
+        let end = self.rest.find('\n').unwrap_or(self.rest.len());
+        let trailing = self.rest[..end].trim();
+        if !trailing.is_empty() && !trailing.starts_with('#') {
+            return Err(Problem {
+                line,
+                message: format!(
+                    "unexpected text after the closing quote of `{}` (quote the whole value)",
+                    name
+                ),
+            });
+        }
+        self.advance(end);
+        Ok(())
+    }
+
+    /// Skips characters while `keep` holds.
+    fn skip_while(&mut self, keep: impl Fn(char) -> bool) {
+        self.take_while(keep);
+    }
+
+    /// Takes characters while `keep` holds.
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let end = self
+            .rest
+            .char_indices()
+            .find(|(_, c)| !keep(*c))
+            .map_or(self.rest.len(), |(i, _)| i);
+        self.advance(end).to_string()
+    }
+
+    /// Consumes `length` bytes, counting the lines they span.
+    fn advance(&mut self, length: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(length);
+        self.line += taken.matches('\n').count();
+        self.rest = rest;
+        taken
+    }
+}
+
+/// Reads env files in order; later files override earlier ones.
+///
+/// # Arguments
+///
+/// * `paths` - The files.
+///
+/// # Returns
+///
+/// * `Result<HashMap<String, String>>` - The variables of all files.
+///
+/// # Errors
+///
+/// Returns `DevrsError::FileSystem` if a file cannot be read, or `DevrsError::Config`
+/// naming the file, line and variable if one is malformed.
+pub fn load_files<P: AsRef<Path>>(paths: &[P]) -> Result<HashMap<String, String>> {
+    let mut vars: HashMap<String, String> = HashMap::new();
+    for path in paths {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to read env file '{}': {}",
+                path.display(),
+                e
+            )))
+        })?;
+        let parsed = parse(&content, &|name| {
+            vars.get(name).cloned().or_else(|| env::var(name).ok())
+        })
+        .map_err(|problem| {
+            anyhow!(DevrsError::Config(format!(
+                "Invalid env file {}:{}: {}",
+                path.display(),
+                problem.line,
+                problem.message
+            )))
+        })?;
+        let names: BTreeSet<&str> = parsed.iter().map(|(name, _)| name.as_str()).collect();
+        debug!(
+            "Loaded {} variable(s) from {}: {}",
+            names.len(),
+            path.display(),
+            names.into_iter().collect::<Vec<_>>().join(", ")
+        );
+        vars.extend(parsed);
+    }
+    Ok(vars)
+}
+
+/// Reads the `env_files` of a config section. Files that do not exist (e.g. a
+/// `.env.local` a developer has not created) are skipped with a warning.
+///
+/// # Arguments
+///
+/// * `paths` - The configured files (absolute after loading the config).
+/// * `setting` - The setting they come from, for the warning, e.g. `core_env.env_files`.
+///
+/// # Errors
+///
+/// As `load_files`.
+pub fn load_config_files(paths: &[String], setting: &str) -> Result<HashMap<String, String>> {
+    let existing: Vec<&String> = paths
+        .iter()
+        .filter(|path| {
+            let exists = Path::new(path).is_file();
+            if !exists {
+                warn!(
+                    "Env file '{}' ({}) does not exist; skipping it.",
+                    path, setting
+                );
+            }
+            exists
+        })
+        .collect();
+    load_files(&existing)
+}
+
+/// # Redacted Environment (`Redacted`)
+///
+/// Displays the names of environment variables with their values hidden
+/// (`A=***, B=***`), for logging.
+pub struct Redacted<'a>(pub &'a HashMap<String, String>);
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: BTreeSet<&String> = self.0.keys().collect();
+        let names: Vec<String> = names
+            .into_iter()
+            .map(|name| format!("{}=***", name))
+            .collect();
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+/// Hides the value of a `KEY=VALUE` assignment (`KEY=***`), for logging.
+pub fn redact_assignment(assignment: &str) -> String {
+    match assignment.split_once('=') {
+        Some((name, _)) => format!("{}=***", name),
+        None => assignment.to_string(),
+    }
+}
+
+/// Takes the first character of `rest`.
+fn next_char(rest: &mut &str) -> Option<char> {
+    let c = rest.chars().next()?;
+    *rest = &rest[c.len_utf8()..];
+    Some(c)
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses with a fixed host environment.
+    fn parsed(content: &str) -> std::result::Result<Vec<(String, String)>, Problem> {
+        parse(content, &|name| match name {
+            "HOST_VAR" => Some("host".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        })
+    }
+
+    /// Test quoting, escapes, comments, `export`, multiline values and expansion.
+    #[test]
+    fn test_parse() {
+        let content = r#"
+# A comment
+export A=plain value   # trailing comment
+B = 'single $A \n # kept'
+C="double \"$A\"\tand\\ ${B}"
+D="line 1
+line 2"
+E=${MISSING:-fallback}-${EMPTY-unset}-${EMPTY:-empty}-$HOST_VAR-$NOPE.
+F=a#b
+G='multi
+line'  # comment
+HOST_VAR
+NOT_ON_HOST
+H="price \$5 ${MISSING:-$A}"
+"#;
+        let vars = parsed(content).unwrap();
+        let get = |name: &str| {
+            vars.iter()
+                .rev()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("A"), Some("plain value"));
+        assert_eq!(get("B"), Some("single $A \\n # kept"));
+        assert_eq!(
+            get("C"),
+            Some("double \"plain value\"\tand\\ single $A \\n # kept")
+        );
+        assert_eq!(get("D"), Some("line 1\nline 2"));
+        assert_eq!(get("E"), Some("fallback--empty-host-."));
+        assert_eq!(get("F"), Some("a#b"));
+        assert_eq!(get("G"), Some("multi\nline"));
+        assert_eq!(get("HOST_VAR"), Some("host"));
+        assert_eq!(get("NOT_ON_HOST"), None);
+        assert_eq!(get("H"), Some("price $5 plain value"));
+    }
+
+    /// Test that problems name the line and variable, never the value.
+    #[test]
+    fn test_parse_problems() {
+        let problem = parsed("A=1\n\nB=\"secret\nC=2\n").unwrap_err();
+        assert_eq!(problem.line, 3);
+        assert!(problem.message.contains("`B`"));
+        assert!(!problem.message.contains("secret"));
+        assert_eq!(parsed("A=1\nB 2\n").unwrap_err().line, 2);
+        assert!(parsed("X='a' b\n").is_err());
+        assert!(parsed("1X=a\n").is_err());
+        assert!(parsed("X=${UNCLOSED\n").is_err());
+    }
+
+    /// Test that later files override earlier ones and can refer to them, and that
+    /// missing config files are skipped.
+    #[test]
+    fn test_load_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join(".env");
+        let second = dir.path().join(".env.local");
+        fs::write(&first, "USER_NAME=dev\nDB=postgres\n").unwrap();
+        fs::write(&second, "DB=\"${DB}://${USER_NAME}@db\"\n").unwrap();
+        let vars = load_files(&[&first, &second]).unwrap();
+        assert_eq!(vars["DB"], "postgres://dev@db");
+        assert!(load_files(&[dir.path().join("missing")]).is_err());
+
+        let configured = vec![
+            first.display().to_string(),
+            dir.path().join("absent").display().to_string(),
+        ];
+        let vars = load_config_files(&configured, "core_env.env_files").unwrap();
+        assert_eq!(vars.len(), 2);
+        assert_eq!(Redacted(&vars).to_string(), "DB=***, USER_NAME=***");
+        assert_eq!(redact_assignment("TOKEN=abc=def"), "TOKEN=***");
+    }
+}
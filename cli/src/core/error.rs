@@ -89,6 +89,9 @@ pub enum DevrsError {
     #[error("Image '{name}' is in use by one or more containers.")]
     ImageInUse { name: String },
 
+    #[error("Volume '{name}' is in use by one or more containers.")]
+    VolumeInUse { name: String },
+
     #[error("Template rendering error: {source}")]
     Template {
         #[from]
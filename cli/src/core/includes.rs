@@ -0,0 +1,281 @@
+//! # DevRS Configuration Includes
+//!
+//! File: cli/src/core/includes.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module resolves `include` in config files, so a team can keep a shared
+//! baseline (mounts, ports, apps, env vars) in one place and each project or developer
+//! only writes what differs:
+//!
+//! ```toml
+//! include = ["~/team-devrs/base.toml", "../shared/devrs.d/*.toml"]
+//!
+//! [core_env]
+//! image_tag = "v3"   # Overrides the included files
+//! ```
+//!
+//! ## Architecture
+//!
+//! - `include` is a list of file paths at the top level of a config file. It is a
+//!   directive, not a setting: it is removed when the file is loaded and never appears
+//!   in the merged configuration.
+//! - Each included file becomes a layer directly beneath the file that includes it, so
+//!   the including file overrides them, and later entries override earlier ones. Lists
+//!   and maps combine according to `[merge]`, as between any other layers.
+//! - Paths are interpolated like other values (`${PROJECT_ROOT}/...`), may start with
+//!   `~`, and are relative to the directory of the including file.
+//! - Entries containing `*`, `?` or `[` are glob patterns, expanded in alphabetical
+//!   order; a pattern without matches is skipped with a warning. A plain path that does
+//!   not exist is an error.
+//! - Included files may include further files. A file that (indirectly) includes itself
+//!   is an error naming the cycle; a file included twice by the same config is loaded
+//!   once, at its first position.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::includes::{self, Chain};
+//! # fn example(mut table: toml::Table, path: &std::path::Path) -> anyhow::Result<()> {
+//! let mut chain = Chain::default();
+//! chain.enter(path)?;
+//! for file in includes::take_includes(&mut table, path)? {
+//!     if chain.enter(&file)? {
+//!         // ... load `file` (and its includes) as a layer ...
+//!         chain.leave();
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+}; // Resolving and tracking included files.
+use toml::{Table, Value}; // Layers are plain TOML tables.
+use tracing::{debug, warn}; // Logging framework utilities.
+
+/// The top-level key listing the files a config file includes.
+pub const INCLUDE_KEY: &str = "include";
+
+/// Characters that make an include entry a glob pattern.
+const GLOB_CHARS: &[char] = &['*', '?', '['];
+
+/// Removes `include` from a config file's table and resolves its entries.
+///
+/// # Arguments
+///
+/// * `table` - The file's settings, already interpolated.
+/// * `path` - The file, the base of relative entries.
+///
+/// # Returns
+///
+/// * `Result<Vec<PathBuf>>` - The included files, in order (lowest precedence first).
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if `include` is not a list of strings, or names a file
+/// that does not exist.
+pub fn take_includes(table: &mut Table, path: &Path) -> Result<Vec<PathBuf>> {
+    let Some(value) = table.remove(INCLUDE_KEY) else {
+        return Ok(Vec::new());
+    };
+    let entries = match value {
+        Value::Array(entries) => entries,
+        _ => return Err(not_a_list(path)),
+    };
+    let base = match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => std::path::absolute(dir),
+        None => std::env::current_dir(),
+    }
+    .map_err(|e| {
+        anyhow!(DevrsError::FileSystem(format!(
+            "Failed to resolve the directory of {}: {}",
+            path.display(),
+            e
+        )))
+    })?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let Value::String(entry) = entry else {
+            return Err(not_a_list(path));
+        };
+        files.extend(resolve(&entry, &base, path)?);
+    }
+    Ok(files)
+}
+
+/// The error for an `include` that is not a list of paths.
+fn not_a_list(path: &Path) -> anyhow::Error {
+    anyhow!(DevrsError::Config(format!(
+        "`{}` in {} must be a list of file paths, e.g. include = [\"base.toml\"].",
+        INCLUDE_KEY,
+        path.display()
+    )))
+}
+
+/// Resolves one `include` entry to the files it names.
+fn resolve(entry: &str, base: &Path, including: &Path) -> Result<Vec<PathBuf>> {
+    let target = base.join(shellexpand::tilde(entry).as_ref());
+    if !entry.contains(GLOB_CHARS) {
+        if !target.is_file() {
+            return Err(anyhow!(DevrsError::Config(format!(
+                "'{}' (included by {}) does not exist or is not a file.",
+                target.display(),
+                including.display()
+            ))));
+        }
+        return Ok(vec![canonical(&target)]);
+    }
+
+    let pattern = target.to_string_lossy();
+    let matches = glob::glob(&pattern).map_err(|e| {
+        anyhow!(DevrsError::Config(format!(
+            "Invalid include pattern '{}' in {}: {}",
+            entry,
+            including.display(),
+            e
+        )))
+    })?;
+    let mut files: Vec<PathBuf> = matches
+        .filter_map(|m| m.ok())
+        .filter(|p| p.is_file())
+        .map(|p| canonical(&p))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        warn!(
+            "Include pattern '{}' in {} matches no files.",
+            entry,
+            including.display()
+        );
+    }
+    Ok(files)
+}
+
+/// The canonical form of an existing file (`..` and symlinks resolved), for messages
+/// and cycle detection.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// # Include Chain (`Chain`)
+///
+/// The files being loaded for one config file, to detect include cycles and files
+/// included more than once.
+#[derive(Debug, Default)]
+pub struct Chain {
+    /// The files currently being loaded, outermost first.
+    open: Vec<PathBuf>,
+    /// Every file entered so far.
+    loaded: HashSet<PathBuf>,
+}
+
+impl Chain {
+    /// Starts loading a file. Call `leave` once it and its includes are loaded.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - `false` if the file was already loaded (skip it; `leave`
+    ///   must not be called).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::Config` naming the cycle if the file is one of the files
+    /// being loaded, i.e. it (indirectly) includes itself.
+    pub fn enter(&mut self, path: &Path) -> Result<bool> {
+        let file = fs::canonicalize(path)
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf());
+        if let Some(start) = self.open.iter().position(|open| *open == file) {
+            let cycle: Vec<String> = self.open[start..]
+                .iter()
+                .chain([&file])
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(anyhow!(DevrsError::Config(format!(
+                "Include cycle: {}",
+                cycle.join(" -> ")
+            ))));
+        }
+        if !self.loaded.insert(file.clone()) {
+            debug!("{} is already included, skipping it.", file.display());
+            return Ok(false);
+        }
+        self.open.push(file);
+        Ok(true)
+    }
+
+    /// Finishes loading the most recently entered file.
+    pub fn leave(&mut self) {
+        self.open.pop();
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that entries resolve relative to the including file, globs expand in order,
+    /// and missing files are errors.
+    #[test]
+    fn test_take_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = &fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(root.join("team.d")).unwrap();
+        for file in [
+            "base.toml",
+            "team.d/b.toml",
+            "team.d/a.toml",
+            "team.d/skip.txt",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let including = root.join("config.toml");
+        let mut table: Table =
+            toml::from_str("include = [\"base.toml\", \"team.d/*.toml\", \"none/*.toml\"]\n")
+                .unwrap();
+        let files = take_includes(&mut table, &including).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                root.join("base.toml"),
+                root.join("team.d/a.toml"),
+                root.join("team.d/b.toml"),
+            ]
+        );
+        assert!(table.is_empty(), "`include` is removed from the settings");
+
+        let mut table: Table = toml::from_str("include = [\"missing.toml\"]\n").unwrap();
+        let err = take_includes(&mut table, &including).unwrap_err();
+        assert!(err.to_string().contains("missing.toml"));
+        let mut table: Table = toml::from_str("include = \"base.toml\"\n").unwrap();
+        assert!(take_includes(&mut table, &including).is_err());
+    }
+
+    /// Test that cycles are reported and repeated files are skipped.
+    #[test]
+    fn test_chain() {
+        let mut chain = Chain::default();
+        assert!(chain.enter(Path::new("/t/a.toml")).unwrap());
+        assert!(chain.enter(Path::new("/t/b.toml")).unwrap());
+        let err = chain.enter(Path::new("/t/a.toml")).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Include cycle: /t/a.toml -> /t/b.toml -> /t/a.toml"));
+        chain.leave();
+        assert!(!chain.enter(Path::new("/t/b.toml")).unwrap());
+        assert!(chain.enter(Path::new("/t/c.toml")).unwrap());
+    }
+}
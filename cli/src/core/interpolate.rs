@@ -0,0 +1,362 @@
+//! # DevRS Configuration Interpolation
+//!
+//! File: cli/src/core/interpolate.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module substitutes variables in configuration values, so one config file
+//! works across machines and checkouts:
+//!
+//! ```toml
+//! [core_env]
+//! image_tag = "${DEVRS_TAG:-latest}"
+//! ports = ["${WEB_PORT:-8080}:8080"]
+//! mounts = [{ host = "./data", container = "/data" }]   # Relative to this file
+//! env_vars = { CACHE = "${CONFIG_DIR}/cache", OWNER = "${USER}" }
+//! ```
+//!
+//! ## Architecture
+//!
+//! - Every string value of a layer is interpolated when the layer is loaded (before
+//!   merging), so `${CONFIG_DIR}` and relative paths refer to the file that contains them.
+//! - Syntax: `${NAME}` and `${NAME:-default}` (the default applies when the variable is
+//!   unset or empty). `$${` produces a literal `${`, e.g. for hook commands that should
+//!   expand a variable inside the container. A `$` not followed by `{` is kept as-is.
+//! - Variables:
+//!   * `PROJECT_ROOT`: The directory holding `.devrs.toml`, else the repository root
+//!     (`.git`), else the current directory.
+//!   * `CONFIG_DIR`: The directory of the config file (the current directory for
+//!     `DEVRS_*` variables and `--set` flags).
+//!   * `HOME`, `USER`: From the environment, falling back to the home directory and
+//!     login name of the current user.
+//!   * Anything else: The environment variable of that name.
+//! - A variable that cannot be resolved (unset, without default) is an error naming
+//!   the key that uses it, e.g. `core_env.mounts[1].host`.
+//! - Relative mount host paths (`core_env.mounts`, `apps.<name>.mounts`) and env files
+//!   (`env_files`) are resolved against `CONFIG_DIR`. Paths starting with `~` are left
+//!   to tilde expansion.
+//! - The devcontainer.json layer is not interpolated; `core::devcontainer` substitutes
+//!   devcontainer variables itself.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::interpolate::{self, Vars};
+//! # fn example(mut table: toml::Table) -> anyhow::Result<()> {
+//! let vars = Vars::new("/work/app".into(), "/work/app".into());
+//! interpolate::interpolate_layer(&mut table, &vars)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use std::{
+    env,
+    path::{Component, Path, PathBuf},
+}; // Variable lookup and path resolution.
+use toml::{Table, Value}; // Layers are plain TOML tables.
+
+/// # Interpolation Variables (`Vars`)
+///
+/// The values of the built-in variables for one layer.
+#[derive(Debug, Clone)]
+pub struct Vars {
+    /// `${PROJECT_ROOT}`.
+    pub project_root: PathBuf,
+    /// `${CONFIG_DIR}`, also the base of relative mount paths.
+    pub config_dir: PathBuf,
+}
+
+impl Vars {
+    /// Creates the variables for a layer.
+    pub fn new(project_root: PathBuf, config_dir: PathBuf) -> Self {
+        Self {
+            project_root,
+            config_dir,
+        }
+    }
+
+    /// Resolves a variable name (see the module documentation).
+    fn lookup(&self, name: &str) -> Option<String> {
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+        match name {
+            "PROJECT_ROOT" => Some(self.project_root.to_string_lossy().into_owned()),
+            "CONFIG_DIR" => Some(self.config_dir.to_string_lossy().into_owned()),
+            "HOME" => env::var("HOME")
+                .ok()
+                .and_then(non_empty)
+                .or_else(|| dirs::home_dir().map(|home| home.to_string_lossy().into_owned())),
+            "USER" => env::var("USER")
+                .ok()
+                .and_then(non_empty)
+                .or_else(|| env::var("LOGNAME").ok().and_then(non_empty)),
+            _ => env::var(name).ok(),
+        }
+    }
+}
+
+/// Interpolates every string in a layer and resolves relative mount host paths and
+/// env files.
+///
+/// # Arguments
+///
+/// * `table` - The layer's settings, modified in place.
+/// * `vars` - The variables for this layer.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` naming the key and variable if a variable cannot be
+/// resolved or a `${` is not closed.
+pub fn interpolate_layer(table: &mut Table, vars: &Vars) -> Result<()> {
+    interpolate_table(table, "", &|name| vars.lookup(name))?;
+    resolve_relative_paths(table, &vars.config_dir);
+    Ok(())
+}
+
+/// Interpolates the strings of a table, recursively. `lookup` resolves variable names.
+fn interpolate_table(
+    table: &mut Table,
+    prefix: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    for (key, value) in table.iter_mut() {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        interpolate_value(value, &path, lookup)?;
+    }
+    Ok(())
+}
+
+/// Interpolates one value: strings directly, arrays and tables element by element.
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            *s = interpolate_str(s, lookup).map_err(|problem| {
+                anyhow!(DevrsError::Config(format!("{} in '{}'", problem, path)))
+            })?;
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                interpolate_value(item, &format!("{}[{}]", path, index), lookup)?;
+            }
+        }
+        Value::Table(inner) => interpolate_table(inner, path, lookup)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Substitutes the variables in one string.
+///
+/// # Arguments
+///
+/// * `input` - The string, e.g. `"${WEB_PORT:-8080}:8080"`.
+/// * `lookup` - Resolves a variable name; `None` means unset.
+///
+/// # Returns
+///
+/// * `Result<String, String>` - The interpolated string, or a description of the problem.
+pub fn interpolate_str(
+    input: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        // `$${` is an escaped `${`.
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let Some(length) = rest[start..].find('}') else {
+            return Err(format!("Unclosed '${{' in \"{}\"", input));
+        };
+        let expression = &rest[start + 2..start + length];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+        let resolved = match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Some(value), _) => value,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => {
+                return Err(format!(
+                    "Unresolved variable '${{{}}}' (set it, or give a default with '${{{}:-...}}')",
+                    name, name
+                ))
+            }
+        };
+        out.push_str(&resolved);
+        rest = &rest[start + length + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Makes relative mount host paths and env files absolute, relative to `base`.
+fn resolve_relative_paths(table: &mut Table, base: &Path) {
+    let mut sections: Vec<&mut Table> = Vec::new();
+    for (section, value) in table.iter_mut() {
+        match (section.as_str(), value) {
+            ("core_env", Value::Table(core)) => sections.push(core),
+            ("apps", Value::Table(apps)) => {
+                for (_, app) in apps.iter_mut() {
+                    if let Value::Table(app) = app {
+                        sections.push(app);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    for section in sections {
+        if let Some(Value::Array(mounts)) = section.get_mut("mounts") {
+            for mount in mounts {
+                if let Some(Value::String(host)) = mount.get_mut("host") {
+                    if let Some(absolute) = resolve_relative(base, host) {
+                        *host = absolute;
+                    }
+                }
+            }
+        }
+        if let Some(Value::Array(files)) = section.get_mut("env_files") {
+            for file in files {
+                if let Value::String(file) = file {
+                    if let Some(absolute) = resolve_relative(base, file) {
+                        *file = absolute;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Joins a relative path onto `base`, dropping `.` components.
+///
+/// # Returns
+///
+/// * `Option<String>` - The absolute path, or `None` if `path` is absolute, empty or
+///   starts with `~`.
+fn resolve_relative(base: &Path, path: &str) -> Option<String> {
+    if path.is_empty() || path.starts_with('~') || Path::new(path).is_absolute() {
+        return None;
+    }
+    let joined: PathBuf = base
+        .join(path)
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .collect();
+    Some(joined.to_string_lossy().into_owned())
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A lookup over fixed variables.
+    fn fixed(name: &str) -> Option<String> {
+        match name {
+            "PORT" => Some("3000".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    /// Test variables, defaults and escapes.
+    #[test]
+    fn test_interpolate_str() {
+        assert_eq!(interpolate_str("${PORT}:80", &fixed).unwrap(), "3000:80");
+        assert_eq!(
+            interpolate_str("${MISSING:-8080}:80", &fixed).unwrap(),
+            "8080:80"
+        );
+        assert_eq!(interpolate_str("${EMPTY:-x}", &fixed).unwrap(), "x");
+        assert_eq!(interpolate_str("${EMPTY}", &fixed).unwrap(), "");
+        assert_eq!(interpolate_str("${MISSING:-}", &fixed).unwrap(), "");
+        assert_eq!(
+            interpolate_str("echo $${HOME} $PATH $$", &fixed).unwrap(),
+            "echo ${HOME} $PATH $$"
+        );
+        let err = interpolate_str("${MISSING}", &fixed).unwrap_err();
+        assert!(err.contains("${MISSING}"));
+        assert!(interpolate_str("${PORT", &fixed).is_err());
+    }
+
+    /// Test that errors name the key, and that built-ins, relative mounts and env files
+    /// resolve.
+    #[test]
+    fn test_interpolate_layer() {
+        let vars = Vars::new("/work".into(), "/work/app".into());
+        let mut table: Table = toml::from_str(
+            r#"
+            [core_env]
+            env_vars = { CACHE = "${CONFIG_DIR}/cache", ROOT = "${PROJECT_ROOT}" }
+            mounts = [
+              { host = "./data", container = "/data" },
+              { host = "../shared", container = "/shared" },
+              { host = "~/code", container = "/code" },
+              { host = "/abs", container = "/abs" },
+            ]
+            env_files = [".env", "${PROJECT_ROOT}/.env.local"]
+            [apps.db]
+            mounts = [{ host = "db", container = "/var/lib/db" }]
+            env_files = ["db.env"]
+            "#,
+        )
+        .unwrap();
+        interpolate_layer(&mut table, &vars).unwrap();
+        let core = &table["core_env"];
+        assert_eq!(core["env_vars"]["CACHE"].as_str(), Some("/work/app/cache"));
+        assert_eq!(core["env_vars"]["ROOT"].as_str(), Some("/work"));
+        let hosts: Vec<&str> = core["mounts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["host"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            hosts,
+            vec!["/work/app/data", "/work/app/../shared", "~/code", "/abs"]
+        );
+        assert_eq!(
+            table["apps"]["db"]["mounts"][0]["host"].as_str(),
+            Some("/work/app/db")
+        );
+        assert_eq!(
+            core["env_files"],
+            toml::Value::from(vec!["/work/app/.env", "/work/.env.local"])
+        );
+        assert_eq!(
+            table["apps"]["db"]["env_files"][0].as_str(),
+            Some("/work/app/db.env")
+        );
+
+        let mut table: Table = toml::from_str(
+            "[core_env]\nports = [\"1:1\", \"${DEVRS_TEST_SURELY_UNSET_VAR}:80\"]\n",
+        )
+        .unwrap();
+        let err = interpolate_layer(&mut table, &vars)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("core_env.ports[1]"), "{}", err);
+        assert!(err.contains("DEVRS_TEST_SURELY_UNSET_VAR"), "{}", err);
+    }
+}
@@ -0,0 +1,747 @@
+//! # DevRS Configuration Layers
+//!
+//! File: cli/src/core/layers.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module implements the layering model behind `config::load_config`. Every
+//! configuration source is a *layer*: a TOML table holding only the settings that
+//! source sets explicitly. Layers are merged in order of increasing precedence:
+//!
+//! 1. Built-in defaults (applied when the merged table is deserialized)
+//! 2. System config, `/etc/devrs/config.toml`
+//! 3. Team configs registered with `devrs config team add` (see `core::team`)
+//! 4. User config, `~/.config/devrs/config.toml`
+//! 5. The project's devcontainer.json (see `core::devcontainer`)
+//! 6. Project config, `.devrs.toml`
+//! 7. `DEVRS_*` environment variables, e.g. `DEVRS_CORE_ENV__IMAGE_TAG=v3`
+//! 8. `devrs --set KEY=VALUE` flags (command-specific flags such as `--name` still win)
+//!
+//! A config file's `include = [...]` files (see `core::includes`) are layers directly
+//! beneath the file that includes them.
+//!
+//! Because only explicitly set keys are present, a project that sets
+//! `image_tag = "latest"` overrides a user's `"v2"`, even though `"latest"` is the default.
+//!
+//! ## Architecture
+//!
+//! - Scalars are replaced by the higher layer; sections (`[core_env]`, ...) merge key by key.
+//! - Lists and maps follow a `MergeStrategy`, configurable per field in a `[merge]` table
+//!   in any layer (e.g. `"core_env.mounts" = "replace"`); `DEFAULT_STRATEGIES` lists the
+//!   fields and their defaults:
+//!   * `replace`: the higher layer's value replaces the lower one.
+//!   * `append`: lists are concatenated (lower first); maps behave like `merge`.
+//!   * `merge`: maps are merged by key (higher wins); lists of tables are merged by their
+//!     key field (`container` for mounts, `command` for hooks); other lists are unioned.
+//! - Environment variables: `DEVRS_<SECTION>__<KEY>[__<KEY>...]`, where `__` separates
+//!   levels. Field names are lowercased; map keys (variable names in `env_vars`, build
+//!   args, app names) are kept as written. Only variables naming a known section are used.
+//! - Override values (`DEVRS_*`, `--set`) are parsed as TOML booleans, integers, arrays or
+//!   inline tables when possible and as strings otherwise; quote a value (`'"2"'`) to force a string.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::layers::{self, Layer, LayerSource};
+//! # fn example(user: toml::Table, project: toml::Table) {
+//! let layers = vec![
+//!     Layer { source: LayerSource::User("config.toml".into()), table: user },
+//!     Layer { source: LayerSource::Project(".devrs.toml".into()), table: project },
+//! ];
+//! let merged: toml::Table = layers::merge_layers(&layers);
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use schemars::JsonSchema; // `devrs config schema`.
+use serde::{Deserialize, Serialize}; // For the strategy names in `[merge]`.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+}; // Strategy lookup, layer sources and provenance.
+use toml::{Table, Value}; // Layers are plain TOML tables.
+
+/// Prefix of environment variables that override configuration keys.
+pub const ENV_PREFIX: &str = "DEVRS_";
+
+/// # Layer Source (`LayerSource`)
+///
+/// Where a configuration layer came from, in order of increasing precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerSource {
+    /// The system-wide config file.
+    System(PathBuf),
+    /// The config file of a registered team (`devrs config team add`).
+    Team(PathBuf),
+    /// The user's config file.
+    User(PathBuf),
+    /// The project's devcontainer.json.
+    Devcontainer(PathBuf),
+    /// The project's `.devrs.toml`.
+    Project(PathBuf),
+    /// A file named in another config file's `include`.
+    Include(PathBuf),
+    /// `DEVRS_*` environment variables.
+    Env,
+    /// `--set` command-line flags.
+    Cli,
+}
+
+impl fmt::Display for LayerSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerSource::System(path) => write!(f, "system config ({})", path.display()),
+            LayerSource::Team(path) => write!(f, "team config ({})", path.display()),
+            LayerSource::User(path) => write!(f, "user config ({})", path.display()),
+            LayerSource::Devcontainer(path) => write!(f, "devcontainer ({})", path.display()),
+            LayerSource::Project(path) => write!(f, "project config ({})", path.display()),
+            LayerSource::Include(path) => write!(f, "included config ({})", path.display()),
+            LayerSource::Env => write!(f, "environment ({}*)", ENV_PREFIX),
+            LayerSource::Cli => write!(f, "command line (--set)"),
+        }
+    }
+}
+
+impl LayerSource {
+    /// The DevRS config file the layer was read from, if it was read from one
+    /// (devcontainer.json is translated, not a config file).
+    pub fn config_file(&self) -> Option<&Path> {
+        match self {
+            LayerSource::System(path)
+            | LayerSource::Team(path)
+            | LayerSource::User(path)
+            | LayerSource::Project(path)
+            | LayerSource::Include(path) => Some(path),
+            LayerSource::Devcontainer(_) | LayerSource::Env | LayerSource::Cli => None,
+        }
+    }
+}
+
+/// # Configuration Layer (`Layer`)
+///
+/// The settings one source sets explicitly.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    /// Where the settings came from.
+    pub source: LayerSource,
+    /// The explicitly set keys, in the same shape as `config.toml`.
+    pub table: Table,
+}
+
+/// # Merge Strategy (`MergeStrategy`)
+///
+/// How a list or map in a higher layer combines with the same field below it.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// The higher layer's value replaces the lower one.
+    Replace,
+    /// Lists are concatenated, lower layer first (maps merge by key).
+    Append,
+    /// Maps merge by key; lists of tables merge by their key field; other lists are unioned.
+    Merge,
+}
+
+/// The lists and maps whose merge strategy can be configured, with their defaults.
+pub const DEFAULT_STRATEGIES: &[(&str, MergeStrategy)] = &[
+    ("core_env.mounts", MergeStrategy::Merge),
+    ("core_env.ports", MergeStrategy::Replace),
+    ("core_env.env_vars", MergeStrategy::Merge),
+    ("core_env.env_files", MergeStrategy::Merge),
+    ("core_env.secrets", MergeStrategy::Merge),
+    ("core_env.build.args", MergeStrategy::Merge),
+    ("core_env.hooks.on_create", MergeStrategy::Append),
+    ("core_env.hooks.on_start", MergeStrategy::Append),
+    ("core_env.hooks.on_shell", MergeStrategy::Append),
+    ("core_env.persist.paths", MergeStrategy::Merge),
+    ("application_defaults.default_ports", MergeStrategy::Replace),
+    ("shims.allow", MergeStrategy::Replace),
+    ("lint.allow", MergeStrategy::Merge),
+    // A project app replaces a user app of the same name as a whole.
+    ("apps", MergeStrategy::Merge),
+];
+
+/// The field identifying an entry of a list of tables under `MergeStrategy::Merge`.
+fn merge_key(path: &str) -> Option<&'static str> {
+    match path {
+        "core_env.mounts" => Some("container"),
+        p if p.starts_with("core_env.hooks.") => Some("command"),
+        _ => None,
+    }
+}
+
+/// Collects the effective merge strategies: the defaults, overridden by the `[merge]`
+/// tables of the layers (higher layers win). Unknown fields and strategy names are
+/// ignored here; validation of the merged configuration reports them.
+///
+/// # Arguments
+///
+/// * `layers` - The layers, in order of increasing precedence.
+///
+/// # Returns
+///
+/// * `HashMap<String, MergeStrategy>` - Strategy per dotted field path.
+pub fn strategies(layers: &[Layer]) -> HashMap<String, MergeStrategy> {
+    let mut strategies: HashMap<String, MergeStrategy> = DEFAULT_STRATEGIES
+        .iter()
+        .map(|(path, strategy)| (path.to_string(), *strategy))
+        .collect();
+    for layer in layers {
+        let Some(Value::Table(overrides)) = layer.table.get("merge") else {
+            continue;
+        };
+        for (path, value) in overrides {
+            if let Ok(strategy) = value.clone().try_into::<MergeStrategy>() {
+                if strategies.contains_key(path) {
+                    strategies.insert(path.clone(), strategy);
+                }
+            }
+        }
+    }
+    strategies
+}
+
+/// Merges layers, lowest precedence first, into a single table.
+///
+/// # Arguments
+///
+/// * `layers` - The layers, in order of increasing precedence.
+///
+/// # Returns
+///
+/// * `Table` - The merged settings (keys no layer sets are absent).
+pub fn merge_layers(layers: &[Layer]) -> Table {
+    let strategies = strategies(layers);
+    let mut merged = Table::new();
+    for layer in layers {
+        merge_table(&mut merged, layer.table.clone(), "", &strategies);
+    }
+    merged
+}
+
+/// Merges `upper` into `base`, key by key.
+fn merge_table(
+    base: &mut Table,
+    upper: Table,
+    prefix: &str,
+    strategies: &HashMap<String, MergeStrategy>,
+) {
+    for (key, value) in upper {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match base.remove(&key) {
+            Some(lower) => {
+                let merged = merge_value(lower, value, &path, strategies);
+                base.insert(key, merged);
+            }
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Combines the values of one field from two layers.
+fn merge_value(
+    lower: Value,
+    upper: Value,
+    path: &str,
+    strategies: &HashMap<String, MergeStrategy>,
+) -> Value {
+    let strategy = strategies.get(path).copied();
+    match (lower, upper) {
+        (Value::Table(mut lower), Value::Table(upper)) => match strategy {
+            Some(MergeStrategy::Replace) => Value::Table(upper),
+            // Maps: entries are replaced as a whole.
+            Some(_) => {
+                lower.extend(upper);
+                Value::Table(lower)
+            }
+            // Sections: merged recursively.
+            None => {
+                merge_table(&mut lower, upper, path, strategies);
+                Value::Table(lower)
+            }
+        },
+        (Value::Array(mut lower), Value::Array(upper)) => match strategy {
+            Some(MergeStrategy::Append) => {
+                lower.extend(upper);
+                Value::Array(lower)
+            }
+            Some(MergeStrategy::Merge) => Value::Array(merge_lists(lower, upper, merge_key(path))),
+            Some(MergeStrategy::Replace) | None => Value::Array(upper),
+        },
+        (_, upper) => upper,
+    }
+}
+
+/// Merges two lists: entries with the same key (or equal values, without a key field)
+/// are replaced in place by the upper entry; new entries are appended.
+fn merge_lists(mut lower: Vec<Value>, upper: Vec<Value>, key: Option<&str>) -> Vec<Value> {
+    let identity = |value: &Value| -> Value {
+        match (key, value) {
+            (Some(key), Value::Table(table)) => table.get(key).cloned().unwrap_or(value.clone()),
+            _ => value.clone(),
+        }
+    };
+    for entry in upper {
+        let id = identity(&entry);
+        match lower.iter().position(|existing| identity(existing) == id) {
+            Some(index) => lower[index] = entry,
+            None => lower.push(entry),
+        }
+    }
+    lower
+}
+
+/// Parses an override value (`DEVRS_*` or `--set`): TOML booleans, integers, quoted
+/// strings, arrays and inline tables are taken as such; anything else is a string.
+///
+/// # Arguments
+///
+/// * `raw` - The value as given.
+///
+/// # Returns
+///
+/// * `Value` - The parsed value.
+pub fn parse_value(raw: &str) -> Value {
+    let parsed = toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut t| t.remove("value"));
+    match parsed {
+        Some(
+            value @ (Value::Boolean(_)
+            | Value::Integer(_)
+            | Value::String(_)
+            | Value::Array(_)
+            | Value::Table(_)),
+        ) => value,
+        // Floats and dates (e.g. a tag like `1.0`) are kept verbatim.
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+/// Sets a value at a path of keys, creating (or replacing non-table values with) tables
+/// along the way.
+///
+/// # Arguments
+///
+/// * `table` - The table to modify.
+/// * `keys` - The path, e.g. `["core_env", "image_tag"]`.
+/// * `value` - The value to set.
+pub fn set_path(table: &mut Table, keys: &[String], value: Value) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for key in parents {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        current = entry.as_table_mut().expect("entry is a table");
+    }
+    current.insert(last.clone(), value);
+}
+
+/// Builds the environment layer from `DEVRS_*` variables.
+///
+/// # Arguments
+///
+/// * `vars` - The environment variables (name, value).
+/// * `schema` - The schema of the configuration (`schema::config_schema`). Variables
+///   whose first key is not a section are ignored.
+///
+/// # Returns
+///
+/// * `Option<Layer>` - The layer, or `None` if no variable applies.
+pub fn env_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+    schema: &serde_json::Value,
+) -> Option<Layer> {
+    let mut table = Table::new();
+    for (name, value) in vars {
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let Some(keys) = env_keys(rest, schema) else {
+            continue;
+        };
+        set_path(&mut table, &keys, parse_value(&value));
+    }
+    (!table.is_empty()).then_some(Layer {
+        source: LayerSource::Env,
+        table,
+    })
+}
+
+/// Turns the part of a variable name after `DEVRS_` into config keys.
+///
+/// Field names are lowercased; keys of maps (`env_vars`, `build.args`, `apps`, ...) are
+/// kept as written, so `CORE_ENV__ENV_VARS__RUST_LOG` sets `core_env.env_vars.RUST_LOG`.
+/// Returns `None` for an empty key or a first key that is not a section.
+fn env_keys(rest: &str, schema: &serde_json::Value) -> Option<Vec<String>> {
+    let mut keys = Vec::new();
+    let mut current = Some(schema);
+    for segment in rest.split("__") {
+        if segment.is_empty() {
+            return None;
+        }
+        let field = segment.to_lowercase();
+        let known = current.and_then(|s| s.get("properties")?.get(&field));
+        let map_values = current
+            .and_then(|s| s.get("additionalProperties"))
+            .filter(|values| values.is_object());
+        if keys.is_empty() && known.and_then(|s| s.get("type")) != Some(&"object".into()) {
+            return None;
+        }
+        // Unknown fields are kept (lowercased) for the config parser to report.
+        let (key, next) = match (known, map_values) {
+            (Some(known), _) => (field, Some(known)),
+            (None, Some(values)) => (segment.to_string(), Some(values)),
+            (None, None) => (field, None),
+        };
+        keys.push(key);
+        current = next;
+    }
+    Some(keys)
+}
+
+/// Builds the command-line layer from `--set KEY=VALUE` flags.
+///
+/// # Arguments
+///
+/// * `assignments` - The flag values, e.g. `core_env.image_tag=v3`.
+///
+/// # Returns
+///
+/// * `Result<Option<Layer>>` - The layer, or `None` without flags.
+///
+/// # Errors
+///
+/// Returns `DevrsError::ArgumentParsing` for a flag without `=` or with an empty key.
+pub fn cli_layer(assignments: &[String]) -> Result<Option<Layer>> {
+    let mut table = Table::new();
+    for assignment in assignments {
+        let (key, value) = assignment
+            .split_once('=')
+            .filter(|(key, _)| key.split('.').all(|k| !k.trim().is_empty()))
+            .ok_or_else(|| {
+                anyhow!(DevrsError::ArgumentParsing(format!(
+                    "Invalid --set '{}': expected KEY=VALUE, e.g. core_env.image_tag=v3",
+                    assignment
+                )))
+            })?;
+        let keys: Vec<String> = key.split('.').map(|k| k.trim().to_string()).collect();
+        set_path(&mut table, &keys, parse_value(value));
+    }
+    Ok((!table.is_empty()).then_some(Layer {
+        source: LayerSource::Cli,
+        table,
+    }))
+}
+
+/// Drops the entries of `table` that equal `defaults`, keeping only what differs.
+/// Used for layers that are produced as a complete configuration (devcontainer.json).
+///
+/// # Arguments
+///
+/// * `table` - A complete configuration as a table.
+/// * `defaults` - The default configuration as a table.
+///
+/// # Returns
+///
+/// * `Table` - The entries that differ from the defaults.
+pub fn without_defaults(table: Table, defaults: &Table) -> Table {
+    table
+        .into_iter()
+        .filter_map(|(key, value)| match (value, defaults.get(&key)) {
+            (value, Some(default)) if value == *default => None,
+            (Value::Table(inner), Some(Value::Table(default))) => {
+                let inner = without_defaults(inner, default);
+                (!inner.is_empty()).then_some((key, Value::Table(inner)))
+            }
+            (value, _) => Some((key, value)),
+        })
+        .collect()
+}
+
+/// Records which layers set each field, for `devrs config show`. Scalars and replaced
+/// lists belong to the highest layer setting them; lists combined by `append`/`merge`
+/// belong to every layer that contributed; map entries are tracked individually.
+///
+/// # Arguments
+///
+/// * `layers` - The layers, in order of increasing precedence.
+///
+/// # Returns
+///
+/// * `BTreeMap<String, Vec<LayerSource>>` - Sources per dotted field path. Sections are
+///   not listed; look fields up with `source_of`.
+pub fn provenance(layers: &[Layer]) -> BTreeMap<String, Vec<LayerSource>> {
+    let strategies = strategies(layers);
+    let mut sources = BTreeMap::new();
+    for layer in layers {
+        record_sources(&layer.table, "", &layer.source, &strategies, &mut sources);
+    }
+    sources
+}
+
+/// Records the fields one layer sets (see `provenance`).
+fn record_sources(
+    table: &Table,
+    prefix: &str,
+    source: &LayerSource,
+    strategies: &HashMap<String, MergeStrategy>,
+    sources: &mut BTreeMap<String, Vec<LayerSource>>,
+) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        let strategy = strategies.get(&path).copied();
+        match (value, strategy) {
+            (Value::Table(inner), None) => {
+                record_sources(inner, &path, source, strategies, sources)
+            }
+            (Value::Table(inner), Some(strategy)) => {
+                if strategy == MergeStrategy::Replace {
+                    let entries = format!("{}.", path);
+                    sources.retain(|field, _| !field.starts_with(&entries));
+                }
+                for entry in inner.keys() {
+                    sources.insert(format!("{}.{}", path, entry), vec![source.clone()]);
+                }
+            }
+            (Value::Array(_), Some(MergeStrategy::Append | MergeStrategy::Merge)) => {
+                let contributors = sources.entry(path).or_default();
+                if !contributors.contains(source) {
+                    contributors.push(source.clone());
+                }
+            }
+            _ => {
+                sources.insert(path, vec![source.clone()]);
+            }
+        }
+    }
+}
+
+/// Finds the sources of a field, or of the nearest enclosing field that has them
+/// (e.g. `apps.web.image` belongs to whichever layer defined `apps.web`).
+///
+/// # Returns
+///
+/// * `Option<&[LayerSource]>` - `None` if no layer sets the field (it is a default).
+pub fn source_of<'a>(
+    sources: &'a BTreeMap<String, Vec<LayerSource>>,
+    path: &str,
+) -> Option<&'a [LayerSource]> {
+    let mut current = path;
+    loop {
+        if let Some(found) = sources.get(current) {
+            return Some(found);
+        }
+        current = &current[..current.rfind('.')?];
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::schema;
+
+    /// Builds a layer from TOML text.
+    fn layer(source: LayerSource, text: &str) -> Layer {
+        Layer {
+            source,
+            table: toml::from_str(text).unwrap(),
+        }
+    }
+
+    /// Test scalar precedence and the default list/map strategies.
+    #[test]
+    fn test_merge_layers_defaults() {
+        let user = layer(
+            LayerSource::User("u".into()),
+            r#"
+            [core_env]
+            image_tag = "v2"
+            ports = ["8080:8080"]
+            mounts = [
+              { host = "~/code", container = "/code" },
+              { host = "~/.ssh", container = "/home/me/.ssh", readonly = true },
+            ]
+            env_vars = { A = "1", B = "1" }
+            persist = { paths = ["/a", "/b"] }
+            "#,
+        );
+        let project = layer(
+            LayerSource::Project("p".into()),
+            r#"
+            [core_env]
+            image_tag = "latest"
+            ports = ["3000:3000"]
+            mounts = [
+              { host = "/src", container = "/code" },
+              { host = "/data", container = "/data" },
+            ]
+            env_vars = { B = "2" }
+            persist = { paths = ["/b", "/c"] }
+            "#,
+        );
+        let merged = merge_layers(&[user, project]);
+        let core = merged["core_env"].as_table().unwrap();
+        // An explicit default still overrides.
+        assert_eq!(core["image_tag"].as_str(), Some("latest"));
+        assert_eq!(core["ports"], Value::Array(vec!["3000:3000".into()]));
+        let mounts = core["mounts"].as_array().unwrap();
+        let hosts: Vec<&str> = mounts.iter().map(|m| m["host"].as_str().unwrap()).collect();
+        assert_eq!(hosts, vec!["/src", "~/.ssh", "/data"]);
+        assert_eq!(core["env_vars"]["A"].as_str(), Some("1"));
+        assert_eq!(core["env_vars"]["B"].as_str(), Some("2"));
+        let paths: Vec<&str> = core["persist"]["paths"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p.as_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["/a", "/b", "/c"]);
+    }
+
+    /// Test configuring strategies through `[merge]`.
+    #[test]
+    fn test_merge_layers_configured_strategies() {
+        let user = layer(
+            LayerSource::User("u".into()),
+            r#"
+            core_env = { ports = ["8080:8080"], env_vars = { A = "1" } }
+            merge = { "core_env.ports" = "append" }
+            "#,
+        );
+        let project = layer(
+            LayerSource::Project("p".into()),
+            r#"
+            core_env = { ports = ["3000:3000"], env_vars = { B = "2" } }
+            merge = { "core_env.env_vars" = "replace", "not.a.list" = "append" }
+            "#,
+        );
+        let layers = [user, project];
+        let strategies = strategies(&layers);
+        assert!(!strategies.contains_key("not.a.list"));
+        let merged = merge_layers(&layers);
+        assert_eq!(
+            merged["core_env"]["ports"],
+            Value::Array(vec!["8080:8080".into(), "3000:3000".into()])
+        );
+        let env = merged["core_env"]["env_vars"].as_table().unwrap();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["B"].as_str(), Some("2"));
+    }
+
+    /// Test the environment and command-line layers.
+    #[test]
+    fn test_override_layers() {
+        let vars = [
+            ("DEVRS_CORE_ENV__IMAGE_TAG", "1.0"),
+            ("DEVRS_LOGS__COLLECT", "true"),
+            ("DEVRS_LOGS__MAX_SEGMENTS", "5"),
+            ("DEVRS_CORE_ENV__PORTS", r#"["1:1"]"#),
+            ("DEVRS_HOME", "/opt/devrs"),
+            ("DEVRS_VERSION", "1"),
+            ("PATH", "/bin"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = env_layer(vars, &schema::config_schema()).unwrap();
+        assert_eq!(env.source, LayerSource::Env);
+        assert_eq!(env.table["core_env"]["image_tag"].as_str(), Some("1.0"));
+        assert_eq!(env.table["logs"]["collect"].as_bool(), Some(true));
+        assert_eq!(env.table["logs"]["max_segments"].as_integer(), Some(5));
+        assert!(env.table["core_env"]["ports"].is_array());
+        assert!(!env.table.contains_key("home"));
+        assert!(!env.table.contains_key("version"));
+
+        // Map keys keep their case; the fields around them are lowercased.
+        let vars = [
+            ("DEVRS_CORE_ENV__ENV_VARS__RUST_LOG", "debug"),
+            ("DEVRS_CORE_ENV__BUILD__ARGS__NODE_VERSION", "lts"),
+            ("DEVRS_APPS__Db__IMAGE", "postgres"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = env_layer(vars, &schema::config_schema()).unwrap();
+        let core = &env.table["core_env"];
+        assert_eq!(core["env_vars"]["RUST_LOG"].as_str(), Some("debug"));
+        assert_eq!(core["build"]["args"]["NODE_VERSION"].as_str(), Some("lts"));
+        assert_eq!(env.table["apps"]["Db"]["image"].as_str(), Some("postgres"));
+
+        let cli = cli_layer(&["core_env.image_tag=\"2\"".to_string()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(cli.table["core_env"]["image_tag"].as_str(), Some("2"));
+        assert!(cli_layer(&["core_env.image_tag".to_string()]).is_err());
+        assert!(cli_layer(&["core_env.=x".to_string()]).is_err());
+        assert!(cli_layer(&[]).unwrap().is_none());
+    }
+
+    /// Test dropping entries equal to the defaults.
+    #[test]
+    fn test_without_defaults() {
+        let defaults: Table =
+            toml::from_str("[core_env]\nimage_name = \"d\"\nports = []\n[logs]\n").unwrap();
+        let full: Table =
+            toml::from_str("[core_env]\nimage_name = \"d\"\nports = []\nuser = \"me\"\n[logs]\n")
+                .unwrap();
+        let explicit = without_defaults(full, &defaults);
+        assert_eq!(explicit.len(), 1);
+        assert_eq!(explicit["core_env"].as_table().unwrap().len(), 1);
+        assert_eq!(explicit["core_env"]["user"].as_str(), Some("me"));
+    }
+
+    /// Test attributing fields to the layers that set them.
+    #[test]
+    fn test_provenance() {
+        let user = LayerSource::User("u".into());
+        let project = LayerSource::Project("p".into());
+        let layers = [
+            layer(
+                user.clone(),
+                r#"
+                core_env = { image_tag = "v2", ports = ["1:1"], persist = { paths = ["/a"] } }
+                apps = { db = { image = "postgres" } }
+                "#,
+            ),
+            layer(
+                project.clone(),
+                r#"
+                core_env = { ports = ["2:2"], persist = { paths = ["/b"] } }
+                apps = { web = { image = "nginx" } }
+                "#,
+            ),
+        ];
+        let sources = provenance(&layers);
+        let of = |path: &str| source_of(&sources, path).map(<[LayerSource]>::to_vec);
+        assert_eq!(of("core_env.image_tag"), Some(vec![user.clone()]));
+        assert_eq!(of("core_env.ports"), Some(vec![project.clone()]));
+        assert_eq!(
+            of("core_env.persist.paths"),
+            Some(vec![user.clone(), project.clone()])
+        );
+        assert_eq!(of("apps.db.image"), Some(vec![user]));
+        assert_eq!(of("apps.web.ports"), Some(vec![project]));
+        assert_eq!(of("core_env.image_name"), None);
+    }
+}
@@ -0,0 +1,620 @@
+//! # DevRS Configuration Lint
+//!
+//! File: cli/src/core/lint.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module checks the configuration for settings that weaken the isolation between
+//! the host and the development containers, such as mounting the Docker socket or
+//! publishing ports to the network. It backs `devrs config lint` and runs before the
+//! core environment container is created.
+//!
+//! ## Architecture
+//!
+//! - Each rule has an id and a severity (`RULES`):
+//!   * `docker-socket` (error): The Docker socket is mounted, which gives the container
+//!     root-equivalent control of the host.
+//!   * `sensitive-rw` (warning): Credentials or keys (`~/.ssh`, `~/.aws`, `~/.gnupg`,
+//!     `~/.kube`, ...) are mounted without `readonly = true`.
+//!   * `broad-mount` (warning): `/`, the home directory or a directory shared by the
+//!     whole host session (`/tmp`, `/run`, `/run/user/<uid>`, ...) is mounted as a whole
+//!     (`is_host_wide`).
+//!   * `public-port` (warning): A port is bound to all interfaces (`0.0.0.0`, `::`),
+//!     directly or through `[network] bind_address`.
+//!   * `plaintext-secret` (warning): A config file sets a secret-looking variable
+//!     (`*TOKEN*`, `*PASSWORD*`, ...) to a literal value instead of a secret reference
+//!     (`{ secret = "..." }`, see `core::secrets`) or a `${VAR}` reference.
+//! - Mounts and ports are checked in `core_env`, `apps` and `application_defaults` of the
+//!   effective configuration. Secrets are checked in the text of each config file, since
+//!   interpolation has already replaced `${VAR}` references in the effective configuration.
+//! - `[lint] allow` suppresses findings: `"<rule>"` for the whole rule, or
+//!   `"<rule>:<subject>"` for one mount host path, port mapping or variable name.
+//! - Before the core environment is created (`check_before_create`), findings are printed;
+//!   errors stop the creation.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::{config, lint};
+//! # fn example() -> anyhow::Result<()> {
+//! let cfg = config::load_config()?;
+//! for finding in lint::lint_current(&cfg)? {
+//!     eprintln!("{}", finding);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::{
+    config::{self, Config, MountConfig}, // The configuration being checked.
+    diagnostics::{self, Diagnostic, Segment}, // Locating secrets in files.
+    error::{DevrsError, Result},         // Standard Result type and custom errors.
+    ports::{self, PortMapping},          // Effective port bindings.
+};
+use anyhow::{anyhow, Context}; // For error creation and context.
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+}; // Findings, config files and host directories.
+use tracing::debug; // Logging framework utilities.
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Risky, but sometimes intended.
+    Warning,
+    /// Stops the core environment from being created unless suppressed.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// # Lint Rule (`Rule`)
+#[derive(Debug)]
+pub struct Rule {
+    /// The id used in output and in `[lint] allow`.
+    pub id: &'static str,
+    /// How serious a finding is.
+    pub severity: Severity,
+    /// What the rule looks for.
+    pub summary: &'static str,
+}
+
+/// Mounting the Docker socket.
+const DOCKER_SOCKET: &str = "docker-socket";
+/// Credentials mounted read-write.
+const SENSITIVE_RW: &str = "sensitive-rw";
+/// `/`, the home directory or a shared host directory mounted.
+const BROAD_MOUNT: &str = "broad-mount";
+/// Ports reachable from the network.
+const PUBLIC_PORT: &str = "public-port";
+/// Literal secrets in config files.
+const PLAINTEXT_SECRET: &str = "plaintext-secret";
+
+/// All lint rules.
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: DOCKER_SOCKET,
+        severity: Severity::Error,
+        summary: "The Docker socket is mounted (root-equivalent access to the host)",
+    },
+    Rule {
+        id: SENSITIVE_RW,
+        severity: Severity::Warning,
+        summary: "Credentials or keys are mounted read-write",
+    },
+    Rule {
+        id: BROAD_MOUNT,
+        severity: Severity::Warning,
+        summary: "The root, home or a shared host directory is mounted as a whole",
+    },
+    Rule {
+        id: PUBLIC_PORT,
+        severity: Severity::Warning,
+        summary: "A port is published on all network interfaces",
+    },
+    Rule {
+        id: PLAINTEXT_SECRET,
+        severity: Severity::Warning,
+        summary: "A secret-looking variable is set to a literal value in a config file",
+    },
+];
+
+/// Host paths of the Docker socket.
+const DOCKER_SOCKETS: &[&str] = &["/var/run/docker.sock", "/run/docker.sock"];
+
+/// Credential files and directories, relative to the home directory.
+const SENSITIVE_HOME_PATHS: &[&str] = &[
+    ".ssh",
+    ".gnupg",
+    ".aws",
+    ".azure",
+    ".kube",
+    ".docker",
+    ".config/gcloud",
+    ".config/gh",
+    ".gitconfig",
+    ".git-credentials",
+    ".netrc",
+    ".npmrc",
+    ".pypirc",
+    ".cargo/credentials",
+    ".cargo/credentials.toml",
+];
+
+/// Directories shared by everything running on the host, besides `/` and the home
+/// directory (see `is_host_wide`).
+const SHARED_DIRS: &[&str] = &["/tmp", "/var/tmp", "/run", "/var/run", "/dev/shm"];
+
+/// Parts of variable names that suggest a secret value.
+const SECRET_NAME_PARTS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "APIKEY",
+    "PRIVATE_KEY",
+    "ACCESS_KEY",
+    "CREDENTIAL",
+];
+
+/// Looks up a rule by id.
+pub fn rule(id: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.id == id)
+}
+
+/// # Lint Finding (`Finding`)
+///
+/// One problem found by a rule.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// The rule that found it.
+    pub rule: &'static Rule,
+    /// What it is about (a mount host path, port mapping or variable name), for
+    /// `"<rule>:<subject>"` suppressions.
+    pub subject: String,
+    /// The problem, e.g. "~/.ssh is mounted read-write".
+    pub message: String,
+    /// Where it is: the key, or the file, line and snippet (see `core::diagnostics`).
+    location: String,
+    /// How to fix it.
+    help: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}\n{}\n  = help: {}\n  = help: or suppress it with [lint] allow = [\"{}:{}\"]",
+            self.rule.severity,
+            self.rule.id,
+            self.message,
+            self.location,
+            self.help,
+            self.rule.id,
+            self.subject
+        )
+    }
+}
+
+/// A config file's path and text, for the checks that look at the file as written.
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    /// The file.
+    pub path: std::path::PathBuf,
+    /// Its contents.
+    pub content: String,
+}
+
+/// Lints the configuration DevRS would load here, including its config files and the
+/// files they include.
+///
+/// # Errors
+///
+/// Returns an error if a config file cannot be read.
+pub fn lint_current(cfg: &Config) -> Result<Vec<Finding>> {
+    let mut files = Vec::new();
+    for layer in config::load_layers()? {
+        let Some(path) = layer.source.config_file() else {
+            continue;
+        };
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration file: {}", path.display()))?;
+        files.push(ConfigFile {
+            path: path.to_path_buf(),
+            content,
+        });
+    }
+    Ok(lint(cfg, &files))
+}
+
+/// Runs all rules and drops the findings suppressed by `[lint] allow`.
+///
+/// # Arguments
+///
+/// * `cfg` - The effective configuration (mounts and ports).
+/// * `files` - The config files it was loaded from (secrets).
+///
+/// # Returns
+///
+/// * `Vec<Finding>` - The remaining findings, errors first.
+pub fn lint(cfg: &Config, files: &[ConfigFile]) -> Vec<Finding> {
+    let home = dirs::home_dir().map(|home| home.to_string_lossy().into_owned());
+    let mut findings = Vec::new();
+
+    let mut mounts: Vec<(String, &MountConfig)> = cfg
+        .core_env
+        .mounts
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (format!("core_env.mounts[{}]", i), m))
+        .collect();
+    let mut port_lists: Vec<(String, &[PortMapping])> = vec![
+        ("core_env.ports".to_string(), &cfg.core_env.ports),
+        (
+            "application_defaults.default_ports".to_string(),
+            &cfg.application_defaults.default_ports,
+        ),
+    ];
+    let mut apps: Vec<_> = cfg.apps.iter().collect();
+    apps.sort_by_key(|(name, _)| *name);
+    for (name, app) in apps {
+        mounts.extend(
+            app.mounts
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (format!("apps.{}.mounts[{}]", name, i), m)),
+        );
+        port_lists.push((format!("apps.{}.ports", name), &app.ports));
+    }
+
+    for (key, mount) in &mounts {
+        check_mount(key, mount, home.as_deref(), &mut findings);
+    }
+    for (key, list) in &port_lists {
+        check_ports(key, list, &cfg.network.bind_address, &mut findings);
+    }
+    for file in files {
+        check_secrets(file, &mut findings);
+    }
+
+    findings.retain(|finding| !allowed(&cfg.lint.allow, finding));
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.rule.severity));
+    findings
+}
+
+/// Checks one mount against the mount rules.
+fn check_mount(key: &str, mount: &MountConfig, home: Option<&str>, findings: &mut Vec<Finding>) {
+    let host = mount.host.trim_end_matches('/');
+    let host = if host.is_empty() { "/" } else { host };
+    let shown = display_path(host, home);
+    let location = format!(" --> {}", key);
+    let finding = |id: &str, message: String, help: &str| Finding {
+        rule: rule(id).expect("known rule"),
+        subject: shown.clone(),
+        message,
+        location: location.clone(),
+        help: help.to_string(),
+    };
+
+    if DOCKER_SOCKETS.contains(&host) {
+        findings.push(finding(
+            DOCKER_SOCKET,
+            format!("{} is mounted into {}", host, mount.container),
+            "remove the mount; build and run images from the host instead",
+        ));
+        return;
+    }
+    if is_host_wide(Path::new(host)) || Some(host) == home {
+        findings.push(finding(
+            BROAD_MOUNT,
+            format!("{} is mounted as a whole at {}", shown, mount.container),
+            "mount only the directories the container needs (e.g. ~/code)",
+        ));
+        return;
+    }
+    let Some(home) = home else {
+        return;
+    };
+    let sensitive = SENSITIVE_HOME_PATHS.iter().any(|relative| {
+        let path = format!("{}/{}", home, relative);
+        host == path || host.starts_with(&format!("{}/", path))
+    });
+    if sensitive && !mount.readonly {
+        findings.push(finding(
+            SENSITIVE_RW,
+            format!("{} is mounted read-write", shown),
+            "add `readonly = true` to the mount (or use forward_ssh_agent for SSH keys)",
+        ));
+    }
+}
+
+/// Checks a list of port mappings for bindings to all interfaces.
+fn check_ports(key: &str, list: &[PortMapping], bind_address: &str, findings: &mut Vec<Finding>) {
+    for (index, (configured, bound)) in list
+        .iter()
+        .zip(ports::bind_default(list, bind_address))
+        .enumerate()
+    {
+        let Some(ip) = bound.ip.filter(|ip| ip.is_unspecified()) else {
+            continue;
+        };
+        let via = if configured.ip.is_some() {
+            String::new()
+        } else {
+            " (through network.bind_address)".to_string()
+        };
+        findings.push(Finding {
+            rule: rule(PUBLIC_PORT).expect("known rule"),
+            subject: configured.to_string(),
+            message: format!(
+                "port {} is published on all interfaces ({}){}",
+                configured, ip, via
+            ),
+            location: format!(" --> {}[{}]", key, index),
+            help: "bind it to 127.0.0.1 (the default bind_address) unless other machines need it"
+                .to_string(),
+        });
+    }
+}
+
+/// Checks the environment variables of a config file for literal secrets.
+fn check_secrets(file: &ConfigFile, findings: &mut Vec<Finding>) {
+    let Ok(table) = toml::from_str::<toml::Table>(&file.content) else {
+        debug!(
+            "Skipping secret checks of unparsable {}",
+            file.path.display()
+        );
+        return;
+    };
+    let mut sections: Vec<(Vec<Segment>, &toml::Value)> = Vec::new();
+    if let Some(vars) = table.get("core_env").and_then(|core| core.get("env_vars")) {
+        sections.push((key_path(&["core_env", "env_vars"]), vars));
+    }
+    if let Some(apps) = table.get("apps").and_then(toml::Value::as_table) {
+        for (name, app) in apps {
+            if let Some(vars) = app.get("env_vars") {
+                sections.push((key_path(&["apps", name, "env_vars"]), vars));
+            }
+        }
+    }
+    for (path, vars) in sections {
+        let Some(vars) = vars.as_table() else {
+            continue;
+        };
+        for (name, value) in vars {
+            let Some(value) = value.as_str() else {
+                continue;
+            };
+            if !looks_secret(name) || value.is_empty() || value.contains("${") {
+                continue;
+            }
+            let mut key = path.clone();
+            key.push(Segment::Key(name.clone()));
+            let diagnostic = Diagnostic {
+                message: String::new(),
+                span: diagnostics::key_span(&file.content, &key),
+                help: None,
+            };
+            let rendered = diagnostics::render(&file.path, &file.content, &[diagnostic]);
+            findings.push(Finding {
+                rule: rule(PLAINTEXT_SECRET).expect("known rule"),
+                subject: name.clone(),
+                message: format!("{} is set to a literal value", name),
+                location: rendered.trim_start_matches('\n').to_string(),
+                help: format!(
+                    "reference a secret instead, e.g. {} = {{ secret = \"file:~/.secrets/{}\" }}, or a host variable (\"${{{}}}\")",
+                    name,
+                    name.to_lowercase(),
+                    name
+                ),
+            });
+        }
+    }
+}
+
+/// Whether a variable name suggests a secret.
+fn looks_secret(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Whether `[lint] allow` suppresses a finding.
+fn allowed(allow: &[String], finding: &Finding) -> bool {
+    allow.iter().any(|entry| match entry.split_once(':') {
+        None => entry == finding.rule.id,
+        Some((rule, subject)) => {
+            rule == finding.rule.id
+                && (subject == finding.subject
+                    || shellexpand::tilde(subject) == shellexpand::tilde(&finding.subject))
+        }
+    })
+}
+
+/// Shows a path under the home directory with `~`, as users write it in config files.
+fn display_path(path: &str, home: Option<&str>) -> String {
+    match home.and_then(|home| path.strip_prefix(home)) {
+        Some("") => "~".to_string(),
+        Some(rest) if rest.starts_with('/') => format!("~{}", rest),
+        _ => path.to_string(),
+    }
+}
+
+/// A key path from its parts.
+fn key_path(keys: &[&str]) -> Vec<Segment> {
+    keys.iter()
+        .map(|key| Segment::Key(key.to_string()))
+        .collect()
+}
+
+/// Whether a host directory is shared by the whole host session, so mounting it exposes
+/// unrelated files and sockets: `/`, the home directory, temporary and runtime
+/// directories (`/tmp`, `$TMPDIR`, `/run`, `$XDG_RUNTIME_DIR`, `/run/user/<uid>`, ...).
+///
+/// # Arguments
+///
+/// * `dir` - An absolute host path.
+fn is_host_wide(dir: &Path) -> bool {
+    let mut shared: Vec<PathBuf> = SHARED_DIRS.iter().map(PathBuf::from).collect();
+    shared.push(PathBuf::from("/"));
+    shared.push(env::temp_dir());
+    shared.extend(env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from));
+    shared.extend(dirs::home_dir());
+    dir.parent() == Some(Path::new("/run/user")) || shared.iter().any(|s| s == dir)
+}
+
+/// Lints the configuration before the core environment container is created: prints
+/// the findings, and refuses to continue if any of them is an error.
+///
+/// # Arguments
+///
+/// * `cfg` - The configuration the container is created from.
+/// * `container` - The container's name, for the message.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` if an error-severity finding is not suppressed.
+pub fn check_before_create(cfg: &Config, container: &str) -> Result<()> {
+    let findings = lint_current(cfg)?;
+    for finding in &findings {
+        eprintln!("{}\n", finding);
+    }
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.rule.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(anyhow!(DevrsError::Config(format!(
+            "Not creating '{}': the configuration has {} security error(s) (see above). Fix them, or suppress them in [lint] allow.",
+            container, errors
+        ))));
+    }
+    Ok(())
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::AppContainerConfig;
+
+    fn mount(host: &str, readonly: bool) -> MountConfig {
+        MountConfig {
+            host: host.to_string(),
+            container: "/mnt".to_string(),
+            readonly,
+        }
+    }
+
+    fn ids(findings: &[Finding]) -> Vec<(&str, &str)> {
+        findings
+            .iter()
+            .map(|f| (f.rule.id, f.subject.as_str()))
+            .collect()
+    }
+
+    /// Test the mount and port rules, and suppressions.
+    #[test]
+    fn test_lint_mounts_and_ports() {
+        let home = dirs::home_dir().unwrap().to_string_lossy().into_owned();
+        let mut cfg = Config::default();
+        cfg.core_env.mounts = vec![
+            mount("/var/run/docker.sock", false),
+            mount(&format!("{}/.ssh", home), false),
+            mount(&format!("{}/.aws", home), true),
+            mount(&format!("{}/code", home), false),
+            mount(&format!("{}/", home), false),
+        ];
+        cfg.core_env.ports = vec![
+            "8080:80".parse().unwrap(),
+            "0.0.0.0:9000:9000".parse().unwrap(),
+        ];
+        cfg.apps.insert(
+            "db".into(),
+            AppContainerConfig {
+                mounts: vec![mount("/", true)],
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            ids(&lint(&cfg, &[])),
+            vec![
+                ("docker-socket", "/var/run/docker.sock"),
+                ("sensitive-rw", "~/.ssh"),
+                ("broad-mount", "~"),
+                ("broad-mount", "/"),
+                ("public-port", "0.0.0.0:9000:9000"),
+            ]
+        );
+
+        cfg.network.bind_address = "::".into();
+        cfg.lint.allow = vec![
+            "docker-socket".into(),
+            "sensitive-rw:~/.ssh".into(),
+            "broad-mount:/".into(),
+            "public-port:0.0.0.0:9000:9000".into(),
+        ];
+        let findings = lint(&cfg, &[]);
+        assert_eq!(
+            ids(&findings),
+            vec![("broad-mount", "~"), ("public-port", "8080:80")]
+        );
+        assert!(findings[1].message.contains("through network.bind_address"));
+    }
+
+    /// Test that shared temporary and runtime directories count as host-wide, but
+    /// directories below them do not.
+    #[test]
+    fn test_is_host_wide() {
+        for dir in ["/", "/tmp", "/run", "/run/user/1000", "/dev/shm"] {
+            assert!(is_host_wide(Path::new(dir)), "{}", dir);
+        }
+        for dir in ["/tmp/ssh-AbCd", "/run/user/1000/gnupg", "/srv/data"] {
+            assert!(!is_host_wide(Path::new(dir)), "{}", dir);
+        }
+        assert!(is_host_wide(&dirs::home_dir().unwrap()));
+    }
+
+    /// Test that only literal secret-looking values are reported, with their location.
+    #[test]
+    fn test_lint_secrets() {
+        let file = ConfigFile {
+            path: "/p/.devrs.toml".into(),
+            content: r#"
+[core_env.env_vars]
+GITHUB_TOKEN = "ghp_123"
+DB_PASSWORD = "${DB_PASSWORD}"
+EMPTY_SECRET = ""
+EDITOR = "vim"
+
+[apps.db]
+env_vars = { POSTGRES_PASSWORD = "postgres" }
+"#
+            .to_string(),
+        };
+        let findings = lint(&Config::default(), std::slice::from_ref(&file));
+        assert_eq!(
+            ids(&findings),
+            vec![
+                ("plaintext-secret", "GITHUB_TOKEN"),
+                ("plaintext-secret", "POSTGRES_PASSWORD")
+            ]
+        );
+        let rendered = findings[0].to_string();
+        assert!(rendered.starts_with("warning[plaintext-secret]: GITHUB_TOKEN is set to a literal value\n --> /p/.devrs.toml:3:1\n"), "{}", rendered);
+        assert!(rendered.ends_with("[lint] allow = [\"plaintext-secret:GITHUB_TOKEN\"]"));
+    }
+}
@@ -0,0 +1,338 @@
+//! # DevRS Configuration Migrations
+//!
+//! File: cli/src/core/migrate.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module keeps old config files working when settings move. A config file
+//! states the layout it was written for with a top-level `version = N`. Files written
+//! for an older version are migrated to the current layout when they are loaded, with
+//! a warning at each outdated key; `devrs config migrate` rewrites them on disk:
+//!
+//! ```text
+//! `image_tag` in [core_env.env_vars] sets an environment variable, not the `core_env.image_tag` setting
+//!  --> /home/me/tools/devrs/presets/config.toml:24:1
+//!    |
+//! 24 | image_tag = "latest"
+//!    | ^^^^^^^^^
+//!    = help: move it to `core_env.image_tag`, or run `devrs config migrate /home/me/tools/devrs/presets/config.toml`
+//! ```
+//!
+//! ## Architecture
+//!
+//! - `version` is a directive of the file, like `include`: it is removed when the file is
+//!   loaded. A file without it has version 1, the layout before versioning. A file for a
+//!   newer version than this DevRS knows is an error, rather than being misread.
+//! - `MIGRATIONS` lists one step per version. Each step edits a `toml_edit` document, so
+//!   the same code migrates in memory and on disk, where the file's comments and
+//!   formatting are kept (a moved key takes its comments along).
+//! - Each step reports a `Change` per key it touched: where the key was, and where it
+//!   went (if anywhere). Warnings and `devrs config migrate` are rendered from them.
+//! - Migrations:
+//!   * 1 -> 2: `default_workdir`, `image_name` and `image_tag` under `[core_env.env_vars]`
+//!     (where the sample config used to put them, making them environment variables)
+//!     move to `[core_env]`. If `[core_env]` already sets one, the stray entry is removed.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::migrate;
+//! # fn example(mut doc: toml_edit::DocumentMut, table: &toml::Table, content: &str) {
+//! let from = migrate::file_version(table, content).unwrap();
+//! for change in migrate::migrate_document(&mut doc, from) {
+//!     println!("{}", change);
+//! }
+//! migrate::stamp_version(&mut doc);
+//! # }
+//! ```
+//!
+use crate::core::diagnostics::{self, Diagnostic, Dotted, Segment}; // Locating outdated keys.
+use std::fmt; // Describing changes.
+use toml::{Table, Value}; // Reading the version.
+use toml_edit::{DocumentMut, Item, Key, Table as EditTable}; // Format-preserving migrations.
+
+/// The top-level key holding the layout version of a config file.
+pub const VERSION_KEY: &str = "version";
+
+/// The version of files without `version`.
+const UNVERSIONED: i64 = 1;
+
+/// The current layout version, written by `devrs config migrate`.
+pub const CURRENT_VERSION: i64 = 2;
+
+/// One migration step.
+struct Migration {
+    /// The version the step migrates to (from the one before it).
+    to: i64,
+    /// Applies the step.
+    apply: fn(&mut DocumentMut) -> Vec<Change>,
+}
+
+/// The migration steps, in order.
+const MIGRATIONS: &[Migration] = &[Migration {
+    to: 2,
+    apply: core_settings_from_env_vars,
+}];
+
+/// # Migration Change (`Change`)
+///
+/// One key a migration moved or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Where the key was.
+    pub key: Vec<Segment>,
+    /// Where it went, or `None` if it was removed.
+    pub to: Option<Vec<Segment>>,
+    /// Why the old location is outdated.
+    pub message: String,
+}
+
+impl Change {
+    /// The change as a warning about the unmigrated file.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The unmigrated file, to locate the key.
+    /// * `migrate_hint` - Appended to the help, e.g. "or run `devrs config migrate`".
+    pub fn diagnostic(&self, content: &str, migrate_hint: &str) -> Diagnostic {
+        let fix = match &self.to {
+            Some(to) => format!("move it to `{}`", Dotted(to)),
+            None => "remove it".to_string(),
+        };
+        Diagnostic {
+            message: self.message.clone(),
+            span: diagnostics::key_span(content, &self.key),
+            help: Some(format!("{}, {}", fix, migrate_hint)),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.to {
+            Some(to) => write!(f, "moved `{}` to `{}`", Dotted(&self.key), Dotted(to)),
+            None => write!(f, "removed `{}` ({})", Dotted(&self.key), self.message),
+        }
+    }
+}
+
+/// Reads the layout version of a parsed config file.
+///
+/// # Arguments
+///
+/// * `table` - The parsed file.
+/// * `content` - Its text, to locate a bad `version`.
+///
+/// # Returns
+///
+/// * `Result<i64, Diagnostic>` - The version (1 if unset), or the problem with it: not
+///   a positive integer, or newer than `CURRENT_VERSION`.
+pub fn file_version(table: &Table, content: &str) -> Result<i64, Diagnostic> {
+    let located = |message: String, help: Option<String>| Diagnostic {
+        message,
+        span: diagnostics::key_span(content, &[Segment::Key(VERSION_KEY.into())]),
+        help,
+    };
+    match table.get(VERSION_KEY) {
+        None => Ok(UNVERSIONED),
+        Some(Value::Integer(version)) if (UNVERSIONED..=CURRENT_VERSION).contains(version) => {
+            Ok(*version)
+        }
+        Some(Value::Integer(version)) if *version > CURRENT_VERSION => Err(located(
+            format!(
+                "the file is for config version {}, but this DevRS supports up to version {}",
+                version, CURRENT_VERSION
+            ),
+            Some("update DevRS".to_string()),
+        )),
+        Some(_) => Err(located(
+            format!(
+                "`{}` must be a config version from {} to {}",
+                VERSION_KEY, UNVERSIONED, CURRENT_VERSION
+            ),
+            None,
+        )),
+    }
+}
+
+/// Migrates a config file document from `from` to the current version. `version` is
+/// not changed (see `stamp_version`).
+///
+/// # Returns
+///
+/// * `Vec<Change>` - What was moved or removed, in order; empty if nothing was outdated.
+pub fn migrate_document(doc: &mut DocumentMut, from: i64) -> Vec<Change> {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| migration.to > from)
+        .flat_map(|migration| (migration.apply)(doc))
+        .collect()
+}
+
+/// Sets `version` to the current version, adding it at the top if missing.
+pub fn stamp_version(doc: &mut DocumentMut) {
+    let root = doc.as_table_mut();
+    match root.get_mut(VERSION_KEY).and_then(Item::as_value_mut) {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = CURRENT_VERSION.into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            root.insert(VERSION_KEY, toml_edit::value(CURRENT_VERSION));
+            root.sort_values_by(|a, _, b, _| {
+                (a.get() != VERSION_KEY).cmp(&(b.get() != VERSION_KEY))
+            });
+        }
+    }
+}
+
+/// Settings of `[core_env]` that old sample configs placed under `[core_env.env_vars]`.
+const MISPLACED_CORE_SETTINGS: &[&str] = &["default_workdir", "image_name", "image_tag"];
+
+/// Migration 1 -> 2: moves `MISPLACED_CORE_SETTINGS` from `[core_env.env_vars]` to
+/// `[core_env]`, keeping their comments.
+fn core_settings_from_env_vars(doc: &mut DocumentMut) -> Vec<Change> {
+    let Some(core) = doc.get_mut("core_env").and_then(Item::as_table_like_mut) else {
+        return Vec::new();
+    };
+    let mut moved: Vec<(Key, Item)> = Vec::new();
+    if let Some(env) = core.get_mut("env_vars") {
+        for name in MISPLACED_CORE_SETTINGS {
+            let entry = match env.as_table_mut() {
+                Some(table) => table.remove_entry(name).map(|(mut key, item)| {
+                    keep_section_comments(table, &mut key);
+                    (key, item)
+                }),
+                None => env
+                    .as_table_like_mut()
+                    .and_then(|table| table.remove(name))
+                    .map(|item| (Key::new(*name), item)),
+            };
+            moved.extend(entry);
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (key, item) in moved {
+        let name = key.get().to_string();
+        let at = |section: &[&str]| -> Vec<Segment> {
+            section
+                .iter()
+                .chain([&name.as_str()])
+                .map(|key| Segment::Key(key.to_string()))
+                .collect()
+        };
+        let message = format!(
+            "`{}` in [core_env.env_vars] sets an environment variable, not the `core_env.{}` setting",
+            name, name
+        );
+        let to = if core.contains_key(&name) {
+            None
+        } else {
+            core.insert(&name, item);
+            if let Some(mut inserted) = core.key_mut(&name) {
+                *inserted.leaf_decor_mut() = key.leaf_decor().clone();
+                // Separate it from the settings above, as in the original section.
+                let prefix = key.leaf_decor().prefix().and_then(|p| p.as_str());
+                if prefix.is_some_and(|p| !p.is_empty() && !p.starts_with('\n')) {
+                    let spaced = format!("\n{}", prefix.unwrap_or_default());
+                    inserted.leaf_decor_mut().set_prefix(spaced);
+                }
+            }
+            Some(at(&["core_env"]))
+        };
+        changes.push(Change {
+            key: at(&["core_env", "env_vars"]),
+            to,
+            message,
+        });
+    }
+    changes
+}
+
+/// Splits the comments above a key removed from `table`: the block directly above it
+/// (after the last blank line) describes the key and stays with it; earlier comments
+/// describe the section, so they move below the section's header.
+fn keep_section_comments(table: &mut EditTable, key: &mut Key) {
+    let Some(prefix) = key.leaf_decor().prefix().and_then(|p| p.as_str()) else {
+        return;
+    };
+    let mut own_start = 0;
+    let mut offset = 0;
+    for line in prefix.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim().is_empty() {
+            own_start = offset;
+        }
+    }
+    let (section, own) = prefix.split_at(own_start);
+    let section = section.trim_end();
+    if section.is_empty() {
+        return;
+    }
+    let header = table
+        .decor()
+        .suffix()
+        .and_then(|s| s.as_str())
+        .unwrap_or("");
+    let header = format!("{}\n{}", header.trim_end_matches('\n'), section);
+    let own = own.to_string();
+    table.decor_mut().set_suffix(header);
+    key.leaf_decor_mut().set_prefix(own);
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that versions are read and checked.
+    #[test]
+    fn test_file_version() {
+        let version = |content: &str| file_version(&toml::from_str(content).unwrap(), content);
+        assert_eq!(version("[core_env]\n"), Ok(1));
+        assert_eq!(version("version = 2\n"), Ok(CURRENT_VERSION));
+        let newer = version("version = 99\n").unwrap_err();
+        assert!(newer.message.contains("config version 99"));
+        assert_eq!(newer.span, Some(0..7));
+        assert!(version("version = \"2\"\n").is_err());
+        assert!(version("version = 0\n").is_err());
+    }
+
+    /// Test the 1 -> 2 migration: keys move with their own comments, the section's
+    /// comments stay, and current files are left alone.
+    #[test]
+    fn test_migrate_core_settings_from_env_vars() {
+        let content = "# My config\n[core_env]\nimage_tag = \"v3\"\n\n[core_env.env_vars]\nRUST_LOG = \"info\"\n# FOO = \"bar\"\n\n# Where shells start.\ndefault_workdir = \"/code\" # Code\nimage_tag = \"latest\"\n";
+        let mut doc: DocumentMut = content.parse().unwrap();
+        let changes = migrate_document(&mut doc, 1);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0].to_string(),
+            "moved `core_env.env_vars.default_workdir` to `core_env.default_workdir`"
+        );
+        assert_eq!(changes[1].to, None);
+        stamp_version(&mut doc);
+        assert_eq!(
+            doc.to_string(),
+            "version = 2\n# My config\n[core_env]\nimage_tag = \"v3\"\n\n# Where shells start.\ndefault_workdir = \"/code\" # Code\n\n[core_env.env_vars]\n# FOO = \"bar\"\nRUST_LOG = \"info\"\n"
+        );
+
+        let diagnostic = changes[0].diagnostic(content, "or run `devrs config migrate`");
+        assert_eq!(
+            diagnostic.span.map(|span| &content[span]),
+            Some("default_workdir")
+        );
+
+        let mut current: DocumentMut = "version = 2\n[core_env.env_vars]\nimage_tag = \"x\"\n"
+            .parse()
+            .unwrap();
+        assert!(migrate_document(&mut current, 2).is_empty());
+    }
+}
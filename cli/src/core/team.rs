@@ -0,0 +1,254 @@
+//! # DevRS Team Configs
+//!
+//! File: cli/src/core/team.rs
+//! Author: Christi Mahu
+//! Repository: https://github.com/christimahu/devrs
+//!
+//! **DISCLAIMER:** This repository is in the early phases of being rewritten
+//! and is not suitable for production development yet.
+//!
+//! ## Overview
+//!
+//! This module keeps the registry of team configs: shared baselines (mounts, ports,
+//! apps, env vars) kept in a team's git repository. `devrs config team add` clones the
+//! repository into the DevRS data directory and registers its config file, which is
+//! then loaded as a layer beneath the user config (see `core::layers`). Each developer's
+//! `config.toml` and each project's `.devrs.toml` override it.
+//!
+//! ## Architecture
+//!
+//! - The registry is `teams.toml` in the DevRS data directory
+//!   (`~/.local/share/devrs/teams.toml` on Linux), one `[[team]]` per registered team,
+//!   in the order they were added (later teams override earlier ones).
+//! - Clones live in `teams/<name>/` next to it and are updated with
+//!   `devrs config team sync` (`git pull --ff-only`). A local directory can be registered
+//!   in place instead; it is used as-is and not synced.
+//! - The team's config file is `config.toml` at the root of the repository unless
+//!   another file is given. It may `include` further files of the repository.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use crate::core::team;
+//! # fn example() -> anyhow::Result<()> {
+//! for registered in team::registered()? {
+//!     println!("{}: {}", registered.name, registered.config_path().display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+use crate::core::error::{DevrsError, Result}; // Standard Result type and custom errors.
+use anyhow::anyhow; // For creating errors.
+use serde::{Deserialize, Serialize}; // The registry file.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+}; // Registry and checkout locations.
+
+/// The config file of a team repository, unless another is given.
+pub const DEFAULT_TEAM_FILE: &str = "config.toml";
+
+/// # Registered Team (`Team`)
+///
+/// One entry of the registry.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Team {
+    /// The name used by `devrs config team sync/remove`.
+    pub name: String,
+    /// Where the team config came from: a git URL or a local directory.
+    pub source: String,
+    /// The checkout, or the registered local directory.
+    pub dir: PathBuf,
+    /// The config file, relative to `dir`.
+    pub file: String,
+    /// Whether `dir` is a clone made by DevRS (synced with `git pull`, deleted on removal).
+    pub cloned: bool,
+}
+
+impl Team {
+    /// The team's config file.
+    pub fn config_path(&self) -> PathBuf {
+        self.dir.join(&self.file)
+    }
+}
+
+/// # Team Registry (`Registry`)
+///
+/// The contents of `teams.toml`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Registry {
+    /// The registered teams, lowest precedence first.
+    #[serde(default, rename = "team", skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<Team>,
+}
+
+impl Registry {
+    /// Reads a registry file; a missing file is an empty registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::Config` if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to read '{}': {}",
+                path.display(),
+                e
+            )))
+        })?;
+        toml::from_str(&content).map_err(|e| {
+            anyhow!(DevrsError::Config(format!(
+                "Invalid team registry {}: {}",
+                path.display(),
+                e.message().trim()
+            )))
+        })
+    }
+
+    /// Writes the registry file, creating its directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DevrsError::FileSystem` if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            anyhow!(DevrsError::Config(format!(
+                "Failed to serialize the team registry: {}",
+                e
+            )))
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                anyhow!(DevrsError::FileSystem(format!(
+                    "Failed to create '{}': {}",
+                    parent.display(),
+                    e
+                )))
+            })?;
+        }
+        fs::write(path, content).map_err(|e| {
+            anyhow!(DevrsError::FileSystem(format!(
+                "Failed to write '{}': {}",
+                path.display(),
+                e
+            )))
+        })
+    }
+
+    /// Looks up a team by name.
+    pub fn get(&self, name: &str) -> Option<&Team> {
+        self.teams.iter().find(|team| team.name == name)
+    }
+}
+
+/// The DevRS data directory (`~/.local/share/devrs` on Linux).
+fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("share")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("devrs")
+}
+
+/// The registry file.
+pub fn registry_path() -> PathBuf {
+    data_dir().join("teams.toml")
+}
+
+/// The directory holding the clones of team repositories.
+pub fn checkouts_dir() -> PathBuf {
+    data_dir().join("teams")
+}
+
+/// The registered teams, lowest precedence first.
+///
+/// # Errors
+///
+/// Returns an error if the registry exists but cannot be read.
+pub fn registered() -> Result<Vec<Team>> {
+    Ok(Registry::load(&registry_path())?.teams)
+}
+
+/// Derives a team name from a git URL or path, e.g. `acme-devrs` from
+/// `git@github.com:acme/acme-devrs.git`.
+///
+/// # Returns
+///
+/// * `Option<String>` - The name, or `None` if the source has no usable last component.
+pub fn name_from_source(source: &str) -> Option<String> {
+    let last = source
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\', ':'])
+        .next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    is_valid_name(name).then(|| name.to_string())
+}
+
+/// Whether a team name is usable as a directory name: ASCII letters, digits, `.`, `_`
+/// and `-`, not starting with `.`.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that team names are derived from URLs and paths.
+    #[test]
+    fn test_name_from_source() {
+        let name = |source: &str| name_from_source(source);
+        assert_eq!(
+            name("https://github.com/acme/devrs-team.git").as_deref(),
+            Some("devrs-team")
+        );
+        assert_eq!(
+            name("git@github.com:acme/devrs-team.git").as_deref(),
+            Some("devrs-team")
+        );
+        assert_eq!(
+            name("/srv/shared/team-config/").as_deref(),
+            Some("team-config")
+        );
+        assert_eq!(name("https://example.com/"), Some("example.com".into()));
+        assert_eq!(name("/"), None);
+        assert!(!is_valid_name(".hidden"));
+        assert!(!is_valid_name("a b"));
+    }
+
+    /// Test that the registry round-trips, and a missing file is empty.
+    #[test]
+    fn test_registry_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("teams.toml");
+        assert_eq!(Registry::load(&path).unwrap(), Registry::default());
+        let registry = Registry {
+            teams: vec![Team {
+                name: "acme".into(),
+                source: "https://example.com/acme.git".into(),
+                dir: dir.path().join("teams/acme"),
+                file: DEFAULT_TEAM_FILE.into(),
+                cloned: true,
+            }],
+        };
+        registry.save(&path).unwrap();
+        let loaded = Registry::load(&path).unwrap();
+        assert_eq!(loaded, registry);
+        assert_eq!(
+            loaded.get("acme").unwrap().config_path(),
+            dir.path().join("teams/acme/config.toml")
+        );
+        fs::write(&path, "[[team]]\nname = 1\n").unwrap();
+        assert!(Registry::load(&path).is_err());
+    }
+}
@@ -32,6 +32,9 @@
 //!
 //! # Run a command with increased verbosity
 //! devrs -vv env build
+//!
+//! # Override a configuration value for one run
+//! devrs --set core_env.image_tag=v3 env shell
 //! ```
 //!
 //! Command processing flow:
@@ -66,6 +69,10 @@ struct Cli {
     command: Commands,
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     verbose: u8,
+    /// Override a configuration value for this run, e.g. `--set core_env.image_tag=v3`
+    /// (repeatable; takes precedence over config files and DEVRS_* variables).
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    set: Vec<String>,
 }
 
 /// Enum defining all available top-level commands.
@@ -75,10 +82,18 @@ enum Commands {
     Env(commands::env::EnvArgs),
     #[command(alias = "c")]
     Container(commands::container::ContainerArgs),
+    #[command(alias = "i")]
+    Images(commands::images::ImagesArgs),
+    Du(commands::du::DuArgs),
+    Stats(commands::stats::StatsArgs),
+    Logs(commands::logs::LogsArgs),
+    Collector(commands::collector::CollectorArgs),
+    Config(commands::config::ConfigArgs),
     #[command(alias = "b")]
     Blueprint(commands::blueprint::BlueprintArgs),
     #[command(alias = "s")]
     Setup(commands::setup::SetupArgs),
+    Shims(commands::shims::ShimsArgs),
     Srv(commands::srv::SrvArgs),
 }
 
@@ -103,12 +118,20 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     tracing::debug!("Parsed CLI arguments: {:?}", cli);
+    core::config::set_cli_overrides(cli.set.clone());
 
     let command_result = match cli.command {
         Commands::Env(args) => commands::env::handle_env(args).await,
         Commands::Container(args) => commands::container::handle_container(args).await,
+        Commands::Images(args) => commands::images::handle_images(args).await,
+        Commands::Du(args) => commands::du::handle_du(args).await,
+        Commands::Stats(args) => commands::stats::handle_stats(args).await,
+        Commands::Logs(args) => commands::logs::handle_logs(args).await,
+        Commands::Collector(args) => commands::collector::handle_collector(args).await,
+        Commands::Config(args) => commands::config::handle_config(args).await,
         Commands::Blueprint(args) => commands::blueprint::handle_blueprint(args).await,
         Commands::Setup(args) => commands::setup::handle_setup(args).await,
+        Commands::Shims(args) => commands::shims::handle_shims(args).await,
         Commands::Srv(args) => commands::srv::handle_srv(args).await,
     };
 